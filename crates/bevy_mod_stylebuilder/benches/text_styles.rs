@@ -0,0 +1,101 @@
+//! Benchmarks for inherited text style propagation. Run with `cargo bench -p bevy_mod_stylebuilder`.
+#![feature(test)]
+extern crate test;
+
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_mod_stylebuilder::{InheritableFontStyles, StyleBuilderPlugin, UseInheritedTextStyles};
+use test::Bencher;
+
+const PANELS: usize = 100;
+const LABELS: usize = 50;
+
+/// Build a UI with a root node containing `PANELS` panels, each containing `LABELS` text
+/// nodes. Returns the app, the root, and the first panel.
+fn setup() -> (App, Entity, Entity) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<Font>()
+        .add_plugins(StyleBuilderPlugin);
+    let world = app.world_mut();
+    let root = world
+        .spawn(InheritableFontStyles {
+            font_size: Some(16.),
+            color: Some(Color::WHITE),
+            ..default()
+        })
+        .id();
+    let mut panels = Vec::with_capacity(PANELS);
+    for _ in 0..PANELS {
+        let panel = world
+            .spawn(InheritableFontStyles::default())
+            .set_parent(root)
+            .id();
+        for _ in 0..LABELS {
+            world
+                .spawn((
+                    TextBundle::from_section("label", default()),
+                    UseInheritedTextStyles,
+                ))
+                .set_parent(panel);
+        }
+        panels.push(panel);
+    }
+    app.update();
+    (app, root, panels[0])
+}
+
+fn toggle_color(app: &mut App, entity: Entity, toggle: &mut bool) {
+    *toggle = !*toggle;
+    app.world_mut()
+        .get_mut::<InheritableFontStyles>(entity)
+        .unwrap()
+        .color = Some(if *toggle { Color::BLACK } else { Color::WHITE });
+    app.update();
+}
+
+/// Mark every text node as changed, so that each one is re-computed from its ancestors on the
+/// next update. This reproduces the old propagation, which re-computed every text node
+/// whenever any `InheritableFontStyles` changed.
+fn mark_all_dirty(app: &mut App) {
+    let world = app.world_mut();
+    let mut texts = world.query_filtered::<&mut Text, With<UseInheritedTextStyles>>();
+    for mut text in texts.iter_mut(world) {
+        text.set_changed();
+    }
+}
+
+/// Nothing changes between frames.
+#[bench]
+fn bench_idle(b: &mut Bencher) {
+    let (mut app, _, _) = setup();
+    b.iter(|| app.update());
+}
+
+/// A single panel changes its text color, as happens on hover.
+#[bench]
+fn bench_restyle_panel(b: &mut Bencher) {
+    let (mut app, _, panel) = setup();
+    let mut toggle = false;
+    b.iter(|| toggle_color(&mut app, panel, &mut toggle));
+}
+
+/// The root changes its text color, as happens when switching themes.
+#[bench]
+fn bench_restyle_root(b: &mut Bencher) {
+    let (mut app, root, _) = setup();
+    let mut toggle = false;
+    b.iter(|| toggle_color(&mut app, root, &mut toggle));
+}
+
+/// A single panel changes its text color, with every text node re-computed as before the
+/// propagation became incremental. Compare with `bench_restyle_panel`.
+#[bench]
+fn bench_restyle_panel_baseline(b: &mut Bencher) {
+    let (mut app, _, panel) = setup();
+    let mut toggle = false;
+    b.iter(|| {
+        mark_all_dirty(&mut app);
+        toggle_color(&mut app, panel, &mut toggle);
+    });
+}
@@ -1,77 +1,115 @@
 use bevy::{
-    asset::{AssetLoader, AsyncReadExt},
-    math::{Rect, Vec2},
-    sprite::{TextureAtlas, TextureAtlasLayout},
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    math::{URect, UVec2},
+    prelude::*,
+    sprite::TextureAtlasLayout,
 };
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+/// A texture atlas for use in UI nodes, consisting of a texture and a layout. The layout is
+/// also available as a labeled sub-asset, `"layout"`.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct UiTextureAtlas {
+    /// The texture containing the tiles.
+    pub texture: Handle<Image>,
+    /// The layout of the tiles within the texture.
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+/// Loader for texture atlases. Two formats are supported:
+///
+/// * `.atlas.ron` files contain a list of texture rectangles.
+/// * `.atlas.grid.ron` files describe a grid of equally-sized tiles.
+///
+/// In both cases, the `texture` path is relative to the atlas file.
+#[derive(Default)]
 pub struct TextureAtlasLoader;
 
 #[derive(Debug, Deserialize, Serialize)]
 struct TextureAtlasSer {
     texture: String,
-    size: Vec2,
-    textures: Vec<Rect>,
+    size: UVec2,
+    textures: Vec<RectSer>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RectSer {
+    min: UVec2,
+    max: UVec2,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct TextureAtlasGridSer {
     texture: String,
-    tile_size: Vec2,
-    columns: usize,
-    rows: usize,
-    padding: Option<Vec2>,
-    offset: Option<Vec2>,
+    tile_size: UVec2,
+    columns: u32,
+    rows: u32,
+    padding: Option<UVec2>,
+    offset: Option<UVec2>,
+}
+
+/// Error type for [`TextureAtlasLoader`].
+#[derive(Debug, Error)]
+pub enum TextureAtlasLoaderError {
+    /// An I/O error.
+    #[error("Could not load texture atlas: {0}")]
+    Io(#[from] std::io::Error),
+    /// The atlas file could not be parsed.
+    #[error("Could not parse texture atlas: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    /// The texture path could not be resolved.
+    #[error("Invalid texture path: {0}")]
+    TexturePath(#[from] bevy::asset::ParseAssetPathError),
+}
+
+/// Parse an atlas file, returning the texture path and the layout.
+fn parse_atlas(
+    bytes: &[u8],
+    grid: bool,
+) -> Result<(String, TextureAtlasLayout), TextureAtlasLoaderError> {
+    if grid {
+        let atlas: TextureAtlasGridSer = ron::de::from_bytes(bytes)?;
+        let layout = TextureAtlasLayout::from_grid(
+            atlas.tile_size,
+            atlas.columns,
+            atlas.rows,
+            atlas.padding,
+            atlas.offset,
+        );
+        Ok((atlas.texture, layout))
+    } else {
+        let atlas: TextureAtlasSer = ron::de::from_bytes(bytes)?;
+        let mut layout = TextureAtlasLayout::new_empty(atlas.size);
+        for rect in atlas.textures {
+            layout.add_texture(URect::from_corners(rect.min, rect.max));
+        }
+        Ok((atlas.texture, layout))
+    }
 }
 
 impl AssetLoader for TextureAtlasLoader {
-    type Asset = TextureAtlasLayout;
+    type Asset = UiTextureAtlas;
     type Settings = ();
-    type Error = anyhow::Error;
+    type Error = TextureAtlasLoaderError;
 
-    fn load<'a>(
+    async fn load<'a>(
         &'a self,
-        reader: &'a mut bevy::asset::io::Reader,
+        reader: &'a mut Reader<'_>,
         _settings: &'a Self::Settings,
-        load_context: &'a mut bevy::asset::LoadContext,
-    ) -> bevy::utils::BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
-        if let Some(ext) = load_context.asset_path().get_full_extension() {
-            if ext == "atlas.grid.ron" {
-                return Box::pin(async move {
-                    let mut bytes = Vec::new();
-                    reader.read_to_end(&mut bytes).await?;
-                    let atlas_ser: TextureAtlasGridSer =
-                        ron::de::from_str(&String::from_utf8(bytes)?)?;
-                    let texture_path = load_context
-                        .asset_path()
-                        .resolve_embed(&atlas_ser.texture)?;
-                    let texture = load_context.load(&texture_path);
-                    let result = TextureAtlas::from_grid(
-                        texture,
-                        atlas_ser.tile_size,
-                        atlas_ser.columns,
-                        atlas_ser.rows,
-                        atlas_ser.padding,
-                        atlas_ser.offset,
-                    );
-                    Ok(result)
-                });
-            }
-        }
-
-        Box::pin(async move {
-            let mut bytes = Vec::new();
-            reader.read_to_end(&mut bytes).await?;
-            let atlas_ser: TextureAtlasSer = ron::de::from_str(&String::from_utf8(bytes)?)?;
-            let texture_path = load_context
-                .asset_path()
-                .resolve_embed(&atlas_ser.texture)?;
-            let texture = load_context.load(&texture_path);
-            let mut result = TextureAtlas::new_empty(texture, atlas_ser.size);
-            for texture in atlas_ser.textures.iter() {
-                result.add_texture(*texture);
-            }
-            Ok(result)
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let grid = load_context
+            .asset_path()
+            .get_full_extension()
+            .is_some_and(|ext| ext == "atlas.grid.ron");
+        let (texture, layout) = parse_atlas(&bytes, grid)?;
+        let texture_path = load_context.asset_path().resolve_embed(&texture)?;
+        Ok(UiTextureAtlas {
+            texture: load_context.load(texture_path),
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
         })
     }
 
@@ -79,3 +117,71 @@ impl AssetLoader for TextureAtlasLoader {
         &["atlas.ron", "atlas.grid.ron"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_grid() {
+        let (texture, layout) = parse_atlas(
+            br#"(
+                texture: "./button.png",
+                tile_size: (16, 16),
+                rows: 3,
+                columns: 3,
+            )"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(texture, "./button.png");
+        assert_eq!(layout.len(), 9);
+        assert_eq!(layout.size, UVec2::new(48, 48));
+        assert_eq!(layout.textures[4], URect::new(16, 16, 32, 32));
+    }
+
+    #[test]
+    fn test_parse_grid_padding() {
+        let (_, layout) = parse_atlas(
+            br#"(
+                texture: "icons.png",
+                tile_size: (10, 10),
+                rows: 1,
+                columns: 2,
+                padding: Some((2, 2)),
+                offset: Some((1, 1)),
+            )"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(layout.len(), 2);
+        assert_eq!(layout.textures[1], URect::new(13, 1, 23, 11));
+    }
+
+    #[test]
+    fn test_parse_rects() {
+        let (texture, layout) = parse_atlas(
+            br#"(
+                texture: "icons.png",
+                size: (64, 32),
+                textures: [
+                    (min: (0, 0), max: (32, 32)),
+                    (min: (32, 0), max: (64, 16)),
+                ],
+            )"#,
+            false,
+        )
+        .unwrap();
+        assert_eq!(texture, "icons.png");
+        assert_eq!(layout.len(), 2);
+        assert_eq!(layout.textures[1], URect::new(32, 0, 64, 16));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            parse_atlas(b"(texture: \"a.png\")", true),
+            Err(TextureAtlasLoaderError::Parse(_))
+        ));
+    }
+}
@@ -1,6 +1,19 @@
 #![allow(missing_docs)]
 //! Defines fluent builder for styles.
 
+use crate::{
+    builder_pseudo_states::{
+        revert_conditional_styles, update_conditional_styles, ConditionalBlock,
+    },
+    builder_transition::update_transitions,
+    transition::TransitionDecl,
+    units::{
+        record_length, resolve_length, Em, Length, LengthCorners, LengthProperty, LengthRect, Rem,
+        Space, StyleUnits,
+    },
+};
+use std::panic::Location;
+
 use bevy::{
     asset::AssetPath,
     color::{LinearRgba, Srgba},
@@ -15,18 +28,44 @@ pub struct StyleBuilder<'a, 'w> {
     pub target: &'a mut EntityWorldMut<'w>,
     pub(crate) style: ui::Style,
     pub(crate) style_changed: bool,
+    /// Identifies the style function which is running; conditional blocks are replaced by
+    /// the ones defined by the next run of the same function.
+    pub(crate) source: &'static Location<'static>,
+    pub(crate) conditions: Vec<ConditionalBlock>,
+    pub(crate) transitions: Vec<TransitionDecl>,
 }
 
 impl<'a, 'w> StyleBuilder<'a, 'w> {
-    /// Construct a new StyleBuilder instance.
-    pub fn new(target: &'a mut EntityWorldMut<'w>, style: ui::Style) -> Self {
+    /// Construct a new StyleBuilder instance. If the target has conditional styles, these
+    /// are reverted first, so that the builder starts from the unconditional styles.
+    ///
+    /// The call site identifies the style function: when the builder is finished, the
+    /// conditional blocks from the previous run at the same call site are replaced. Use
+    /// [`StyleBuilder::with_source`] when the same call site applies several style functions.
+    #[track_caller]
+    pub fn new(target: &'a mut EntityWorldMut<'w>, mut style: ui::Style) -> Self {
+        if revert_conditional_styles(target) {
+            if let Some(s) = target.get::<ui::Style>() {
+                style.clone_from(s);
+            }
+        }
         Self {
             target,
             style,
             style_changed: false,
+            source: Location::caller(),
+            conditions: Vec::new(),
+            transitions: Vec::new(),
         }
     }
 
+    /// Set the location which identifies the style function, in place of the call site of
+    /// [`StyleBuilder::new`].
+    pub fn with_source(mut self, source: &'static Location<'static>) -> Self {
+        self.source = source;
+        self
+    }
+
     /// Helper method for loading assets.
     pub fn load_asset<A: Asset>(&mut self, path: AssetPath<'_>) -> Handle<A> {
         self.target.world_scope(|world| {
@@ -35,16 +74,45 @@ impl<'a, 'w> StyleBuilder<'a, 'w> {
         })
     }
 
+    /// Resolve a length for the given property. Relative lengths are recorded on the target, so
+    /// that they can be re-computed when the [`StyleUnits`](crate::StyleUnits) change.
+    pub(crate) fn resolve_length(
+        &mut self,
+        property: LengthProperty,
+        length: impl LengthParam,
+    ) -> ui::Val {
+        let length = length.to_length();
+        record_length(self.target, property, length);
+        resolve_length(self.target, property, length)
+    }
+
+    /// Resolve the lengths for each edge of a rectangle, in the order left, right, top, bottom.
+    pub(crate) fn resolve_rect(
+        &mut self,
+        properties: [LengthProperty; 4],
+        rect: impl UiRectParam,
+    ) -> ui::UiRect {
+        let [left, right, top, bottom] = properties;
+        let rect = rect.to_length_rect();
+        ui::UiRect {
+            left: self.resolve_length(left, rect.left),
+            right: self.resolve_length(right, rect.right),
+            top: self.resolve_length(top, rect.top),
+            bottom: self.resolve_length(bottom, rect.bottom),
+        }
+    }
+
     /// Consumes the [`StyleBuilder`] and applies the style to the target entity.
     pub fn finish(self) {
         if self.style_changed {
             self.target.insert(self.style);
         }
+        let source = self.source;
+        update_transitions(self.target, source, self.transitions);
+        update_conditional_styles(self.target, |b| b.source == source, self.conditions);
     }
 }
 
-// LineBreak(BreakLineOn),
-
 /// Trait that represents a CSS color
 pub trait ColorParam {
     fn to_val(self) -> Option<Color>;
@@ -93,9 +161,37 @@ impl ColorParam for &str {
     }
 }
 
+/// Resolve a length which isn't attached to an entity: relative units use the default
+/// [`StyleUnits`], and [`Em`] units the root font size.
+fn resolve_default(length: Length) -> ui::Val {
+    let units = StyleUnits::default();
+    length.resolve(&units, units.rem)
+}
+
 /// Trait that represents a CSS "length"
 pub trait LengthParam {
+    /// Convert to a [`ui::Val`]. Relative units are resolved against the default
+    /// [`StyleUnits`], since there is no target entity.
     fn to_val(self) -> ui::Val;
+
+    /// Convert to a [`Length`], which keeps relative units so that they can be resolved
+    /// for the target entity.
+    fn to_length(self) -> Length
+    where
+        Self: Sized,
+    {
+        Length::Val(self.to_val())
+    }
+}
+
+impl LengthParam for Length {
+    fn to_val(self) -> ui::Val {
+        resolve_default(self)
+    }
+
+    fn to_length(self) -> Length {
+        self
+    }
 }
 
 impl LengthParam for ui::Val {
@@ -116,6 +212,36 @@ impl LengthParam for i32 {
     }
 }
 
+impl LengthParam for Rem {
+    fn to_val(self) -> ui::Val {
+        resolve_default(self.to_length())
+    }
+
+    fn to_length(self) -> Length {
+        Length::Rem(self.0)
+    }
+}
+
+impl LengthParam for Em {
+    fn to_val(self) -> ui::Val {
+        resolve_default(self.to_length())
+    }
+
+    fn to_length(self) -> Length {
+        Length::Em(self.0)
+    }
+}
+
+impl LengthParam for Space {
+    fn to_val(self) -> ui::Val {
+        resolve_default(self.to_length())
+    }
+
+    fn to_length(self) -> Length {
+        Length::Space(self.0)
+    }
+}
+
 /// Trait that represents a CSS Z-index
 pub trait ZIndexParam {
     fn to_val(self) -> ZIndex;
@@ -135,30 +261,48 @@ impl ZIndexParam for i32 {
 
 /// Trait that represents CSS edge widths (margin, padding, etc.)
 pub trait UiRectParam {
+    /// Convert to a [`ui::UiRect`]. Relative units are resolved against the default
+    /// [`StyleUnits`], since there is no target entity.
     fn to_uirect(self) -> ui::UiRect;
+
+    /// Convert to a [`LengthRect`], which keeps relative units so that they can be resolved
+    /// for the target entity.
+    fn to_length_rect(self) -> LengthRect
+    where
+        Self: Sized,
+    {
+        self.to_uirect().into()
+    }
 }
 
-impl UiRectParam for ui::UiRect {
+impl UiRectParam for LengthRect {
     fn to_uirect(self) -> ui::UiRect {
+        ui::UiRect {
+            left: resolve_default(self.left),
+            right: resolve_default(self.right),
+            top: resolve_default(self.top),
+            bottom: resolve_default(self.bottom),
+        }
+    }
+
+    fn to_length_rect(self) -> LengthRect {
         self
     }
 }
 
-impl UiRectParam for ui::Val {
+impl UiRectParam for ui::UiRect {
     fn to_uirect(self) -> ui::UiRect {
-        ui::UiRect::all(self)
+        self
     }
 }
 
-impl UiRectParam for f32 {
+impl<L: LengthParam> UiRectParam for L {
     fn to_uirect(self) -> ui::UiRect {
-        ui::UiRect::all(ui::Val::Px(self))
+        ui::UiRect::all(self.to_val())
     }
-}
 
-impl UiRectParam for i32 {
-    fn to_uirect(self) -> ui::UiRect {
-        ui::UiRect::all(ui::Val::Px(self as f32))
+    fn to_length_rect(self) -> LengthRect {
+        LengthRect::all(self.to_length())
     }
 }
 
@@ -166,34 +310,79 @@ impl<H: LengthParam, V: LengthParam> UiRectParam for (H, V) {
     fn to_uirect(self) -> ui::UiRect {
         ui::UiRect::axes(self.0.to_val(), self.1.to_val())
     }
+
+    fn to_length_rect(self) -> LengthRect {
+        LengthRect::axes(self.0.to_length(), self.1.to_length())
+    }
 }
 
 /// Trait that represents border radius
 pub trait BorderRadiusParam {
+    /// Convert to a [`ui::BorderRadius`]. Relative units are resolved against the default
+    /// [`StyleUnits`], since there is no target entity.
     fn to_border_radius(self) -> ui::BorderRadius;
+
+    /// Convert to [`LengthCorners`], which keeps relative units so that they can be resolved
+    /// for the target entity.
+    fn to_length_corners(self) -> LengthCorners
+    where
+        Self: Sized,
+    {
+        self.to_border_radius().into()
+    }
 }
 
-impl BorderRadiusParam for ui::BorderRadius {
+impl BorderRadiusParam for LengthCorners {
     fn to_border_radius(self) -> ui::BorderRadius {
+        ui::BorderRadius {
+            top_left: resolve_default(self.top_left),
+            top_right: resolve_default(self.top_right),
+            bottom_right: resolve_default(self.bottom_right),
+            bottom_left: resolve_default(self.bottom_left),
+        }
+    }
+
+    fn to_length_corners(self) -> LengthCorners {
         self
     }
 }
 
-impl BorderRadiusParam for ui::Val {
+impl BorderRadiusParam for ui::BorderRadius {
     fn to_border_radius(self) -> ui::BorderRadius {
-        ui::BorderRadius::all(self)
+        self
     }
 }
 
-impl BorderRadiusParam for f32 {
+impl<L: LengthParam> BorderRadiusParam for L {
     fn to_border_radius(self) -> ui::BorderRadius {
-        ui::BorderRadius::all(ui::Val::Px(self))
+        ui::BorderRadius::all(self.to_val())
+    }
+
+    fn to_length_corners(self) -> LengthCorners {
+        LengthCorners::all(self.to_length())
     }
 }
 
-impl BorderRadiusParam for i32 {
-    fn to_border_radius(self) -> ui::BorderRadius {
-        ui::BorderRadius::all(ui::Val::Px(self as f32))
+/// Trait that represents a font size, which may be unset.
+pub trait FontSizeParam {
+    fn to_font_size(self) -> Option<Length>;
+}
+
+impl FontSizeParam for Option<f32> {
+    fn to_font_size(self) -> Option<Length> {
+        self.map(|size| Length::Val(ui::Val::Px(size)))
+    }
+}
+
+impl FontSizeParam for Option<Length> {
+    fn to_font_size(self) -> Option<Length> {
+        self
+    }
+}
+
+impl<L: LengthParam> FontSizeParam for L {
+    fn to_font_size(self) -> Option<Length> {
+        Some(self.to_length())
     }
 }
 
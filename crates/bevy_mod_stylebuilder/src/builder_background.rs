@@ -1,11 +1,20 @@
 use bevy::{
     prelude::*,
     render::texture::Image,
+    sprite::{BorderRect, ImageScaleMode, SliceScaleMode, TextureSlicer},
     ui::{self, UiImage},
 };
 
 use super::builder::{AssetPathParam, ColorParam, StyleBuilder};
 
+/// Component which records the desired on-screen tile size of a tiled background image. The
+/// `ImageScaleMode` stretch value depends on the size of the texture, so it can only be
+/// computed once the image has loaded.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TiledBackground {
+    tile_size: f32,
+}
+
 #[allow(missing_docs)]
 pub trait StyleBuilderBackground {
     /// Set the background image of the target entity.
@@ -20,6 +29,26 @@ pub trait StyleBuilderBackground {
         flip_y: bool,
     ) -> &mut Self;
 
+    /// Set a nine-slice background image. The `border_insets` are measured in texture pixels
+    /// and mark the corners, which are drawn unscaled; the edges are stretched, and the center
+    /// is either stretched or tiled depending on `center_mode`. The slices are recomputed
+    /// whenever the node is resized.
+    fn background_image_sliced<'p>(
+        &mut self,
+        path: impl AssetPathParam<'p>,
+        border_insets: impl Into<BorderRect>,
+        center_mode: SliceScaleMode,
+    ) -> &mut Self;
+
+    /// Set a background image which repeats in both directions to fill the node. `tile_size`
+    /// is the on-screen width of each tile in logical pixels; the height is scaled to
+    /// preserve the aspect ratio of the image.
+    fn background_image_tiled<'p>(
+        &mut self,
+        path: impl AssetPathParam<'p>,
+        tile_size: f32,
+    ) -> &mut Self;
+
     /// Set the background color, or `None` for transparent.
     fn background_color(&mut self, color: impl ColorParam) -> &mut Self;
 
@@ -29,22 +58,8 @@ pub trait StyleBuilderBackground {
 
 impl<'a, 'w> StyleBuilderBackground for StyleBuilder<'a, 'w> {
     fn background_image<'p>(&mut self, path: impl AssetPathParam<'p>) -> &mut Self {
-        let texture = path.to_path().map(|p| self.load_asset::<Image>(p));
-        match (texture, self.target.get_mut::<UiImage>()) {
-            (Some(texture), Some(mut uii)) => {
-                uii.texture = texture;
-            }
-            (Some(texture), None) => {
-                self.target.insert(UiImage {
-                    texture,
-                    ..default()
-                });
-            }
-            (None, Some(_)) => {
-                self.target.remove::<UiImage>();
-            }
-            _ => (),
-        };
+        set_background_texture(self, path);
+        self.target.remove::<(ImageScaleMode, TiledBackground)>();
         self
     }
 
@@ -74,6 +89,55 @@ impl<'a, 'w> StyleBuilderBackground for StyleBuilder<'a, 'w> {
             }
             _ => (),
         };
+        self.target.remove::<(ImageScaleMode, TiledBackground)>();
+        self
+    }
+
+    fn background_image_sliced<'p>(
+        &mut self,
+        path: impl AssetPathParam<'p>,
+        border_insets: impl Into<BorderRect>,
+        center_mode: SliceScaleMode,
+    ) -> &mut Self {
+        self.target.remove::<TiledBackground>();
+        if set_background_texture(self, path) {
+            self.target.insert(ImageScaleMode::Sliced(TextureSlicer {
+                border: border_insets.into(),
+                center_scale_mode: center_mode,
+                sides_scale_mode: SliceScaleMode::Stretch,
+                max_corner_scale: 1.0,
+            }));
+        } else {
+            self.target.remove::<ImageScaleMode>();
+        }
+        self
+    }
+
+    fn background_image_tiled<'p>(
+        &mut self,
+        path: impl AssetPathParam<'p>,
+        tile_size: f32,
+    ) -> &mut Self {
+        if !set_background_texture(self, path) {
+            self.target.remove::<(ImageScaleMode, TiledBackground)>();
+            return self;
+        }
+        // Until the image is loaded, draw it at its natural size.
+        let texture = self.target.get::<UiImage>().unwrap().texture.clone();
+        let stretch_value = self
+            .target
+            .world()
+            .get_resource::<Assets<Image>>()
+            .and_then(|images| images.get(&texture))
+            .map_or(1.0, |image| tile_stretch_value(tile_size, image));
+        self.target.insert((
+            ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value,
+            },
+            TiledBackground { tile_size },
+        ));
         self
     }
 
@@ -102,3 +166,108 @@ impl<'a, 'w> StyleBuilderBackground for StyleBuilder<'a, 'w> {
         self
     }
 }
+
+/// Set the texture of the target's `UiImage`, or remove it if the path is `None`. Returns
+/// whether the target has a background image afterwards.
+fn set_background_texture<'p>(builder: &mut StyleBuilder, path: impl AssetPathParam<'p>) -> bool {
+    let texture = path.to_path().map(|p| builder.load_asset::<Image>(p));
+    match (texture, builder.target.get_mut::<UiImage>()) {
+        (Some(texture), Some(mut uii)) => {
+            uii.texture = texture;
+            true
+        }
+        (Some(texture), None) => {
+            builder.target.insert(UiImage {
+                texture,
+                ..default()
+            });
+            true
+        }
+        (None, Some(_)) => {
+            builder.target.remove::<UiImage>();
+            false
+        }
+        _ => false,
+    }
+}
+
+fn tile_stretch_value(tile_size: f32, image: &Image) -> f32 {
+    let width = image.texture_descriptor.size.width as f32;
+    if width > 0. && tile_size > 0. {
+        tile_size / width
+    } else {
+        1.0
+    }
+}
+
+/// Recomputes the stretch value of tiled backgrounds when the tile size or image changes.
+pub(crate) fn update_tiled_backgrounds(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut query: Query<(Ref<TiledBackground>, Ref<UiImage>, &mut ImageScaleMode)>,
+) {
+    let loaded: Vec<AssetId<Image>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for (tiled, image, mut scale_mode) in query.iter_mut() {
+        if !tiled.is_changed() && !image.is_changed() && !loaded.contains(&image.texture.id()) {
+            continue;
+        }
+        let Some(texture) = images.get(&image.texture) else {
+            continue;
+        };
+        let stretch = tile_stretch_value(tiled.tile_size, texture);
+        if let ImageScaleMode::Tiled { stretch_value, .. } = scale_mode.as_ref() {
+            if *stretch_value == stretch {
+                continue;
+            }
+        }
+        *scale_mode = ImageScaleMode::Tiled {
+            tile_x: true,
+            tile_y: true,
+            stretch_value: stretch,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style_snapshot::StyleSnapshot;
+
+    #[test]
+    fn test_restore_scale_mode() {
+        let mut world = World::new();
+        let mut target = world.spawn_empty();
+        let empty = StyleSnapshot::capture(&target);
+        target.insert((
+            ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 0.5,
+            },
+            TiledBackground { tile_size: 8. },
+        ));
+        let tiled = StyleSnapshot::capture(&target);
+
+        target.remove::<TiledBackground>();
+        target.insert(ImageScaleMode::Sliced(TextureSlicer::default()));
+        tiled.restore(&mut target);
+        assert!(matches!(
+            target.get::<ImageScaleMode>(),
+            Some(ImageScaleMode::Tiled { stretch_value, .. }) if *stretch_value == 0.5
+        ));
+        assert_eq!(
+            target.get::<TiledBackground>(),
+            Some(&TiledBackground { tile_size: 8. })
+        );
+
+        empty.restore(&mut target);
+        assert!(!target.contains::<ImageScaleMode>());
+        assert!(!target.contains::<TiledBackground>());
+    }
+}
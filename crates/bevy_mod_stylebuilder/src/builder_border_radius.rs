@@ -1,4 +1,6 @@
 use super::builder::{BorderRadiusParam, StyleBuilder};
+use crate::units::LengthProperty;
+use bevy::ui;
 
 #[allow(missing_docs)]
 pub trait StyleBuilderBorderRadius {
@@ -7,7 +9,15 @@ pub trait StyleBuilderBorderRadius {
 
 impl<'a, 'w> StyleBuilderBorderRadius for StyleBuilder<'a, 'w> {
     fn border_radius(&mut self, radius: impl BorderRadiusParam) -> &mut Self {
-        self.target.insert(radius.to_border_radius());
+        let radius = radius.to_length_corners();
+        let radius = ui::BorderRadius {
+            top_left: self.resolve_length(LengthProperty::RadiusTopLeft, radius.top_left),
+            top_right: self.resolve_length(LengthProperty::RadiusTopRight, radius.top_right),
+            bottom_right: self
+                .resolve_length(LengthProperty::RadiusBottomRight, radius.bottom_right),
+            bottom_left: self.resolve_length(LengthProperty::RadiusBottomLeft, radius.bottom_left),
+        };
+        self.target.insert(radius);
         self
     }
 }
@@ -0,0 +1,57 @@
+use bevy::log::error;
+
+use crate::{
+    builder::StyleBuilder,
+    css::{parse_declarations, CssError},
+};
+
+/// Methods for applying styles written as CSS declarations, such as
+/// `display: flex; padding: 8px 12px; background-color: #333`.
+pub trait StyleBuilderCss {
+    /// Parse a list of CSS declarations and apply them, in order. If the declarations
+    /// cannot be parsed, an error is logged and none of them are applied.
+    ///
+    /// Use the [`css!`](crate::css) macro to check the declarations at compile time.
+    fn css(&mut self, source: &str) -> &mut Self;
+
+    /// Parse a list of CSS declarations and apply them, in order. If the declarations
+    /// cannot be parsed, the error is returned and none of them are applied.
+    fn try_css(&mut self, source: &str) -> Result<&mut Self, CssError>;
+}
+
+impl<'a, 'w> StyleBuilderCss for StyleBuilder<'a, 'w> {
+    fn css(&mut self, source: &str) -> &mut Self {
+        if let Err(err) = self.try_css(source) {
+            error!("Invalid CSS declarations `{}`: {}", source, err);
+        }
+        self
+    }
+
+    fn try_css(&mut self, source: &str) -> Result<&mut Self, CssError> {
+        for decl in parse_declarations(source)? {
+            decl.apply(self);
+        }
+        Ok(self)
+    }
+}
+
+/// Checks a string of CSS declarations at compile time, returning it unchanged. Intended for
+/// use with [`StyleBuilderCss::css`]:
+///
+/// ```ignore
+/// ss.css(css!("display: flex; gap: 4px; padding: 8px 12px"));
+/// ```
+///
+/// Unknown properties and malformed values produce a compile error.
+#[macro_export]
+macro_rules! css {
+    ($source:literal) => {{
+        const SOURCE: &str = $source;
+        const _: () = {
+            if let ::core::result::Result::Err(err) = $crate::check_declarations(SOURCE) {
+                ::core::panic!("{}", err);
+            }
+        };
+        SOURCE
+    }};
+}
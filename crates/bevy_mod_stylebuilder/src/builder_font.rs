@@ -1,14 +1,40 @@
 #![allow(missing_docs)]
 
-use crate::text_styles::InheritableFontStyles;
+use crate::{font_families::FontWeight, text_styles::InheritableFontStyles};
 
-use super::builder::{AssetPathParam, ColorParam, OptFloatParam, StyleBuilder};
-use bevy::prelude::*;
+use super::builder::{AssetPathParam, ColorParam, FontSizeParam, StyleBuilder};
+use crate::units::{Length, LengthProperty};
+use bevy::{
+    prelude::*,
+    text::{BreakLineOn, JustifyText},
+    ui,
+};
 
 pub trait StyleBuilderFont {
     fn color(&mut self, color: impl ColorParam) -> &mut Self;
     fn font<'p>(&mut self, path: impl AssetPathParam<'p>) -> &mut Self;
-    fn font_size(&mut self, val: impl OptFloatParam) -> &mut Self;
+
+    /// Set the font size. Percentages are relative to the inherited font size, like [`Em`]
+    /// units; `Auto` and viewport units are not supported, and leave the font size unchanged.
+    ///
+    /// [`Em`]: crate::Em
+    fn font_size(&mut self, val: impl FontSizeParam) -> &mut Self;
+
+    /// Set the font family, which must be registered in [`FontFamilies`](crate::FontFamilies).
+    /// This replaces any font set via [`font`](StyleBuilderFont::font).
+    fn font_family<'f>(&mut self, family: impl Into<Option<&'f str>>) -> &mut Self;
+
+    /// Set the font weight, used to select a face from the font family.
+    fn font_weight(&mut self, weight: impl Into<Option<FontWeight>>) -> &mut Self;
+
+    /// Set whether to use the italic face from the font family.
+    fn font_italic(&mut self, italic: impl Into<Option<bool>>) -> &mut Self;
+
+    /// Set the justification of descendant text nodes.
+    fn text_justify(&mut self, justify: impl Into<Option<JustifyText>>) -> &mut Self;
+
+    /// Set how descendant text nodes break lines.
+    fn line_break(&mut self, line_break: impl Into<Option<BreakLineOn>>) -> &mut Self;
 }
 
 impl<'a, 'w> StyleBuilderFont for StyleBuilder<'a, 'w> {
@@ -30,6 +56,7 @@ impl<'a, 'w> StyleBuilderFont for StyleBuilder<'a, 'w> {
         match self.target.get_mut::<InheritableFontStyles>() {
             Some(mut text_style) => {
                 text_style.font = font;
+                text_style.family = None;
             }
             None => {
                 self.target.insert(InheritableFontStyles {
@@ -41,14 +68,112 @@ impl<'a, 'w> StyleBuilderFont for StyleBuilder<'a, 'w> {
         self
     }
 
-    fn font_size(&mut self, val: impl OptFloatParam) -> &mut Self {
+    fn font_size(&mut self, val: impl FontSizeParam) -> &mut Self {
+        let length = match val.to_font_size() {
+            Some(Length::Val(ui::Val::Percent(percent))) => Some(Length::Em(percent / 100.)),
+            Some(Length::Val(
+                val @ (ui::Val::Auto
+                | ui::Val::Vw(_)
+                | ui::Val::Vh(_)
+                | ui::Val::VMin(_)
+                | ui::Val::VMax(_)),
+            )) => {
+                warn!("Unsupported font size `{:?}`", val);
+                return self;
+            }
+            length => length,
+        };
+        let font_size = match length {
+            Some(length) => match self.resolve_length(LengthProperty::FontSize, length) {
+                ui::Val::Px(size) => Some(size),
+                _ => None,
+            },
+            None => {
+                // Unsetting the font size also removes any relative font size.
+                self.resolve_length(LengthProperty::FontSize, Length::Val(ui::Val::Auto));
+                None
+            }
+        };
+        match self.target.get_mut::<InheritableFontStyles>() {
+            Some(mut text_style) => {
+                text_style.font_size = font_size;
+            }
+            None => {
+                self.target.insert(InheritableFontStyles {
+                    font_size,
+                    ..Default::default()
+                });
+            }
+        };
+        self
+    }
+
+    fn font_family<'f>(&mut self, family: impl Into<Option<&'f str>>) -> &mut Self {
+        let family = family.into().map(str::to_string);
         match self.target.get_mut::<InheritableFontStyles>() {
             Some(mut text_style) => {
-                text_style.font_size = val.to_val();
+                text_style.family = family;
+                text_style.font = None;
+            }
+            None => {
+                self.target.insert(InheritableFontStyles {
+                    family,
+                    ..Default::default()
+                });
             }
+        };
+        self
+    }
+
+    fn font_weight(&mut self, weight: impl Into<Option<FontWeight>>) -> &mut Self {
+        let weight = weight.into();
+        match self.target.get_mut::<InheritableFontStyles>() {
+            Some(mut text_style) => text_style.weight = weight,
+            None => {
+                self.target.insert(InheritableFontStyles {
+                    weight,
+                    ..Default::default()
+                });
+            }
+        };
+        self
+    }
+
+    fn font_italic(&mut self, italic: impl Into<Option<bool>>) -> &mut Self {
+        let italic = italic.into();
+        match self.target.get_mut::<InheritableFontStyles>() {
+            Some(mut text_style) => text_style.italic = italic,
+            None => {
+                self.target.insert(InheritableFontStyles {
+                    italic,
+                    ..Default::default()
+                });
+            }
+        };
+        self
+    }
+
+    fn text_justify(&mut self, justify: impl Into<Option<JustifyText>>) -> &mut Self {
+        let justify = justify.into();
+        match self.target.get_mut::<InheritableFontStyles>() {
+            Some(mut text_style) => text_style.justify = justify,
+            None => {
+                self.target.insert(InheritableFontStyles {
+                    justify,
+                    ..Default::default()
+                });
+            }
+        };
+        self
+    }
+
+    fn line_break(&mut self, line_break: impl Into<Option<BreakLineOn>>) -> &mut Self {
+        let line_break = line_break.into();
+        match self.target.get_mut::<InheritableFontStyles>() {
+            Some(mut text_style) => text_style.line_break = line_break,
             None => {
                 self.target.insert(InheritableFontStyles {
-                    font_size: val.to_val(),
+                    line_break,
                     ..Default::default()
                 });
             }
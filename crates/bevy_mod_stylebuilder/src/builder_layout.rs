@@ -1,4 +1,5 @@
 use super::builder::{LengthParam, OptFloatParam, StyleBuilder, UiRectParam};
+use crate::units::LengthProperty;
 use bevy::ui;
 
 #[allow(missing_docs)]
@@ -104,61 +105,61 @@ impl<'a, 'w> StyleBuilderLayout for StyleBuilder<'a, 'w> {
     }
 
     fn left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.left = length.to_val();
+        self.style.left = self.resolve_length(LengthProperty::Left, length);
         self.style_changed = true;
         self
     }
 
     fn right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.right = length.to_val();
+        self.style.right = self.resolve_length(LengthProperty::Right, length);
         self.style_changed = true;
         self
     }
 
     fn top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.top = length.to_val();
+        self.style.top = self.resolve_length(LengthProperty::Top, length);
         self.style_changed = true;
         self
     }
 
     fn bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.bottom = length.to_val();
+        self.style.bottom = self.resolve_length(LengthProperty::Bottom, length);
         self.style_changed = true;
         self
     }
 
     fn width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.width = length.to_val();
+        self.style.width = self.resolve_length(LengthProperty::Width, length);
         self.style_changed = true;
         self
     }
 
     fn height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.height = length.to_val();
+        self.style.height = self.resolve_length(LengthProperty::Height, length);
         self.style_changed = true;
         self
     }
 
     fn min_width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.min_width = length.to_val();
+        self.style.min_width = self.resolve_length(LengthProperty::MinWidth, length);
         self.style_changed = true;
         self
     }
 
     fn min_height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.min_height = length.to_val();
+        self.style.min_height = self.resolve_length(LengthProperty::MinHeight, length);
         self.style_changed = true;
         self
     }
 
     fn max_width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.max_width = length.to_val();
+        self.style.max_width = self.resolve_length(LengthProperty::MaxWidth, length);
         self.style_changed = true;
         self
     }
 
     fn max_height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.max_height = length.to_val();
+        self.style.max_height = self.resolve_length(LengthProperty::MaxHeight, length);
         self.style_changed = true;
         self
     }
@@ -170,91 +171,91 @@ impl<'a, 'w> StyleBuilderLayout for StyleBuilder<'a, 'w> {
     }
 
     fn margin(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.style.margin = rect.to_uirect();
+        self.style.margin = self.resolve_rect(LengthProperty::MARGIN, rect);
         self.style_changed = true;
         self
     }
 
     fn margin_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.margin.left = length.to_val();
+        self.style.margin.left = self.resolve_length(LengthProperty::MarginLeft, length);
         self.style_changed = true;
         self
     }
 
     fn margin_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.margin.right = length.to_val();
+        self.style.margin.right = self.resolve_length(LengthProperty::MarginRight, length);
         self.style_changed = true;
         self
     }
 
     fn margin_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.margin.top = length.to_val();
+        self.style.margin.top = self.resolve_length(LengthProperty::MarginTop, length);
         self.style_changed = true;
         self
     }
 
     fn margin_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.margin.bottom = length.to_val();
+        self.style.margin.bottom = self.resolve_length(LengthProperty::MarginBottom, length);
         self.style_changed = true;
         self
     }
 
     fn padding(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.style.padding = rect.to_uirect();
+        self.style.padding = self.resolve_rect(LengthProperty::PADDING, rect);
         self.style_changed = true;
         self
     }
 
     fn padding_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.padding.left = length.to_val();
+        self.style.padding.left = self.resolve_length(LengthProperty::PaddingLeft, length);
         self.style_changed = true;
         self
     }
 
     fn padding_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.padding.right = length.to_val();
+        self.style.padding.right = self.resolve_length(LengthProperty::PaddingRight, length);
         self.style_changed = true;
         self
     }
 
     fn padding_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.padding.top = length.to_val();
+        self.style.padding.top = self.resolve_length(LengthProperty::PaddingTop, length);
         self.style_changed = true;
         self
     }
 
     fn padding_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.padding.bottom = length.to_val();
+        self.style.padding.bottom = self.resolve_length(LengthProperty::PaddingBottom, length);
         self.style_changed = true;
         self
     }
 
     fn border(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.style.border = rect.to_uirect();
+        self.style.border = self.resolve_rect(LengthProperty::BORDER, rect);
         self.style_changed = true;
         self
     }
 
     fn border_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.border.left = length.to_val();
+        self.style.border.left = self.resolve_length(LengthProperty::BorderLeft, length);
         self.style_changed = true;
         self
     }
 
     fn border_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.border.right = length.to_val();
+        self.style.border.right = self.resolve_length(LengthProperty::BorderRight, length);
         self.style_changed = true;
         self
     }
 
     fn border_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.border.top = length.to_val();
+        self.style.border.top = self.resolve_length(LengthProperty::BorderTop, length);
         self.style_changed = true;
         self
     }
 
     fn border_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.border.bottom = length.to_val();
+        self.style.border.bottom = self.resolve_length(LengthProperty::BorderBottom, length);
         self.style_changed = true;
         self
     }
@@ -274,7 +275,7 @@ impl<'a, 'w> StyleBuilderLayout for StyleBuilder<'a, 'w> {
     fn flex(&mut self, grow: f32, shrink: f32, basis: impl LengthParam) -> &mut Self {
         self.style.flex_grow = grow;
         self.style.flex_shrink = shrink;
-        self.style.flex_basis = basis.to_val();
+        self.style.flex_basis = self.resolve_length(LengthProperty::FlexBasis, basis);
         self.style_changed = true;
         self
     }
@@ -292,26 +293,27 @@ impl<'a, 'w> StyleBuilderLayout for StyleBuilder<'a, 'w> {
     }
 
     fn flex_basis(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.flex_basis = length.to_val();
+        self.style.flex_basis = self.resolve_length(LengthProperty::FlexBasis, length);
         self.style_changed = true;
         self
     }
 
     fn row_gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.row_gap = length.to_val();
+        self.style.row_gap = self.resolve_length(LengthProperty::RowGap, length);
         self.style_changed = true;
         self
     }
 
     fn column_gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.column_gap = length.to_val();
+        self.style.column_gap = self.resolve_length(LengthProperty::ColumnGap, length);
         self.style_changed = true;
         self
     }
 
     fn gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.style.row_gap = length.to_val();
-        self.style.column_gap = self.style.row_gap;
+        let length = length.to_length();
+        self.style.row_gap = self.resolve_length(LengthProperty::RowGap, length);
+        self.style.column_gap = self.resolve_length(LengthProperty::ColumnGap, length);
         self.style_changed = true;
         self
     }
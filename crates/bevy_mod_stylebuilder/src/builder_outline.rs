@@ -1,4 +1,5 @@
 use super::builder::{ColorParam, LengthParam, StyleBuilder};
+use crate::units::LengthProperty;
 use bevy::ui;
 
 #[allow(missing_docs)]
@@ -29,13 +30,14 @@ impl<'a, 'w> StyleBuilderOutline for StyleBuilder<'a, 'w> {
     }
 
     fn outline_width(&mut self, length: impl LengthParam) -> &mut Self {
+        let width = self.resolve_length(LengthProperty::OutlineWidth, length);
         match self.target.get_mut::<ui::Outline>() {
             Some(mut outline) => {
-                outline.width = length.to_val();
+                outline.width = width;
             }
             None => {
                 self.target.insert(ui::Outline {
-                    width: length.to_val(),
+                    width,
                     ..Default::default()
                 });
             }
@@ -44,13 +46,14 @@ impl<'a, 'w> StyleBuilderOutline for StyleBuilder<'a, 'w> {
     }
 
     fn outline_offset(&mut self, length: impl LengthParam) -> &mut Self {
+        let offset = self.resolve_length(LengthProperty::OutlineOffset, length);
         match self.target.get_mut::<ui::Outline>() {
             Some(mut outline) => {
-                outline.offset = length.to_val();
+                outline.offset = offset;
             }
             None => {
                 self.target.insert(ui::Outline {
-                    offset: length.to_val(),
+                    offset,
                     ..Default::default()
                 });
             }
@@ -0,0 +1,375 @@
+use std::{panic::Location, sync::Arc};
+
+use bevy::{prelude::*, ui};
+
+use crate::{
+    builder::StyleBuilder,
+    builder_size_queries::{container_size, ContainerSize, SizeCondition},
+    builder_transition::update_transitions,
+    interaction_state::{InteractionState, PseudoState},
+    style_snapshot::StyleSnapshot,
+};
+
+/// The condition under which a [`ConditionalBlock`] is applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Condition {
+    /// The target is in the given pseudo-state.
+    State(PseudoState),
+    /// The size of the target's container matches.
+    Size(SizeCondition),
+}
+
+/// A style block which is applied only when a condition holds, such as the target being in a
+/// given pseudo-state.
+#[derive(Clone)]
+pub(crate) struct ConditionalBlock {
+    /// The style function which registered the block; see [`StyleBuilder::with_source`].
+    pub(crate) source: &'static Location<'static>,
+    /// Call site within the style function which registered the block.
+    pub(crate) location: &'static Location<'static>,
+    pub(crate) condition: Condition,
+    pub(crate) style: Arc<dyn Fn(&mut StyleBuilder) + Send + Sync>,
+}
+
+/// Methods for defining styles which are applied conditionally, depending on the
+/// [`InteractionState`] of the target entity. When the interaction state changes, the
+/// conditional styles are applied or reverted automatically, without re-running the view.
+///
+/// Conditional blocks are applied in the order they were defined, on top of the
+/// unconditional styles. Conditional blocks cannot be nested.
+pub trait StyleBuilderPseudoStates {
+    /// Apply styles when the target is in the given pseudo-state.
+    fn on_state(
+        &mut self,
+        state: PseudoState,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the pointer is over the target or one of its descendants.
+    fn on_hover(&mut self, style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static) -> &mut Self;
+
+    /// Apply styles when the target is being pressed.
+    fn on_pressed(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the target has keyboard focus and the focus ring is visible.
+    fn on_focus_visible(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the target is disabled.
+    fn on_disabled(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the target is selected.
+    fn on_selected(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderPseudoStates for StyleBuilder<'a, 'w> {
+    #[track_caller]
+    fn on_state(
+        &mut self,
+        state: PseudoState,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.conditions.push(ConditionalBlock {
+            source: self.source,
+            location: Location::caller(),
+            condition: Condition::State(state),
+            style: Arc::new(style),
+        });
+        self
+    }
+
+    #[track_caller]
+    fn on_hover(&mut self, style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static) -> &mut Self {
+        self.on_state(PseudoState::Hover, style)
+    }
+
+    #[track_caller]
+    fn on_pressed(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_state(PseudoState::Pressed, style)
+    }
+
+    #[track_caller]
+    fn on_focus_visible(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_state(PseudoState::FocusVisible, style)
+    }
+
+    #[track_caller]
+    fn on_disabled(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_state(PseudoState::Disabled, style)
+    }
+
+    #[track_caller]
+    fn on_selected(
+        &mut self,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_state(PseudoState::Selected, style)
+    }
+}
+
+/// Component which holds the conditional styles for an entity, along with a snapshot of the
+/// unconditional styles so that the conditional styles can be reverted.
+#[derive(Component)]
+pub(crate) struct ConditionalStyles {
+    blocks: Vec<ConditionalBlock>,
+    base: StyleSnapshot,
+}
+
+impl ConditionalStyles {
+    /// The size conditions of the blocks.
+    pub(crate) fn size_conditions(&self) -> impl Iterator<Item = SizeCondition> + '_ {
+        self.blocks.iter().filter_map(|b| match b.condition {
+            Condition::Size(size) => Some(size),
+            Condition::State(_) => None,
+        })
+    }
+}
+
+/// Called when a [`StyleBuilder`] is created: reverts any conditional styles, so that the new
+/// styles are applied on top of the unconditional styles. Returns true if the target has
+/// conditional styles.
+pub(crate) fn revert_conditional_styles(target: &mut EntityWorldMut) -> bool {
+    match target.get::<ConditionalStyles>() {
+        Some(cond) => {
+            let base = cond.base.clone();
+            base.restore(target);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Called when a [`StyleBuilder`] is finished: replaces the conditional blocks selected by
+/// `replace` with the newly-defined blocks, snapshots the unconditional styles, and then
+/// re-applies the active conditional styles. The new blocks take the place of the first
+/// replaced block, so that blocks from other style functions keep their order.
+pub(crate) fn update_conditional_styles(
+    target: &mut EntityWorldMut,
+    replace: impl Fn(&ConditionalBlock) -> bool,
+    conditions: Vec<ConditionalBlock>,
+) {
+    if conditions.is_empty() && !target.contains::<ConditionalStyles>() {
+        return;
+    }
+    let base = StyleSnapshot::capture(target);
+    match target.get_mut::<ConditionalStyles>() {
+        Some(mut cond) => {
+            let index = cond
+                .blocks
+                .iter()
+                .position(&replace)
+                .unwrap_or(cond.blocks.len());
+            cond.blocks.retain(|b| !replace(b));
+            cond.blocks.splice(index..index, conditions);
+            cond.base = base;
+        }
+        None => {
+            target.insert(ConditionalStyles {
+                blocks: conditions,
+                base,
+            });
+        }
+    }
+    if !target.contains::<InteractionState>() {
+        target.insert(InteractionState::default());
+    }
+    let has_size_conditions = target
+        .get::<ConditionalStyles>()
+        .is_some_and(|cond| cond.size_conditions().next().is_some());
+    if has_size_conditions && !target.contains::<ContainerSize>() {
+        let id = target.id();
+        let size = target.world_scope(|world| container_size(world, id));
+        target.insert(size);
+    }
+    apply_conditional_styles(target);
+}
+
+/// Reverts the conditional styles of the target, and then applies the ones which match the
+/// current interaction state and container size.
+pub(crate) fn apply_conditional_styles(target: &mut EntityWorldMut) {
+    let Some(cond) = target.get::<ConditionalStyles>() else {
+        return;
+    };
+    let state = target
+        .get::<InteractionState>()
+        .copied()
+        .unwrap_or_default();
+    let size = target.get::<ContainerSize>().and_then(|size| size.size);
+    let base = cond.base.clone();
+    let active: Vec<_> = cond
+        .blocks
+        .iter()
+        .filter(|b| match b.condition {
+            Condition::State(s) => state.contains(s),
+            Condition::Size(query) => size.is_some_and(|size| query.matches(size)),
+        })
+        .map(|b| b.style.clone())
+        .collect();
+    base.restore(target);
+    if active.is_empty() {
+        return;
+    }
+
+    let style = target.get::<ui::Style>().cloned().unwrap_or_default();
+    let mut sb = StyleBuilder {
+        target,
+        style,
+        style_changed: false,
+        source: Location::caller(),
+        conditions: Vec::new(),
+        transitions: Vec::new(),
+    };
+    for block in active {
+        (block)(&mut sb);
+    }
+    // The base styles have no transitions from this source, so this adds the transitions
+    // declared by the blocks.
+    let transitions = std::mem::take(&mut sb.transitions);
+    update_transitions(sb.target, sb.source, transitions);
+    if sb.style_changed {
+        let style = sb.style;
+        target.insert(style);
+    }
+}
+
+/// Updates the conditional styles of all entities whose interaction state has changed.
+pub(crate) fn update_pseudo_state_styles(
+    world: &mut World,
+    query: &mut QueryState<Entity, (Changed<InteractionState>, With<ConditionalStyles>)>,
+) {
+    let changed: Vec<Entity> = query.iter(world).collect();
+    for entity in changed {
+        apply_conditional_styles(&mut world.entity_mut(entity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::Location;
+
+    use bevy::color::palettes::css;
+
+    use super::*;
+    use crate::StyleBuilderBackground;
+
+    fn set_hovered(world: &mut World, entity: Entity, hovered: bool) {
+        world.get_mut::<InteractionState>(entity).unwrap().hovered = hovered;
+        let mut query = QueryState::new(world);
+        update_pseudo_state_styles(world, &mut query);
+    }
+
+    fn background(world: &World, entity: Entity) -> Option<Color> {
+        world.get::<BackgroundColor>(entity).map(|bg| bg.0)
+    }
+
+    #[test]
+    fn test_apply_revert() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut target = world.entity_mut(entity);
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        sb.background_color(css::RED).on_hover(|sb| {
+            sb.background_color(css::BLUE);
+        });
+        sb.finish();
+        assert_eq!(background(&world, entity), Some(css::RED.into()));
+
+        set_hovered(&mut world, entity, true);
+        assert_eq!(background(&world, entity), Some(css::BLUE.into()));
+
+        set_hovered(&mut world, entity, false);
+        assert_eq!(background(&world, entity), Some(css::RED.into()));
+    }
+
+    #[test]
+    fn test_remove_block() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let source = Location::caller();
+
+        let build = |world: &mut World, hover: bool| {
+            let mut target = world.entity_mut(entity);
+            let mut sb = StyleBuilder::new(&mut target, ui::Style::default()).with_source(source);
+            sb.background_color(css::RED);
+            if hover {
+                sb.on_hover(|sb| {
+                    sb.background_color(css::BLUE);
+                });
+            }
+            sb.finish();
+        };
+
+        // A second style function, whose blocks are kept.
+        {
+            let mut target = world.entity_mut(entity);
+            let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+            sb.on_pressed(|sb| {
+                sb.background_color(css::GREEN);
+            });
+            sb.finish();
+        }
+
+        build(&mut world, true);
+        set_hovered(&mut world, entity, true);
+        assert_eq!(background(&world, entity), Some(css::BLUE.into()));
+
+        // Re-running the style function without the block removes it.
+        build(&mut world, false);
+        assert_eq!(background(&world, entity), Some(css::RED.into()));
+        assert_eq!(
+            world.get::<ConditionalStyles>(entity).unwrap().blocks.len(),
+            1
+        );
+
+        world.get_mut::<InteractionState>(entity).unwrap().pressed = true;
+        let mut query = QueryState::new(&mut world);
+        update_pseudo_state_styles(&mut world, &mut query);
+        assert_eq!(background(&world, entity), Some(css::GREEN.into()));
+    }
+
+    #[test]
+    fn test_nested_hover() {
+        let mut world = World::new();
+        let outer = world.spawn_empty().id();
+        let inner = world.spawn_empty().set_parent(outer).id();
+        for entity in [outer, inner] {
+            let mut target = world.entity_mut(entity);
+            let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+            sb.background_color(css::RED).on_hover(|sb| {
+                sb.background_color(css::BLUE);
+            });
+            sb.finish();
+        }
+
+        // Hovering the inner element also hovers the outer one.
+        world.get_mut::<InteractionState>(outer).unwrap().hovered = true;
+        set_hovered(&mut world, inner, true);
+        assert_eq!(background(&world, outer), Some(css::BLUE.into()));
+        assert_eq!(background(&world, inner), Some(css::BLUE.into()));
+
+        // Moving the pointer to the outer element un-hovers only the inner one.
+        set_hovered(&mut world, inner, false);
+        assert_eq!(background(&world, outer), Some(css::BLUE.into()));
+        assert_eq!(background(&world, inner), Some(css::RED.into()));
+    }
+}
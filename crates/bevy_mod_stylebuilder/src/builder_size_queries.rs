@@ -0,0 +1,354 @@
+use std::{panic::Location, sync::Arc};
+
+use bevy::{prelude::*, utils::HashSet, window::PrimaryWindow};
+
+use crate::{
+    builder::StyleBuilder,
+    builder_pseudo_states::{
+        apply_conditional_styles, Condition, ConditionalBlock, ConditionalStyles,
+    },
+};
+
+/// A condition on the size of the space available to an entity, used to select responsive
+/// styles. See [`StyleBuilderSizeQueries`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeCondition {
+    /// The available width is less than the given number of logical pixels.
+    WidthBelow(f32),
+    /// The available width is at least the given number of logical pixels.
+    WidthAtLeast(f32),
+    /// The available height is less than the given number of logical pixels.
+    HeightBelow(f32),
+    /// The available height is at least the given number of logical pixels.
+    HeightAtLeast(f32),
+}
+
+impl SizeCondition {
+    /// True if the given available size satisfies the condition.
+    pub fn matches(&self, size: Vec2) -> bool {
+        match *self {
+            SizeCondition::WidthBelow(width) => size.x < width,
+            SizeCondition::WidthAtLeast(width) => size.x >= width,
+            SizeCondition::HeightBelow(height) => size.y < height,
+            SizeCondition::HeightAtLeast(height) => size.y >= height,
+        }
+    }
+}
+
+/// Marker component for a UI node whose measured size is used to evaluate the size conditions
+/// of its descendants, rather than the size of the window.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct SizeContainer;
+
+/// The available size that was used to evaluate the size conditions of an entity, or `None` if
+/// it is not yet known, along with the [`SizeContainer`] it was measured from (`None` for the
+/// window). The container is cached so that it only needs to be looked up again when the
+/// hierarchy above the entity changes.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub(crate) struct ContainerSize {
+    pub(crate) container: Option<Entity>,
+    pub(crate) size: Option<Vec2>,
+}
+
+/// Methods for defining responsive styles, which are applied depending on the space available
+/// to the target: either the size of the nearest ancestor marked as a [`SizeContainer`], or
+/// the size of the primary window if there is none. The styles are re-applied automatically
+/// when the set of matching conditions changes, without re-running the view.
+///
+/// Like pseudo-state styles, the blocks are applied in the order they were defined and cannot
+/// be nested.
+pub trait StyleBuilderSizeQueries {
+    /// Apply styles when the available size matches the given condition.
+    fn when_size(
+        &mut self,
+        condition: SizeCondition,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the available width is less than `width`.
+    fn when_width_below(
+        &mut self,
+        width: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the available width is at least `width`.
+    fn when_width_at_least(
+        &mut self,
+        width: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the available height is less than `height`.
+    fn when_height_below(
+        &mut self,
+        height: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Apply styles when the available height is at least `height`.
+    fn when_height_at_least(
+        &mut self,
+        height: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderSizeQueries for StyleBuilder<'a, 'w> {
+    #[track_caller]
+    fn when_size(
+        &mut self,
+        condition: SizeCondition,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.conditions.push(ConditionalBlock {
+            source: self.source,
+            location: Location::caller(),
+            condition: Condition::Size(condition),
+            style: Arc::new(style),
+        });
+        self
+    }
+
+    #[track_caller]
+    fn when_width_below(
+        &mut self,
+        width: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.when_size(SizeCondition::WidthBelow(width), style)
+    }
+
+    #[track_caller]
+    fn when_width_at_least(
+        &mut self,
+        width: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.when_size(SizeCondition::WidthAtLeast(width), style)
+    }
+
+    #[track_caller]
+    fn when_height_below(
+        &mut self,
+        height: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.when_size(SizeCondition::HeightBelow(height), style)
+    }
+
+    #[track_caller]
+    fn when_height_at_least(
+        &mut self,
+        height: f32,
+        style: impl Fn(&mut StyleBuilder) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.when_size(SizeCondition::HeightAtLeast(height), style)
+    }
+}
+
+fn primary_window_size(world: &mut World) -> Option<Vec2> {
+    world
+        .query_filtered::<&Window, With<PrimaryWindow>>()
+        .get_single(world)
+        .ok()
+        .map(|window| window.size())
+}
+
+/// Find the nearest ancestor of an entity which is a [`SizeContainer`], if any.
+fn find_container(world: &World, entity: Entity) -> Option<Entity> {
+    let mut ancestor = entity;
+    while let Some(parent) = world.get::<Parent>(ancestor) {
+        ancestor = parent.get();
+        if world.get::<SizeContainer>(ancestor).is_some() {
+            return Some(ancestor);
+        }
+    }
+    None
+}
+
+/// The size available within a container: its measured size, or else the size of the window.
+/// Containers which have not been laid out yet have no known size.
+fn measure_container(
+    world: &World,
+    container: Option<Entity>,
+    window: Option<Vec2>,
+) -> Option<Vec2> {
+    match container {
+        Some(container) => world
+            .get::<Node>(container)
+            .map(|node| node.size())
+            .filter(|size| *size != Vec2::ZERO),
+        None => window,
+    }
+}
+
+/// Compute the size available to an entity.
+pub(crate) fn container_size(world: &mut World, entity: Entity) -> ContainerSize {
+    let window = primary_window_size(world);
+    let container = find_container(world, entity);
+    ContainerSize {
+        container,
+        size: measure_container(world, container, window),
+    }
+}
+
+/// Re-applies the conditional styles of entities whose size conditions changed from matching
+/// to not matching, or vice versa. Changes in size which don't cross a breakpoint are recorded,
+/// but don't re-apply the styles.
+///
+/// Only entities whose container was resized, or which were moved within the hierarchy, are
+/// re-evaluated; the container of every other entity is taken from its [`ContainerSize`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_size_query_styles(
+    world: &mut World,
+    query: &mut QueryState<(Entity, &ConditionalStyles, &ContainerSize)>,
+    resized: &mut QueryState<Entity, (With<SizeContainer>, Changed<Node>)>,
+    window_changed: &mut QueryState<(), (With<PrimaryWindow>, Changed<Window>)>,
+    moved: &mut QueryState<Entity, Or<(Changed<Parent>, Added<SizeContainer>)>>,
+) {
+    let window = primary_window_size(world);
+    let window_resized = window_changed.iter(world).next().is_some();
+    let resized: HashSet<Entity> = resized.iter(world).collect();
+
+    // Entities below a hierarchy change may have a different container now, so they have to
+    // look it up again.
+    let mut stack: Vec<Entity> = moved.iter(world).collect();
+    stack.extend(world.removed::<Parent>());
+    stack.extend(world.removed::<SizeContainer>());
+    let mut moved: HashSet<Entity> = HashSet::new();
+    while let Some(entity) = stack.pop() {
+        if moved.insert(entity) {
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter());
+            }
+        }
+    }
+
+    if !window_resized && resized.is_empty() && moved.is_empty() {
+        return;
+    }
+
+    let mut changed: Vec<(Entity, ContainerSize, bool)> = Vec::new();
+    for (entity, cond, prev) in query.iter(world) {
+        let container = if moved.contains(&entity) {
+            find_container(world, entity)
+        } else {
+            prev.container
+        };
+        let dirty = match container {
+            Some(container) => resized.contains(&container),
+            None => window_resized,
+        };
+        if !dirty && container == prev.container {
+            continue;
+        }
+        let size = measure_container(world, container, window);
+        if size == prev.size && container == prev.container {
+            continue;
+        }
+        let matches =
+            |query: SizeCondition, size: Option<Vec2>| size.is_some_and(|size| query.matches(size));
+        let crossed = cond
+            .size_conditions()
+            .any(|query| matches(query, prev.size) != matches(query, size));
+        changed.push((entity, ContainerSize { container, size }, crossed));
+    }
+    for (entity, size, crossed) in changed {
+        let mut target = world.entity_mut(entity);
+        target.insert(size);
+        if crossed {
+            apply_conditional_styles(&mut target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ui, window::WindowResolution};
+
+    use super::*;
+    use crate::StyleBuilderLayout;
+
+    #[test]
+    fn test_size_condition() {
+        let size = Vec2::new(500., 300.);
+        assert!(SizeCondition::WidthBelow(600.).matches(size));
+        assert!(!SizeCondition::WidthBelow(500.).matches(size));
+        assert!(SizeCondition::WidthAtLeast(500.).matches(size));
+        assert!(SizeCondition::HeightBelow(400.).matches(size));
+        assert!(!SizeCondition::HeightAtLeast(400.).matches(size));
+    }
+
+    #[test]
+    fn test_update_size_query_styles() {
+        let mut world = World::new();
+        world.spawn((
+            Window {
+                resolution: WindowResolution::new(800., 600.),
+                ..default()
+            },
+            PrimaryWindow,
+        ));
+        let panel = world.spawn(ui::Style::default()).id();
+        {
+            let mut target = world.entity_mut(panel);
+            let mut ss = StyleBuilder::new(&mut target, ui::Style::default());
+            ss.when_width_below(600., |ss| {
+                ss.display(ui::Display::None);
+            });
+            ss.finish();
+        }
+        assert_eq!(
+            world.get::<ContainerSize>(panel).unwrap().size,
+            Some(Vec2::new(800., 600.))
+        );
+        assert_eq!(
+            world.get::<ui::Style>(panel).unwrap().display,
+            ui::Display::Flex
+        );
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_size_query_styles);
+        let mut window = world.query::<&mut Window>();
+        window.single_mut(&mut world).resolution.set(500., 600.);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<ui::Style>(panel).unwrap().display,
+            ui::Display::None
+        );
+
+        window.single_mut(&mut world).resolution.set(700., 600.);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<ui::Style>(panel).unwrap().display,
+            ui::Display::Flex
+        );
+
+        // Moving the panel into a container which has not been laid out yet leaves its size
+        // unknown, and it no longer follows the window.
+        let container = world.spawn((SizeContainer, Node::default())).id();
+        world.entity_mut(panel).set_parent(container);
+        schedule.run(&mut world);
+        let size = world.get::<ContainerSize>(panel).unwrap();
+        assert_eq!(size.container, Some(container));
+        assert_eq!(size.size, None);
+
+        world.clear_trackers();
+        window.single_mut(&mut world).resolution.set(500., 600.);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<ui::Style>(panel).unwrap().display,
+            ui::Display::Flex
+        );
+
+        // Removing the container marker makes the panel follow the window again.
+        world.entity_mut(container).remove::<SizeContainer>();
+        schedule.run(&mut world);
+        assert_eq!(world.get::<ContainerSize>(panel).unwrap().container, None);
+        assert_eq!(
+            world.get::<ui::Style>(panel).unwrap().display,
+            ui::Display::None
+        );
+    }
+}
@@ -1,10 +1,22 @@
-use bevy::{sprite::TextureAtlas, ui::UiTextureAtlasImage};
+use bevy::{
+    prelude::*,
+    sprite::TextureAtlas,
+    ui::{self, UiImage},
+};
+
+use crate::atlas_loader::UiTextureAtlas;
 
 use super::builder::{AssetPathParam, StyleBuilder};
 
+/// Component which records the texture atlas asset used by a UI node. Once the asset is
+/// loaded, its texture and layout are copied into the node's `UiImage` and `TextureAtlas`.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub(crate) struct UiTextureAtlasHandle(pub(crate) Handle<UiTextureAtlas>);
+
 #[allow(missing_docs)]
 pub trait StyleBuilderTextureAtlas {
-    /// Set the background image of the target entity.
+    /// Set the texture atlas of the target entity, loaded from an `.atlas.ron` or
+    /// `.atlas.grid.ron` file, or `None` to remove it.
     fn texture_atlas<'p>(&mut self, path: impl AssetPathParam<'p>) -> &mut Self;
 
     /// Set the index of which tile is being used in the texture atlas
@@ -22,26 +34,38 @@ pub trait StyleBuilderTextureAtlas {
 
 impl<'a, 'w> StyleBuilderTextureAtlas for StyleBuilder<'a, 'w> {
     fn texture_atlas<'p>(&mut self, path: impl AssetPathParam<'p>) -> &mut Self {
-        todo!("Implement texture atlas loading");
-        // let texture = path
-        //     .to_path()
-        //     .map(|p| self.load_asset::<TextureAtlas>(p))
-        //     .unwrap();
-        // self.target.insert(texture);
-        // self
+        let Some(handle) = path.to_path().map(|p| self.load_asset::<UiTextureAtlas>(p)) else {
+            self.target
+                .remove::<(UiTextureAtlasHandle, TextureAtlas, UiImage)>();
+            return self;
+        };
+        if !self.target.contains::<UiImage>() {
+            self.target.insert(UiImage::default());
+        }
+        if !self.target.contains::<TextureAtlas>() {
+            self.target.insert(TextureAtlas::default());
+        }
+        // If the atlas is already loaded, apply it immediately.
+        let atlas = self
+            .target
+            .world()
+            .get_resource::<Assets<UiTextureAtlas>>()
+            .and_then(|assets| assets.get(&handle))
+            .cloned();
+        if let Some(atlas) = atlas {
+            apply_atlas(self.target, &atlas);
+        }
+        self.target.insert(UiTextureAtlasHandle(handle));
+        self
     }
 
     fn texture_atlas_tile(&mut self, index: usize) -> &mut Self {
-        match self.target.get_mut::<UiTextureAtlasImage>() {
-            Some(mut uii) => {
-                uii.index = index;
+        match self.target.get_mut::<TextureAtlas>() {
+            Some(mut atlas) => {
+                atlas.index = index;
             }
             None => {
-                self.target.insert(UiTextureAtlasImage {
-                    index,
-                    flip_x: false,
-                    flip_y: false,
-                });
+                self.target.insert(TextureAtlas { index, ..default() });
             }
         };
         self
@@ -53,20 +77,60 @@ impl<'a, 'w> StyleBuilderTextureAtlas for StyleBuilder<'a, 'w> {
         flip_x: bool,
         flip_y: bool,
     ) -> &mut Self {
-        match self.target.get_mut::<UiTextureAtlasImage>() {
+        self.texture_atlas_tile(index);
+        match self.target.get_mut::<UiImage>() {
             Some(mut uii) => {
-                uii.index = index;
                 uii.flip_x = flip_x;
                 uii.flip_y = flip_y;
             }
             None => {
-                self.target.insert(UiTextureAtlasImage {
-                    index,
+                self.target.insert(UiImage {
                     flip_x,
                     flip_y,
+                    ..default()
                 });
             }
         };
         self
     }
 }
+
+fn apply_atlas(target: &mut EntityWorldMut, atlas: &UiTextureAtlas) {
+    if let Some(mut image) = target.get_mut::<UiImage>() {
+        if image.texture != atlas.texture {
+            image.texture = atlas.texture.clone();
+        }
+    }
+    if let Some(mut layout) = target.get_mut::<TextureAtlas>() {
+        if layout.layout != atlas.layout {
+            layout.layout = atlas.layout.clone();
+        }
+    }
+}
+
+/// Applies texture atlases to UI nodes once they have finished loading.
+pub(crate) fn update_texture_atlases(
+    mut events: EventReader<AssetEvent<UiTextureAtlas>>,
+    assets: Res<Assets<UiTextureAtlas>>,
+    mut query: Query<(&UiTextureAtlasHandle, &mut ui::UiImage, &mut TextureAtlas)>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(atlas) = assets.get(*id) else {
+            continue;
+        };
+        for (handle, mut image, mut layout) in query.iter_mut() {
+            if handle.0.id() == *id {
+                if image.texture != atlas.texture {
+                    image.texture = atlas.texture.clone();
+                }
+                if layout.layout != atlas.layout {
+                    layout.layout = atlas.layout.clone();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::panic::Location;
+
+use bevy::prelude::*;
+
+use crate::{
+    builder::StyleBuilder,
+    transition::{Easing, StyleTransitions, Transition, TransitionDecl},
+};
+
+/// Methods for declaring animated transitions.
+pub trait StyleBuilderTransition {
+    /// Animate subsequent changes to the given property of the target entity, over `duration`
+    /// seconds. Changes are animated from the current value of the property, so a transition
+    /// which is interrupted by another change will reverse smoothly.
+    ///
+    /// Transitions remain in effect until the style function is run again without declaring
+    /// them; a duration of zero disables the transition.
+    fn transition(&mut self, property: Transition, duration: f32, easing: Easing) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderTransition for StyleBuilder<'a, 'w> {
+    fn transition(&mut self, property: Transition, duration: f32, easing: Easing) -> &mut Self {
+        self.transitions.push(TransitionDecl {
+            property,
+            duration,
+            easing,
+            source: self.source,
+        });
+        self
+    }
+}
+
+/// Called when a [`StyleBuilder`] is finished: replaces the transitions declared by the
+/// previous run of the same style function with the newly-declared transitions.
+pub(crate) fn update_transitions(
+    target: &mut EntityWorldMut,
+    source: &'static Location<'static>,
+    transitions: Vec<TransitionDecl>,
+) {
+    let Some(current) = target.get::<StyleTransitions>() else {
+        if !transitions.is_empty() {
+            target.insert(StyleTransitions::new(transitions));
+        }
+        return;
+    };
+    let decls: Vec<TransitionDecl> = current
+        .decls()
+        .filter(|decl| decl.source != source)
+        .chain(transitions)
+        .collect();
+    if decls.is_empty() {
+        target.remove::<StyleTransitions>();
+    } else if !current.decls().eq(decls.iter().copied()) {
+        target
+            .get_mut::<StyleTransitions>()
+            .unwrap()
+            .set_decls(decls);
+    }
+}
@@ -0,0 +1,823 @@
+//! Parser for CSS-style declarations, such as `padding: 4px 8px; background-color: #333`.
+
+use std::fmt;
+
+use bevy::{asset::AssetPath, color::Srgba, prelude::*, ui};
+
+use crate::{
+    css_check::{check_value, ValueKind},
+    font_families::FontWeight,
+    units::{Length, LengthCorners, LengthRect},
+    StyleBuilder, StyleBuilderBackground, StyleBuilderBorderColor, StyleBuilderBorderRadius,
+    StyleBuilderFont, StyleBuilderLayout, StyleBuilderOutline, StyleBuilderVisibility,
+    StyleBuilderZIndex,
+};
+
+#[cfg(feature = "mod_picking")]
+use crate::StyleBuilderPointerEvents;
+
+/// Error produced when parsing CSS declarations or stylesheets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssError {
+    /// Line number (starting from 1) where the error occurred, if known.
+    pub line: Option<usize>,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl CssError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+}
+
+impl fmt::Display for CssError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for CssError {}
+
+/// A single parsed style declaration, which can be applied to a [`StyleBuilder`].
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum StyleDecl {
+    Display(ui::Display),
+    Position(ui::PositionType),
+    Overflow(ui::OverflowAxis),
+    OverflowX(ui::OverflowAxis),
+    OverflowY(ui::OverflowAxis),
+    Direction(ui::Direction),
+    Left(Length),
+    Right(Length),
+    Top(Length),
+    Bottom(Length),
+    Width(Length),
+    Height(Length),
+    MinWidth(Length),
+    MinHeight(Length),
+    MaxWidth(Length),
+    MaxHeight(Length),
+    AspectRatio(Option<f32>),
+    Margin(LengthRect),
+    MarginLeft(Length),
+    MarginRight(Length),
+    MarginTop(Length),
+    MarginBottom(Length),
+    Padding(LengthRect),
+    PaddingLeft(Length),
+    PaddingRight(Length),
+    PaddingTop(Length),
+    PaddingBottom(Length),
+    Border(LengthRect),
+    BorderLeft(Length),
+    BorderRight(Length),
+    BorderTop(Length),
+    BorderBottom(Length),
+    FlexDirection(ui::FlexDirection),
+    FlexWrap(ui::FlexWrap),
+    Flex(f32, f32, Length),
+    FlexGrow(f32),
+    FlexShrink(f32),
+    FlexBasis(Length),
+    RowGap(Length),
+    ColumnGap(Length),
+    /// Row gap and column gap.
+    Gap(Length, Length),
+    AlignItems(ui::AlignItems),
+    AlignSelf(ui::AlignSelf),
+    AlignContent(ui::AlignContent),
+    JustifyItems(ui::JustifyItems),
+    JustifySelf(ui::JustifySelf),
+    JustifyContent(ui::JustifyContent),
+    BackgroundColor(Option<Color>),
+    BackgroundImage(Option<String>),
+    BorderColor(Option<Color>),
+    BorderRadius(LengthCorners),
+    OutlineColor(Option<Color>),
+    OutlineWidth(Length),
+    OutlineOffset(Length),
+    Color(Option<Color>),
+    Font(Option<String>),
+    FontFamily(Option<String>),
+    FontSize(Option<Length>),
+    FontWeight(FontWeight),
+    FontItalic(bool),
+    ZIndex(i32),
+    Visible(bool),
+    PointerEvents(bool),
+}
+
+impl StyleDecl {
+    /// Apply this declaration to a [`StyleBuilder`].
+    pub fn apply(&self, ss: &mut StyleBuilder) {
+        match self {
+            StyleDecl::Display(v) => ss.display(*v),
+            StyleDecl::Position(v) => ss.position(*v),
+            StyleDecl::Overflow(v) => ss.overflow(*v),
+            StyleDecl::OverflowX(v) => ss.overflow_x(*v),
+            StyleDecl::OverflowY(v) => ss.overflow_y(*v),
+            StyleDecl::Direction(v) => ss.direction(*v),
+            StyleDecl::Left(v) => ss.left(*v),
+            StyleDecl::Right(v) => ss.right(*v),
+            StyleDecl::Top(v) => ss.top(*v),
+            StyleDecl::Bottom(v) => ss.bottom(*v),
+            StyleDecl::Width(v) => ss.width(*v),
+            StyleDecl::Height(v) => ss.height(*v),
+            StyleDecl::MinWidth(v) => ss.min_width(*v),
+            StyleDecl::MinHeight(v) => ss.min_height(*v),
+            StyleDecl::MaxWidth(v) => ss.max_width(*v),
+            StyleDecl::MaxHeight(v) => ss.max_height(*v),
+            StyleDecl::AspectRatio(v) => ss.aspect_ratio(*v),
+            StyleDecl::Margin(v) => ss.margin(*v),
+            StyleDecl::MarginLeft(v) => ss.margin_left(*v),
+            StyleDecl::MarginRight(v) => ss.margin_right(*v),
+            StyleDecl::MarginTop(v) => ss.margin_top(*v),
+            StyleDecl::MarginBottom(v) => ss.margin_bottom(*v),
+            StyleDecl::Padding(v) => ss.padding(*v),
+            StyleDecl::PaddingLeft(v) => ss.padding_left(*v),
+            StyleDecl::PaddingRight(v) => ss.padding_right(*v),
+            StyleDecl::PaddingTop(v) => ss.padding_top(*v),
+            StyleDecl::PaddingBottom(v) => ss.padding_bottom(*v),
+            StyleDecl::Border(v) => ss.border(*v),
+            StyleDecl::BorderLeft(v) => ss.border_left(*v),
+            StyleDecl::BorderRight(v) => ss.border_right(*v),
+            StyleDecl::BorderTop(v) => ss.border_top(*v),
+            StyleDecl::BorderBottom(v) => ss.border_bottom(*v),
+            StyleDecl::FlexDirection(v) => ss.flex_direction(*v),
+            StyleDecl::FlexWrap(v) => ss.flex_wrap(*v),
+            StyleDecl::Flex(grow, shrink, basis) => ss.flex(*grow, *shrink, *basis),
+            StyleDecl::FlexGrow(v) => ss.flex_grow(*v),
+            StyleDecl::FlexShrink(v) => ss.flex_shrink(*v),
+            StyleDecl::FlexBasis(v) => ss.flex_basis(*v),
+            StyleDecl::RowGap(v) => ss.row_gap(*v),
+            StyleDecl::ColumnGap(v) => ss.column_gap(*v),
+            StyleDecl::Gap(row, column) => ss.row_gap(*row).column_gap(*column),
+            StyleDecl::AlignItems(v) => ss.align_items(*v),
+            StyleDecl::AlignSelf(v) => ss.align_self(*v),
+            StyleDecl::AlignContent(v) => ss.align_content(*v),
+            StyleDecl::JustifyItems(v) => ss.justify_items(*v),
+            StyleDecl::JustifySelf(v) => ss.justify_self(*v),
+            StyleDecl::JustifyContent(v) => ss.justify_content(*v),
+            StyleDecl::BackgroundColor(v) => ss.background_color(*v),
+            StyleDecl::BackgroundImage(v) => ss.background_image(v.clone().map(AssetPath::from)),
+            StyleDecl::BorderColor(v) => ss.border_color(*v),
+            StyleDecl::BorderRadius(v) => ss.border_radius(*v),
+            StyleDecl::OutlineColor(v) => ss.outline_color(*v),
+            StyleDecl::OutlineWidth(v) => ss.outline_width(*v),
+            StyleDecl::OutlineOffset(v) => ss.outline_offset(*v),
+            StyleDecl::Color(v) => ss.color(*v),
+            StyleDecl::Font(v) => ss.font(v.clone().map(AssetPath::from)),
+            StyleDecl::FontFamily(v) => ss.font_family(v.as_deref()),
+            StyleDecl::FontSize(v) => ss.font_size(*v),
+            StyleDecl::FontWeight(v) => ss.font_weight(*v),
+            StyleDecl::FontItalic(v) => ss.font_italic(*v),
+            StyleDecl::ZIndex(v) => ss.z_index(*v),
+            StyleDecl::Visible(v) => ss.visible(*v),
+            #[cfg(feature = "mod_picking")]
+            StyleDecl::PointerEvents(v) => ss.pointer_events(*v),
+            #[cfg(not(feature = "mod_picking"))]
+            StyleDecl::PointerEvents(_) => ss,
+        };
+    }
+}
+
+/// Parse a list of declarations separated by semicolons, such as
+/// `display: flex; padding: 4px 8px`.
+pub fn parse_declarations(source: &str) -> Result<Vec<StyleDecl>, CssError> {
+    let mut result = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        for decl in strip_comments(line).split(';') {
+            let decl = decl.trim();
+            if decl.is_empty() {
+                continue;
+            }
+            result.push(parse_declaration(decl).map_err(|e| e.at_line(index + 1))?);
+        }
+    }
+    Ok(result)
+}
+
+/// Remove `/* ... */` comments which begin and end on the same line.
+fn strip_comments(line: &str) -> std::borrow::Cow<'_, str> {
+    if !line.contains("/*") {
+        return line.into();
+    }
+    let mut result = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + end + 4..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result.into()
+}
+
+/// Parse a single `property: value` declaration.
+pub(crate) fn parse_declaration(decl: &str) -> Result<StyleDecl, CssError> {
+    let Some((name, value)) = decl.split_once(':') else {
+        return Err(CssError::new(format!(
+            "expected `property: value`, found `{}`",
+            decl
+        )));
+    };
+    let name = name.trim();
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(CssError::new(format!("missing value for `{}`", name)));
+    }
+    parse_property(name, value).map_err(|e| CssError::new(format!("`{}`: {}", name, e.message)))
+}
+
+/// Parse the value of a property. The property is looked up in [`PROPERTIES`], and the value is
+/// validated with the same grammar as the compile-time checker before it is converted.
+fn parse_property(name: &str, value: &str) -> Result<StyleDecl, CssError> {
+    let Some(property) = PROPERTIES.iter().find(|p| p.name == name) else {
+        return Err(CssError::new("unknown property"));
+    };
+    if let Err(message) = check_value(property.kind, value.as_bytes()) {
+        return Err(CssError::new(match property.kind {
+            ValueKind::Keyword(names) => format!(
+                "invalid value `{}`, expected one of: {}",
+                value,
+                names.join(", ")
+            ),
+            _ => format!("{}, found `{}`", message, value),
+        }));
+    }
+    (property.parse)(value)
+}
+
+/// A property which can appear in a declaration.
+pub(crate) struct Property {
+    /// The name of the property.
+    pub(crate) name: &'static str,
+    /// The values which the property accepts, checked at compile time by the [`css!`] macro
+    /// and at run time before `parse` is called.
+    ///
+    /// [`css!`]: crate::css
+    pub(crate) kind: ValueKind,
+    /// Convert a value which has already been checked.
+    parse: fn(&str) -> Result<StyleDecl, CssError>,
+}
+
+/// A set of keywords, and the values which they stand for.
+struct Keywords<T: 'static> {
+    names: &'static [&'static str],
+    values: &'static [T],
+}
+
+impl<T: Copy> Keywords<T> {
+    fn parse(&self, value: &str) -> Result<T, CssError> {
+        self.names
+            .iter()
+            .position(|name| *name == value)
+            .map(|index| self.values[index])
+            .ok_or_else(|| CssError::new(format!("invalid value `{}`", value)))
+    }
+}
+
+/// Define a [`Keywords`] table from a list of `"keyword" => value` pairs.
+macro_rules! keywords {
+    ($($name:literal => $value:expr),* $(,)?) => {
+        Keywords {
+            names: &[$($name),*],
+            values: &[$($value),*],
+        }
+    };
+}
+
+const DISPLAY: Keywords<ui::Display> = keywords! {
+    "flex" => ui::Display::Flex,
+    "grid" => ui::Display::Grid,
+    "block" => ui::Display::Block,
+    "none" => ui::Display::None,
+};
+
+const POSITION: Keywords<ui::PositionType> = keywords! {
+    "relative" => ui::PositionType::Relative,
+    "absolute" => ui::PositionType::Absolute,
+};
+
+const OVERFLOW: Keywords<ui::OverflowAxis> = keywords! {
+    "visible" => ui::OverflowAxis::Visible,
+    "hidden" => ui::OverflowAxis::Clip,
+    "clip" => ui::OverflowAxis::Clip,
+};
+
+const DIRECTION: Keywords<ui::Direction> = keywords! {
+    "inherit" => ui::Direction::Inherit,
+    "ltr" => ui::Direction::LeftToRight,
+    "rtl" => ui::Direction::RightToLeft,
+};
+
+const FLEX_DIRECTION: Keywords<ui::FlexDirection> = keywords! {
+    "row" => ui::FlexDirection::Row,
+    "column" => ui::FlexDirection::Column,
+    "row-reverse" => ui::FlexDirection::RowReverse,
+    "column-reverse" => ui::FlexDirection::ColumnReverse,
+};
+
+const FLEX_WRAP: Keywords<ui::FlexWrap> = keywords! {
+    "nowrap" => ui::FlexWrap::NoWrap,
+    "wrap" => ui::FlexWrap::Wrap,
+    "wrap-reverse" => ui::FlexWrap::WrapReverse,
+};
+
+const ALIGN_ITEMS: Keywords<ui::AlignItems> = keywords! {
+    "default" => ui::AlignItems::Default,
+    "start" => ui::AlignItems::Start,
+    "end" => ui::AlignItems::End,
+    "flex-start" => ui::AlignItems::FlexStart,
+    "flex-end" => ui::AlignItems::FlexEnd,
+    "center" => ui::AlignItems::Center,
+    "baseline" => ui::AlignItems::Baseline,
+    "stretch" => ui::AlignItems::Stretch,
+};
+
+const ALIGN_SELF: Keywords<ui::AlignSelf> = keywords! {
+    "auto" => ui::AlignSelf::Auto,
+    "start" => ui::AlignSelf::Start,
+    "end" => ui::AlignSelf::End,
+    "flex-start" => ui::AlignSelf::FlexStart,
+    "flex-end" => ui::AlignSelf::FlexEnd,
+    "center" => ui::AlignSelf::Center,
+    "baseline" => ui::AlignSelf::Baseline,
+    "stretch" => ui::AlignSelf::Stretch,
+};
+
+const ALIGN_CONTENT: Keywords<ui::AlignContent> = keywords! {
+    "default" => ui::AlignContent::Default,
+    "start" => ui::AlignContent::Start,
+    "end" => ui::AlignContent::End,
+    "flex-start" => ui::AlignContent::FlexStart,
+    "flex-end" => ui::AlignContent::FlexEnd,
+    "center" => ui::AlignContent::Center,
+    "stretch" => ui::AlignContent::Stretch,
+    "space-between" => ui::AlignContent::SpaceBetween,
+    "space-evenly" => ui::AlignContent::SpaceEvenly,
+    "space-around" => ui::AlignContent::SpaceAround,
+};
+
+const JUSTIFY_ITEMS: Keywords<ui::JustifyItems> = keywords! {
+    "default" => ui::JustifyItems::Default,
+    "start" => ui::JustifyItems::Start,
+    "end" => ui::JustifyItems::End,
+    "center" => ui::JustifyItems::Center,
+    "baseline" => ui::JustifyItems::Baseline,
+    "stretch" => ui::JustifyItems::Stretch,
+};
+
+const JUSTIFY_SELF: Keywords<ui::JustifySelf> = keywords! {
+    "auto" => ui::JustifySelf::Auto,
+    "start" => ui::JustifySelf::Start,
+    "end" => ui::JustifySelf::End,
+    "center" => ui::JustifySelf::Center,
+    "baseline" => ui::JustifySelf::Baseline,
+    "stretch" => ui::JustifySelf::Stretch,
+};
+
+const JUSTIFY_CONTENT: Keywords<ui::JustifyContent> = keywords! {
+    "default" => ui::JustifyContent::Default,
+    "start" => ui::JustifyContent::Start,
+    "end" => ui::JustifyContent::End,
+    "flex-start" => ui::JustifyContent::FlexStart,
+    "flex-end" => ui::JustifyContent::FlexEnd,
+    "center" => ui::JustifyContent::Center,
+    "stretch" => ui::JustifyContent::Stretch,
+    "space-between" => ui::JustifyContent::SpaceBetween,
+    "space-evenly" => ui::JustifyContent::SpaceEvenly,
+    "space-around" => ui::JustifyContent::SpaceAround,
+};
+
+const FONT_WEIGHT: Keywords<FontWeight> = keywords! {
+    "normal" => FontWeight::Normal,
+    "bold" => FontWeight::Bold,
+    "100" => FontWeight::Thin,
+    "200" => FontWeight::ExtraLight,
+    "300" => FontWeight::Light,
+    "400" => FontWeight::Normal,
+    "500" => FontWeight::Medium,
+    "600" => FontWeight::SemiBold,
+    "700" => FontWeight::Bold,
+    "800" => FontWeight::ExtraBold,
+    "900" => FontWeight::Black,
+};
+
+const FONT_STYLE: Keywords<bool> = keywords! {
+    "normal" => false,
+    "italic" => true,
+    "oblique" => true,
+};
+
+const VISIBILITY: Keywords<bool> = keywords! {
+    "visible" => true,
+    "inherit" => true,
+    "hidden" => false,
+};
+
+const POINTER_EVENTS: Keywords<bool> = keywords! {
+    "auto" => true,
+    "none" => false,
+};
+
+/// Define a [`Property`].
+macro_rules! property {
+    ($name:literal, $kind:expr, $parse:expr) => {
+        Property {
+            name: $name,
+            kind: $kind,
+            parse: $parse,
+        }
+    };
+}
+
+/// Table of known properties, shared by [`parse_declarations`] and the compile-time checker
+/// used by the [`css!`](crate::css) macro.
+#[rustfmt::skip]
+pub(crate) const PROPERTIES: &[Property] = &[
+    property!("display", ValueKind::Keyword(DISPLAY.names), |v| Ok(StyleDecl::Display(DISPLAY.parse(v)?))),
+    property!("position", ValueKind::Keyword(POSITION.names), |v| Ok(StyleDecl::Position(POSITION.parse(v)?))),
+    property!("overflow", ValueKind::Keyword(OVERFLOW.names), |v| Ok(StyleDecl::Overflow(OVERFLOW.parse(v)?))),
+    property!("overflow-x", ValueKind::Keyword(OVERFLOW.names), |v| Ok(StyleDecl::OverflowX(OVERFLOW.parse(v)?))),
+    property!("overflow-y", ValueKind::Keyword(OVERFLOW.names), |v| Ok(StyleDecl::OverflowY(OVERFLOW.parse(v)?))),
+    property!("direction", ValueKind::Keyword(DIRECTION.names), |v| Ok(StyleDecl::Direction(DIRECTION.parse(v)?))),
+    property!("left", ValueKind::Length, |v| Ok(StyleDecl::Left(length(v)?))),
+    property!("right", ValueKind::Length, |v| Ok(StyleDecl::Right(length(v)?))),
+    property!("top", ValueKind::Length, |v| Ok(StyleDecl::Top(length(v)?))),
+    property!("bottom", ValueKind::Length, |v| Ok(StyleDecl::Bottom(length(v)?))),
+    property!("width", ValueKind::Length, |v| Ok(StyleDecl::Width(length(v)?))),
+    property!("height", ValueKind::Length, |v| Ok(StyleDecl::Height(length(v)?))),
+    property!("min-width", ValueKind::Length, |v| Ok(StyleDecl::MinWidth(length(v)?))),
+    property!("min-height", ValueKind::Length, |v| Ok(StyleDecl::MinHeight(length(v)?))),
+    property!("max-width", ValueKind::Length, |v| Ok(StyleDecl::MaxWidth(length(v)?))),
+    property!("max-height", ValueKind::Length, |v| Ok(StyleDecl::MaxHeight(length(v)?))),
+    property!("aspect-ratio", ValueKind::AspectRatio, |v| Ok(StyleDecl::AspectRatio(match v {
+        "auto" => None,
+        _ => Some(aspect_ratio(v)?),
+    }))),
+    property!("margin", ValueKind::Rect, |v| Ok(StyleDecl::Margin(rect(v)?))),
+    property!("margin-left", ValueKind::Length, |v| Ok(StyleDecl::MarginLeft(length(v)?))),
+    property!("margin-right", ValueKind::Length, |v| Ok(StyleDecl::MarginRight(length(v)?))),
+    property!("margin-top", ValueKind::Length, |v| Ok(StyleDecl::MarginTop(length(v)?))),
+    property!("margin-bottom", ValueKind::Length, |v| Ok(StyleDecl::MarginBottom(length(v)?))),
+    property!("padding", ValueKind::Rect, |v| Ok(StyleDecl::Padding(rect(v)?))),
+    property!("padding-left", ValueKind::Length, |v| Ok(StyleDecl::PaddingLeft(length(v)?))),
+    property!("padding-right", ValueKind::Length, |v| Ok(StyleDecl::PaddingRight(length(v)?))),
+    property!("padding-top", ValueKind::Length, |v| Ok(StyleDecl::PaddingTop(length(v)?))),
+    property!("padding-bottom", ValueKind::Length, |v| Ok(StyleDecl::PaddingBottom(length(v)?))),
+    property!("border", ValueKind::Rect, |v| Ok(StyleDecl::Border(rect(v)?))),
+    property!("border-width", ValueKind::Rect, |v| Ok(StyleDecl::Border(rect(v)?))),
+    property!("border-left", ValueKind::Length, |v| Ok(StyleDecl::BorderLeft(length(v)?))),
+    property!("border-left-width", ValueKind::Length, |v| Ok(StyleDecl::BorderLeft(length(v)?))),
+    property!("border-right", ValueKind::Length, |v| Ok(StyleDecl::BorderRight(length(v)?))),
+    property!("border-right-width", ValueKind::Length, |v| Ok(StyleDecl::BorderRight(length(v)?))),
+    property!("border-top", ValueKind::Length, |v| Ok(StyleDecl::BorderTop(length(v)?))),
+    property!("border-top-width", ValueKind::Length, |v| Ok(StyleDecl::BorderTop(length(v)?))),
+    property!("border-bottom", ValueKind::Length, |v| Ok(StyleDecl::BorderBottom(length(v)?))),
+    property!("border-bottom-width", ValueKind::Length, |v| Ok(StyleDecl::BorderBottom(length(v)?))),
+    property!("flex-direction", ValueKind::Keyword(FLEX_DIRECTION.names), |v| Ok(StyleDecl::FlexDirection(FLEX_DIRECTION.parse(v)?))),
+    property!("flex-wrap", ValueKind::Keyword(FLEX_WRAP.names), |v| Ok(StyleDecl::FlexWrap(FLEX_WRAP.parse(v)?))),
+    property!("flex", ValueKind::Flex, flex),
+    property!("flex-grow", ValueKind::Number, |v| Ok(StyleDecl::FlexGrow(number(v)?))),
+    property!("flex-shrink", ValueKind::Number, |v| Ok(StyleDecl::FlexShrink(number(v)?))),
+    property!("flex-basis", ValueKind::Length, |v| Ok(StyleDecl::FlexBasis(length(v)?))),
+    property!("row-gap", ValueKind::Length, |v| Ok(StyleDecl::RowGap(length(v)?))),
+    property!("column-gap", ValueKind::Length, |v| Ok(StyleDecl::ColumnGap(length(v)?))),
+    property!("gap", ValueKind::Gap, gap),
+    property!("align-items", ValueKind::Keyword(ALIGN_ITEMS.names), |v| Ok(StyleDecl::AlignItems(ALIGN_ITEMS.parse(v)?))),
+    property!("align-self", ValueKind::Keyword(ALIGN_SELF.names), |v| Ok(StyleDecl::AlignSelf(ALIGN_SELF.parse(v)?))),
+    property!("align-content", ValueKind::Keyword(ALIGN_CONTENT.names), |v| Ok(StyleDecl::AlignContent(ALIGN_CONTENT.parse(v)?))),
+    property!("justify-items", ValueKind::Keyword(JUSTIFY_ITEMS.names), |v| Ok(StyleDecl::JustifyItems(JUSTIFY_ITEMS.parse(v)?))),
+    property!("justify-self", ValueKind::Keyword(JUSTIFY_SELF.names), |v| Ok(StyleDecl::JustifySelf(JUSTIFY_SELF.parse(v)?))),
+    property!("justify-content", ValueKind::Keyword(JUSTIFY_CONTENT.names), |v| Ok(StyleDecl::JustifyContent(JUSTIFY_CONTENT.parse(v)?))),
+    property!("background", ValueKind::Color, |v| Ok(StyleDecl::BackgroundColor(color(v)?))),
+    property!("background-color", ValueKind::Color, |v| Ok(StyleDecl::BackgroundColor(color(v)?))),
+    property!("background-image", ValueKind::Url, |v| Ok(StyleDecl::BackgroundImage(url(v)?))),
+    property!("border-color", ValueKind::Color, |v| Ok(StyleDecl::BorderColor(color(v)?))),
+    property!("border-radius", ValueKind::Rect, |v| Ok(StyleDecl::BorderRadius(border_radius(v)?))),
+    property!("outline-color", ValueKind::Color, |v| Ok(StyleDecl::OutlineColor(color(v)?))),
+    property!("outline-width", ValueKind::Length, |v| Ok(StyleDecl::OutlineWidth(length(v)?))),
+    property!("outline-offset", ValueKind::Length, |v| Ok(StyleDecl::OutlineOffset(length(v)?))),
+    property!("color", ValueKind::Color, |v| Ok(StyleDecl::Color(color(v)?))),
+    property!("font", ValueKind::Url, |v| Ok(StyleDecl::Font(url(v)?))),
+    property!("font-family", ValueKind::FontFamily, |v| Ok(StyleDecl::FontFamily(font_family(v)?))),
+    property!("font-size", ValueKind::FontSize, |v| Ok(StyleDecl::FontSize(match v {
+        "inherit" => None,
+        _ => Some(font_size(v)?),
+    }))),
+    property!("font-weight", ValueKind::Keyword(FONT_WEIGHT.names), |v| Ok(StyleDecl::FontWeight(FONT_WEIGHT.parse(v)?))),
+    property!("font-style", ValueKind::Keyword(FONT_STYLE.names), |v| Ok(StyleDecl::FontItalic(FONT_STYLE.parse(v)?))),
+    property!("z-index", ValueKind::ZIndex, |v| Ok(StyleDecl::ZIndex(match v {
+        "auto" => 0,
+        _ => v
+            .parse::<i32>()
+            .map_err(|_| CssError::new(format!("invalid integer `{}`", v)))?,
+    }))),
+    property!("visibility", ValueKind::Keyword(VISIBILITY.names), |v| Ok(StyleDecl::Visible(VISIBILITY.parse(v)?))),
+    property!("pointer-events", ValueKind::Keyword(POINTER_EVENTS.names), |v| Ok(StyleDecl::PointerEvents(POINTER_EVENTS.parse(v)?))),
+];
+
+/// Parse `grow [shrink [basis]]`.
+fn flex(value: &str) -> Result<StyleDecl, CssError> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    match parts.as_slice() {
+        [grow] => Ok(StyleDecl::Flex(
+            number(grow)?,
+            1.,
+            Length::Val(ui::Val::Px(0.)),
+        )),
+        [grow, shrink] => Ok(StyleDecl::Flex(
+            number(grow)?,
+            number(shrink)?,
+            Length::Val(ui::Val::Px(0.)),
+        )),
+        [grow, shrink, basis] => Ok(StyleDecl::Flex(
+            number(grow)?,
+            number(shrink)?,
+            length(basis)?,
+        )),
+        _ => Err(CssError::new("expected 1 to 3 values")),
+    }
+}
+
+/// Parse a row gap and an optional column gap.
+fn gap(value: &str) -> Result<StyleDecl, CssError> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    match parts.as_slice() {
+        [gap] => Ok(StyleDecl::Gap(length(gap)?, length(gap)?)),
+        [row, column] => Ok(StyleDecl::Gap(length(row)?, length(column)?)),
+        _ => Err(CssError::new("expected 1 or 2 lengths")),
+    }
+}
+
+fn number(value: &str) -> Result<f32, CssError> {
+    value
+        .parse::<f32>()
+        .map_err(|_| CssError::new(format!("invalid number `{}`", value)))
+}
+
+fn aspect_ratio(value: &str) -> Result<f32, CssError> {
+    match value.split_once('/') {
+        Some((w, h)) => Ok(number(w.trim())? / number(h.trim())?),
+        None => number(value),
+    }
+}
+
+/// Parse a font size, which must be a length in pixels, `rem` or `em`.
+fn font_size(value: &str) -> Result<Length, CssError> {
+    match length(value)? {
+        Length::Val(ui::Val::Px(_)) | Length::Rem(_) | Length::Em(_) => length(value),
+        _ => Err(CssError::new(format!("invalid font size `{}`", value))),
+    }
+}
+
+/// Parse a CSS length, such as `auto`, `10px`, `50%`, `100vw` or `1.5rem`. Unitless numbers are
+/// treated as pixels.
+pub(crate) fn length(value: &str) -> Result<Length, CssError> {
+    if value == "auto" {
+        return Ok(Length::Val(ui::Val::Auto));
+    }
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(value.len());
+    let (num, unit) = value.split_at(split);
+    let n = num
+        .parse::<f32>()
+        .map_err(|_| CssError::new(format!("invalid length `{}`", value)))?;
+    match unit {
+        "" | "px" => Ok(Length::Val(ui::Val::Px(n))),
+        "%" => Ok(Length::Val(ui::Val::Percent(n))),
+        "vw" => Ok(Length::Val(ui::Val::Vw(n))),
+        "vh" => Ok(Length::Val(ui::Val::Vh(n))),
+        "vmin" => Ok(Length::Val(ui::Val::VMin(n))),
+        "vmax" => Ok(Length::Val(ui::Val::VMax(n))),
+        "rem" => Ok(Length::Rem(n)),
+        "em" => Ok(Length::Em(n)),
+        _ => Err(CssError::new(format!("unknown unit `{}`", unit))),
+    }
+}
+
+/// Parse 1 to 4 lengths, using the CSS ordering of top, right, bottom, left.
+fn rect(value: &str) -> Result<LengthRect, CssError> {
+    let parts = value
+        .split_whitespace()
+        .map(length)
+        .collect::<Result<Vec<_>, _>>()?;
+    match parts.as_slice() {
+        [all] => Ok(LengthRect::all(*all)),
+        [v, h] => Ok(LengthRect::axes(*h, *v)),
+        [t, h, b] => Ok(LengthRect {
+            left: *h,
+            right: *h,
+            top: *t,
+            bottom: *b,
+        }),
+        [t, r, b, l] => Ok(LengthRect {
+            left: *l,
+            right: *r,
+            top: *t,
+            bottom: *b,
+        }),
+        _ => Err(CssError::new("expected 1 to 4 lengths")),
+    }
+}
+
+/// Parse 1 to 4 lengths, using the CSS ordering of top-left, top-right, bottom-right,
+/// bottom-left.
+fn border_radius(value: &str) -> Result<LengthCorners, CssError> {
+    let parts = value
+        .split_whitespace()
+        .map(length)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (top_left, top_right, bottom_right, bottom_left) = match parts.as_slice() {
+        [all] => (*all, *all, *all, *all),
+        [a, b] => (*a, *b, *a, *b),
+        [a, b, c] => (*a, *b, *c, *b),
+        [a, b, c, d] => (*a, *b, *c, *d),
+        _ => return Err(CssError::new("expected 1 to 4 lengths")),
+    };
+    Ok(LengthCorners {
+        top_left,
+        top_right,
+        bottom_right,
+        bottom_left,
+    })
+}
+
+/// Parse a color, such as `#fff`, `#102030`, `rgb(10, 20, 30)`, `rgba(10, 20, 30, 0.5)`,
+/// or `transparent`.
+pub(crate) fn color(value: &str) -> Result<Option<Color>, CssError> {
+    if let Some(hex) = value.strip_prefix('#') {
+        return Srgba::hex(hex)
+            .map(|c| Some(Color::Srgba(c)))
+            .map_err(|_| CssError::new(format!("invalid color `{}`", value)));
+    }
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        let channels = args
+            .split(',')
+            .map(|c| number(c.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        return match channels.as_slice() {
+            [r, g, b] => Ok(Some(Color::srgb_u8(*r as u8, *g as u8, *b as u8))),
+            [r, g, b, a] => Ok(Some(Color::srgba(r / 255., g / 255., b / 255., *a))),
+            _ => Err(CssError::new(format!("invalid color `{}`", value))),
+        };
+    }
+    match value {
+        "transparent" | "none" => Ok(None),
+        "black" => Ok(Some(Color::BLACK)),
+        "white" => Ok(Some(Color::WHITE)),
+        _ => Err(CssError::new(format!("invalid color `{}`", value))),
+    }
+}
+
+/// Parse an asset path, either quoted or in the form `url(...)`, or `none`.
+/// Parse a font family name, which may be quoted or a sequence of bare words.
+fn font_family(value: &str) -> Result<Option<String>, CssError> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let unquoted = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')));
+    match unquoted {
+        Some(name) => Ok(Some(name.to_string())),
+        None if value
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_') =>
+        {
+            Ok(Some(value.to_string()))
+        }
+        None => Err(CssError::new(format!("invalid font family `{}`", value))),
+    }
+}
+
+fn url(value: &str) -> Result<Option<String>, CssError> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let inner = value
+        .strip_prefix("url(")
+        .and_then(|v| v.strip_suffix(')'))
+        .map(str::trim)
+        .unwrap_or(value);
+    let unquoted = inner
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| inner.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')));
+    match unquoted {
+        Some(path) => Ok(Some(path.to_string())),
+        None => Err(CssError::new(format!(
+            "expected a quoted path, found `{}`",
+            value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lengths() {
+        assert_eq!(length("auto"), Ok(Length::Val(ui::Val::Auto)));
+        assert_eq!(length("10"), Ok(Length::Val(ui::Val::Px(10.))));
+        assert_eq!(length("10.5px"), Ok(Length::Val(ui::Val::Px(10.5))));
+        assert_eq!(length("-4px"), Ok(Length::Val(ui::Val::Px(-4.))));
+        assert_eq!(length("50%"), Ok(Length::Val(ui::Val::Percent(50.))));
+        assert_eq!(length("100vw"), Ok(Length::Val(ui::Val::Vw(100.))));
+        assert_eq!(length("1.5rem"), Ok(Length::Rem(1.5)));
+        assert_eq!(length("2em"), Ok(Length::Em(2.)));
+        assert!(length("10pt").is_err());
+        assert!(length("px").is_err());
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        assert_eq!(color("#fff"), Ok(Some(Color::Srgba(Srgba::WHITE))));
+        assert_eq!(
+            color("#102030"),
+            Ok(Some(Color::Srgba(Srgba::rgb_u8(16, 32, 48))))
+        );
+        assert_eq!(
+            color("rgb(16, 32, 48)"),
+            Ok(Some(Color::srgb_u8(16, 32, 48)))
+        );
+        assert_eq!(color("transparent"), Ok(None));
+        assert!(color("#12").is_err());
+        assert!(color("blurple").is_err());
+    }
+
+    #[test]
+    fn test_parse_declarations() {
+        let decls = parse_declarations(
+            "display: flex; flex-direction: column;\npadding: 8px 12px; /* comment */ gap: 4px",
+        )
+        .unwrap();
+        assert_eq!(
+            decls,
+            vec![
+                StyleDecl::Display(ui::Display::Flex),
+                StyleDecl::FlexDirection(ui::FlexDirection::Column),
+                StyleDecl::Padding(LengthRect::axes(
+                    Length::Val(ui::Val::Px(12.)),
+                    Length::Val(ui::Val::Px(8.))
+                )),
+                StyleDecl::Gap(Length::Val(ui::Val::Px(4.)), Length::Val(ui::Val::Px(4.))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_font_declarations() {
+        let decls =
+            parse_declarations("font-family: \"Open Sans\"; font-weight: 600; font-style: italic")
+                .unwrap();
+        assert_eq!(
+            decls,
+            vec![
+                StyleDecl::FontFamily(Some("Open Sans".to_string())),
+                StyleDecl::FontWeight(FontWeight::SemiBold),
+                StyleDecl::FontItalic(true),
+            ]
+        );
+        assert_eq!(font_family("Fira Code"), Ok(Some("Fira Code".to_string())));
+        assert!(font_family("a;b").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_declarations("display: flex;\ncolour: red").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.message, "`colour`: unknown property");
+
+        let err = parse_declarations("display: flexbox").unwrap_err();
+        assert!(err
+            .message
+            .starts_with("`display`: invalid value `flexbox`"));
+
+        let err = parse_declarations("width 10px").unwrap_err();
+        assert!(err.message.starts_with("expected `property: value`"));
+    }
+}
@@ -0,0 +1,576 @@
+//! Compile-time validation of CSS-style declarations, used by the [`css!`](crate::css) macro.
+//!
+//! The checker is written as a `const fn` so that errors can be reported when the crate is
+//! compiled. It shares the property table with [`parse_declarations`](crate::parse_declarations),
+//! and the runtime parser validates values with [`check_value`] before converting them, so the
+//! two accept the same properties and values. The `test_parity_with_parser` test checks that
+//! they also split declarations and strip comments in the same way.
+
+use crate::css::PROPERTIES;
+
+/// The kind of value accepted by a property.
+#[derive(Clone, Copy)]
+pub(crate) enum ValueKind {
+    /// One of the given keywords.
+    Keyword(&'static [&'static str]),
+    Length,
+    /// 1 to 4 lengths.
+    Rect,
+    /// 1 or 2 lengths.
+    Gap,
+    Flex,
+    Number,
+    AspectRatio,
+    Color,
+    Url,
+    FontSize,
+    FontFamily,
+    ZIndex,
+}
+
+/// Longest declaration which can be checked, not counting comments.
+const MAX_DECLARATION_LEN: usize = 1024;
+
+/// Check that a list of declarations is valid, returning a description of the first error.
+/// Used by the [`css!`](crate::css) macro.
+pub const fn check_declarations(source: &str) -> Result<(), &'static str> {
+    // As in `parse_declarations`, declarations end at a semicolon or line break, and comments
+    // end at the end of the line. A `const fn` can't allocate, so each declaration is copied,
+    // without its comments, to a fixed-size buffer.
+    let source = source.as_bytes();
+    let mut buffer = [0u8; MAX_DECLARATION_LEN];
+    let mut len = 0;
+    let mut i = 0;
+    while i <= source.len() {
+        if i == source.len() || source[i] == b';' || source[i] == b'\n' {
+            let decl = trim(buffer.split_at(len).0);
+            if !decl.is_empty() {
+                if let Err(e) = check_declaration(decl) {
+                    return Err(e);
+                }
+            }
+            len = 0;
+            i += 1;
+        } else if source[i] == b'/' && i + 1 < source.len() && source[i + 1] == b'*' {
+            i += 2;
+            while i < source.len() && source[i] != b'\n' {
+                if source[i] == b'*' && i + 1 < source.len() && source[i + 1] == b'/' {
+                    i += 2;
+                    break;
+                }
+                i += 1;
+            }
+        } else if len == MAX_DECLARATION_LEN {
+            return Err("declaration is too long");
+        } else {
+            buffer[len] = source[i];
+            len += 1;
+            i += 1;
+        }
+    }
+    Ok(())
+}
+
+const fn check_declaration(decl: &[u8]) -> Result<(), &'static str> {
+    let Some(colon) = find(decl, b':') else {
+        return Err("expected `property: value`");
+    };
+    let (name, value) = decl.split_at(colon);
+    let name = trim(name);
+    let value = trim(value.split_at(1).1);
+    if value.is_empty() {
+        return Err("missing value");
+    }
+    let mut i = 0;
+    while i < PROPERTIES.len() {
+        if eq(name, PROPERTIES[i].name.as_bytes()) {
+            return check_value(PROPERTIES[i].kind, value);
+        }
+        i += 1;
+    }
+    Err("unknown property")
+}
+
+/// Check that a value is valid for a property, returning a description of the error if not.
+pub(crate) const fn check_value(kind: ValueKind, value: &[u8]) -> Result<(), &'static str> {
+    let ok = match kind {
+        ValueKind::Keyword(options) => {
+            let mut i = 0;
+            loop {
+                if i >= options.len() {
+                    break false;
+                }
+                if eq(value, options[i].as_bytes()) {
+                    break true;
+                }
+                i += 1;
+            }
+        }
+        ValueKind::Length => is_length(value),
+        ValueKind::Rect => is_length_list(value, 1, 4),
+        ValueKind::Gap => is_length_list(value, 1, 2),
+        ValueKind::Flex => is_flex(value),
+        ValueKind::Number => is_number(value),
+        ValueKind::AspectRatio => {
+            eq(value, b"auto")
+                || match find(value, b'/') {
+                    Some(index) => {
+                        let (w, h) = value.split_at(index);
+                        is_number(trim(w)) && is_number(trim(h.split_at(1).1))
+                    }
+                    None => is_number(value),
+                }
+        }
+        ValueKind::Color => is_color(value),
+        ValueKind::Url => is_url(value),
+        ValueKind::FontSize => {
+            eq(value, b"inherit")
+                || is_number(strip_suffix(value, b"px"))
+                || is_number(strip_suffix(value, b"rem"))
+                || is_number(strip_suffix(value, b"em"))
+        }
+        ValueKind::FontFamily => {
+            eq(value, b"none")
+                || matches!(value, [b'"', .., b'"'] | [b'\'', .., b'\''] if value.len() >= 2)
+                || is_family_name(value)
+        }
+        ValueKind::ZIndex => eq(value, b"auto") || is_integer(value),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(match kind {
+            ValueKind::Keyword(_) => "invalid keyword",
+            ValueKind::Length | ValueKind::Rect | ValueKind::Gap => "invalid length",
+            ValueKind::Flex => "invalid flex value",
+            ValueKind::Number | ValueKind::AspectRatio | ValueKind::FontSize => "invalid number",
+            ValueKind::Color => "invalid color",
+            ValueKind::Url => "expected a quoted path",
+            ValueKind::FontFamily => "invalid font family",
+            ValueKind::ZIndex => "invalid integer",
+        })
+    }
+}
+
+/// A number, with optional sign and fraction, such as `-1.5`.
+const fn is_number(value: &[u8]) -> bool {
+    let mut i = 0;
+    if i < value.len() && (value[i] == b'-' || value[i] == b'+') {
+        i += 1;
+    }
+    let mut digits = 0;
+    let mut dot = false;
+    while i < value.len() {
+        match value[i] {
+            b'0'..=b'9' => digits += 1,
+            b'.' if !dot => dot = true,
+            _ => return false,
+        }
+        i += 1;
+    }
+    digits > 0
+}
+
+const fn is_integer(value: &[u8]) -> bool {
+    is_number(value) && find(value, b'.').is_none()
+}
+
+const fn is_length(value: &[u8]) -> bool {
+    if eq(value, b"auto") {
+        return true;
+    }
+    let mut split = 0;
+    while split < value.len() {
+        match value[split] {
+            b'0'..=b'9' | b'.' | b'-' | b'+' => split += 1,
+            _ => break,
+        }
+    }
+    let (num, unit) = value.split_at(split);
+    is_number(num)
+        && (unit.is_empty()
+            || eq(unit, b"px")
+            || eq(unit, b"%")
+            || eq(unit, b"vw")
+            || eq(unit, b"vh")
+            || eq(unit, b"vmin")
+            || eq(unit, b"vmax")
+            || eq(unit, b"rem")
+            || eq(unit, b"em"))
+}
+
+/// A whitespace-separated list of between `min` and `max` lengths.
+const fn is_length_list(value: &[u8], min: usize, max: usize) -> bool {
+    let mut rest = value;
+    let mut count = 0;
+    loop {
+        rest = trim(rest);
+        if rest.is_empty() {
+            break;
+        }
+        let (item, next) = split_word(rest);
+        if !is_length(item) {
+            return false;
+        }
+        count += 1;
+        rest = next;
+    }
+    count >= min && count <= max
+}
+
+/// `grow [shrink [basis]]`.
+const fn is_flex(value: &[u8]) -> bool {
+    let (grow, rest) = split_word(value);
+    let (shrink, rest) = split_word(trim(rest));
+    let (basis, rest) = split_word(trim(rest));
+    is_number(grow)
+        && (shrink.is_empty() || is_number(shrink))
+        && (basis.is_empty() || is_length(basis))
+        && trim(rest).is_empty()
+}
+
+const fn is_color(value: &[u8]) -> bool {
+    if let [b'#', hex @ ..] = value {
+        let mut i = 0;
+        while i < hex.len() {
+            if !hex[i].is_ascii_hexdigit() {
+                return false;
+            }
+            i += 1;
+        }
+        return matches!(hex.len(), 3 | 4 | 6 | 8);
+    }
+    let args = if starts_with(value, b"rgba(") {
+        value.split_at(5).1
+    } else if starts_with(value, b"rgb(") {
+        value.split_at(4).1
+    } else {
+        return eq(value, b"transparent")
+            || eq(value, b"none")
+            || eq(value, b"black")
+            || eq(value, b"white");
+    };
+    let [args @ .., b')'] = args else {
+        return false;
+    };
+    let mut rest = args;
+    let mut count = 0;
+    loop {
+        let (channel, next) = match find(rest, b',') {
+            Some(index) => {
+                let (channel, next) = rest.split_at(index);
+                (channel, Some(next.split_at(1).1))
+            }
+            None => (rest, None),
+        };
+        if !is_number(trim(channel)) {
+            return false;
+        }
+        count += 1;
+        match next {
+            Some(next) => rest = next,
+            None => break,
+        }
+    }
+    count == 3 || count == 4
+}
+
+const fn is_url(value: &[u8]) -> bool {
+    if eq(value, b"none") {
+        return true;
+    }
+    let inner = match value {
+        [b'u', b'r', b'l', b'(', inner @ .., b')'] => trim(inner),
+        _ => value,
+    };
+    matches!(inner, [b'"', .., b'"'] | [b'\'', .., b'\''] if inner.len() >= 2)
+}
+
+/// An unquoted font family name, such as `Open Sans`.
+const fn is_family_name(value: &[u8]) -> bool {
+    let mut i = 0;
+    while i < value.len() {
+        match value[i] {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b' ' | b'-' | b'_' => (),
+            _ => return false,
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn starts_with(value: &[u8], prefix: &[u8]) -> bool {
+    value.len() >= prefix.len() && eq(value.split_at(prefix.len()).0, prefix)
+}
+
+const fn strip_suffix<'a>(value: &'a [u8], suffix: &[u8]) -> &'a [u8] {
+    if value.len() < suffix.len() {
+        return value;
+    }
+    let (head, tail) = value.split_at(value.len() - suffix.len());
+    if eq(tail, suffix) {
+        head
+    } else {
+        value
+    }
+}
+
+const fn find(value: &[u8], ch: u8) -> Option<usize> {
+    let mut i = 0;
+    while i < value.len() {
+        if value[i] == ch {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split off the first whitespace-delimited word.
+const fn split_word(value: &[u8]) -> (&[u8], &[u8]) {
+    let mut i = 0;
+    while i < value.len() && !value[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    value.split_at(i)
+}
+
+/// Skip leading whitespace and comments.
+const fn trim(mut value: &[u8]) -> &[u8] {
+    while let [ch, rest @ ..] = value {
+        if !ch.is_ascii_whitespace() {
+            break;
+        }
+        value = rest;
+    }
+    while let [rest @ .., ch] = value {
+        if !ch.is_ascii_whitespace() {
+            break;
+        }
+        value = rest;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::parse_declarations;
+
+    #[test]
+    fn test_check_declarations() {
+        assert_eq!(
+            check_declarations(
+                "display: flex; flex-direction: column; gap: 4px; padding: 8px 12px; \
+                background-color: #333"
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_declarations("/* note */ color: rgba(0, 0, 0, 0.5);\nflex: 1 1 auto;"),
+            Ok(())
+        );
+        assert_eq!(check_declarations("colour: red"), Err("unknown property"));
+        assert_eq!(
+            check_declarations("display: flexbox"),
+            Err("invalid keyword")
+        );
+        assert_eq!(check_declarations("width: 10pt"), Err("invalid length"));
+        assert_eq!(check_declarations("color: #12"), Err("invalid color"));
+        assert_eq!(
+            check_declarations("font: fonts/a.ttf"),
+            Err("expected a quoted path")
+        );
+        assert_eq!(
+            check_declarations("width 10px"),
+            Err("expected `property: value`")
+        );
+    }
+
+    #[test]
+    fn test_css_macro() {
+        assert_eq!(
+            crate::css!("display: flex; gap: 4px"),
+            "display: flex; gap: 4px"
+        );
+    }
+
+    #[test]
+    fn test_properties_match_parser() {
+        // Every property and keyword accepted at compile time must also be accepted by the
+        // runtime parser.
+        for property in PROPERTIES {
+            let values: &[&str] = match property.kind {
+                ValueKind::Keyword(options) => options,
+                ValueKind::Length | ValueKind::Rect | ValueKind::Gap => &["4px"],
+                ValueKind::Flex | ValueKind::Number | ValueKind::AspectRatio => &["1"],
+                ValueKind::Color => &["#333"],
+                ValueKind::Url => &["\"a.png\""],
+                ValueKind::FontSize => &["12px"],
+                ValueKind::FontFamily => &["\"Open Sans\"", "Open Sans"],
+                ValueKind::ZIndex => &["auto"],
+            };
+            for value in values {
+                let decl = format!("{}: {}", property.name, value);
+                assert!(parse_declarations(&decl).is_ok(), "{}", decl);
+            }
+        }
+    }
+
+    /// Values to try for each kind of property, both valid and invalid.
+    fn fixtures(kind: ValueKind) -> &'static [&'static str] {
+        const LENGTHS: &[&str] = &[
+            "0",
+            "4",
+            "4px",
+            "-4px",
+            "1.5px",
+            "50%",
+            "2em",
+            "1.5rem",
+            "10vw",
+            "10vh",
+            "10vmin",
+            "10vmax",
+            "auto",
+            "4pt",
+            "px",
+            "4 px",
+            "calc(4px)",
+            "",
+        ];
+        const RECTS: &[&str] = &[
+            "4px",
+            "4px 8px",
+            "4px 8px 2px",
+            "4px 8px 2px 1px",
+            "4px 8px 2px 1px 0",
+            "auto 4px",
+            "4px,8px",
+            "4pt 8px",
+        ];
+        const NUMBERS: &[&str] = &["0", "1", "1.5", "-1", ".5", "1.", "1px", "a", "1 2"];
+        const FLEX: &[&str] = &[
+            "1", "1 1", "1 1 auto", "1 0 10px", "auto", "none", "1 1 1 1", "a", "1px",
+        ];
+        const ASPECT_RATIOS: &[&str] = &["1", "1.5", "16 / 9", "16/9", "auto", "a", "1 /"];
+        const COLORS: &[&str] = &[
+            "#fff",
+            "#ffff",
+            "#ffffff",
+            "#ffffff80",
+            "#12",
+            "#fffff",
+            "#ggg",
+            "red",
+            "transparent",
+            "blurple",
+            "rgb(1, 2, 3)",
+            "rgba(0, 0, 0, 0.5)",
+            "rgb(1, 2)",
+            "rgba(0, 0, 0, 0.5",
+            "hsl(0, 50%, 50%)",
+            "hsla(0, 50%, 50%, 1)",
+            "none",
+        ];
+        const URLS: &[&str] = &[
+            "\"a.png\"",
+            "'a.png'",
+            "url(a.png)",
+            "url(\"a.png\")",
+            "a.png",
+            "\"a.png",
+            "none",
+        ];
+        const FONT_SIZES: &[&str] = &["12", "12px", "1.5em", "1rem", "50%", "auto", "a"];
+        const FONT_FAMILIES: &[&str] = &[
+            "\"Open Sans\"",
+            "Open Sans",
+            "'Open Sans'",
+            "\"Open",
+            "a;b",
+            "inherit",
+        ];
+        const Z_INDICES: &[&str] = &["auto", "0", "3", "-3", "global(3)", "1.5", "a"];
+        const KEYWORDS: &[&str] = &["inherit", "initial", "Flex", "none", "auto", "a b"];
+        match kind {
+            ValueKind::Keyword(_) => KEYWORDS,
+            ValueKind::Length => LENGTHS,
+            ValueKind::Rect | ValueKind::Gap => RECTS,
+            ValueKind::Flex => FLEX,
+            ValueKind::Number => NUMBERS,
+            ValueKind::AspectRatio => ASPECT_RATIOS,
+            ValueKind::Color => COLORS,
+            ValueKind::Url => URLS,
+            ValueKind::FontSize => FONT_SIZES,
+            ValueKind::FontFamily => FONT_FAMILIES,
+            ValueKind::ZIndex => Z_INDICES,
+        }
+    }
+
+    #[test]
+    fn test_parity_with_parser() {
+        // The compile-time checker must accept exactly the declarations which the runtime
+        // parser accepts.
+        let mut mismatches = Vec::new();
+        let mut check = |decl: &str| {
+            let checked = check_declarations(decl).is_ok();
+            let parsed = parse_declarations(decl).is_ok();
+            if checked != parsed {
+                mismatches.push(format!("{:?}: check {}, parse {}", decl, checked, parsed));
+            }
+        };
+        for property in PROPERTIES {
+            let keywords: &[&str] = match property.kind {
+                ValueKind::Keyword(options) => options,
+                _ => &[],
+            };
+            for value in keywords.iter().chain(fixtures(property.kind)) {
+                check(&format!("{}: {}", property.name, value));
+            }
+        }
+        for decl in [
+            "",
+            ";",
+            "display: flex;",
+            "display: flex;;",
+            "  display : flex ; gap: 4px ",
+            "/* note */ display: flex",
+            "display: flex /* note */",
+            "display: /* note */ flex",
+            "padding: 4px /* note */ 8px",
+            "/* a; b */ display: flex",
+            "/* a\n b */ display: flex",
+            "/* unterminated display: flex",
+            "/* unterminated\ndisplay: flex",
+            "/*/ display: flex */",
+            "display:\nflex",
+            "display: flex\ngap: 4px",
+            "display: flex;\r\ngap: 4px",
+            "display flex",
+            "display:",
+            "display: flex: grid",
+            ": flex",
+            "colour: red",
+            "Display: flex",
+            "display: flex; width 10px",
+        ] {
+            check(decl);
+        }
+        assert!(mismatches.is_empty(), "{:#?}", mismatches);
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// The weight (boldness) of a font face.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FontWeight {
+    Thin,
+    ExtraLight,
+    Light,
+    #[default]
+    Normal,
+    Medium,
+    SemiBold,
+    Bold,
+    ExtraBold,
+    Black,
+}
+
+impl FontWeight {
+    /// The numeric weight, as used in CSS, from 100 (thin) to 900 (black).
+    pub fn value(self) -> u16 {
+        match self {
+            FontWeight::Thin => 100,
+            FontWeight::ExtraLight => 200,
+            FontWeight::Light => 300,
+            FontWeight::Normal => 400,
+            FontWeight::Medium => 500,
+            FontWeight::SemiBold => 600,
+            FontWeight::Bold => 700,
+            FontWeight::ExtraBold => 800,
+            FontWeight::Black => 900,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FontFace {
+    weight: FontWeight,
+    italic: bool,
+    font: Handle<Font>,
+}
+
+/// Registry of font families. Each family is a set of font faces, one per combination of
+/// weight and style, so that text can select a family by name and have the weight and italic
+/// style resolved independently.
+///
+/// ```ignore
+/// app.world_mut().resource_mut::<FontFamilies>().add(
+///     "Open Sans",
+///     FontWeight::Bold,
+///     false,
+///     asset_server.load("fonts/OpenSans-Bold.ttf"),
+/// );
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct FontFamilies {
+    families: HashMap<String, Vec<FontFace>>,
+}
+
+impl FontFamilies {
+    /// Add a font face to a family, replacing any existing face with the same weight and style.
+    pub fn add(
+        &mut self,
+        family: &str,
+        weight: FontWeight,
+        italic: bool,
+        font: Handle<Font>,
+    ) -> &mut Self {
+        let faces = self.families.entry(family.to_string()).or_default();
+        faces.retain(|face| face.weight != weight || face.italic != italic);
+        faces.push(FontFace {
+            weight,
+            italic,
+            font,
+        });
+        self
+    }
+
+    /// True if any faces have been registered for the given family.
+    pub fn contains(&self, family: &str) -> bool {
+        self.families.contains_key(family)
+    }
+
+    /// Find the face in a family which best matches the requested weight and style. A face with
+    /// the requested style is always preferred; among those, the one with the closest weight is
+    /// chosen. For ties, bolder faces win when asking for a weight above normal, and lighter
+    /// faces otherwise.
+    pub fn get(&self, family: &str, weight: FontWeight, italic: bool) -> Option<&Handle<Font>> {
+        let target = weight.value();
+        let bold = weight > FontWeight::Normal;
+        self.families
+            .get(family)?
+            .iter()
+            .min_by_key(|face| {
+                let value = face.weight.value();
+                (
+                    face.italic != italic,
+                    value.abs_diff(target),
+                    if bold { value < target } else { value > target },
+                )
+            })
+            .map(|face| &face.font)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(index: u128) -> Handle<Font> {
+        Handle::weak_from_u128(index)
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let mut families = FontFamilies::default();
+        families
+            .add("Sans", FontWeight::Normal, false, handle(1))
+            .add("Sans", FontWeight::Bold, false, handle(2))
+            .add("Sans", FontWeight::Bold, true, handle(3));
+        assert_eq!(
+            families.get("Sans", FontWeight::Bold, false),
+            Some(&handle(2))
+        );
+        assert_eq!(
+            families.get("Sans", FontWeight::Bold, true),
+            Some(&handle(3))
+        );
+        assert_eq!(families.get("Serif", FontWeight::Bold, true), None);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let mut families = FontFamilies::default();
+        families
+            .add("Sans", FontWeight::Light, false, handle(1))
+            .add("Sans", FontWeight::Medium, false, handle(2))
+            .add("Sans", FontWeight::Black, false, handle(3));
+        // No italic faces, so fall back to upright.
+        assert_eq!(
+            families.get("Sans", FontWeight::Medium, true),
+            Some(&handle(2))
+        );
+        assert_eq!(
+            families.get("Sans", FontWeight::SemiBold, false),
+            Some(&handle(2))
+        );
+        // Equidistant between Medium and Black: prefer bolder.
+        assert_eq!(
+            families.get("Sans", FontWeight::Bold, false),
+            Some(&handle(3))
+        );
+        // Equidistant between Light and Medium: prefer lighter.
+        assert_eq!(
+            families.get("Sans", FontWeight::Normal, false),
+            Some(&handle(1))
+        );
+    }
+
+    #[test]
+    fn test_replace_face() {
+        let mut families = FontFamilies::default();
+        families
+            .add("Sans", FontWeight::Normal, false, handle(1))
+            .add("Sans", FontWeight::Normal, false, handle(2));
+        assert_eq!(
+            families.get("Sans", FontWeight::Normal, false),
+            Some(&handle(2))
+        );
+    }
+}
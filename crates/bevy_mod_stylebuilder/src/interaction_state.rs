@@ -0,0 +1,202 @@
+use bevy::{a11y::Focus, prelude::*};
+
+/// An interaction pseudo-state, used to select conditional styles. See
+/// [`StyleBuilderPseudoStates`](crate::StyleBuilderPseudoStates).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PseudoState {
+    /// The pointer is over the entity or one of its descendants.
+    Hover,
+    /// The primary pointer button was pressed while the pointer was over the entity or one of
+    /// its descendants, and is still down with the pointer over the entity.
+    Pressed,
+    /// The entity has keyboard focus.
+    Focus,
+    /// The entity has keyboard focus, and the focus ring should be visible.
+    FocusVisible,
+    /// The entity is disabled.
+    Disabled,
+    /// The entity is selected.
+    Selected,
+}
+
+/// Component which records the interaction state of an entity, used to select which
+/// conditional styles are applied. This is added automatically to any entity which has
+/// conditional styles.
+///
+/// The `hovered`, `pressed`, `focused` and `selected` fields are maintained by the
+/// [`InteractionStatePlugin`]. The `focus_visible` and `disabled` fields depend on
+/// application-specific state, and are expected to be maintained by the widget library.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InteractionState {
+    pub hovered: bool,
+    pub pressed: bool,
+    pub focused: bool,
+    pub focus_visible: bool,
+    pub disabled: bool,
+    pub selected: bool,
+}
+
+impl InteractionState {
+    /// True if the entity is in the given pseudo-state.
+    pub fn contains(&self, state: PseudoState) -> bool {
+        match state {
+            PseudoState::Hover => self.hovered,
+            PseudoState::Pressed => self.pressed,
+            PseudoState::Focus => self.focused,
+            PseudoState::FocusVisible => self.focus_visible,
+            PseudoState::Disabled => self.disabled,
+            PseudoState::Selected => self.selected,
+        }
+    }
+}
+
+/// Marker component which indicates that an entity is selected, for use with
+/// [`PseudoState::Selected`].
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Selected;
+
+/// Plugin which maintains the [`InteractionState`] of entities from the picking and
+/// focus state.
+pub struct InteractionStatePlugin;
+
+impl Plugin for InteractionStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_interaction_states.before(crate::StyleBuilderSystemSet),
+        );
+    }
+}
+
+/// Records the entities which were pressed: those under the pointer, and their ancestors,
+/// at the time the primary button went down.
+#[cfg(feature = "mod_picking")]
+#[derive(Default)]
+struct PressTracker(Option<bevy::utils::HashSet<Entity>>);
+
+#[cfg(feature = "mod_picking")]
+impl PressTracker {
+    fn update(&mut self, down: bool, hovered: &bevy::utils::HashSet<Entity>) {
+        if !down {
+            self.0 = None;
+        } else if self.0.is_none() {
+            self.0 = Some(hovered.clone());
+        }
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.0.as_ref().is_some_and(|set| set.contains(&entity))
+    }
+}
+
+#[cfg(feature = "mod_picking")]
+fn update_interaction_states(
+    hover_map: Option<Res<bevy_mod_picking::focus::HoverMap>>,
+    pointers: Query<(
+        &bevy_mod_picking::pointer::PointerId,
+        &bevy_mod_picking::pointer::PointerPress,
+    )>,
+    focus: Option<Res<Focus>>,
+    mut query: Query<(Entity, &mut InteractionState, Has<Selected>)>,
+    parent_query: Query<&Parent>,
+    mut press: Local<PressTracker>,
+) {
+    use bevy_mod_picking::pointer::PointerId;
+
+    // The hovered entities, and their ancestors.
+    let mut hovered = bevy::utils::HashSet::new();
+    if let Some(map) = hover_map
+        .as_ref()
+        .and_then(|hover_map| hover_map.get(&PointerId::Mouse))
+    {
+        for hit in map.keys() {
+            hovered.insert(*hit);
+            hovered.extend(parent_query.iter_ancestors(*hit));
+        }
+    }
+    let down = pointers
+        .iter()
+        .any(|(id, press)| *id == PointerId::Mouse && press.is_primary_pressed());
+    press.update(down, &hovered);
+    let focus = focus.and_then(|f| f.0);
+    for (entity, mut state, selected) in query.iter_mut() {
+        let hovered = hovered.contains(&entity);
+        let next = InteractionState {
+            hovered,
+            pressed: hovered && press.contains(entity),
+            focused: focus == Some(entity),
+            selected,
+            ..*state
+        };
+        state.set_if_neq(next);
+    }
+}
+
+#[cfg(not(feature = "mod_picking"))]
+fn update_interaction_states(
+    focus: Option<Res<Focus>>,
+    mut query: Query<(Entity, &mut InteractionState, Has<Selected>)>,
+) {
+    let focus = focus.and_then(|f| f.0);
+    for (entity, mut state, selected) in query.iter_mut() {
+        let next = InteractionState {
+            focused: focus == Some(entity),
+            selected,
+            ..*state
+        };
+        state.set_if_neq(next);
+    }
+}
+
+#[cfg(all(test, feature = "mod_picking"))]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, utils::HashMap};
+    use bevy_mod_picking::{backend::HitData, focus::HoverMap, pointer::PointerId};
+
+    use super::*;
+
+    #[test]
+    fn test_press_tracker() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let mut press = PressTracker::default();
+        press.update(false, &[a].into_iter().collect());
+        assert!(!press.contains(a));
+
+        // Pressing records the entities under the pointer.
+        press.update(true, &[a].into_iter().collect());
+        assert!(press.contains(a));
+
+        // Dragging onto another entity doesn't press it.
+        press.update(true, &[b].into_iter().collect());
+        assert!(press.contains(a));
+        assert!(!press.contains(b));
+
+        press.update(false, &[b].into_iter().collect());
+        assert!(!press.contains(a));
+    }
+
+    #[test]
+    fn test_nested_hover() {
+        let mut world = World::new();
+        let outer = world.spawn(InteractionState::default()).id();
+        let inner = world
+            .spawn(InteractionState::default())
+            .set_parent(outer)
+            .id();
+        let other = world.spawn(InteractionState::default()).id();
+
+        let camera = world.spawn_empty().id();
+        let mut hits = HashMap::new();
+        hits.insert(inner, HitData::new(camera, 0., None, None));
+        let mut hover_map = HoverMap::default();
+        hover_map.insert(PointerId::Mouse, hits);
+        world.insert_resource(hover_map);
+
+        world.run_system_once(update_interaction_states);
+        assert!(world.get::<InteractionState>(inner).unwrap().hovered);
+        assert!(world.get::<InteractionState>(outer).unwrap().hovered);
+        assert!(!world.get::<InteractionState>(other).unwrap().hovered);
+        assert!(!world.get::<InteractionState>(inner).unwrap().pressed);
+    }
+}
@@ -1,39 +1,73 @@
-// mod atlas_loader;
+mod atlas_loader;
 mod builder;
 mod builder_background;
 mod builder_border_color;
 mod builder_border_radius;
+mod builder_css;
 mod builder_font;
 mod builder_layout;
 mod builder_outline;
+mod builder_pseudo_states;
+mod builder_size_queries;
+mod builder_texture_atlas;
+mod builder_transition;
 mod builder_visibility;
 mod builder_z_index;
+mod css;
+mod css_check;
+mod font_families;
+mod interaction_state;
+mod style_snapshot;
+mod stylesheet;
 mod text_styles;
-// mod builder_texture_atlas;
+mod transition;
+mod units;
 
 #[cfg(feature = "mod_picking")]
 mod builder_pointer_events;
 
 use std::sync::Arc;
 
+pub use atlas_loader::{TextureAtlasLoader, TextureAtlasLoaderError, UiTextureAtlas};
 use bevy::{
     app::{Plugin, Update},
+    asset::AssetApp,
     prelude::{IntoSystemConfigs, SystemSet},
 };
-// pub use atlas_loader::TextureAtlasLoader;
 pub use builder::*;
+use builder_background::update_tiled_backgrounds;
 pub use builder_background::StyleBuilderBackground;
 pub use builder_border_color::StyleBuilderBorderColor;
 pub use builder_border_radius::StyleBuilderBorderRadius;
+pub use builder_css::StyleBuilderCss;
 pub use builder_font::StyleBuilderFont;
 pub use builder_layout::StyleBuilderLayout;
 pub use builder_outline::StyleBuilderOutline;
+use builder_pseudo_states::update_pseudo_state_styles;
+pub use builder_pseudo_states::StyleBuilderPseudoStates;
+use builder_size_queries::update_size_query_styles;
+pub use builder_size_queries::{SizeCondition, SizeContainer, StyleBuilderSizeQueries};
+use builder_texture_atlas::update_texture_atlases;
+pub use builder_texture_atlas::StyleBuilderTextureAtlas;
+pub use builder_transition::StyleBuilderTransition;
 pub use builder_visibility::StyleBuilderVisibility;
 pub use builder_z_index::StyleBuilderZIndex;
+pub use css::{parse_declarations, CssError, StyleDecl};
+pub use css_check::check_declarations;
+pub use font_families::{FontFamilies, FontWeight};
 use impl_trait_for_tuples::*;
+pub use interaction_state::{InteractionState, InteractionStatePlugin, PseudoState, Selected};
+use stylesheet::reapply_stylesheets;
+pub use stylesheet::{
+    Selector, StyleBuilderClasses, StyleClasses, StyleRule, StyleSheet, StyleSheetLoader,
+    StyleSheetLoaderError, StyleSheets,
+};
 use text_styles::update_text_styles;
-pub use text_styles::{InheritableFontStyles, UseInheritedTextStyles};
-// pub use builder_texture_atlas::StyleBuilderTextureAtlas;
+pub use text_styles::{InheritableFontStyles, TextSpanStyles, UseInheritedTextStyles};
+use transition::update_style_transitions;
+pub use transition::{Easing, Transition};
+use units::update_relative_lengths;
+pub use units::{Em, Length, LengthCorners, LengthRect, Rem, Space, StyleUnits};
 
 #[cfg(feature = "mod_picking")]
 pub use builder_pointer_events::StyleBuilderPointerEvents;
@@ -131,6 +165,28 @@ pub struct StyleBuilderPlugin;
 
 impl Plugin for StyleBuilderPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_systems(Update, update_text_styles.in_set(StyleBuilderSystemSet));
+        app.add_plugins(InteractionStatePlugin)
+            .init_asset::<StyleSheet>()
+            .init_asset_loader::<StyleSheetLoader>()
+            .init_resource::<StyleSheets>()
+            .init_resource::<StyleUnits>()
+            .init_resource::<FontFamilies>()
+            .init_asset::<UiTextureAtlas>()
+            .init_asset_loader::<TextureAtlasLoader>()
+            .add_systems(
+                Update,
+                (
+                    reapply_stylesheets,
+                    update_pseudo_state_styles,
+                    update_size_query_styles,
+                    update_relative_lengths,
+                    update_text_styles,
+                    update_style_transitions,
+                    update_texture_atlases,
+                    update_tiled_backgrounds,
+                )
+                    .chain()
+                    .in_set(StyleBuilderSystemSet),
+            );
     }
 }
@@ -0,0 +1,376 @@
+use bevy::{
+    prelude::*,
+    reflect::Struct,
+    sprite::{ImageScaleMode, TextureAtlas},
+    ui::{self, UiImage, ZIndex},
+};
+
+use crate::{
+    builder_background::TiledBackground,
+    builder_texture_atlas::UiTextureAtlasHandle,
+    text_styles::InheritableFontStyles,
+    transition::{StyleTransitions, TransitionDecl},
+    units::RelativeLengths,
+};
+
+/// Copy of the style components of an entity, used to revert conditional styles and to
+/// re-apply stylesheet rules.
+#[derive(Clone, Default)]
+pub(crate) struct StyleSnapshot {
+    style: Option<ui::Style>,
+    background_color: Option<ui::BackgroundColor>,
+    border_color: Option<ui::BorderColor>,
+    border_radius: Option<ui::BorderRadius>,
+    outline: Option<ui::Outline>,
+    z_index: Option<ZIndex>,
+    visibility: Option<Visibility>,
+    image: Option<UiImage>,
+    scale_mode: Option<ImageScaleMode>,
+    tiled: Option<TiledBackground>,
+    atlas_handle: Option<UiTextureAtlasHandle>,
+    texture_atlas: Option<TextureAtlas>,
+    font_styles: Option<InheritableFontStyles>,
+    relative_lengths: Option<RelativeLengths>,
+    transitions: Option<Vec<TransitionDecl>>,
+    #[cfg(feature = "mod_picking")]
+    pickable: Option<bevy_mod_picking::picking_core::Pickable>,
+}
+
+impl StyleSnapshot {
+    pub(crate) fn capture(target: &EntityWorldMut) -> Self {
+        Self {
+            style: target.get().cloned(),
+            background_color: target.get().cloned(),
+            border_color: target.get().cloned(),
+            border_radius: target.get().cloned(),
+            outline: target.get().cloned(),
+            z_index: target.get().cloned(),
+            visibility: target.get().cloned(),
+            image: target.get().cloned(),
+            scale_mode: target.get().cloned(),
+            tiled: target.get().cloned(),
+            atlas_handle: target.get().cloned(),
+            texture_atlas: target.get().cloned(),
+            font_styles: target.get().cloned(),
+            relative_lengths: target.get().cloned(),
+            transitions: target
+                .get::<StyleTransitions>()
+                .map(|transitions| transitions.decls().collect()),
+            #[cfg(feature = "mod_picking")]
+            pickable: target.get().cloned(),
+        }
+    }
+
+    /// Replace the `Style` in the snapshot. This is used when capturing the state of a
+    /// [`StyleBuilder`](crate::StyleBuilder), which caches the `Style` until it's finished.
+    pub(crate) fn set_style(&mut self, style: ui::Style) {
+        self.style = Some(style);
+    }
+
+    /// Replace a layer of styles: properties of `self` which still have the value they had
+    /// in `old` are changed to the value in `new`; other properties have been overridden since
+    /// `old` was applied, and are kept. `Style` properties are compared individually.
+    pub(crate) fn rebase(&self, old: &Self, new: &Self) -> Self {
+        Self {
+            style: match (&self.style, &old.style, &new.style) {
+                (Some(current), Some(old), Some(new)) => Some(rebase_style(current, old, new)),
+                _ => rebase_value(&self.style, &old.style, &new.style),
+            },
+            background_color: rebase_value(
+                &self.background_color,
+                &old.background_color,
+                &new.background_color,
+            ),
+            border_color: rebase_value(&self.border_color, &old.border_color, &new.border_color),
+            border_radius: rebase_value(
+                &self.border_radius,
+                &old.border_radius,
+                &new.border_radius,
+            ),
+            outline: rebase_value(&self.outline, &old.outline, &new.outline),
+            z_index: rebase_value(&self.z_index, &old.z_index, &new.z_index),
+            visibility: rebase_value(&self.visibility, &old.visibility, &new.visibility),
+            image: if images_eq(&self.image, &old.image) {
+                new.image.clone()
+            } else {
+                self.image.clone()
+            },
+            scale_mode: rebase_reflect(&self.scale_mode, &old.scale_mode, &new.scale_mode),
+            tiled: rebase_value(&self.tiled, &old.tiled, &new.tiled),
+            atlas_handle: rebase_value(&self.atlas_handle, &old.atlas_handle, &new.atlas_handle),
+            texture_atlas: rebase_reflect(
+                &self.texture_atlas,
+                &old.texture_atlas,
+                &new.texture_atlas,
+            ),
+            font_styles: rebase_value(&self.font_styles, &old.font_styles, &new.font_styles),
+            relative_lengths: rebase_value(
+                &self.relative_lengths,
+                &old.relative_lengths,
+                &new.relative_lengths,
+            ),
+            transitions: rebase_value(&self.transitions, &old.transitions, &new.transitions),
+            #[cfg(feature = "mod_picking")]
+            pickable: rebase_value(&self.pickable, &old.pickable, &new.pickable),
+        }
+    }
+
+    pub(crate) fn restore(&self, target: &mut EntityWorldMut) {
+        restore_component(target, &self.style);
+        restore_component(target, &self.background_color);
+        restore_component(target, &self.border_color);
+        restore_component(target, &self.border_radius);
+        restore_component(target, &self.outline);
+        restore_component(target, &self.z_index);
+        restore_component(target, &self.visibility);
+        restore_reflect(target, &self.scale_mode);
+        restore_component(target, &self.tiled);
+        restore_component(target, &self.atlas_handle);
+        restore_reflect(target, &self.texture_atlas);
+        restore_component(target, &self.font_styles);
+        restore_component(target, &self.relative_lengths);
+        #[cfg(feature = "mod_picking")]
+        restore_component(target, &self.pickable);
+
+        match (&self.image, target.get::<UiImage>()) {
+            (Some(image), Some(current)) if image_eq(image, current) => {}
+            (Some(image), _) => {
+                target.insert(image.clone());
+            }
+            (None, Some(_)) => {
+                target.remove::<UiImage>();
+            }
+            (None, None) => {}
+        }
+
+        // Only the declarations are restored, so that running animations are not interrupted.
+        match (&self.transitions, target.get_mut::<StyleTransitions>()) {
+            (Some(decls), Some(mut current)) => {
+                if !current.decls().eq(decls.iter().copied()) {
+                    current.set_decls(decls.iter().copied());
+                }
+            }
+            (Some(decls), None) => {
+                target.insert(StyleTransitions::new(decls.iter().copied()));
+            }
+            (None, Some(_)) => {
+                target.remove::<StyleTransitions>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Restore a component to a previous value, avoiding change detection if it's unchanged.
+fn restore_component<C: Component + Clone + PartialEq>(
+    target: &mut EntityWorldMut,
+    value: &Option<C>,
+) {
+    match (value, target.get_mut::<C>()) {
+        (Some(value), Some(mut current)) => {
+            current.set_if_neq(value.clone());
+        }
+        (Some(value), None) => {
+            target.insert(value.clone());
+        }
+        (None, Some(_)) => {
+            target.remove::<C>();
+        }
+        (None, None) => {}
+    }
+}
+
+/// Restore a component which doesn't implement `PartialEq`, comparing it using reflection.
+fn restore_reflect<C: Component + Clone + Reflect>(target: &mut EntityWorldMut, value: &Option<C>) {
+    match (value, target.get::<C>()) {
+        (Some(value), Some(current)) if reflect_eq(value, current) => {}
+        (Some(value), _) => {
+            target.insert(value.clone());
+        }
+        (None, Some(_)) => {
+            target.remove::<C>();
+        }
+        (None, None) => {}
+    }
+}
+
+fn reflect_eq<T: Reflect>(a: &T, b: &T) -> bool {
+    a.reflect_partial_eq(b).unwrap_or(false)
+}
+
+fn rebase_reflect<C: Clone + Reflect>(
+    current: &Option<C>,
+    old: &Option<C>,
+    new: &Option<C>,
+) -> Option<C> {
+    let unchanged = match (current, old) {
+        (Some(current), Some(old)) => reflect_eq(current, old),
+        (None, None) => true,
+        _ => false,
+    };
+    if unchanged {
+        new.clone()
+    } else {
+        current.clone()
+    }
+}
+
+fn rebase_value<C: Clone + PartialEq>(
+    current: &Option<C>,
+    old: &Option<C>,
+    new: &Option<C>,
+) -> Option<C> {
+    if current == old {
+        new.clone()
+    } else {
+        current.clone()
+    }
+}
+
+/// Rebase the individual properties of a `Style`.
+fn rebase_style(current: &ui::Style, old: &ui::Style, new: &ui::Style) -> ui::Style {
+    let mut result = current.clone();
+    for i in 0..current.field_len() {
+        let unchanged = current
+            .field_at(i)
+            .zip(old.field_at(i))
+            .and_then(|(c, o)| c.reflect_partial_eq(o))
+            .unwrap_or(false);
+        if unchanged {
+            if let (Some(field), Some(value)) = (result.field_at_mut(i), new.field_at(i)) {
+                field.apply(value);
+            }
+        }
+    }
+    result
+}
+
+// `UiImage` doesn't implement `PartialEq`.
+fn image_eq(a: &UiImage, b: &UiImage) -> bool {
+    a.texture == b.texture && a.color == b.color && a.flip_x == b.flip_x && a.flip_y == b.flip_y
+}
+
+fn images_eq(a: &Option<UiImage>, b: &Option<UiImage>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => image_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::color::palettes::css;
+
+    use super::*;
+    use crate::{
+        Easing, Em, StyleBuilder, StyleBuilderBackground, StyleBuilderBorderColor,
+        StyleBuilderBorderRadius, StyleBuilderFont, StyleBuilderLayout, StyleBuilderOutline,
+        StyleBuilderPointerEvents, StyleBuilderTextureAtlas, StyleBuilderTransition,
+        StyleBuilderVisibility, StyleBuilderZIndex, Transition,
+    };
+
+    /// Set every component which is captured by the snapshot.
+    fn set_all(sb: &mut StyleBuilder) {
+        sb.width(Em(2.))
+            .background_color(css::RED)
+            .background_image_color(css::BLUE)
+            .border_color(css::GREEN)
+            .border_radius(4.)
+            .outline_color(css::WHITE)
+            .z_index(3)
+            .visible(false)
+            .texture_atlas_tile(3)
+            .font_size(12.)
+            .pointer_events(false)
+            .transition(Transition::Width, 0.5, Easing::Linear);
+    }
+
+    #[test]
+    fn test_restore_removes() {
+        let mut world = World::new();
+        let mut target = world.spawn_empty();
+        let snapshot = StyleSnapshot::capture(&target);
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        set_all(&mut sb);
+        sb.finish();
+        assert!(target.contains::<ui::BackgroundColor>());
+        assert!(target.contains::<RelativeLengths>());
+
+        snapshot.restore(&mut target);
+        assert!(!target.contains::<ui::Style>());
+        assert!(!target.contains::<ui::BackgroundColor>());
+        assert!(!target.contains::<UiImage>());
+        assert!(!target.contains::<TextureAtlas>());
+        assert!(!target.contains::<ui::BorderColor>());
+        assert!(!target.contains::<ui::BorderRadius>());
+        assert!(!target.contains::<ui::Outline>());
+        assert!(!target.contains::<ZIndex>());
+        assert!(!target.contains::<Visibility>());
+        assert!(!target.contains::<InheritableFontStyles>());
+        assert!(!target.contains::<RelativeLengths>());
+        assert!(!target.contains::<StyleTransitions>());
+        #[cfg(feature = "mod_picking")]
+        assert!(!target.contains::<bevy_mod_picking::picking_core::Pickable>());
+    }
+
+    #[test]
+    fn test_restore_values() {
+        let mut world = World::new();
+        let mut target = world.spawn_empty();
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        set_all(&mut sb);
+        sb.finish();
+        let snapshot = StyleSnapshot::capture(&target);
+
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        sb.width(Em(3.))
+            .background_color(css::BLACK)
+            .background_image_color(css::BLACK)
+            .border_color(css::BLACK)
+            .border_radius(8.)
+            .outline_color(css::BLACK)
+            .z_index(4)
+            .visible(true)
+            .texture_atlas_tile(5)
+            .font_size(14.)
+            .pointer_events(true)
+            .transition(Transition::Width, 1.0, Easing::EaseIn)
+            .transition(Transition::Height, 1.0, Easing::EaseIn);
+        sb.finish();
+
+        snapshot.restore(&mut target);
+        let restored = StyleSnapshot::capture(&target);
+        assert_eq!(restored.style, snapshot.style);
+        assert_eq!(restored.background_color, snapshot.background_color);
+        assert!(images_eq(&restored.image, &snapshot.image));
+        assert_eq!(restored.texture_atlas.map(|atlas| atlas.index), Some(3));
+        assert_eq!(restored.border_color, snapshot.border_color);
+        assert_eq!(restored.border_radius, snapshot.border_radius);
+        assert_eq!(restored.outline, snapshot.outline);
+        assert_eq!(restored.z_index, snapshot.z_index);
+        assert_eq!(restored.visibility, snapshot.visibility);
+        assert_eq!(restored.font_styles, snapshot.font_styles);
+        assert_eq!(restored.relative_lengths, snapshot.relative_lengths);
+        assert!(restored.transitions == snapshot.transitions);
+        #[cfg(feature = "mod_picking")]
+        assert_eq!(restored.pickable, snapshot.pickable);
+    }
+
+    #[test]
+    fn test_restore_atlas_handle() {
+        let mut world = World::new();
+        let mut target = world.spawn_empty();
+        let snapshot = StyleSnapshot::capture(&target);
+        target.insert(UiTextureAtlasHandle(Handle::default()));
+        let loaded = StyleSnapshot::capture(&target);
+
+        snapshot.restore(&mut target);
+        assert!(!target.contains::<UiTextureAtlasHandle>());
+        loaded.restore(&mut target);
+        assert_eq!(
+            target.get::<UiTextureAtlasHandle>(),
+            Some(&UiTextureAtlasHandle(Handle::default()))
+        );
+    }
+}
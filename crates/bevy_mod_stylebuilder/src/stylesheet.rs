@@ -0,0 +1,584 @@
+use std::{panic::Location, sync::Arc};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt, LoadContext},
+    ecs::event::ManualEventReader,
+    prelude::*,
+    ui,
+};
+use thiserror::Error;
+
+use crate::{
+    builder::StyleBuilder,
+    builder_pseudo_states::{
+        revert_conditional_styles, update_conditional_styles, Condition, ConditionalBlock,
+    },
+    css::{parse_declarations, CssError, StyleDecl},
+    interaction_state::PseudoState,
+    style_snapshot::StyleSnapshot,
+};
+
+/// A selector which matches entities having all of the given classes, and optionally
+/// being in an interaction pseudo-state, such as `.button.primary:hover`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    /// Classes which must all be present.
+    pub classes: Vec<String>,
+    /// Pseudo-state which must be active for the rule to apply.
+    pub state: Option<PseudoState>,
+}
+
+impl Selector {
+    /// Parse a selector.
+    pub fn parse(source: &str) -> Result<Self, CssError> {
+        let source = source.trim();
+        let (compound, state) = match source.split_once(':') {
+            Some((compound, state)) => (compound, Some(parse_pseudo_state(state)?)),
+            None => (source, None),
+        };
+        if !compound.starts_with('.') {
+            return Err(CssError::new(format!(
+                "invalid selector `{}`, only class selectors are supported",
+                source
+            )));
+        }
+        let classes: Vec<String> = compound[1..].split('.').map(String::from).collect();
+        if classes.iter().any(|c| !is_valid_class_name(c)) {
+            return Err(CssError::new(format!("invalid selector `{}`", source)));
+        }
+        Ok(Self { classes, state })
+    }
+
+    /// The specificity of the selector: rules with higher specificity are applied later,
+    /// overriding rules with lower specificity.
+    pub fn specificity(&self) -> usize {
+        self.classes.len() + usize::from(self.state.is_some())
+    }
+
+    /// True if all of the classes in the selector are contained in `classes`.
+    pub fn matches(&self, classes: &[String]) -> bool {
+        self.classes.iter().all(|c| classes.contains(c))
+    }
+}
+
+fn is_valid_class_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn parse_pseudo_state(name: &str) -> Result<PseudoState, CssError> {
+    match name.trim() {
+        "hover" => Ok(PseudoState::Hover),
+        "active" | "pressed" => Ok(PseudoState::Pressed),
+        "focus" => Ok(PseudoState::Focus),
+        "focus-visible" => Ok(PseudoState::FocusVisible),
+        "disabled" => Ok(PseudoState::Disabled),
+        "selected" => Ok(PseudoState::Selected),
+        _ => Err(CssError::new(format!("unknown pseudo-class `:{}`", name))),
+    }
+}
+
+/// A rule in a [`StyleSheet`].
+#[derive(Debug, Clone)]
+pub struct StyleRule {
+    /// Selector which determines which entities the rule applies to.
+    pub selector: Selector,
+    /// The style declarations in the rule.
+    pub declarations: Arc<Vec<StyleDecl>>,
+}
+
+/// A stylesheet containing named style rules, written in a subset of CSS:
+///
+/// ```css
+/// /* Comments are allowed. */
+/// .button {
+///     background-color: #333;
+///     padding: 0 12px;
+/// }
+///
+/// .button.primary, .button:hover {
+///     background-color: #446;
+/// }
+/// ```
+///
+/// Only class selectors (including compound selectors such as `.a.b`) and interaction
+/// pseudo-classes are supported.
+#[derive(Asset, TypePath, Debug, Default, Clone)]
+pub struct StyleSheet {
+    rules: Vec<StyleRule>,
+}
+
+impl StyleSheet {
+    /// Parse a stylesheet from source text.
+    pub fn parse(source: &str) -> Result<Self, CssError> {
+        let source = strip_block_comments(source);
+        let mut rules = Vec::new();
+        let mut rest = source.as_str();
+        let mut line = 1;
+        while let Some(open) = rest.find('{') {
+            let selectors = &rest[..open];
+            let selector_line =
+                line + count_lines(&selectors[..selectors.len() - selectors.trim_start().len()]);
+            let Some(close) = rest[open..].find('}') else {
+                return Err(CssError::new("missing `}`").at_line(selector_line));
+            };
+            let body = &rest[open + 1..open + close];
+            let body_line = line + count_lines(&rest[..open]);
+            let declarations = Arc::new(parse_declarations(body).map_err(|e| CssError {
+                line: Some(body_line + e.line.unwrap_or(1) - 1),
+                message: e.message,
+            })?);
+            for selector in selectors.split(',') {
+                rules.push(StyleRule {
+                    selector: Selector::parse(selector).map_err(|e| e.at_line(selector_line))?,
+                    declarations: declarations.clone(),
+                });
+            }
+            line += count_lines(&rest[..open + close + 1]);
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.trim().is_empty() {
+            return Err(CssError::new("expected `{`")
+                .at_line(line + count_lines(&rest[..rest.len() - rest.trim_start().len()])));
+        }
+        Ok(Self { rules })
+    }
+
+    /// The rules in the stylesheet, in source order.
+    pub fn rules(&self) -> &[StyleRule] {
+        &self.rules
+    }
+}
+
+fn count_lines(text: &str) -> usize {
+    text.chars().filter(|c| *c == '\n').count()
+}
+
+/// Remove `/* ... */` comments, preserving line breaks so that line numbers are unaffected.
+fn strip_block_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("*/")
+            .map(|end| start + end + 2)
+            .unwrap_or(rest.len());
+        result.extend(rest[start..end].chars().filter(|c| *c == '\n'));
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Errors that can occur when loading a [`StyleSheet`].
+#[derive(Debug, Error)]
+pub enum StyleSheetLoaderError {
+    /// The file could not be read.
+    #[error("could not read stylesheet: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid UTF-8.
+    #[error("stylesheet is not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    /// The file contains a syntax error.
+    #[error("could not parse stylesheet: {0}")]
+    Parse(#[from] CssError),
+}
+
+/// Asset loader for `.css` stylesheets.
+#[derive(Default)]
+pub struct StyleSheetLoader;
+
+impl AssetLoader for StyleSheetLoader {
+    type Asset = StyleSheet;
+    type Settings = ();
+    type Error = StyleSheetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes)?;
+        Ok(StyleSheet::parse(&source)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["css"]
+    }
+}
+
+/// Resource which holds the list of active stylesheets. Rules from all active stylesheets
+/// are considered when applying classes; for rules of equal specificity, rules from
+/// stylesheets which were added later take precedence.
+#[derive(Resource, Default)]
+pub struct StyleSheets {
+    sheets: Vec<Handle<StyleSheet>>,
+}
+
+impl StyleSheets {
+    /// Add a stylesheet to the list of active stylesheets.
+    pub fn add(&mut self, sheet: Handle<StyleSheet>) {
+        self.sheets.push(sheet);
+    }
+
+    /// Load a stylesheet and add it to the list of active stylesheets.
+    pub fn load<'a>(&mut self, server: &AssetServer, path: impl Into<AssetPath<'a>>) {
+        let handle = server.load(path.into());
+        self.add(handle);
+    }
+
+    /// Remove a stylesheet from the list of active stylesheets.
+    pub fn remove(&mut self, sheet: &Handle<StyleSheet>) {
+        self.sheets.retain(|s| s != sheet);
+    }
+
+    /// Iterate over the active stylesheets.
+    pub fn iter(&self) -> impl Iterator<Item = &Handle<StyleSheet>> {
+        self.sheets.iter()
+    }
+}
+
+/// A set of classes applied by a single call to [`StyleBuilderClasses::class`].
+#[derive(Clone)]
+struct ClassLayer {
+    /// The style function which applied the classes, and the call site within it; re-running
+    /// the same style function replaces the layer rather than adding a new one.
+    source: &'static Location<'static>,
+    location: &'static Location<'static>,
+    classes: Vec<String>,
+    /// Styles before the rules were applied.
+    base: StyleSnapshot,
+    /// Styles after the rules were applied.
+    applied: StyleSnapshot,
+}
+
+/// Component which records the stylesheet classes of an entity, so that the styles can be
+/// re-applied when the stylesheets change.
+#[derive(Component, Clone)]
+pub struct StyleClasses {
+    layers: Vec<ClassLayer>,
+}
+
+impl StyleClasses {
+    /// True if the entity has the given class.
+    pub fn contains(&self, class: &str) -> bool {
+        self.classes().any(|c| c == class)
+    }
+
+    /// Iterate over the classes of the entity.
+    pub fn classes(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.classes.iter().map(|c| c.as_str()))
+    }
+}
+
+/// Find the rules from the active stylesheets which match the given classes, sorted in order
+/// of increasing specificity.
+fn matching_rules(world: &World, classes: &[String]) -> Vec<StyleRule> {
+    let (Some(sheets), Some(assets)) = (
+        world.get_resource::<StyleSheets>(),
+        world.get_resource::<Assets<StyleSheet>>(),
+    ) else {
+        return Vec::new();
+    };
+    let mut rules: Vec<StyleRule> = sheets
+        .iter()
+        .filter_map(|handle| assets.get(handle))
+        .flat_map(|sheet| sheet.rules.iter())
+        .filter(|rule| rule.selector.matches(classes))
+        .cloned()
+        .collect();
+    // Stable sort, so that rules of equal specificity are applied in source order.
+    rules.sort_by_key(|rule| rule.selector.specificity());
+    rules
+}
+
+/// Apply the declarations of the rules which don't have a pseudo-state.
+fn apply_rules(ss: &mut StyleBuilder, rules: &[StyleRule]) {
+    for rule in rules.iter().filter(|rule| rule.selector.state.is_none()) {
+        for decl in rule.declarations.iter() {
+            decl.apply(ss);
+        }
+    }
+}
+
+const PSEUDO_STATES: [PseudoState; 6] = [
+    PseudoState::Hover,
+    PseudoState::Pressed,
+    PseudoState::Focus,
+    PseudoState::FocusVisible,
+    PseudoState::Disabled,
+    PseudoState::Selected,
+];
+
+/// Create conditional style blocks for the rules which have a pseudo-state.
+fn pseudo_state_blocks(
+    source: &'static Location<'static>,
+    location: &'static Location<'static>,
+    rules: &[StyleRule],
+) -> Vec<ConditionalBlock> {
+    PSEUDO_STATES
+        .iter()
+        .filter_map(|state| {
+            let decls: Vec<Arc<Vec<StyleDecl>>> = rules
+                .iter()
+                .filter(|rule| rule.selector.state == Some(*state))
+                .map(|rule| rule.declarations.clone())
+                .collect();
+            if decls.is_empty() {
+                return None;
+            }
+            Some(ConditionalBlock {
+                source,
+                location,
+                condition: Condition::State(*state),
+                style: Arc::new(move |ss: &mut StyleBuilder| {
+                    for decl in decls.iter().flat_map(|d| d.iter()) {
+                        decl.apply(ss);
+                    }
+                }),
+            })
+        })
+        .collect()
+}
+
+fn capture_builder(ss: &StyleBuilder) -> StyleSnapshot {
+    let mut snapshot = StyleSnapshot::capture(ss.target);
+    snapshot.set_style(ss.style.clone());
+    snapshot
+}
+
+/// Methods for applying named style rules from stylesheets.
+pub trait StyleBuilderClasses {
+    /// Apply the rules from the active [`StyleSheets`] which match the given classes,
+    /// separated by spaces. Rules are applied in order of specificity, and rules with
+    /// pseudo-classes such as `:hover` are applied as conditional styles. Call this before
+    /// setting any inline styles, so that the inline styles take precedence.
+    ///
+    /// The classes are remembered, and the styles re-applied when a stylesheet is changed.
+    fn class(&mut self, names: &str) -> &mut Self;
+}
+
+impl<'a, 'w> StyleBuilderClasses for StyleBuilder<'a, 'w> {
+    #[track_caller]
+    fn class(&mut self, names: &str) -> &mut Self {
+        let location = Location::caller();
+        let classes: Vec<String> = names.split_whitespace().map(String::from).collect();
+        let base = capture_builder(self);
+        let rules = self
+            .target
+            .world_scope(|world| matching_rules(world, &classes));
+        apply_rules(self, &rules);
+        let applied = capture_builder(self);
+        let source = self.source;
+        self.conditions
+            .extend(pseudo_state_blocks(source, location, &rules));
+
+        let layer = ClassLayer {
+            source,
+            location,
+            classes,
+            base,
+            applied,
+        };
+        match self.target.get_mut::<StyleClasses>() {
+            Some(mut style_classes) => {
+                match style_classes
+                    .layers
+                    .iter_mut()
+                    .find(|l| l.source == source && l.location == location)
+                {
+                    Some(existing) => *existing = layer,
+                    None => style_classes.layers.push(layer),
+                }
+            }
+            None => {
+                self.target.insert(StyleClasses {
+                    layers: vec![layer],
+                });
+            }
+        }
+        self
+    }
+}
+
+/// Re-apply the stylesheet rules for an entity. Properties which still have the value set by
+/// the previous version of the rules are updated; properties which have since been overridden
+/// by inline styles are left alone.
+fn restyle_entity(world: &mut World, entity: Entity) {
+    let Some(mut style_classes) = world.get::<StyleClasses>(entity).cloned() else {
+        return;
+    };
+    revert_conditional_styles(&mut world.entity_mut(entity));
+    let mut blocks = Vec::new();
+    for layer in style_classes.layers.iter_mut() {
+        let rules = matching_rules(world, &layer.classes);
+
+        // Compute the new styles by applying the rules to the layer's base styles, then put
+        // back the current styles, rebased onto the new rules.
+        let mut target = world.entity_mut(entity);
+        let current = StyleSnapshot::capture(&target);
+        layer.base.restore(&mut target);
+        let style = target.get::<ui::Style>().cloned().unwrap_or_default();
+        let mut sb = StyleBuilder::new(&mut target, style);
+        apply_rules(&mut sb, &rules);
+        let applied = capture_builder(&sb);
+        drop(sb);
+        current
+            .rebase(&layer.applied, &applied)
+            .restore(&mut target);
+        layer.applied = applied;
+        blocks.extend(pseudo_state_blocks(layer.source, layer.location, &rules));
+    }
+    let layers: Vec<_> = style_classes
+        .layers
+        .iter()
+        .map(|layer| (layer.source, layer.location))
+        .collect();
+    let mut target = world.entity_mut(entity);
+    target.insert(style_classes);
+    update_conditional_styles(
+        &mut target,
+        |b| layers.contains(&(b.source, b.location)),
+        blocks,
+    );
+}
+
+/// Re-applies stylesheet rules to all entities with classes when a stylesheet is loaded or
+/// modified, or when the set of active stylesheets changes.
+pub(crate) fn reapply_stylesheets(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<AssetEvent<StyleSheet>>>,
+) {
+    let mut changed = world.is_resource_changed::<StyleSheets>();
+    if let Some(events) = world.get_resource::<Events<AssetEvent<StyleSheet>>>() {
+        for ev in reader.read(events) {
+            if matches!(
+                ev,
+                AssetEvent::LoadedWithDependencies { .. }
+                    | AssetEvent::Modified { .. }
+                    | AssetEvent::Removed { .. }
+            ) {
+                changed = true;
+            }
+        }
+    }
+    if !changed {
+        return;
+    }
+    let mut query = world.query_filtered::<Entity, With<StyleClasses>>();
+    let entities: Vec<Entity> = query.iter(world).collect();
+    for entity in entities {
+        restyle_entity(world, entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder_pseudo_states::apply_conditional_styles, InteractionState};
+
+    #[test]
+    fn test_parse_selector() {
+        let sel = Selector::parse(".button").unwrap();
+        assert_eq!(sel.classes, vec!["button"]);
+        assert_eq!(sel.state, None);
+        assert_eq!(sel.specificity(), 1);
+
+        let sel = Selector::parse(" .button.primary:hover ").unwrap();
+        assert_eq!(sel.classes, vec!["button", "primary"]);
+        assert_eq!(sel.state, Some(PseudoState::Hover));
+        assert_eq!(sel.specificity(), 3);
+        assert!(sel.matches(&["primary".into(), "button".into(), "large".into()]));
+        assert!(!sel.matches(&["button".into()]));
+
+        assert!(Selector::parse("button").is_err());
+        assert!(Selector::parse(".a .b").is_err());
+        assert!(Selector::parse(".a:hovered").is_err());
+    }
+
+    #[test]
+    fn test_parse_stylesheet() {
+        let sheet = StyleSheet::parse(
+            "/* Buttons\n */\n.button {\n  padding: 4px;\n}\n\n.button.primary, .button:hover { color: #fff; }\n",
+        )
+        .unwrap();
+        assert_eq!(sheet.rules().len(), 3);
+        assert_eq!(sheet.rules()[0].selector.classes, vec!["button"]);
+        assert_eq!(
+            *sheet.rules()[0].declarations,
+            vec![StyleDecl::Padding(ui::UiRect::all(ui::Val::Px(4.)).into())]
+        );
+        assert_eq!(sheet.rules()[2].selector.state, Some(PseudoState::Hover));
+        assert!(Arc::ptr_eq(
+            &sheet.rules()[1].declarations,
+            &sheet.rules()[2].declarations
+        ));
+    }
+
+    #[test]
+    fn test_stylesheet_errors() {
+        let err = StyleSheet::parse(".a {\n  color: #fff;\n  widht: 10px;\n}").unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        let err = StyleSheet::parse(".a {}\n\nbutton { color: #fff; }").unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        let err = StyleSheet::parse(".a {}\n.b { color: #fff;").unwrap_err();
+        assert_eq!(err.message, "missing `}`");
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn test_restyle() {
+        let mut world = World::new();
+        let mut assets = Assets::<StyleSheet>::default();
+        let handle = assets.add(
+            StyleSheet::parse(".a { padding: 4px; } .b { margin: 2px; } .b:hover { margin: 3px; }")
+                .unwrap(),
+        );
+        world.insert_resource(assets);
+        let mut sheets = StyleSheets::default();
+        sheets.add(handle.clone());
+        world.insert_resource(sheets);
+
+        // Two style functions, each applying classes from the same call site.
+        let entity = world.spawn_empty().id();
+        for (source, class) in [(Location::caller(), "a"), (Location::caller(), "b")] {
+            let mut target = world.entity_mut(entity);
+            let style = target.get::<ui::Style>().cloned().unwrap_or_default();
+            let mut sb = StyleBuilder::new(&mut target, style).with_source(source);
+            sb.class(class);
+            sb.finish();
+        }
+        let style_classes = world.get::<StyleClasses>(entity).unwrap();
+        assert_eq!(style_classes.classes().collect::<Vec<_>>(), vec!["a", "b"]);
+        let style = world.get::<ui::Style>(entity).unwrap();
+        assert_eq!(style.padding, ui::UiRect::all(ui::Val::Px(4.)));
+        assert_eq!(style.margin, ui::UiRect::all(ui::Val::Px(2.)));
+
+        world.resource_mut::<Assets<StyleSheet>>().insert(
+            &handle,
+            StyleSheet::parse(".a { padding: 5px; } .b { margin: 6px; }").unwrap(),
+        );
+        let count = world.entities().len();
+        restyle_entity(&mut world, entity);
+        assert_eq!(world.entities().len(), count);
+        let style = world.get::<ui::Style>(entity).unwrap();
+        assert_eq!(style.padding, ui::UiRect::all(ui::Val::Px(5.)));
+        assert_eq!(style.margin, ui::UiRect::all(ui::Val::Px(6.)));
+
+        // The hover rule was removed from the stylesheet.
+        world.get_mut::<InteractionState>(entity).unwrap().hovered = true;
+        apply_conditional_styles(&mut world.entity_mut(entity));
+        let style = world.get::<ui::Style>(entity).unwrap();
+        assert_eq!(style.margin, ui::UiRect::all(ui::Val::Px(6.)));
+    }
+}
@@ -1,6 +1,14 @@
 #![allow(missing_docs)]
 
-use bevy::prelude::*;
+use std::rc::Rc;
+
+use bevy::{
+    prelude::*,
+    text::{BreakLineOn, JustifyText},
+    utils::HashSet,
+};
+
+use crate::font_families::{FontFamilies, FontWeight};
 
 /// Struct that holds the properties for text rendering, which can be inherited. This allows
 /// setting for font face, size and color to be established at a parent level and inherited by
@@ -8,28 +16,63 @@ use bevy::prelude::*;
 ///
 /// This will be applied to any text nodes that are children of the target entity, unless
 /// those nodes explicitly override the properties.
-#[derive(Component, Default, Clone, Debug)]
+///
+/// The font can be given either as an explicit font handle or as a family name registered in
+/// [`FontFamilies`]; whichever is set nearest to the text wins. Weight and italic style are
+/// inherited independently of the font, so a bold child of a themed parent keeps the parent's
+/// family.
+#[derive(Component, Default, Clone, Debug, PartialEq)]
 pub struct InheritableFontStyles {
     /// Path to the font asset.
     pub font: Option<Handle<Font>>,
 
+    /// Inherited font family name.
+    pub family: Option<String>,
+
+    /// Inherited font weight.
+    pub weight: Option<FontWeight>,
+
+    /// Inherited italic style.
+    pub italic: Option<bool>,
+
     /// Inherited size of the font.
     pub font_size: Option<f32>,
 
     /// Inherited text color.
     pub color: Option<Color>,
+
+    /// Inherited text justification.
+    pub justify: Option<JustifyText>,
+
+    /// Inherited line breaking behavior.
+    pub line_break: Option<BreakLineOn>,
 }
 
 impl InheritableFontStyles {
     /// True if all text style properties are set.
     pub fn is_final(&self) -> bool {
-        self.font.is_some() && self.font_size.is_some() && self.color.is_some()
+        (self.font.is_some() || self.family.is_some())
+            && self.font_size.is_some()
+            && self.color.is_some()
+            && self.weight.is_some()
+            && self.italic.is_some()
+            && self.justify.is_some()
+            && self.line_break.is_some()
     }
 
     /// Merge the properties from another `InheritableTextStyles` into this one.
     pub fn merge(&mut self, other: &InheritableFontStyles) {
-        if other.font.is_some() && self.font.is_none() {
+        // Font and family are alternative ways of specifying the same thing, so they are
+        // inherited together.
+        if self.font.is_none() && self.family.is_none() {
             self.font.clone_from(&other.font);
+            self.family.clone_from(&other.family);
+        }
+        if other.weight.is_some() && self.weight.is_none() {
+            self.weight = other.weight;
+        }
+        if other.italic.is_some() && self.italic.is_none() {
+            self.italic = other.italic;
         }
         if other.font_size.is_some() && self.font_size.is_none() {
             self.font_size = other.font_size;
@@ -37,6 +80,12 @@ impl InheritableFontStyles {
         if other.color.is_some() && self.color.is_none() {
             self.color = other.color;
         }
+        if other.justify.is_some() && self.justify.is_none() {
+            self.justify = other.justify;
+        }
+        if other.line_break.is_some() && self.line_break.is_none() {
+            self.line_break = other.line_break;
+        }
     }
 }
 
@@ -45,38 +94,186 @@ impl InheritableFontStyles {
 #[derive(Component)]
 pub struct UseInheritedTextStyles;
 
+/// Per-section style overrides for a text entity that uses inherited text styles. Each entry
+/// corresponds to the text section with the same index; properties which are set in the
+/// override take precedence over the inherited ones, and properties which are not set are
+/// inherited as usual. Sections which have no corresponding entry use the inherited style.
+#[derive(Component, Default, Clone, Debug)]
+pub struct TextSpanStyles(pub Vec<InheritableFontStyles>);
+
+/// The justification and line breaking a text node had before an inherited style overrode
+/// them, so that they can be restored once no ancestor sets them any more.
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct OwnTextLayout {
+    justify: JustifyText,
+    line_break: BreakLineOn,
+}
+
+/// Propagates inherited text styles to text nodes. Rather than re-computing every text node
+/// each frame, only the subtrees whose styles may have changed are visited: those rooted at
+/// entities whose `InheritableFontStyles` changed or was removed, entities which were
+/// re-parented or detached from their parent, and text nodes whose text or span styles changed. Each subtree is walked
+/// top-down, so the inherited style is computed once per node rather than once per ancestor.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(crate) fn update_text_styles(
-    mut query: Query<(Entity, &mut Text), With<UseInheritedTextStyles>>,
-    inherited: Query<Ref<InheritableFontStyles>>,
+    mut commands: Commands,
+    mut texts: Query<
+        (
+            Entity,
+            &mut Text,
+            Option<Ref<TextSpanStyles>>,
+            Option<&OwnTextLayout>,
+        ),
+        With<UseInheritedTextStyles>,
+    >,
+    changed_styles: Query<Entity, Changed<InheritableFontStyles>>,
+    reparented: Query<Entity, Changed<Parent>>,
+    mut removed: RemovedComponents<InheritableFontStyles>,
+    mut orphaned: RemovedComponents<Parent>,
+    inherited: Query<&InheritableFontStyles>,
     parents: Query<&Parent>,
+    children: Query<&Children>,
+    families: Res<FontFamilies>,
 ) {
-    let inherited_changed = inherited.iter().any(|cmp| cmp.is_changed());
-    for (entity, mut text) in query.iter_mut() {
-        if text.is_changed() || inherited_changed {
-            let style = match compute_inherited_style(entity, &inherited, &parents) {
-                Some(value) => value,
-                None => continue,
-            };
+    let mut roots: HashSet<Entity> = HashSet::new();
+    // If the font families changed, fonts may resolve differently, so every text node needs
+    // to be re-computed.
+    let families_changed = families.is_changed();
+    roots.extend(
+        texts
+            .iter_mut()
+            .filter(|(_, text, span_styles, _)| {
+                families_changed
+                    || text.is_changed()
+                    || span_styles.as_ref().is_some_and(|s| s.is_changed())
+            })
+            .map(|(entity, ..)| entity),
+    );
+    roots.extend(changed_styles.iter());
+    roots.extend(reparented.iter());
+    roots.extend(removed.read());
+    roots.extend(orphaned.read());
+    if roots.is_empty() {
+        return;
+    }
 
-            let styles_changed = text.sections.iter().any(|section| {
-                section.style.font != style.font
-                    || section.style.font_size != style.font_size
-                    || section.style.color != style.color
-            });
-            if styles_changed {
-                for section in text.sections.iter_mut() {
-                    section.style = style.clone();
+    let mut stack: Vec<(Entity, Rc<InheritableFontStyles>)> = Vec::new();
+    for &root in roots.iter() {
+        // Skip roots which are inside another root's subtree, since they will be visited anyway.
+        let mut ancestor = root;
+        let mut nested = false;
+        while let Ok(parent) = parents.get(ancestor) {
+            ancestor = parent.get();
+            if roots.contains(&ancestor) {
+                nested = true;
+                break;
+            }
+        }
+        if nested {
+            continue;
+        }
+
+        let base = match parents.get(root) {
+            Ok(parent) => compute_inherited_style(parent.get(), &inherited, &parents),
+            Err(_) => InheritableFontStyles::default(),
+        };
+        stack.push((root, Rc::new(base)));
+        while let Some((entity, parent_style)) = stack.pop() {
+            let style = match inherited.get(entity) {
+                Ok(own) => {
+                    let mut merged = own.clone();
+                    merged.merge(&parent_style);
+                    Rc::new(merged)
                 }
+                Err(_) => parent_style,
+            };
+            if let Ok((_, mut text, span_styles, own_layout)) = texts.get_mut(entity) {
+                apply_text_style(&mut text, span_styles.as_deref(), &style, &families);
+                apply_text_layout(&mut commands, entity, &mut text, own_layout, &style);
+            }
+            if let Ok(children) = children.get(entity) {
+                stack.extend(children.iter().map(|child| (*child, style.clone())));
             }
         }
     }
 }
 
+/// Update the sections of a text node from the inherited style, merged with any per-section
+/// overrides. The text is only mutated if something actually changed.
+fn apply_text_style(
+    text: &mut Mut<Text>,
+    span_styles: Option<&TextSpanStyles>,
+    inherited_style: &InheritableFontStyles,
+    families: &FontFamilies,
+) {
+    let section_style = |index: usize| match span_styles.and_then(|spans| spans.0.get(index)) {
+        Some(span) => {
+            let mut merged = span.clone();
+            merged.merge(inherited_style);
+            to_text_style(&merged, families)
+        }
+        None => to_text_style(inherited_style, families),
+    };
+
+    let styles_changed = text.sections.iter().enumerate().any(|(index, section)| {
+        let style = section_style(index);
+        section.style.font != style.font
+            || section.style.font_size != style.font_size
+            || section.style.color != style.color
+    });
+    if styles_changed {
+        for (index, section) in text.sections.iter_mut().enumerate() {
+            section.style = section_style(index);
+        }
+    }
+}
+
+/// Justification and line breaking apply to the whole text, and are only overridden while some
+/// ancestor sets them. The text node's own settings are remembered in [`OwnTextLayout`] and
+/// restored once no ancestor sets either of them.
+fn apply_text_layout(
+    commands: &mut Commands,
+    entity: Entity,
+    text: &mut Mut<Text>,
+    own_layout: Option<&OwnTextLayout>,
+    inherited_style: &InheritableFontStyles,
+) {
+    let (justify, line_break) =
+        if inherited_style.justify.is_some() || inherited_style.line_break.is_some() {
+            let own = match own_layout {
+                Some(own) => *own,
+                None => {
+                    let own = OwnTextLayout {
+                        justify: text.justify,
+                        line_break: text.linebreak_behavior,
+                    };
+                    commands.entity(entity).insert(own);
+                    own
+                }
+            };
+            (
+                inherited_style.justify.unwrap_or(own.justify),
+                inherited_style.line_break.unwrap_or(own.line_break),
+            )
+        } else if let Some(own) = own_layout {
+            commands.entity(entity).remove::<OwnTextLayout>();
+            (own.justify, own.line_break)
+        } else {
+            return;
+        };
+    if text.justify != justify {
+        text.justify = justify;
+    }
+    if text.linebreak_behavior != line_break {
+        text.linebreak_behavior = line_break;
+    }
+}
+
 fn compute_inherited_style(
     entity: Entity,
-    inherited: &Query<Ref<InheritableFontStyles>, ()>,
-    parents: &Query<&Parent, ()>,
-) -> Option<TextStyle> {
+    inherited: &Query<&InheritableFontStyles>,
+    parents: &Query<&Parent>,
+) -> InheritableFontStyles {
     let mut styles = InheritableFontStyles::default();
     let mut ancestor = entity;
     loop {
@@ -84,7 +281,7 @@ fn compute_inherited_style(
             break;
         }
         if let Ok(inherited_styles) = inherited.get(ancestor) {
-            styles.merge(inherited_styles.as_ref());
+            styles.merge(inherited_styles);
             if styles.is_final() {
                 break;
             }
@@ -95,10 +292,189 @@ fn compute_inherited_style(
             break;
         }
     }
-    let style = TextStyle {
-        font: styles.font.unwrap_or_default(),
+    styles
+}
+
+/// Convert a set of inherited styles into a concrete `TextStyle`, using defaults for any
+/// properties that were not set.
+fn to_text_style(styles: &InheritableFontStyles, families: &FontFamilies) -> TextStyle {
+    let family_font = styles.family.as_ref().and_then(|family| {
+        families.get(
+            family,
+            styles.weight.unwrap_or_default(),
+            styles.italic.unwrap_or(false),
+        )
+    });
+    TextStyle {
+        font: family_font
+            .or(styles.font.as_ref())
+            .cloned()
+            .unwrap_or_default(),
         font_size: styles.font_size.unwrap_or(12.),
         color: styles.color.unwrap_or(Color::WHITE),
-    };
-    Some(style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::schedule::Schedule;
+
+    use super::*;
+
+    fn spawn_text(world: &mut World, parent: Entity) -> Entity {
+        world
+            .spawn((
+                Text::from_section("text", TextStyle::default()),
+                UseInheritedTextStyles,
+            ))
+            .set_parent(parent)
+            .id()
+    }
+
+    #[test]
+    fn test_update_text_styles() {
+        let mut world = World::new();
+        world.init_resource::<FontFamilies>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_text_styles);
+
+        let root = world
+            .spawn(InheritableFontStyles {
+                font_size: Some(20.),
+                justify: Some(JustifyText::Center),
+                ..default()
+            })
+            .id();
+        let panel_a = world
+            .spawn(InheritableFontStyles::default())
+            .set_parent(root)
+            .id();
+        let panel_b = world.spawn_empty().set_parent(root).id();
+        let text_a = spawn_text(&mut world, panel_a);
+        let text_b = spawn_text(&mut world, panel_b);
+        schedule.run(&mut world);
+
+        let text = world.get::<Text>(text_b).unwrap();
+        assert_eq!(text.sections[0].style.font_size, 20.);
+        assert_eq!(text.justify, JustifyText::Center);
+
+        // Changing one panel only touches the text nodes within it.
+        world.clear_trackers();
+        world
+            .get_mut::<InheritableFontStyles>(panel_a)
+            .unwrap()
+            .color = Some(Color::BLACK);
+        schedule.run(&mut world);
+        let text = world.entity(text_a).get_ref::<Text>().unwrap();
+        assert!(text.is_changed());
+        assert_eq!(text.sections[0].style.color, Color::BLACK);
+        assert!(!world.entity(text_b).get_ref::<Text>().unwrap().is_changed());
+
+        // Re-parented text picks up the styles of its new ancestors.
+        world.entity_mut(text_b).set_parent(panel_a);
+        schedule.run(&mut world);
+        let text = world.get::<Text>(text_b).unwrap();
+        assert_eq!(text.sections[0].style.color, Color::BLACK);
+
+        // Removing the styles reverts to the inherited ones.
+        world.entity_mut(panel_a).remove::<InheritableFontStyles>();
+        schedule.run(&mut world);
+        let text = world.get::<Text>(text_a).unwrap();
+        assert_eq!(text.sections[0].style.color, Color::WHITE);
+        assert_eq!(text.sections[0].style.font_size, 20.);
+    }
+
+    #[test]
+    fn test_unset_inherited_styles() {
+        let mut world = World::new();
+        world.init_resource::<FontFamilies>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_text_styles);
+
+        let root = world
+            .spawn(InheritableFontStyles {
+                font_size: Some(20.),
+                justify: Some(JustifyText::Center),
+                line_break: Some(BreakLineOn::AnyCharacter),
+                ..default()
+            })
+            .id();
+        let text = spawn_text(&mut world, root);
+        world.get_mut::<Text>(text).unwrap().justify = JustifyText::Right;
+        schedule.run(&mut world);
+        let t = world.get::<Text>(text).unwrap();
+        assert_eq!(t.justify, JustifyText::Center);
+        assert_eq!(t.linebreak_behavior, BreakLineOn::AnyCharacter);
+
+        // Un-setting one property restores the text's own value for it.
+        world
+            .get_mut::<InheritableFontStyles>(root)
+            .unwrap()
+            .justify = None;
+        schedule.run(&mut world);
+        let t = world.get::<Text>(text).unwrap();
+        assert_eq!(t.justify, JustifyText::Right);
+        assert_eq!(t.linebreak_behavior, BreakLineOn::AnyCharacter);
+
+        // Un-setting the other restores the text's own layout entirely.
+        world
+            .get_mut::<InheritableFontStyles>(root)
+            .unwrap()
+            .line_break = None;
+        schedule.run(&mut world);
+        let t = world.get::<Text>(text).unwrap();
+        assert_eq!(t.justify, JustifyText::Right);
+        assert_eq!(t.linebreak_behavior, BreakLineOn::WordBoundary);
+        assert!(world.get::<OwnTextLayout>(text).is_none());
+
+        // Detaching the text from its parent drops the inherited styles.
+        world
+            .get_mut::<InheritableFontStyles>(root)
+            .unwrap()
+            .justify = Some(JustifyText::Center);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Text>(text).unwrap().justify,
+            JustifyText::Center
+        );
+        world.entity_mut(text).remove_parent();
+        schedule.run(&mut world);
+        let t = world.get::<Text>(text).unwrap();
+        assert_eq!(t.justify, JustifyText::Right);
+        assert_eq!(t.sections[0].style.font_size, 12.);
+    }
+
+    #[test]
+    fn test_inherit_family_and_weight() {
+        let regular = Handle::<Font>::weak_from_u128(1);
+        let bold = Handle::<Font>::weak_from_u128(2);
+        let mut families = FontFamilies::default();
+        families
+            .add("Sans", FontWeight::Normal, false, regular.clone())
+            .add("Sans", FontWeight::Bold, false, bold.clone());
+
+        let parent = InheritableFontStyles {
+            family: Some("Sans".to_string()),
+            font_size: Some(16.),
+            ..default()
+        };
+        let mut child = InheritableFontStyles {
+            weight: Some(FontWeight::Bold),
+            ..default()
+        };
+        child.merge(&parent);
+        assert_eq!(child.family.as_deref(), Some("Sans"));
+        assert_eq!(to_text_style(&parent, &families).font, regular);
+        assert_eq!(to_text_style(&child, &families).font, bold);
+
+        // An explicit font on the child overrides the inherited family.
+        let mono = Handle::<Font>::weak_from_u128(3);
+        let mut child = InheritableFontStyles {
+            font: Some(mono.clone()),
+            ..default()
+        };
+        child.merge(&parent);
+        assert_eq!(child.family, None);
+        assert_eq!(to_text_style(&child, &families).font, mono);
+    }
 }
@@ -0,0 +1,466 @@
+use std::panic::Location;
+
+use bevy::{
+    color::{Alpha, Mix, Srgba},
+    math::{cubic_splines::CubicSegment, Vec2},
+    prelude::*,
+    ui,
+};
+
+/// A style property which can be animated using
+/// [`transition`](crate::StyleBuilderTransition::transition).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Transition {
+    BackgroundColor,
+    BorderColor,
+    OutlineColor,
+    OutlineWidth,
+    OutlineOffset,
+    BorderRadius,
+    Width,
+    Height,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Timing function for a [`Transition`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[allow(missing_docs)]
+pub enum Easing {
+    Linear,
+    /// Equivalent to the CSS `ease` timing function.
+    #[default]
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A cubic bezier curve with the given control points, as in CSS `cubic-bezier()`.
+    CubicBezier(Vec2, Vec2),
+}
+
+impl Easing {
+    /// Map a linear time value in the range 0..1 to the eased value.
+    pub fn ease(&self, t: f32) -> f32 {
+        let (p1, p2) = match *self {
+            Easing::Linear => return t,
+            Easing::Ease => (Vec2::new(0.25, 0.1), Vec2::new(0.25, 1.0)),
+            Easing::EaseIn => (Vec2::new(0.42, 0.0), Vec2::new(1.0, 1.0)),
+            Easing::EaseOut => (Vec2::new(0.0, 0.0), Vec2::new(0.58, 1.0)),
+            Easing::EaseInOut => (Vec2::new(0.42, 0.0), Vec2::new(0.58, 1.0)),
+            Easing::CubicBezier(p1, p2) => (p1, p2),
+        };
+        CubicSegment::new_bezier(p1, p2).ease(t)
+    }
+}
+
+/// The value of an animatable property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransitionValue {
+    Color(Option<Srgba>),
+    Length(ui::Val),
+    Radius(ui::BorderRadius),
+}
+
+impl TransitionValue {
+    fn mix(&self, target: &Self, t: f32) -> Self {
+        match (*self, *target) {
+            (TransitionValue::Color(a), TransitionValue::Color(b)) => {
+                // Transitions to or from `None` fade the other color in or out.
+                match (a, b) {
+                    (Some(a), Some(b)) => TransitionValue::Color(Some(a.mix(&b, t))),
+                    (Some(a), None) => TransitionValue::Color(Some(a.mix(&a.with_alpha(0.), t))),
+                    (None, Some(b)) => TransitionValue::Color(Some(b.with_alpha(0.).mix(&b, t))),
+                    (None, None) => TransitionValue::Color(None),
+                }
+            }
+            (TransitionValue::Length(a), TransitionValue::Length(b)) => {
+                TransitionValue::Length(mix_val(a, b, t))
+            }
+            (TransitionValue::Radius(a), TransitionValue::Radius(b)) => {
+                TransitionValue::Radius(ui::BorderRadius {
+                    top_left: mix_val(a.top_left, b.top_left, t),
+                    top_right: mix_val(a.top_right, b.top_right, t),
+                    bottom_right: mix_val(a.bottom_right, b.bottom_right, t),
+                    bottom_left: mix_val(a.bottom_left, b.bottom_left, t),
+                })
+            }
+            _ => *target,
+        }
+    }
+}
+
+/// Interpolate between two lengths. Lengths in different units can't be interpolated, so
+/// they change immediately.
+fn mix_val(a: ui::Val, b: ui::Val, t: f32) -> ui::Val {
+    match (a, b) {
+        (ui::Val::Px(a), ui::Val::Px(b)) => ui::Val::Px(a.lerp(b, t)),
+        (ui::Val::Percent(a), ui::Val::Percent(b)) => ui::Val::Percent(a.lerp(b, t)),
+        (ui::Val::Vw(a), ui::Val::Vw(b)) => ui::Val::Vw(a.lerp(b, t)),
+        (ui::Val::Vh(a), ui::Val::Vh(b)) => ui::Val::Vh(a.lerp(b, t)),
+        (ui::Val::VMin(a), ui::Val::VMin(b)) => ui::Val::VMin(a.lerp(b, t)),
+        (ui::Val::VMax(a), ui::Val::VMax(b)) => ui::Val::VMax(a.lerp(b, t)),
+        _ => b,
+    }
+}
+
+fn read_color(color: Option<Color>) -> TransitionValue {
+    TransitionValue::Color(color.map(Srgba::from))
+}
+
+impl Transition {
+    /// Read the current value of the property, or `None` if the entity doesn't have the
+    /// component which contains it.
+    fn read(self, target: &EntityWorldMut) -> Option<TransitionValue> {
+        let style_val = |get: fn(&ui::Style) -> ui::Val| {
+            target
+                .get::<ui::Style>()
+                .map(|style| TransitionValue::Length(get(style)))
+        };
+        match self {
+            Transition::BackgroundColor => {
+                Some(read_color(target.get::<ui::BackgroundColor>().map(|c| c.0)))
+            }
+            Transition::BorderColor => {
+                Some(read_color(target.get::<ui::BorderColor>().map(|c| c.0)))
+            }
+            Transition::OutlineColor => target
+                .get::<ui::Outline>()
+                .map(|o| read_color(Some(o.color))),
+            Transition::OutlineWidth => target
+                .get::<ui::Outline>()
+                .map(|o| TransitionValue::Length(o.width)),
+            Transition::OutlineOffset => target
+                .get::<ui::Outline>()
+                .map(|o| TransitionValue::Length(o.offset)),
+            Transition::BorderRadius => Some(TransitionValue::Radius(
+                target
+                    .get::<ui::BorderRadius>()
+                    .copied()
+                    .unwrap_or_default(),
+            )),
+            Transition::Width => style_val(|s| s.width),
+            Transition::Height => style_val(|s| s.height),
+            Transition::Left => style_val(|s| s.left),
+            Transition::Right => style_val(|s| s.right),
+            Transition::Top => style_val(|s| s.top),
+            Transition::Bottom => style_val(|s| s.bottom),
+        }
+    }
+
+    /// Write the value of the property.
+    fn write(self, target: &mut EntityWorldMut, value: TransitionValue) {
+        match (self, value) {
+            (Transition::BackgroundColor, TransitionValue::Color(color)) => match color {
+                Some(color) => {
+                    target.insert(ui::BackgroundColor(color.into()));
+                }
+                None => {
+                    target.remove::<ui::BackgroundColor>();
+                }
+            },
+            (Transition::BorderColor, TransitionValue::Color(color)) => match color {
+                Some(color) => {
+                    target.insert(ui::BorderColor(color.into()));
+                }
+                None => {
+                    target.remove::<ui::BorderColor>();
+                }
+            },
+            (Transition::OutlineColor, TransitionValue::Color(Some(color))) => {
+                if let Some(mut outline) = target.get_mut::<ui::Outline>() {
+                    outline.color = color.into();
+                }
+            }
+            (Transition::OutlineWidth, TransitionValue::Length(val)) => {
+                if let Some(mut outline) = target.get_mut::<ui::Outline>() {
+                    outline.width = val;
+                }
+            }
+            (Transition::OutlineOffset, TransitionValue::Length(val)) => {
+                if let Some(mut outline) = target.get_mut::<ui::Outline>() {
+                    outline.offset = val;
+                }
+            }
+            (Transition::BorderRadius, TransitionValue::Radius(radius)) => {
+                target.insert(radius);
+            }
+            (_, TransitionValue::Length(val)) => {
+                if let Some(mut style) = target.get_mut::<ui::Style>() {
+                    match self {
+                        Transition::Width => style.width = val,
+                        Transition::Height => style.height = val,
+                        Transition::Left => style.left = val,
+                        Transition::Right => style.right = val,
+                        Transition::Top => style.top = val,
+                        Transition::Bottom => style.bottom = val,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct ActiveTransition {
+    origin: TransitionValue,
+    target: TransitionValue,
+    clock: f32,
+}
+
+/// A transition declared by a style function.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct TransitionDecl {
+    pub(crate) property: Transition,
+    pub(crate) duration: f32,
+    pub(crate) easing: Easing,
+    /// The style function which declared the transition; see
+    /// [`StyleBuilder::with_source`](crate::StyleBuilder::with_source).
+    pub(crate) source: &'static Location<'static>,
+}
+
+struct TransitionState {
+    decl: TransitionDecl,
+    /// The value of the property as of the last update; if the property has a different value,
+    /// then it was changed by a style, and a new transition is started.
+    displayed: Option<TransitionValue>,
+    active: Option<ActiveTransition>,
+}
+
+/// Component which holds the transitions declared for an entity.
+#[derive(Component, Default)]
+pub(crate) struct StyleTransitions(Vec<TransitionState>);
+
+impl StyleTransitions {
+    pub(crate) fn new(decls: impl IntoIterator<Item = TransitionDecl>) -> Self {
+        let mut transitions = Self::default();
+        transitions.set_decls(decls);
+        transitions
+    }
+
+    /// The declared transitions.
+    pub(crate) fn decls(&self) -> impl Iterator<Item = TransitionDecl> + '_ {
+        self.0.iter().map(|state| state.decl)
+    }
+
+    /// Replace the declared transitions, keeping the animation state of properties which are
+    /// still declared. If a property is declared more than once, the last declaration wins.
+    pub(crate) fn set_decls(&mut self, decls: impl IntoIterator<Item = TransitionDecl>) {
+        let mut previous = std::mem::take(&mut self.0);
+        for decl in decls {
+            if let Some(state) = self.0.iter_mut().find(|s| s.decl.property == decl.property) {
+                state.decl = decl;
+                continue;
+            }
+            let (displayed, active) = match previous
+                .iter()
+                .position(|s| s.decl.property == decl.property)
+            {
+                Some(index) => {
+                    let state = previous.swap_remove(index);
+                    (state.displayed, state.active)
+                }
+                None => (None, None),
+            };
+            self.0.push(TransitionState {
+                decl,
+                displayed,
+                active,
+            });
+        }
+    }
+}
+
+/// Detects changes to properties which have transitions, and animates them from their
+/// previous value.
+pub(crate) fn update_style_transitions(
+    world: &mut World,
+    query: &mut QueryState<Entity, With<StyleTransitions>>,
+) {
+    let entities: Vec<Entity> = query.iter(world).collect();
+    if entities.is_empty() {
+        return;
+    }
+    let delta = world
+        .get_resource::<Time>()
+        .map(|time| time.delta_seconds())
+        .unwrap_or_default();
+    for entity in entities {
+        let mut target = world.entity_mut(entity);
+        let mut transitions = std::mem::take(
+            &mut target
+                .get_mut::<StyleTransitions>()
+                .unwrap()
+                .bypass_change_detection()
+                .0,
+        );
+        for state in transitions.iter_mut() {
+            let Some(current) = state.decl.property.read(&target) else {
+                continue;
+            };
+            match state.displayed {
+                None => state.displayed = Some(current),
+                // If we're already animating to the same target, don't restart.
+                Some(displayed)
+                    if displayed != current
+                        && state.active.as_ref().map(|a| a.target) != Some(current) =>
+                {
+                    state.active = Some(ActiveTransition {
+                        origin: displayed,
+                        target: current,
+                        clock: 0.,
+                    });
+                }
+                _ => {}
+            }
+
+            if let Some(active) = state.active.as_mut() {
+                active.clock += delta;
+                let t = if state.decl.duration > 0.0001 {
+                    (active.clock / state.decl.duration).min(1.0)
+                } else {
+                    1.0
+                };
+                let value = if t >= 1.0 {
+                    active.target
+                } else {
+                    active.origin.mix(&active.target, state.decl.easing.ease(t))
+                };
+                if t >= 1.0 {
+                    state.active = None;
+                }
+                if value != current {
+                    state.decl.property.write(&mut target, value);
+                }
+                state.displayed = state.decl.property.read(&target);
+            }
+        }
+        target
+            .get_mut::<StyleTransitions>()
+            .unwrap()
+            .bypass_change_detection()
+            .0 = transitions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_easing() {
+        assert_eq!(Easing::Linear.ease(0.5), 0.5);
+        assert!(Easing::EaseIn.ease(0.5) < 0.5);
+        assert!(Easing::EaseOut.ease(0.5) > 0.5);
+        assert!((Easing::EaseInOut.ease(1.0) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_transition() {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        let transitions = StyleTransitions::new(
+            [Transition::Width, Transition::BackgroundColor].map(|property| TransitionDecl {
+                property,
+                duration: 1.0,
+                easing: Easing::Linear,
+                source: Location::caller(),
+            }),
+        );
+        let entity = world
+            .spawn((
+                ui::Style {
+                    width: ui::Val::Px(10.),
+                    ..default()
+                },
+                transitions,
+            ))
+            .id();
+        let mut system = IntoSystem::into_system(update_style_transitions);
+        system.initialize(&mut world);
+
+        // The initial values are not animated.
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(entity).unwrap().width,
+            ui::Val::Px(10.)
+        );
+
+        // Subsequent changes are animated from the current value.
+        world.get_mut::<ui::Style>(entity).unwrap().width = ui::Val::Px(20.);
+        world
+            .entity_mut(entity)
+            .insert(ui::BackgroundColor(Srgba::RED.into()));
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(250));
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(entity).unwrap().width,
+            ui::Val::Px(12.5)
+        );
+        let color = Srgba::from(world.get::<ui::BackgroundColor>(entity).unwrap().0);
+        assert!((color.alpha - 0.25).abs() < 0.001);
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(1000));
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(entity).unwrap().width,
+            ui::Val::Px(20.)
+        );
+        assert_eq!(
+            world.get::<ui::BackgroundColor>(entity).unwrap().0,
+            Srgba::RED.into()
+        );
+    }
+
+    #[test]
+    fn test_replace_transitions() {
+        use crate::{StyleBuilder, StyleBuilderTransition};
+
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let properties = |world: &World| {
+            world
+                .get::<StyleTransitions>(entity)
+                .map(|t| t.decls().map(|d| d.property).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        let source = Location::caller();
+        let build = |world: &mut World, properties: &[Transition]| {
+            let mut target = world.entity_mut(entity);
+            let mut sb = StyleBuilder::new(&mut target, ui::Style::default()).with_source(source);
+            for property in properties {
+                sb.transition(*property, 1.0, Easing::Linear);
+            }
+            sb.finish();
+        };
+
+        // A transition from another style function, which is kept.
+        {
+            let mut target = world.entity_mut(entity);
+            let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+            sb.transition(Transition::Left, 1.0, Easing::Linear);
+            sb.finish();
+        }
+
+        build(&mut world, &[Transition::Width, Transition::Height]);
+        assert_eq!(
+            properties(&world),
+            vec![Transition::Left, Transition::Width, Transition::Height]
+        );
+
+        build(&mut world, &[Transition::Width]);
+        assert_eq!(
+            properties(&world),
+            vec![Transition::Left, Transition::Width]
+        );
+    }
+}
@@ -0,0 +1,607 @@
+//! Relative length units, which are resolved against the [`StyleUnits`] resource or the
+//! inherited font size.
+
+use bevy::{prelude::*, ui, utils::HashSet};
+
+use crate::text_styles::InheritableFontStyles;
+
+/// A length in multiples of the root font size, [`StyleUnits::rem`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rem(pub f32);
+
+/// A length in multiples of the inherited font size, as set by
+/// [`font_size`](crate::StyleBuilderFont::font_size). When used for the font size itself, it is
+/// relative to the font size of the parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Em(pub f32);
+
+/// A length in multiples of the theme spacing step, [`StyleUnits::space`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Space(pub f32);
+
+/// Resource which defines the size of relative length units. When this resource is modified,
+/// any style properties which were set using relative units are re-computed.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct StyleUnits {
+    /// Size of the root font, in pixels, used by [`Rem`] units, and by [`Em`] units when
+    /// there is no inherited font size.
+    pub rem: f32,
+    /// Size of a spacing step, in pixels, used by [`Space`] units.
+    pub space: f32,
+    /// Global scale factor which is applied to all relative units. Pixel lengths are not
+    /// affected.
+    pub scale: f32,
+}
+
+impl Default for StyleUnits {
+    fn default() -> Self {
+        Self {
+            rem: 16.,
+            space: 4.,
+            scale: 1.,
+        }
+    }
+}
+
+/// A length which may be relative to the [`StyleUnits`] or the inherited font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// An absolute length.
+    Val(ui::Val),
+    /// See [`Rem`].
+    Rem(f32),
+    /// See [`Em`].
+    Em(f32),
+    /// See [`Space`].
+    Space(f32),
+}
+
+impl Length {
+    /// Resolve the length to a [`ui::Val`], given the font size to use for [`Em`] units.
+    pub fn resolve(&self, units: &StyleUnits, font_size: f32) -> ui::Val {
+        match *self {
+            Length::Val(val) => val,
+            Length::Rem(n) => ui::Val::Px(n * units.rem * units.scale),
+            Length::Em(n) => ui::Val::Px(n * font_size),
+            Length::Space(n) => ui::Val::Px(n * units.space * units.scale),
+        }
+    }
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Length::Val(ui::Val::Auto)
+    }
+}
+
+impl From<ui::Val> for Length {
+    fn from(val: ui::Val) -> Self {
+        Length::Val(val)
+    }
+}
+
+/// Lengths for each edge of a rectangle, such as margins or padding.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[allow(missing_docs)]
+pub struct LengthRect {
+    pub left: Length,
+    pub right: Length,
+    pub top: Length,
+    pub bottom: Length,
+}
+
+impl LengthRect {
+    /// The same length for all edges.
+    pub fn all(length: Length) -> Self {
+        Self {
+            left: length,
+            right: length,
+            top: length,
+            bottom: length,
+        }
+    }
+
+    /// Separate lengths for the horizontal and vertical edges.
+    pub fn axes(horizontal: Length, vertical: Length) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+impl From<ui::UiRect> for LengthRect {
+    fn from(rect: ui::UiRect) -> Self {
+        Self {
+            left: rect.left.into(),
+            right: rect.right.into(),
+            top: rect.top.into(),
+            bottom: rect.bottom.into(),
+        }
+    }
+}
+
+/// Lengths for each corner of a rectangle, used for border radius.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[allow(missing_docs)]
+pub struct LengthCorners {
+    pub top_left: Length,
+    pub top_right: Length,
+    pub bottom_right: Length,
+    pub bottom_left: Length,
+}
+
+impl LengthCorners {
+    /// The same length for all corners.
+    pub fn all(length: Length) -> Self {
+        Self {
+            top_left: length,
+            top_right: length,
+            bottom_right: length,
+            bottom_left: length,
+        }
+    }
+}
+
+impl From<ui::BorderRadius> for LengthCorners {
+    fn from(radius: ui::BorderRadius) -> Self {
+        Self {
+            top_left: radius.top_left.into(),
+            top_right: radius.top_right.into(),
+            bottom_right: radius.bottom_right.into(),
+            bottom_left: radius.bottom_left.into(),
+        }
+    }
+}
+
+/// Identifies a style property which can be set using a relative length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LengthProperty {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Width,
+    Height,
+    MinWidth,
+    MinHeight,
+    MaxWidth,
+    MaxHeight,
+    MarginLeft,
+    MarginRight,
+    MarginTop,
+    MarginBottom,
+    PaddingLeft,
+    PaddingRight,
+    PaddingTop,
+    PaddingBottom,
+    BorderLeft,
+    BorderRight,
+    BorderTop,
+    BorderBottom,
+    FlexBasis,
+    RowGap,
+    ColumnGap,
+    OutlineWidth,
+    OutlineOffset,
+    RadiusTopLeft,
+    RadiusTopRight,
+    RadiusBottomRight,
+    RadiusBottomLeft,
+    FontSize,
+}
+
+impl LengthProperty {
+    pub(crate) const MARGIN: [Self; 4] = [
+        Self::MarginLeft,
+        Self::MarginRight,
+        Self::MarginTop,
+        Self::MarginBottom,
+    ];
+    pub(crate) const PADDING: [Self; 4] = [
+        Self::PaddingLeft,
+        Self::PaddingRight,
+        Self::PaddingTop,
+        Self::PaddingBottom,
+    ];
+    pub(crate) const BORDER: [Self; 4] = [
+        Self::BorderLeft,
+        Self::BorderRight,
+        Self::BorderTop,
+        Self::BorderBottom,
+    ];
+
+    /// Returns the field of `Style` which holds this property, if any.
+    fn style_field(self, style: &mut ui::Style) -> Option<&mut ui::Val> {
+        Some(match self {
+            Self::Left => &mut style.left,
+            Self::Right => &mut style.right,
+            Self::Top => &mut style.top,
+            Self::Bottom => &mut style.bottom,
+            Self::Width => &mut style.width,
+            Self::Height => &mut style.height,
+            Self::MinWidth => &mut style.min_width,
+            Self::MinHeight => &mut style.min_height,
+            Self::MaxWidth => &mut style.max_width,
+            Self::MaxHeight => &mut style.max_height,
+            Self::MarginLeft => &mut style.margin.left,
+            Self::MarginRight => &mut style.margin.right,
+            Self::MarginTop => &mut style.margin.top,
+            Self::MarginBottom => &mut style.margin.bottom,
+            Self::PaddingLeft => &mut style.padding.left,
+            Self::PaddingRight => &mut style.padding.right,
+            Self::PaddingTop => &mut style.padding.top,
+            Self::PaddingBottom => &mut style.padding.bottom,
+            Self::BorderLeft => &mut style.border.left,
+            Self::BorderRight => &mut style.border.right,
+            Self::BorderTop => &mut style.border.top,
+            Self::BorderBottom => &mut style.border.bottom,
+            Self::FlexBasis => &mut style.flex_basis,
+            Self::RowGap => &mut style.row_gap,
+            Self::ColumnGap => &mut style.column_gap,
+            _ => return None,
+        })
+    }
+}
+
+/// Component which records the style properties of an entity that were set using relative
+/// units, so that they can be re-computed when the units change.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub(crate) struct RelativeLengths(Vec<(LengthProperty, Length)>);
+
+impl RelativeLengths {
+    fn uses_em(&self) -> bool {
+        self.0.iter().any(|(_, l)| matches!(l, Length::Em(_)))
+    }
+}
+
+/// Records the length used for a property: relative lengths are added to the entity's
+/// [`RelativeLengths`], absolute lengths remove any previous relative length.
+pub(crate) fn record_length(target: &mut EntityWorldMut, property: LengthProperty, length: Length) {
+    let relative = !matches!(length, Length::Val(_));
+    match target.get::<RelativeLengths>() {
+        Some(lengths) => {
+            let index = lengths.0.iter().position(|(p, _)| *p == property);
+            match (index, relative) {
+                (Some(index), true) if lengths.0[index].1 != length => {
+                    target.get_mut::<RelativeLengths>().unwrap().0[index].1 = length;
+                }
+                (Some(index), false) => {
+                    target.get_mut::<RelativeLengths>().unwrap().0.remove(index);
+                }
+                (None, true) => {
+                    target
+                        .get_mut::<RelativeLengths>()
+                        .unwrap()
+                        .0
+                        .push((property, length));
+                }
+                _ => {}
+            }
+        }
+        None if relative => {
+            target.insert(RelativeLengths(vec![(property, length)]));
+        }
+        None => {}
+    }
+}
+
+/// Resolve a length for the given property of the target entity.
+pub(crate) fn resolve_length(
+    target: &EntityWorldMut,
+    property: LengthProperty,
+    length: Length,
+) -> ui::Val {
+    match length {
+        Length::Val(val) => val,
+        _ => {
+            let world = target.world();
+            let units = world
+                .get_resource::<StyleUnits>()
+                .cloned()
+                .unwrap_or_default();
+            let font_size = match length {
+                Length::Em(_) => em_size(world, target.id(), property, &units),
+                _ => 0.,
+            };
+            length.resolve(&units, font_size)
+        }
+    }
+}
+
+/// The font size used by [`Em`] units for the given property.
+fn em_size(world: &World, entity: Entity, property: LengthProperty, units: &StyleUnits) -> f32 {
+    let start = match property {
+        LengthProperty::FontSize => world.get::<Parent>(entity).map(|p| p.get()),
+        _ => Some(entity),
+    };
+    start
+        .and_then(|e| inherited_font_size(world, e))
+        .unwrap_or(units.rem * units.scale)
+}
+
+fn inherited_font_size(world: &World, entity: Entity) -> Option<f32> {
+    let mut ancestor = entity;
+    loop {
+        if let Some(size) = world
+            .get::<InheritableFontStyles>(ancestor)
+            .and_then(|s| s.font_size)
+        {
+            return Some(size);
+        }
+        ancestor = world.get::<Parent>(ancestor)?.get();
+    }
+}
+
+/// Re-computes relative lengths when the [`StyleUnits`] change, or when the inherited font
+/// size of an entity which uses [`Em`] units may have changed.
+pub(crate) fn update_relative_lengths(
+    world: &mut World,
+    query: &mut QueryState<(Entity, Ref<RelativeLengths>)>,
+    fonts: &mut QueryState<Entity, Changed<InheritableFontStyles>>,
+    reparented: &mut QueryState<Entity, Changed<Parent>>,
+) {
+    let units_changed = world.is_resource_changed::<StyleUnits>();
+    let mut changed: Vec<(Entity, RelativeLengths)> = query
+        .iter(world)
+        .filter(|(_, lengths)| units_changed || lengths.is_changed())
+        .map(|(entity, lengths)| (entity, lengths.clone()))
+        .collect();
+
+    // The inherited font size may have changed for entities whose font styles were changed or
+    // removed, or which were re-parented, and for their descendants. These are visited
+    // parent-first, so that a relative font size is resolved before its children use it.
+    if !units_changed {
+        let mut stack: Vec<Entity> = fonts.iter(world).chain(reparented.iter(world)).collect();
+        stack.extend(world.removed::<InheritableFontStyles>());
+        let mut queued: HashSet<Entity> = changed.iter().map(|(entity, _)| *entity).collect();
+        let mut visited = HashSet::new();
+        stack.reverse();
+        while let Some(entity) = stack.pop() {
+            if !visited.insert(entity) {
+                continue;
+            }
+            if let Ok((_, lengths)) = query.get(world, entity) {
+                if lengths.uses_em() && queued.insert(entity) {
+                    changed.push((entity, lengths.clone()));
+                }
+            }
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.extend(children.iter().rev());
+            }
+        }
+    }
+    if changed.is_empty() {
+        return;
+    }
+
+    let units = world
+        .get_resource::<StyleUnits>()
+        .cloned()
+        .unwrap_or_default();
+    for (entity, lengths) in changed {
+        // The font size needs to be resolved first, since `Em` units depend on it.
+        if let Some((_, length)) = lengths
+            .0
+            .iter()
+            .find(|(p, _)| *p == LengthProperty::FontSize)
+        {
+            let size = length.resolve(
+                &units,
+                em_size(world, entity, LengthProperty::FontSize, &units),
+            );
+            if let ui::Val::Px(size) = size {
+                if let Some(mut styles) = world.get_mut::<InheritableFontStyles>(entity) {
+                    if styles.font_size != Some(size) {
+                        styles.font_size = Some(size);
+                    }
+                }
+            }
+        }
+
+        let font_size = inherited_font_size(world, entity).unwrap_or(units.rem * units.scale);
+        let mut target = world.entity_mut(entity);
+        let mut style = target.get::<ui::Style>().cloned().unwrap_or_default();
+        let mut outline = target.get::<ui::Outline>().copied();
+        let mut radius = target.get::<ui::BorderRadius>().copied();
+        for (property, length) in lengths.0.iter() {
+            let val = length.resolve(&units, font_size);
+            if let Some(field) = property.style_field(&mut style) {
+                *field = val;
+                continue;
+            }
+            match (property, outline.as_mut(), radius.as_mut()) {
+                (LengthProperty::OutlineWidth, Some(outline), _) => outline.width = val,
+                (LengthProperty::OutlineOffset, Some(outline), _) => outline.offset = val,
+                (LengthProperty::RadiusTopLeft, _, Some(radius)) => radius.top_left = val,
+                (LengthProperty::RadiusTopRight, _, Some(radius)) => radius.top_right = val,
+                (LengthProperty::RadiusBottomRight, _, Some(radius)) => radius.bottom_right = val,
+                (LengthProperty::RadiusBottomLeft, _, Some(radius)) => radius.bottom_left = val,
+                _ => {}
+            }
+        }
+        if let Some(mut current) = target.get_mut::<ui::Style>() {
+            current.set_if_neq(style);
+        }
+        if let (Some(mut current), Some(outline)) = (target.get_mut::<ui::Outline>(), outline) {
+            current.set_if_neq(outline);
+        }
+        if let (Some(mut current), Some(radius)) = (target.get_mut::<ui::BorderRadius>(), radius) {
+            current.set_if_neq(radius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let units = StyleUnits {
+            rem: 10.,
+            space: 4.,
+            scale: 1.5,
+        };
+        assert_eq!(
+            Length::Val(ui::Val::Px(3.)).resolve(&units, 12.),
+            ui::Val::Px(3.)
+        );
+        assert_eq!(Length::Rem(2.).resolve(&units, 12.), ui::Val::Px(30.));
+        assert_eq!(Length::Space(2.).resolve(&units, 12.), ui::Val::Px(12.));
+        assert_eq!(Length::Em(0.5).resolve(&units, 12.), ui::Val::Px(6.));
+    }
+
+    #[test]
+    fn test_update_relative_lengths() {
+        let mut world = World::new();
+        world.insert_resource(StyleUnits::default());
+        let parent = world
+            .spawn(InheritableFontStyles {
+                font_size: Some(20.),
+                ..default()
+            })
+            .id();
+        let child = world
+            .spawn((
+                ui::Style::default(),
+                RelativeLengths(vec![
+                    (LengthProperty::Width, Length::Space(2.)),
+                    (LengthProperty::PaddingLeft, Length::Em(1.)),
+                ]),
+            ))
+            .set_parent(parent)
+            .id();
+        let mut system = IntoSystem::into_system(update_relative_lengths);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let style = world.get::<ui::Style>(child).unwrap();
+        assert_eq!(style.width, ui::Val::Px(8.));
+        assert_eq!(style.padding.left, ui::Val::Px(20.));
+
+        world.resource_mut::<StyleUnits>().scale = 2.;
+        system.run((), &mut world);
+        let style = world.get::<ui::Style>(child).unwrap();
+        assert_eq!(style.width, ui::Val::Px(16.));
+        assert_eq!(style.padding.left, ui::Val::Px(20.));
+    }
+
+    #[test]
+    fn test_update_inherited_font_size() {
+        let mut world = World::new();
+        world.insert_resource(StyleUnits::default());
+        let parent = world
+            .spawn(InheritableFontStyles {
+                font_size: Some(20.),
+                ..default()
+            })
+            .id();
+        let child = world
+            .spawn((
+                ui::Style::default(),
+                RelativeLengths(vec![(LengthProperty::Width, Length::Em(1.))]),
+            ))
+            .set_parent(parent)
+            .id();
+        let other = world
+            .spawn((
+                ui::Style::default(),
+                InheritableFontStyles::default(),
+                RelativeLengths(vec![(LengthProperty::Width, Length::Em(1.))]),
+            ))
+            .id();
+        let mut system = IntoSystem::into_system(update_relative_lengths);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(child).unwrap().width,
+            ui::Val::Px(20.)
+        );
+        assert_eq!(
+            world.get::<ui::Style>(other).unwrap().width,
+            ui::Val::Px(16.)
+        );
+
+        // Changing the parent's font only updates its descendants.
+        world.get_mut::<ui::Style>(other).unwrap().width = ui::Val::Auto;
+        world
+            .get_mut::<InheritableFontStyles>(parent)
+            .unwrap()
+            .font_size = Some(10.);
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(child).unwrap().width,
+            ui::Val::Px(10.)
+        );
+        assert_eq!(world.get::<ui::Style>(other).unwrap().width, ui::Val::Auto);
+
+        // As does removing it.
+        world.entity_mut(parent).remove::<InheritableFontStyles>();
+        system.run((), &mut world);
+        assert_eq!(
+            world.get::<ui::Style>(child).unwrap().width,
+            ui::Val::Px(16.)
+        );
+        assert_eq!(world.get::<ui::Style>(other).unwrap().width, ui::Val::Auto);
+    }
+
+    #[test]
+    fn test_length_params() {
+        use crate::{BorderRadiusParam, LengthParam, UiRectParam};
+
+        assert_eq!(Rem(2.).to_val(), ui::Val::Px(32.));
+        assert_eq!(Rem(2.).to_length(), Length::Rem(2.));
+        assert_eq!(4.0.to_val(), ui::Val::Px(4.));
+        assert_eq!(
+            (Space(1.), 2.).to_uirect(),
+            ui::UiRect::axes(ui::Val::Px(4.), ui::Val::Px(2.))
+        );
+        assert_eq!(
+            (Space(1.), 2.).to_length_rect(),
+            LengthRect::axes(Length::Space(1.), Length::Val(ui::Val::Px(2.)))
+        );
+        assert_eq!(
+            Em(0.5).to_border_radius(),
+            ui::BorderRadius::all(ui::Val::Px(8.))
+        );
+        assert_eq!(
+            Em(0.5).to_length_corners(),
+            LengthCorners::all(Length::Em(0.5))
+        );
+    }
+
+    #[test]
+    fn test_font_size() {
+        use crate::{StyleBuilder, StyleBuilderFont};
+
+        let mut world = World::new();
+        let parent = world
+            .spawn(InheritableFontStyles {
+                font_size: Some(20.),
+                ..default()
+            })
+            .id();
+        let child = world.spawn_empty().set_parent(parent).id();
+        let font_size = |world: &World| {
+            world
+                .get::<InheritableFontStyles>(child)
+                .and_then(|styles| styles.font_size)
+        };
+
+        let mut target = world.entity_mut(child);
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        sb.font_size(ui::Val::Percent(50.));
+        sb.finish();
+        assert_eq!(font_size(&world), Some(10.));
+
+        // Unsupported units leave the font size unchanged.
+        let mut target = world.entity_mut(child);
+        let mut sb = StyleBuilder::new(&mut target, ui::Style::default());
+        sb.font_size(ui::Val::Vw(5.));
+        sb.finish();
+        assert_eq!(font_size(&world), Some(10.));
+    }
+}
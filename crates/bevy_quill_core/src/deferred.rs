@@ -0,0 +1,563 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::{
+    core::Name,
+    hierarchy::{BuildWorldChildren, Parent},
+    prelude::{Component, Entity, Resource, World},
+    utils::{HashMap, Instant},
+};
+
+#[cfg(feature = "verbose")]
+use bevy::log::info;
+
+use crate::{
+    cx::Cx, tracking_scope::TrackingScope, AnyViewAdapter, NodeSpan, OutputChanged, View, ViewThunk,
+};
+
+/// Resource which enables time-sliced building of [`Deferred`] views. If this resource is
+/// present, then deferred views are not built immediately; instead, a placeholder is displayed,
+/// and the deferred views are built in later frames, as many per frame as fit within the
+/// budget. If this resource is not present, deferred views are built immediately.
+///
+/// The budget includes the time spent building and reacting to changes in other views during
+/// the same frame. At least one deferred view is built each frame, so that progress is always
+/// made.
+#[derive(Resource, Debug, Clone)]
+pub struct RebuildBudget {
+    /// Maximum amount of time per frame to spend building views.
+    pub frame_budget: Duration,
+
+    /// Start time of the current frame's view updates.
+    pub(crate) frame_start: Option<Instant>,
+}
+
+impl RebuildBudget {
+    /// Construct a new `RebuildBudget` with the given per-frame time budget.
+    pub fn new(frame_budget: Duration) -> Self {
+        Self {
+            frame_budget,
+            frame_start: None,
+        }
+    }
+}
+
+impl Default for RebuildBudget {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(4))
+    }
+}
+
+/// Marker component on the view entity of a [`Deferred`] view that has not yet been built.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PendingBuild {
+    /// Pending views with higher priority are built first. This is never greater than the
+    /// priority of a pending ancestor.
+    pub priority: i32,
+}
+
+/// Ordering of a queued view: the highest priority first, then the shallowest, then the one
+/// queued first.
+type QueueOrder = (Reverse<i32>, usize, u64);
+
+/// Queue of deferred views waiting to be built, so that the pending views don't have to be
+/// found by searching the view hierarchy each frame.
+#[derive(Resource, Default)]
+pub(crate) struct DeferredQueue {
+    heap: BinaryHeap<Reverse<(QueueOrder, Entity)>>,
+    /// The current ordering of each queued view. Changing the priority of a view pushes a new
+    /// heap entry, and the stale entry is skipped when it is popped.
+    queued: HashMap<Entity, QueueOrder>,
+    next_seq: u64,
+}
+
+impl DeferredQueue {
+    /// Add a view to the queue. `depth` is the depth of the view entity in the view hierarchy.
+    fn push(&mut self, entity: Entity, priority: i32, depth: usize) {
+        let order = (Reverse(priority), depth, self.next_seq);
+        self.next_seq += 1;
+        self.queued.insert(entity, order);
+        self.heap.push(Reverse((order, entity)));
+    }
+
+    fn set_priority(&mut self, entity: Entity, priority: i32) {
+        if let Some(order) = self.queued.get_mut(&entity) {
+            if order.0 != Reverse(priority) {
+                order.0 = Reverse(priority);
+                self.heap.push(Reverse((*order, entity)));
+            }
+        }
+    }
+
+    /// Remove and return the next view to build: the one with the highest priority, then the
+    /// shallowest, then the one queued first. Since a view's priority is clamped to that of its
+    /// pending ancestors, ancestors are always built before their descendants.
+    fn pop(&mut self) -> Option<Entity> {
+        while let Some(Reverse((order, entity))) = self.heap.pop() {
+            if self.queued.get(&entity) == Some(&order) {
+                self.queued.remove(&entity);
+                return Some(entity);
+            }
+        }
+        None
+    }
+
+    fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+}
+
+/// Returns the depth of the entity in the view hierarchy, and the priority clamped to that of
+/// the nearest pending ancestor.
+fn clamp_priority(world: &World, entity: Entity, priority: i32) -> (usize, i32) {
+    let mut depth = 0;
+    let mut priority = priority;
+    let mut clamped = false;
+    let mut current = entity;
+    while let Some(parent) = world.get::<Parent>(current) {
+        current = parent.get();
+        depth += 1;
+        if !clamped {
+            if let Some(pending) = world.get::<PendingBuild>(current) {
+                priority = priority.min(pending.priority);
+                clamped = true;
+            }
+        }
+    }
+    (depth, priority)
+}
+
+/// A view which defers building its content when a [`RebuildBudget`] is present, displaying
+/// a placeholder view in the meantime. Deferred views are built in top-down order, so
+/// a deferred view is never built before its ancestors. Use this for large, low-priority
+/// subtrees which would otherwise cause a hitch when mounted.
+///
+/// Like [`ViewTemplate`](crate::ViewTemplate), the content of a `Deferred` is managed by
+/// a separate view entity with its own tracking scope.
+#[derive(Clone, PartialEq)]
+pub struct Deferred<V: View + Clone + PartialEq, P: View + Clone + PartialEq = ()> {
+    view: V,
+    placeholder: P,
+    priority: i32,
+}
+
+impl<V: View + Clone + PartialEq> Deferred<V, ()> {
+    /// Construct a new `Deferred` view, which displays nothing until it is built.
+    pub fn new(view: V) -> Self {
+        Self {
+            view,
+            placeholder: (),
+            priority: 0,
+        }
+    }
+}
+
+impl<V: View + Clone + PartialEq, P: View + Clone + PartialEq> Deferred<V, P> {
+    /// Set the view to display until the deferred content is built.
+    pub fn placeholder<P2: View + Clone + PartialEq>(self, placeholder: P2) -> Deferred<V, P2> {
+        Deferred {
+            view: self.view,
+            placeholder,
+            priority: self.priority,
+        }
+    }
+
+    /// Set the priority of this view relative to other pending deferred views. Views with
+    /// higher priority are built first.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+enum DeferredContent<V: View, P: View> {
+    Placeholder(P::State),
+    Built(V::State),
+}
+
+struct DeferredState<V: View + Clone + PartialEq, P: View + Clone + PartialEq> {
+    deferred: Deferred<V, P>,
+    content: DeferredContent<V, P>,
+}
+
+impl<V: View + Clone + PartialEq, P: View + Clone + PartialEq> DeferredState<V, P> {
+    fn nodes(&self, world: &World) -> NodeSpan {
+        match &self.content {
+            DeferredContent::Placeholder(state) => self.deferred.placeholder.nodes(world, state),
+            DeferredContent::Built(state) => self.deferred.view.nodes(world, state),
+        }
+    }
+
+    fn rebuild(&mut self, cx: &mut Cx, pending: bool) -> bool {
+        match &mut self.content {
+            DeferredContent::Placeholder(state) if pending => {
+                self.deferred.placeholder.rebuild(cx, state)
+            }
+            DeferredContent::Placeholder(state) => {
+                self.deferred.placeholder.raze(cx.world_mut(), state);
+                self.content = DeferredContent::Built(self.deferred.view.build(cx));
+                true
+            }
+            DeferredContent::Built(state) => self.deferred.view.rebuild(cx, state),
+        }
+    }
+
+    fn attach_children(&mut self, world: &mut World) -> bool {
+        match &mut self.content {
+            DeferredContent::Placeholder(state) => {
+                self.deferred.placeholder.attach_children(world, state)
+            }
+            DeferredContent::Built(state) => self.deferred.view.attach_children(world, state),
+        }
+    }
+
+    fn raze(&mut self, world: &mut World) {
+        match &mut self.content {
+            DeferredContent::Placeholder(state) => self.deferred.placeholder.raze(world, state),
+            DeferredContent::Built(state) => self.deferred.view.raze(world, state),
+        }
+    }
+}
+
+#[derive(Component)]
+struct DeferredStateCell<V: View + Clone + PartialEq, P: View + Clone + PartialEq>(
+    Arc<Mutex<DeferredState<V, P>>>,
+);
+
+impl<V: View + Clone + PartialEq, P: View + Clone + PartialEq> View for Deferred<V, P> {
+    type State = (Entity, NodeSpan);
+
+    fn nodes(&self, world: &World, state: &Self::State) -> NodeSpan {
+        let cell = world
+            .entity(state.0)
+            .get::<DeferredStateCell<V, P>>()
+            .unwrap();
+        let inner = cell.0.lock().unwrap();
+        inner.nodes(world)
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let tick = cx.world_mut().change_tick();
+        let parent = cx.owner();
+        let defer = cx.world().contains_resource::<RebuildBudget>();
+        let child_entity = cx
+            .world_mut()
+            .spawn_empty()
+            .insert(Name::new(std::any::type_name::<Self>()))
+            .set_parent(parent)
+            .id();
+
+        #[cfg(feature = "verbose")]
+        info!("build() deferred {}", child_entity);
+
+        let mut scope = TrackingScope::new(tick);
+        let mut cx_inner = Cx::new(cx.world_mut(), child_entity, &mut scope);
+        let content = if defer {
+            DeferredContent::Placeholder(self.placeholder.build(&mut cx_inner))
+        } else {
+            DeferredContent::Built(self.view.build(&mut cx_inner))
+        };
+        let state = DeferredState {
+            deferred: self.clone(),
+            content,
+        };
+        let nodes = state.nodes(cx.world());
+        let mut entt = cx.world_mut().entity_mut(child_entity);
+        entt.insert((
+            DeferredStateCell(Arc::new(Mutex::new(state))),
+            scope,
+            ViewThunk(&DeferredAdapter::<V, P> {
+                marker: std::marker::PhantomData,
+            }),
+        ));
+        if defer {
+            let (depth, priority) = clamp_priority(cx.world(), child_entity, self.priority);
+            cx.world_mut()
+                .entity_mut(child_entity)
+                .insert(PendingBuild { priority });
+            cx.world_mut()
+                .get_resource_or_insert_with(DeferredQueue::default)
+                .push(child_entity, priority, depth);
+        }
+        (child_entity, nodes)
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let mut entt = cx.world_mut().entity_mut(state.0);
+        let cell = entt.get::<DeferredStateCell<V, P>>().unwrap();
+        let mut inner = cell.0.lock().unwrap();
+        if inner.deferred != *self {
+            // Update the view and trigger a rebuild of the view entity.
+            inner.deferred = self.clone();
+            drop(inner);
+            entt.get_mut::<TrackingScope>().unwrap().set_changed();
+            if entt.contains::<PendingBuild>() {
+                let (_, priority) = clamp_priority(cx.world(), state.0, self.priority);
+                let world = cx.world_mut();
+                world.get_mut::<PendingBuild>(state.0).unwrap().priority = priority;
+                world
+                    .resource_mut::<DeferredQueue>()
+                    .set_priority(state.0, priority);
+            }
+        }
+
+        // False because we haven't changed the output yet.
+        false
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let cell = world
+            .entity(state.0)
+            .get::<DeferredStateCell<V, P>>()
+            .unwrap();
+        let inner = cell.0.clone();
+        let nodes = inner.lock().unwrap().nodes(world);
+        if state.1 != nodes {
+            state.1 = nodes;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        let entity = state.0;
+
+        #[cfg(feature = "verbose")]
+        info!("raze() deferred {}", entity);
+
+        let cell = world
+            .entity_mut(entity)
+            .take::<DeferredStateCell<V, P>>()
+            .unwrap();
+        cell.0.lock().unwrap().raze(world);
+        world.entity_mut(entity).remove_parent();
+        world.entity_mut(entity).despawn();
+    }
+}
+
+struct DeferredAdapter<V: View + Clone + PartialEq, P: View + Clone + PartialEq> {
+    marker: std::marker::PhantomData<(V, P)>,
+}
+
+impl<V: View + Clone + PartialEq, P: View + Clone + PartialEq> AnyViewAdapter
+    for DeferredAdapter<V, P>
+{
+    fn nodes(&self, world: &mut World, entity: Entity) -> NodeSpan {
+        match world.entity(entity).get::<DeferredStateCell<V, P>>() {
+            Some(cell) => cell.0.lock().unwrap().nodes(world),
+            None => NodeSpan::Empty,
+        }
+    }
+
+    fn rebuild(&self, world: &mut World, entity: Entity, scope: &mut TrackingScope) -> bool {
+        let pending = world.entity(entity).contains::<PendingBuild>();
+        let mut cx = Cx::new(world, entity, scope);
+        if let Some(cell) = cx.world().entity(entity).get::<DeferredStateCell<V, P>>() {
+            let inner = cell.0.clone();
+            let mut inner = inner.lock().unwrap();
+            inner.rebuild(&mut cx, pending)
+        } else {
+            false
+        }
+    }
+
+    fn raze(&self, world: &mut World, entity: Entity) {
+        if let Some(cell) = world.entity_mut(entity).take::<DeferredStateCell<V, P>>() {
+            cell.0.lock().unwrap().raze(world);
+        }
+    }
+
+    fn attach_children(&self, world: &mut World, entity: Entity) -> bool {
+        if let Some(cell) = world.entity(entity).get::<DeferredStateCell<V, P>>() {
+            let inner = cell.0.clone();
+            let mut inner = inner.lock().unwrap();
+            inner.attach_children(world)
+        } else {
+            false
+        }
+    }
+}
+
+/// Records the start of the frame's view updates, for measuring the rebuild budget.
+pub(crate) fn start_rebuild_budget(world: &mut World) {
+    if let Some(mut budget) = world.get_resource_mut::<RebuildBudget>() {
+        budget.frame_start = Some(Instant::now());
+    }
+}
+
+/// Builds pending [`Deferred`] views, in priority order, until the frame budget is exhausted.
+/// If the [`RebuildBudget`] resource has been removed, all pending views are built.
+pub(crate) fn build_deferred_views(world: &mut World) {
+    if world
+        .get_resource::<DeferredQueue>()
+        .is_none_or(|queue| queue.is_empty())
+    {
+        return;
+    }
+    let (frame_budget, frame_start) = match world.get_resource::<RebuildBudget>() {
+        Some(budget) => (
+            budget.frame_budget,
+            budget.frame_start.unwrap_or_else(Instant::now),
+        ),
+        None => (Duration::MAX, Instant::now()),
+    };
+
+    let this_run = world.change_tick();
+    let mut scopes = world.query::<(&mut TrackingScope, &ViewThunk)>();
+    let mut built = 0;
+    while built == 0 || frame_start.elapsed() < frame_budget {
+        let Some(entity) = world.resource_mut::<DeferredQueue>().pop() else {
+            break;
+        };
+        // Skip views which have been razed since they were queued.
+        let Some(mut entt) = world.get_entity_mut(entity) else {
+            continue;
+        };
+        if entt.take::<PendingBuild>().is_none() {
+            continue;
+        }
+        let Ok((mut scope, thunk)) = scopes.get_mut(world, entity) else {
+            continue;
+        };
+        let mut next_scope = TrackingScope::new(this_run);
+        next_scope.take_hooks(scope.as_mut());
+        let thunk = thunk.0;
+        if thunk.rebuild(world, entity, &mut next_scope) {
+            world.entity_mut(entity).insert(OutputChanged);
+        }
+        let (mut scope, _) = scopes.get_mut(world, entity).unwrap();
+        scope.take_deps(&mut next_scope);
+        scope.tick = this_run;
+        built += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::App,
+        asset::{AssetApp, AssetPlugin},
+        prelude::{Image, MinimalPlugins},
+        text::Font,
+    };
+
+    use super::*;
+    use crate::QuillPlugin;
+
+    /// A view consisting of a single named entity.
+    #[derive(Clone, PartialEq)]
+    struct Marker(&'static str);
+
+    impl View for Marker {
+        type State = Entity;
+
+        fn nodes(&self, _world: &World, state: &Self::State) -> NodeSpan {
+            NodeSpan::Node(*state)
+        }
+
+        fn build(&self, cx: &mut Cx) -> Self::State {
+            cx.world_mut().spawn(Name::new(self.0)).id()
+        }
+
+        fn rebuild(&self, _cx: &mut Cx, _state: &mut Self::State) -> bool {
+            false
+        }
+
+        fn raze(&self, world: &mut World, state: &mut Self::State) {
+            world.entity_mut(*state).despawn();
+        }
+    }
+
+    fn names(app: &mut App) -> Vec<String> {
+        let mut query = app.world_mut().query::<&Name>();
+        let mut names: Vec<String> = query
+            .iter(app.world())
+            .map(|name| name.as_str().to_string())
+            .filter(|name| !name.contains("::"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_deferred_build() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), QuillPlugin))
+            .init_asset::<Image>()
+            .init_asset::<Font>()
+            // With no time to spare, one deferred view is built each frame.
+            .insert_resource(RebuildBudget::new(Duration::ZERO));
+        app.world_mut().spawn(
+            (
+                Deferred::new(Marker("a")).placeholder(Marker("a placeholder")),
+                Deferred::new((
+                    Marker("b"),
+                    // Built after its parent, despite having higher priority.
+                    Deferred::new(Marker("c"))
+                        .placeholder(Marker("c placeholder"))
+                        .priority(10),
+                ))
+                .placeholder(Marker("b placeholder"))
+                .priority(5),
+            )
+                .to_root(),
+        );
+        app.update();
+        assert_eq!(names(&mut app), ["a placeholder", "b", "c placeholder"]);
+        app.update();
+        assert_eq!(names(&mut app), ["a placeholder", "b", "c"]);
+        app.update();
+        assert_eq!(names(&mut app), ["a", "b", "c"]);
+        assert!(app.world().resource::<DeferredQueue>().is_empty());
+    }
+
+    #[test]
+    fn test_queue_order() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let c = world.spawn_empty().id();
+        let d = world.spawn_empty().id();
+        let mut queue = DeferredQueue::default();
+        queue.push(a, 0, 1);
+        queue.push(b, 5, 3);
+        queue.push(c, 5, 2);
+        queue.push(d, 0, 1);
+        assert_eq!(queue.pop(), Some(c));
+        assert_eq!(queue.pop(), Some(b));
+        assert_eq!(queue.pop(), Some(a));
+        queue.set_priority(d, -1);
+        queue.push(a, 0, 4);
+        assert_eq!(queue.pop(), Some(a));
+        assert_eq!(queue.pop(), Some(d));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_clamp_priority() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let parent = world
+            .spawn(PendingBuild { priority: 1 })
+            .set_parent(root)
+            .id();
+        let middle = world.spawn_empty().set_parent(parent).id();
+        let child = world.spawn_empty().set_parent(middle).id();
+        assert_eq!(clamp_priority(&world, child, 10), (3, 1));
+        assert_eq!(clamp_priority(&world, child, -2), (3, -2));
+        assert_eq!(clamp_priority(&world, parent, 10), (1, 10));
+
+        // A child with higher priority than its pending parent is still built after it.
+        let mut queue = DeferredQueue::default();
+        queue.push(parent, 1, 1);
+        let (depth, priority) = clamp_priority(&world, child, 10);
+        queue.push(child, priority, depth);
+        assert_eq!(queue.pop(), Some(parent));
+        assert_eq!(queue.pop(), Some(child));
+    }
+}
@@ -1,3 +1,4 @@
+use std::any::Any;
 use std::sync::Arc;
 
 use bevy::ecs::world::World;
@@ -10,21 +11,58 @@ use crate::node_span::NodeSpan;
 /// the old children are razed and the new children are built.
 ///
 /// Dynamic detects when the view type of the children via the method [`View::view_type_id`].
-/// When this id changes, the old children are razed and the new children built.
+/// When this id changes, the old children are razed and the new children built. The children
+/// are also razed and rebuilt when the key, set via [`Dynamic::key`], changes.
 pub struct Dynamic {
     children: ViewChild,
+    key: Option<Arc<dyn AnyKey>>,
+}
+
+/// Type-erased key for [`Dynamic::key`], which can be compared with keys of any type.
+pub trait AnyKey: Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+    fn eq_key(&self, other: &dyn AnyKey) -> bool;
+}
+
+impl<K: PartialEq + Send + Sync + 'static> AnyKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_key(&self, other: &dyn AnyKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+}
+
+fn keys_equal(a: &Option<Arc<dyn AnyKey>>, b: &Option<Arc<dyn AnyKey>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_key(b.as_ref()),
+        _ => false,
+    }
 }
 
 impl Dynamic {
     /// Construct a new [`Dynamic`]. This requires a [`ViewChild`], which is a type-erased
     /// view that can be cloned. You can create a [`ViewChild`] by calling [`View::into_view_child`].
     pub fn new(children: ViewChild) -> Self {
-        Self { children }
+        Self {
+            children,
+            key: None,
+        }
+    }
+
+    /// Set a key which identifies the children. When the key changes, the old children are
+    /// razed and the new children built, even if they are the same type. Keys of different
+    /// types are never equal.
+    pub fn key<K: PartialEq + Send + Sync + 'static>(mut self, key: K) -> Self {
+        self.key = Some(Arc::new(key));
+        self
     }
 }
 
 impl View for Dynamic {
-    type State = (Arc<dyn AnyView>, BoxedState);
+    type State = (Arc<dyn AnyView>, BoxedState, Option<Arc<dyn AnyKey>>);
 
     fn nodes(&self, world: &World, state: &Self::State) -> NodeSpan {
         state.0.nodes(world, &state.1)
@@ -33,11 +71,13 @@ impl View for Dynamic {
     fn build(&self, cx: &mut crate::Cx) -> Self::State {
         let view = self.children.0.clone();
         let state = view.build(cx);
-        (view, state)
+        (view, state, self.key.clone())
     }
 
     fn rebuild(&self, cx: &mut crate::Cx, state: &mut Self::State) -> bool {
-        if state.0.view_type_id() == self.children.0.view_type_id() {
+        if state.0.view_type_id() == self.children.0.view_type_id()
+            && keys_equal(&state.2, &self.key)
+        {
             state.0 = self.children.0.clone();
             state.0.rebuild(cx, &mut state.1)
         } else {
@@ -46,6 +86,7 @@ impl View for Dynamic {
             let new_state = view.build(cx);
             state.0 = view;
             state.1 = new_state;
+            state.2.clone_from(&self.key);
             true
         }
     }
@@ -63,12 +104,37 @@ impl Clone for Dynamic {
     fn clone(&self) -> Self {
         Self {
             children: self.children.clone(),
+            key: self.key.clone(),
         }
     }
 }
 
 impl PartialEq for Dynamic {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.children.0, &other.children.0)
+        keys_equal(&self.key, &other.key) && Arc::ptr_eq(&self.children.0, &other.children.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoViewChild;
+
+    #[test]
+    fn test_keys_equal() {
+        let key = |k: Dynamic| k.key;
+        let view = || Dynamic::new(().into_view_child());
+        assert!(keys_equal(&key(view()), &key(view())));
+        assert!(keys_equal(
+            &key(view().key("apple")),
+            &key(view().key("apple"))
+        ));
+        assert!(!keys_equal(
+            &key(view().key("apple")),
+            &key(view().key("pear"))
+        ));
+        assert!(!keys_equal(&key(view().key(1u64)), &key(view())));
+        // Keys of different types never match, even if they would hash the same.
+        assert!(!keys_equal(&key(view().key(1u64)), &key(view().key(1i64))));
     }
 }
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, panic::Location};
 
 use bevy::prelude::*;
 use bevy_mod_stylebuilder::{StyleBuilder, StyleTuple};
@@ -8,7 +8,7 @@ use crate::{
     effects::{self, AppendEffect, CallbackEffect, EffectTuple, EntityEffect},
     insert::{ConditionalInsertComponentEffect, InsertBundleEffect, StaticInsertBundleEffect},
     node_span::NodeSpan,
-    style::{ApplyDynamicStylesEffect, ApplyStaticStylesEffect},
+    style::{ApplyClassesEffect, ApplyDynamicStylesEffect, ApplyStaticStylesEffect},
     view::View,
 };
 
@@ -102,6 +102,7 @@ impl<B: Bundle + Default, C: View, E: EffectTuple> Element<B, C, E> {
     }
 
     /// Apply a set of styles to the element
+    #[track_caller]
     pub fn style<S: StyleTuple + 'static>(
         self,
         styles: S,
@@ -109,7 +110,26 @@ impl<B: Bundle + Default, C: View, E: EffectTuple> Element<B, C, E> {
     where
         E: AppendEffect<ApplyStaticStylesEffect<S>>,
     {
-        self.add_effect(ApplyStaticStylesEffect { styles })
+        self.add_effect(ApplyStaticStylesEffect {
+            styles,
+            location: Location::caller(),
+        })
+    }
+
+    /// Apply the rules from the active stylesheets which match the given classes, separated
+    /// by spaces. This should be called before any inline styles, which take precedence.
+    #[track_caller]
+    pub fn class(
+        self,
+        classes: impl Into<String>,
+    ) -> Element<B, C, <E as AppendEffect<ApplyClassesEffect>>::Result>
+    where
+        E: AppendEffect<ApplyClassesEffect>,
+    {
+        self.add_effect(ApplyClassesEffect {
+            classes: classes.into(),
+            location: Location::caller(),
+        })
     }
 
     /// Apply a set of dynamic styles to the element. This will be re-run whenever the
@@ -118,6 +138,7 @@ impl<B: Bundle + Default, C: View, E: EffectTuple> Element<B, C, E> {
     /// Arguments:
     /// - style_fn: A function which computes the styles based on the dependencies.
     /// - deps: The dependencies which trigger a recompute of the styles.
+    #[track_caller]
     pub fn style_dyn<
         S: Fn(D, &mut StyleBuilder) + Send + Sync,
         D: PartialEq + Clone + Send + Sync,
@@ -129,7 +150,11 @@ impl<B: Bundle + Default, C: View, E: EffectTuple> Element<B, C, E> {
     where
         E: AppendEffect<ApplyDynamicStylesEffect<S, D>>,
     {
-        self.add_effect(ApplyDynamicStylesEffect { style_fn, deps })
+        self.add_effect(ApplyDynamicStylesEffect {
+            style_fn,
+            deps,
+            location: Location::caller(),
+        })
     }
 
     /// Insert a bundle into the target entity once and never update it.
@@ -1,4 +1,6 @@
-use crate::{ForIndex, View};
+use std::hash::Hash;
+
+use crate::{ForIndex, ForKeyed, View};
 
 use super::ForEach;
 
@@ -6,6 +8,7 @@ use super::ForEach;
 /// * `For::each()`
 /// * `For::each_cmp()`
 /// * `For::index()`
+/// * `For::keyed()`
 pub struct For;
 
 impl For {
@@ -24,6 +27,25 @@ impl For {
         ForIndex::new(items, each_fn)
     }
 
+    /// Construct a keyed for loop for an array of items. The `key_fn` callback returns an
+    /// identifying key for each item, and `each_fn` builds its view. During rebuild, items whose
+    /// key was present before are rebuilt in place, keeping their state and entities, even if
+    /// their position changed; only the items with new keys are built from scratch. Keys are
+    /// looked up in a hash map, so rebuilding takes time proportional to the number of items.
+    pub fn keyed<
+        Item: Send + Sync + Clone + 'static,
+        K: Hash + Eq + Send + Sync + 'static,
+        V: View,
+        KF: Send + Sync + 'static + Fn(&Item) -> K,
+        F: Send + Sync + 'static + Fn(&Item) -> V,
+    >(
+        items: &[Item],
+        key_fn: KF,
+        each_fn: F,
+    ) -> ForKeyed<Item, K, V, KF, F, ()> {
+        ForKeyed::new(items, key_fn, each_fn)
+    }
+
     /// Transforms an iterator of items into an array of child views, one for each element in
     /// the original sequence. The order of child views is determined by the order of the
     /// input items. During rebuilds, the list of child views may be re-ordered based on a
@@ -0,0 +1,279 @@
+use std::hash::Hash;
+
+use bevy::{ecs::world::World, utils::HashMap};
+
+use crate::{node_span::NodeSpan, Cx, View};
+
+pub struct KeyedListItem<K, V: View> {
+    key: K,
+    view: Option<V>,
+    state: V::State,
+}
+
+impl<K, V: View> KeyedListItem<K, V> {
+    fn nodes(&self, world: &World) -> NodeSpan {
+        self.view.as_ref().unwrap().nodes(world, &self.state)
+    }
+}
+
+#[doc(hidden)]
+pub struct ForKeyed<
+    Item: Send + Clone,
+    K: Hash + Eq + Send,
+    V: View,
+    KF: Fn(&Item) -> K + Send,
+    F: Fn(&Item) -> V + Send,
+    FB: View,
+> {
+    items: Vec<Item>,
+    key: KF,
+    each: F,
+    fallback: Option<FB>,
+}
+
+impl<
+        Item: Send + Clone,
+        K: Hash + Eq + Send,
+        V: View,
+        KF: Fn(&Item) -> K + Send,
+        F: Fn(&Item) -> V + Send,
+    > ForKeyed<Item, K, V, KF, F, ()>
+{
+    pub fn new(items: &[Item], key: KF, each: F) -> Self {
+        Self {
+            items: Vec::from(items),
+            key,
+            each,
+            fallback: None,
+        }
+    }
+}
+
+impl<
+        Item: Send + Clone,
+        K: Hash + Eq + Send,
+        V: View,
+        KF: Fn(&Item) -> K + Send,
+        F: Fn(&Item) -> V + Send,
+        FB: View,
+    > ForKeyed<Item, K, V, KF, F, FB>
+{
+    pub fn with_fallback<FB2: View>(self, fallback: FB2) -> ForKeyed<Item, K, V, KF, F, FB2> {
+        ForKeyed::<Item, K, V, KF, F, FB2> {
+            items: self.items,
+            key: self.key,
+            each: self.each,
+            fallback: Some(fallback),
+        }
+    }
+}
+
+impl<
+        Item: Send + Sync + Clone + 'static,
+        K: Hash + Eq + Send + Sync + 'static,
+        V: View,
+        KF: Fn(&Item) -> K + Send + Sync + 'static,
+        F: Fn(&Item) -> V + Send + Sync + 'static,
+        FB: View,
+    > View for ForKeyed<Item, K, V, KF, F, FB>
+{
+    type State = (Vec<KeyedListItem<K, V>>, Option<FB::State>);
+
+    fn nodes(&self, world: &World, state: &Self::State) -> NodeSpan {
+        let mut child_spans: Vec<NodeSpan> = state.0.iter().map(|item| item.nodes(world)).collect();
+        if let Some(ref fallback) = self.fallback {
+            if let Some(ref fbstate) = state.1 {
+                child_spans.push(fallback.nodes(world, fbstate));
+            }
+        }
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let mut state = (Vec::new(), None);
+        self.rebuild(cx, &mut state);
+        state
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let next_len = self.items.len();
+        let mut changed = false;
+
+        // Index the previous items by key, along with their previous position. If a key was
+        // repeated, only the first item with that key is reused.
+        let mut prev: HashMap<K, (usize, Option<V>, V::State)> =
+            HashMap::with_capacity(state.0.len());
+        for (index, item) in state.0.drain(..).enumerate().rev() {
+            if let Some((_, view, mut child_state)) =
+                prev.insert(item.key, (index, item.view, item.state))
+            {
+                if let Some(view) = view {
+                    view.raze(cx.world_mut(), &mut child_state);
+                }
+                changed = true;
+            }
+        }
+
+        // Items whose key was present before are rebuilt in place, keeping their state; the
+        // others are built from scratch. If the surviving items changed order, the nodes
+        // need to be re-attached.
+        let mut last_index: Option<usize> = None;
+        for item in self.items.iter() {
+            let key = (self.key)(item);
+            let view = (self.each)(item);
+            match prev.remove(&key) {
+                Some((index, _, mut child_state)) => {
+                    changed |= view.rebuild(cx, &mut child_state);
+                    changed |= last_index.is_some_and(|last| index < last);
+                    last_index = Some(index);
+                    state.0.push(KeyedListItem {
+                        key,
+                        view: Some(view),
+                        state: child_state,
+                    });
+                }
+                None => {
+                    let st = view.build(cx);
+                    state.0.push(KeyedListItem {
+                        key,
+                        view: Some(view),
+                        state: st,
+                    });
+                    changed = true;
+                }
+            }
+        }
+
+        // Raze items whose key is gone.
+        for (_, view, mut child_state) in prev.into_values() {
+            if let Some(view) = view {
+                view.raze(cx.world_mut(), &mut child_state);
+            }
+            changed = true;
+        }
+
+        // Handle fallback
+        if let Some(ref fallback) = self.fallback {
+            match state.1 {
+                // If there are > 0 items, destroy fallback if present.
+                Some(ref mut fb_ent) if next_len > 0 => {
+                    fallback.raze(cx.world_mut(), fb_ent);
+                    state.1 = None;
+                    changed = true;
+                }
+
+                // If there are no items, render fallback unless already rendered.
+                None if next_len == 0 => {
+                    state.1 = Some(fallback.build(cx));
+                    changed = true;
+                }
+
+                // Otherwise, no change.
+                _ => {}
+            }
+        }
+
+        changed
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        let mut changed = false;
+        for child_state in state.0.iter_mut() {
+            if let Some(ref view) = child_state.view {
+                changed |= view.attach_children(world, &mut child_state.state);
+            }
+        }
+        if let Some(ref mut fbstate) = state.1 {
+            changed |= self
+                .fallback
+                .as_ref()
+                .unwrap()
+                .attach_children(world, fbstate);
+        }
+        changed
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for child_state in state.0.iter_mut() {
+            if let Some(ref view) = child_state.view {
+                view.raze(world, &mut child_state.state);
+            }
+        }
+        if let Some(ref mut fbstate) = state.1 {
+            self.fallback.as_ref().unwrap().raze(world, fbstate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{For, TrackingScope};
+
+    /// A view consisting of a single UI node, which records the value it was last built with.
+    #[derive(Clone, PartialEq)]
+    struct TestNode(u32);
+
+    #[derive(Component)]
+    struct Value(u32);
+
+    impl View for TestNode {
+        type State = Entity;
+
+        fn nodes(&self, _world: &World, state: &Self::State) -> NodeSpan {
+            NodeSpan::Node(*state)
+        }
+
+        fn build(&self, cx: &mut Cx) -> Self::State {
+            cx.world_mut().spawn(Value(self.0)).id()
+        }
+
+        fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+            cx.world_mut().get_mut::<Value>(*state).unwrap().0 = self.0;
+            false
+        }
+
+        fn raze(&self, world: &mut World, state: &mut Self::State) {
+            world.entity_mut(*state).despawn();
+        }
+    }
+
+    #[test]
+    fn test_keyed_rebuild() {
+        let mut world = World::new();
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let view = |items: &[(u32, u32)]| {
+            For::keyed(items, |(key, _)| *key, |(_, value)| TestNode(*value))
+        };
+
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        let mut state = view(&[(1, 10), (2, 20), (3, 30)]).build(&mut cx);
+        let entities: Vec<Entity> = state.0.iter().map(|item| item.state).collect();
+
+        // Scrolling down by one keeps the entities of the items which are still present, and
+        // updates them in place.
+        let changed = view(&[(2, 21), (3, 30), (4, 40)]).rebuild(&mut cx, &mut state);
+        assert!(changed);
+        assert_eq!(state.0[0].state, entities[1]);
+        assert_eq!(state.0[1].state, entities[2]);
+        assert_eq!(world.get::<Value>(entities[1]).unwrap().0, 21);
+        assert!(world.get_entity(entities[0]).is_none());
+
+        // Rebuilding with the same keys in the same order doesn't need to re-attach.
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(!view(&[(2, 22), (3, 30), (4, 40)]).rebuild(&mut cx, &mut state));
+
+        // Re-ordering does.
+        assert!(view(&[(3, 30), (2, 22), (4, 40)]).rebuild(&mut cx, &mut state));
+        assert_eq!(state.0[1].state, entities[1]);
+
+        // A repeated key reuses the first item with that key, and builds the others.
+        assert!(view(&[(3, 30), (3, 31)]).rebuild(&mut cx, &mut state));
+        assert_eq!(state.0[0].state, entities[2]);
+        assert_ne!(state.0[1].state, entities[2]);
+        assert!(world.get_entity(entities[1]).is_none());
+    }
+}
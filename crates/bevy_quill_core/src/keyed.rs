@@ -0,0 +1,116 @@
+use bevy::ecs::world::World;
+
+use crate::{Cx, View};
+
+use crate::node_span::NodeSpan;
+
+/// A view which razes and rebuilds its child view whenever the key changes. Normally the state
+/// of a child view, such as the [`Mutable`](crate::Mutable)s of a
+/// [`ViewTemplate`](crate::ViewTemplate), persists as long as the type of the view stays the
+/// same. Wrapping the view in a `Keyed` ties that state to the identity of the key instead,
+/// so that (for example) a panel which is re-targeted at a different entity starts out fresh.
+#[derive(Clone, PartialEq)]
+pub struct Keyed<K: PartialEq + Clone + Send + Sync + 'static, V: View> {
+    key: K,
+    view: V,
+}
+
+impl<K: PartialEq + Clone + Send + Sync + 'static, V: View> Keyed<K, V> {
+    /// Construct a new [`Keyed`] view.
+    pub fn new(key: K, view: V) -> Self {
+        Self { key, view }
+    }
+}
+
+impl<K: PartialEq + Clone + Send + Sync + 'static, V: View> View for Keyed<K, V> {
+    type State = (K, V::State);
+
+    fn nodes(&self, world: &World, state: &Self::State) -> NodeSpan {
+        self.view.nodes(world, &state.1)
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        (self.key.clone(), self.view.build(cx))
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        if state.0 == self.key {
+            self.view.rebuild(cx, &mut state.1)
+        } else {
+            self.view.raze(cx.world_mut(), &mut state.1);
+            state.0 = self.key.clone();
+            state.1 = self.view.build(cx);
+            true
+        }
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        self.view.raze(world, &mut state.1)
+    }
+
+    fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
+        self.view.attach_children(world, &mut state.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::TrackingScope;
+
+    /// A view consisting of a single entity, whose state counts the number of rebuilds.
+    #[derive(Clone, PartialEq)]
+    struct TestNode(u32);
+
+    #[derive(Component)]
+    struct Value(u32);
+
+    impl View for TestNode {
+        type State = (Entity, usize);
+
+        fn nodes(&self, _world: &World, state: &Self::State) -> NodeSpan {
+            NodeSpan::Node(state.0)
+        }
+
+        fn build(&self, cx: &mut Cx) -> Self::State {
+            (cx.world_mut().spawn(Value(self.0)).id(), 0)
+        }
+
+        fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+            cx.world_mut().get_mut::<Value>(state.0).unwrap().0 = self.0;
+            state.1 += 1;
+            false
+        }
+
+        fn raze(&self, world: &mut World, state: &mut Self::State) {
+            world.entity_mut(state.0).despawn();
+        }
+    }
+
+    #[test]
+    fn test_keyed_rebuild() {
+        let mut world = World::new();
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+
+        let mut state = Keyed::new(1, TestNode(10)).build(&mut cx);
+        let first = state.1 .0;
+
+        // The same key rebuilds the child in place, keeping its entity and state.
+        assert!(!Keyed::new(1, TestNode(11)).rebuild(&mut cx, &mut state));
+        assert_eq!(state.1, (first, 1));
+        assert_eq!(cx.world().get::<Value>(first).unwrap().0, 11);
+
+        // A new key razes the child and builds a fresh one.
+        assert!(Keyed::new(2, TestNode(11)).rebuild(&mut cx, &mut state));
+        let second = state.1 .0;
+        assert_ne!(second, first);
+        assert_eq!(state.0, 2);
+        assert_eq!(state.1 .1, 0);
+        assert_eq!(cx.world().get::<Value>(second).unwrap().0, 11);
+        assert!(world.get_entity(first).is_none());
+    }
+}
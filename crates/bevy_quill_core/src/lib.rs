@@ -2,17 +2,21 @@
 mod callback;
 mod cond;
 mod cx;
+mod deferred;
 mod dynamic;
 mod effects;
 mod element;
 mod r#for;
 mod for_each;
 mod for_index;
+mod for_keyed;
 mod insert;
+mod keyed;
 mod lcs;
 mod mutable;
 mod node_span;
 mod portal;
+mod rich_text;
 mod style;
 mod text_view;
 mod tracking_scope;
@@ -25,17 +29,22 @@ use bevy::{
     prelude::IntoSystemConfigs,
 };
 use bevy_mod_stylebuilder::{StyleBuilderPlugin, StyleBuilderSystemSet};
+use deferred::{build_deferred_views, start_rebuild_budget};
 
 pub mod prelude {
     pub use crate::callback::*;
     pub use crate::cond::Cond;
     pub use crate::cx::Cx;
     pub use crate::cx::EffectOptions;
+    pub use crate::deferred::Deferred;
     pub use crate::element::*;
     pub use crate::for_each::ForEach;
     pub use crate::for_index::ForIndex;
+    pub use crate::for_keyed::ForKeyed;
+    pub use crate::keyed::Keyed;
     pub use crate::mutable::*;
     pub use crate::r#for::For;
+    pub use crate::rich_text::{FontWeight, RichText, Span};
     pub use crate::view::*;
     pub use crate::view_template::ViewTemplate;
 }
@@ -44,14 +53,18 @@ pub use callback::*;
 pub use cond::Cond;
 pub use cx::Cx;
 pub use cx::EffectOptions;
+pub use deferred::{Deferred, PendingBuild, RebuildBudget};
 pub use dynamic::Dynamic;
 pub use element::*;
 pub use for_each::ForEach;
 pub use for_index::ForIndex;
+pub use for_keyed::ForKeyed;
+pub use keyed::Keyed;
 pub use mutable::*;
 pub use node_span::*;
-pub use portal::Portal;
+pub use portal::{OverlayLayer, Portal, PortalTarget};
 pub use r#for::For;
+pub use rich_text::{FontWeight, RichText, Span};
 use tracking_scope::cleanup_tracking_scopes;
 pub use tracking_scope::TrackingScope;
 pub use tracking_scope::TrackingScopeTracing;
@@ -68,7 +81,13 @@ impl Plugin for QuillPlugin {
             .add_systems(Startup, cleanup_tracking_scopes)
             .add_systems(
                 Update,
-                (build_views, reaction_control_system, reattach_children)
+                (
+                    start_rebuild_budget,
+                    build_views,
+                    reaction_control_system,
+                    build_deferred_views,
+                    reattach_children,
+                )
                     .chain()
                     .before(StyleBuilderSystemSet),
             );
@@ -1,27 +1,200 @@
 use bevy::ecs::world::World;
-use bevy::prelude::Entity;
-use bevy::ui::TargetCamera;
+use bevy::hierarchy::{BuildWorldChildren, Parent};
+use bevy::log::warn;
+use bevy::prelude::{Entity, Resource};
+use bevy::ui::{TargetCamera, ZIndex};
+use bevy::utils::HashMap;
 
 use crate::View;
 
 use crate::node_span::NodeSpan;
 
-/// A `Portal` represents a UI node that is displayed with no parent node, causing it's location to
-/// be relative to the window rather than any parent node. This only affects the display hierarchy,
-/// the [`View`] hierarchy is unaffected.
+/// Named overlay layers which portals can be mounted into. Layers are stacked in the order
+/// listed here, from bottom to top; within a layer, the most recently mounted portal is on top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlayLayer {
+    /// Modal dialogs and their backdrops.
+    Modals,
+    /// Menus and other popups, which can be opened from within a dialog.
+    Menus,
+    /// Tooltips, which can be displayed over menus and dialogs.
+    Tooltips,
+    /// Drag previews, which are displayed above everything else.
+    DragPreview,
+}
+
+impl OverlayLayer {
+    /// Number of z-index slots reserved for each layer.
+    const LAYER_SIZE: i32 = 1000;
+
+    /// The global z-index of the bottom of this layer.
+    pub fn z_index(&self) -> i32 {
+        let index = match self {
+            OverlayLayer::Modals => 1,
+            OverlayLayer::Menus => 2,
+            OverlayLayer::Tooltips => 3,
+            OverlayLayer::DragPreview => 4,
+        };
+        index * Self::LAYER_SIZE
+    }
+
+    /// The global z-index of a portal with the given stacking order within this layer. Orders
+    /// beyond the size of the layer are clamped, so that they don't spill into the next layer.
+    fn portal_z_index(&self, order: i32) -> i32 {
+        self.z_index() + order.min(Self::LAYER_SIZE - 1)
+    }
+}
+
+/// Where the children of a [`Portal`] are mounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PortalTarget {
+    /// Children are root nodes, positioned relative to the window.
+    #[default]
+    Root,
+    /// Children are root nodes, stacked within the given overlay layer.
+    Layer(OverlayLayer),
+    /// Children are added as children of the given entity. The entity should not be an
+    /// [`Element`](crate::Element) which has children of its own, since those would replace
+    /// the portal's children.
+    Entity(Entity),
+}
+
+impl From<OverlayLayer> for PortalTarget {
+    fn from(layer: OverlayLayer) -> Self {
+        PortalTarget::Layer(layer)
+    }
+}
+
+impl From<Entity> for PortalTarget {
+    fn from(entity: Entity) -> Self {
+        PortalTarget::Entity(entity)
+    }
+}
+
+/// A mounted portal, for stacking within overlay layers.
+struct LivePortal {
+    id: u64,
+    order: i32,
+    /// The root nodes of the portal, if mounted in an overlay layer.
+    layer_nodes: Option<(OverlayLayer, Vec<Entity>)>,
+}
+
+/// Tracks the stacking order of mounted portals, so that the most recently mounted portal in
+/// a layer is on top. When the orders run out, the live portals in each layer are renumbered.
+#[derive(Resource, Default)]
+struct PortalStack {
+    next_id: u64,
+    next_order: i32,
+    live: Vec<LivePortal>,
+}
+
+impl PortalStack {
+    /// Register a newly-mounted portal, returning its id.
+    fn mount(&mut self) -> u64 {
+        if self.next_order >= OverlayLayer::LAYER_SIZE {
+            self.compact();
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.live.push(LivePortal {
+            id,
+            order: self.next_order,
+            layer_nodes: None,
+        });
+        self.next_order += 1;
+        id
+    }
+
+    fn unmount(&mut self, id: u64) {
+        self.live.retain(|p| p.id != id);
+    }
+
+    fn order(&self, id: u64) -> i32 {
+        self.live.iter().find(|p| p.id == id).map_or(0, |p| p.order)
+    }
+
+    /// Renumber the live portals in each layer from zero, preserving their relative order. The
+    /// live list is always sorted by order, since portals are appended as they are mounted.
+    /// Portals which aren't mounted in a layer are numbered together.
+    fn compact(&mut self) {
+        let mut counts: HashMap<Option<OverlayLayer>, i32> = HashMap::default();
+        for portal in self.live.iter_mut() {
+            let count = counts
+                .entry(portal.layer_nodes.as_ref().map(|(layer, _)| *layer))
+                .or_default();
+            portal.order = *count;
+            *count += 1;
+        }
+        self.next_order = counts.values().copied().max().unwrap_or(0);
+        if self.next_order >= OverlayLayer::LAYER_SIZE {
+            warn!(
+                "More than {} portals are mounted in one overlay layer; the newest ones may \
+                not stack correctly",
+                OverlayLayer::LAYER_SIZE
+            );
+        }
+    }
+}
+
+/// Mount a portal, updating the z-index of the other portals if they were renumbered.
+fn mount_portal(world: &mut World) -> u64 {
+    let mut stack = world.get_resource_or_insert_with(PortalStack::default);
+    let compacting = stack.next_order >= OverlayLayer::LAYER_SIZE;
+    let id = stack.mount();
+    if compacting {
+        world.resource_scope(|world, stack: bevy::prelude::Mut<PortalStack>| {
+            for portal in stack.live.iter() {
+                if let Some((layer, nodes)) = &portal.layer_nodes {
+                    for node in nodes {
+                        if let Some(mut entt) = world.get_entity_mut(*node) {
+                            entt.insert(ZIndex::Global(layer.portal_z_index(portal.order)));
+                        }
+                    }
+                }
+            }
+        });
+    }
+    id
+}
+
+/// A `Portal` represents a UI node that is displayed outside of its parent node, causing it's
+/// location to be relative to the window (or to the target entity) rather than any parent node.
+/// This only affects the display hierarchy, the [`View`] hierarchy is unaffected.
 pub struct Portal<A: View> {
     children: A,
+    target: PortalTarget,
 }
 
 impl<A: View> Portal<A> {
     /// Construct a new [`Portal`] view.
     pub fn new(children: A) -> Self {
-        Self { children }
+        Self {
+            children,
+            target: PortalTarget::Root,
+        }
+    }
+
+    /// Set where the children of this portal are mounted: either an [`OverlayLayer`] or
+    /// a specific [`Entity`].
+    pub fn into(mut self, target: impl Into<PortalTarget>) -> Self {
+        self.target = target.into();
+        self
     }
 }
 
+/// State of a [`Portal`].
+pub struct PortalState<S> {
+    children: S,
+    /// Inherited camera.
+    camera: Option<Entity>,
+    /// Id of the portal in the stack of mounted portals.
+    id: u64,
+    /// Target the children were last attached to.
+    target: Option<PortalTarget>,
+}
+
 impl<A: View> View for Portal<A> {
-    type State = (A::State, Option<Entity>);
+    type State = PortalState<A::State>;
 
     fn nodes(&self, _world: &World, _state: &Self::State) -> NodeSpan {
         NodeSpan::Empty
@@ -31,25 +204,77 @@ impl<A: View> View for Portal<A> {
         let camera = cx
             .use_inherited_component::<TargetCamera>()
             .map(|c| c.entity());
-        (self.children.build(cx), camera)
+        let id = mount_portal(cx.world_mut());
+        PortalState {
+            children: self.children.build(cx),
+            camera,
+            id,
+            target: None,
+        }
     }
 
     fn rebuild(&self, cx: &mut crate::Cx, state: &mut Self::State) -> bool {
-        self.children.rebuild(cx, &mut state.0)
+        let changed = self.children.rebuild(cx, &mut state.children);
+        // Re-attach the children if the target changed.
+        changed || state.target != Some(self.target)
     }
 
     fn raze(&self, world: &mut World, state: &mut Self::State) {
-        self.children.raze(world, &mut state.0)
+        if let Some(mut stack) = world.get_resource_mut::<PortalStack>() {
+            stack.unmount(state.id);
+        }
+        self.children.raze(world, &mut state.children)
     }
 
     fn attach_children(&self, world: &mut World, state: &mut Self::State) -> bool {
-        self.children.attach_children(world, &mut state.0);
-        // Make sure all children are on the correct camera.
-        if let Some(camera) = state.1 {
-            let nodes = self.children.nodes(world, &state.0);
-            for node in nodes.to_vec().iter() {
-                world.entity_mut(*node).insert(TargetCamera(camera));
+        self.children.attach_children(world, &mut state.children);
+        let nodes = self.children.nodes(world, &state.children).to_vec();
+        let prev_target = state.target.replace(self.target);
+        match self.target {
+            PortalTarget::Root | PortalTarget::Layer(_) => {
+                let order = world.resource::<PortalStack>().order(state.id);
+                for node in nodes.iter() {
+                    let mut entt = world.entity_mut(*node);
+                    // Detach from the previous target entity, if any.
+                    if entt.contains::<Parent>() {
+                        entt.remove_parent();
+                    }
+                    // Make sure all children are on the correct camera.
+                    if let Some(camera) = state.camera {
+                        entt.insert(TargetCamera(camera));
+                    }
+                    match self.target {
+                        PortalTarget::Layer(layer) => {
+                            entt.insert(ZIndex::Global(layer.portal_z_index(order)));
+                        }
+                        _ if matches!(prev_target, Some(PortalTarget::Layer(_))) => {
+                            entt.insert(ZIndex::default());
+                        }
+                        _ => {}
+                    }
+                }
             }
+            PortalTarget::Entity(target) => {
+                for node in nodes.iter() {
+                    let mut entt = world.entity_mut(*node);
+                    if matches!(prev_target, Some(PortalTarget::Layer(_))) {
+                        entt.insert(ZIndex::default());
+                    }
+                    let parent = entt.get::<Parent>().map(|p| p.get());
+                    if parent != Some(target) {
+                        world.entity_mut(target).add_child(*node);
+                    }
+                }
+            }
+        }
+
+        // Remember the nodes, so that their z-index can be updated if the stack is renumbered.
+        let mut stack = world.resource_mut::<PortalStack>();
+        if let Some(portal) = stack.live.iter_mut().find(|p| p.id == state.id) {
+            portal.layer_nodes = match self.target {
+                PortalTarget::Layer(layer) => Some((layer, nodes)),
+                _ => None,
+            };
         }
         false
     }
@@ -59,12 +284,152 @@ impl<A: View + Clone> Clone for Portal<A> {
     fn clone(&self) -> Self {
         Self {
             children: self.children.clone(),
+            target: self.target,
         }
     }
 }
 
 impl<A: View + PartialEq> PartialEq for Portal<A> {
     fn eq(&self, other: &Self) -> bool {
-        self.children.eq(&other.children)
+        self.target == other.target && self.children.eq(&other.children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::{Cx, TrackingScope};
+
+    /// A view consisting of a single UI node.
+    #[derive(Clone, PartialEq)]
+    struct TestNode;
+
+    impl View for TestNode {
+        type State = Entity;
+
+        fn nodes(&self, _world: &World, state: &Self::State) -> NodeSpan {
+            NodeSpan::Node(*state)
+        }
+
+        fn build(&self, cx: &mut Cx) -> Self::State {
+            cx.world_mut().spawn(NodeBundle::default()).id()
+        }
+
+        fn rebuild(&self, _cx: &mut Cx, _state: &mut Self::State) -> bool {
+            false
+        }
+
+        fn raze(&self, world: &mut World, state: &mut Self::State) {
+            world.entity_mut(*state).despawn();
+        }
+    }
+
+    fn new_world() -> World {
+        let mut world = World::new();
+        world.init_component::<TargetCamera>();
+        world
+    }
+
+    fn mount(world: &mut World, owner: Entity, portal: &Portal<TestNode>) -> PortalState<Entity> {
+        let mut scope = TrackingScope::new(world.change_tick());
+        let mut cx = Cx::new(world, owner, &mut scope);
+        let mut state = portal.build(&mut cx);
+        portal.attach_children(world, &mut state);
+        state
+    }
+
+    fn z_index(world: &World, state: &PortalState<Entity>) -> i32 {
+        match world.get::<ZIndex>(state.children) {
+            Some(ZIndex::Global(z)) => *z,
+            _ => panic!("expected global z-index"),
+        }
+    }
+
+    #[test]
+    fn test_stacking_order() {
+        let mut world = new_world();
+        let owner = world.spawn_empty().id();
+        let portal = Portal::new(TestNode).into(OverlayLayer::Menus);
+        let mut a = mount(&mut world, owner, &portal);
+        let b = mount(&mut world, owner, &portal);
+        assert!(z_index(&world, &b) > z_index(&world, &a));
+
+        // Exhaust the orders; the next portal must still be on top of the live ones.
+        world.resource_mut::<PortalStack>().next_order = OverlayLayer::LAYER_SIZE;
+        portal.raze(&mut world, &mut a);
+        let c = mount(&mut world, owner, &portal);
+        assert!(z_index(&world, &c) > z_index(&world, &b));
+        assert!(z_index(&world, &b) >= OverlayLayer::Menus.z_index());
+        assert!(z_index(&world, &c) < OverlayLayer::Tooltips.z_index());
+        assert_eq!(world.resource::<PortalStack>().live.len(), 2);
+    }
+
+    #[test]
+    fn test_layer_overflow() {
+        let mut world = new_world();
+        let owner = world.spawn_empty().id();
+        let menu = Portal::new(TestNode).into(OverlayLayer::Menus);
+        let tooltip = Portal::new(TestNode).into(OverlayLayer::Tooltips);
+        let in_layer = |world: &World, state: &PortalState<Entity>, layer: OverlayLayer| {
+            let z = z_index(world, state);
+            z >= layer.z_index() && z < layer.z_index() + OverlayLayer::LAYER_SIZE
+        };
+
+        // More live portals than fit in one layer, split between two layers. Each layer is
+        // renumbered separately, so neither spills into the next.
+        let size = OverlayLayer::LAYER_SIZE as usize;
+        let mut menus = Vec::new();
+        let mut tooltips = Vec::new();
+        for _ in 0..size * 3 / 4 {
+            menus.push(mount(&mut world, owner, &menu));
+            tooltips.push(mount(&mut world, owner, &tooltip));
+        }
+        assert!(menus
+            .iter()
+            .all(|m| in_layer(&world, m, OverlayLayer::Menus)));
+        assert!(tooltips
+            .iter()
+            .all(|t| in_layer(&world, t, OverlayLayer::Tooltips)));
+        let (last, prev) = (&menus[menus.len() - 1], &menus[menus.len() - 2]);
+        assert!(z_index(&world, last) > z_index(&world, prev));
+
+        // Too many portals in a single layer are clamped to the top of the layer.
+        for _ in 0..size / 2 {
+            menus.push(mount(&mut world, owner, &menu));
+        }
+        assert!(menus
+            .iter()
+            .all(|m| in_layer(&world, m, OverlayLayer::Menus)));
+    }
+
+    #[test]
+    fn test_retarget() {
+        let mut world = new_world();
+        let owner = world.spawn_empty().id();
+        let target = world.spawn(NodeBundle::default()).id();
+        let portal = Portal::new(TestNode).into(target);
+        let mut state = mount(&mut world, owner, &portal);
+        assert_eq!(
+            world.get::<Parent>(state.children).map(|p| p.get()),
+            Some(target)
+        );
+
+        let portal = Portal::new(TestNode).into(OverlayLayer::Modals);
+        let mut scope = TrackingScope::new(world.change_tick());
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+        assert!(portal.rebuild(&mut cx, &mut state));
+        portal.attach_children(&mut world, &mut state);
+        assert!(world.get::<Parent>(state.children).is_none());
+        assert!(world.get::<Children>(target).is_none());
+        assert!(z_index(&world, &state) >= OverlayLayer::Modals.z_index());
+
+        let portal = Portal::new(TestNode);
+        portal.attach_children(&mut world, &mut state);
+        assert_eq!(
+            world.get::<ZIndex>(state.children),
+            Some(&ZIndex::default())
+        );
     }
 }
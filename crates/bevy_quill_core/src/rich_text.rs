@@ -0,0 +1,294 @@
+use bevy::{
+    asset::AssetServer,
+    hierarchy::BuildWorldChildren,
+    prelude::{default, Color, Entity, World},
+    text::{Text, TextSection, TextStyle},
+    ui::node_bundles::TextBundle,
+};
+
+#[cfg(feature = "verbose")]
+use bevy::log::info;
+use bevy_mod_stylebuilder::{
+    ColorParam, InheritableFontStyles, TextSpanStyles, UseInheritedTextStyles,
+};
+
+/// Re-exported so that spans can set a weight without depending on the style builder crate.
+pub use bevy_mod_stylebuilder::FontWeight;
+
+use crate::{cx::Cx, NodeSpan, View};
+
+/// A run of text within a [`RichText`] view, with optional style overrides. Any style
+/// properties which are not set on the span are inherited from the parent element.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct Span {
+    /// The text content of the span.
+    pub text: String,
+
+    /// Asset path of the font, if overridden.
+    pub font: Option<String>,
+
+    /// Font size, if overridden.
+    pub font_size: Option<f32>,
+
+    /// Font weight, if overridden. The face is chosen from the inherited font family.
+    pub weight: Option<FontWeight>,
+
+    /// Italic style, if overridden. The face is chosen from the inherited font family.
+    pub italic: Option<bool>,
+
+    /// Text color, if overridden.
+    pub color: Option<Color>,
+}
+
+impl Span {
+    /// Construct a new span with the given text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..default()
+        }
+    }
+
+    /// Override the font for this span.
+    pub fn font(mut self, path: &str) -> Self {
+        self.font = Some(path.to_string());
+        self
+    }
+
+    /// Override the font size for this span.
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Override the font weight for this span.
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Override the italic style for this span.
+    pub fn italic(mut self, italic: bool) -> Self {
+        self.italic = Some(italic);
+        self
+    }
+
+    /// Override the text color for this span.
+    pub fn color(mut self, color: impl ColorParam) -> Self {
+        self.color = color.to_val();
+        self
+    }
+
+    /// Compute the style overrides for this span.
+    fn overrides(&self, world: &World) -> InheritableFontStyles {
+        InheritableFontStyles {
+            font: self
+                .font
+                .as_ref()
+                .map(|path| world.resource::<AssetServer>().load(path.clone())),
+            font_size: self.font_size,
+            color: self.color,
+            weight: self.weight,
+            italic: self.italic,
+            ..default()
+        }
+    }
+}
+
+impl From<&str> for Span {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for Span {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A text view made up of multiple [`Span`]s, each of which can have its own style. The spans
+/// are rendered as sections of a single Bevy `Text` component, so they flow together as a single
+/// paragraph. Span styles are merged with the inherited text styles of the parent element.
+///
+/// When rebuilt, only the sections whose text or style have changed are updated.
+///
+/// Spans are plain data: a `RichText` does not track any reactive dependencies of its own, and
+/// there is no signal- or closure-backed span content. As with `String` views, reactivity comes
+/// from the enclosing [`ViewTemplate`](crate::ViewTemplate): read the signals in `create()` and
+/// construct the spans from them, and the template will rebuild the `RichText` when they change.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+
+impl RichText {
+    /// Construct a new, empty `RichText`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a span.
+    pub fn span(mut self, span: impl Into<Span>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+
+    /// Append a sequence of spans.
+    pub fn spans<S: Into<Span>>(mut self, spans: impl IntoIterator<Item = S>) -> Self {
+        self.spans.extend(spans.into_iter().map(|s| s.into()));
+        self
+    }
+
+    fn overrides(&self, world: &World) -> TextSpanStyles {
+        TextSpanStyles(self.spans.iter().map(|s| s.overrides(world)).collect())
+    }
+}
+
+impl View for RichText {
+    type State = Entity;
+
+    fn nodes(&self, _world: &World, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(*state)
+    }
+
+    fn build(&self, cx: &mut Cx) -> Self::State {
+        let overrides = self.overrides(cx.world());
+        cx.world_mut()
+            .spawn((
+                TextBundle {
+                    text: Text::from_sections(self.spans.iter().map(|span| TextSection {
+                        value: span.text.clone(),
+                        style: TextStyle { ..default() },
+                    })),
+                    ..default()
+                },
+                UseInheritedTextStyles,
+                overrides,
+            ))
+            .id()
+    }
+
+    fn rebuild(&self, cx: &mut Cx, state: &mut Self::State) -> bool {
+        let overrides = self.overrides(cx.world());
+        let mut entt = cx.world_mut().entity_mut(*state);
+
+        // Only touch the span styles if they changed, to avoid re-computing text styles.
+        match entt.get_mut::<TextSpanStyles>() {
+            Some(mut prev) => {
+                if prev.0 != overrides.0 {
+                    *prev = overrides;
+                }
+            }
+            None => {
+                entt.insert(overrides);
+            }
+        }
+
+        if let Some(mut text) = entt.get_mut::<Text>() {
+            if text.sections.len() != self.spans.len() {
+                text.sections = self
+                    .spans
+                    .iter()
+                    .map(|span| TextSection {
+                        value: span.text.clone(),
+                        style: TextStyle { ..default() },
+                    })
+                    .collect();
+            } else {
+                for (index, span) in self.spans.iter().enumerate() {
+                    if text.sections[index].value != span.text {
+                        text.sections[index].value.clone_from(&span.text);
+                    }
+                }
+            }
+        }
+
+        // The text entity doesn't change.
+        false
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        #[cfg(feature = "verbose")]
+        info!("Razing RichText View: {}", *state);
+
+        // Delete the text node.
+        world.entity_mut(*state).remove_parent();
+        world.entity_mut(*state).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackingScope;
+
+    #[test]
+    fn test_span_overrides() {
+        let world = World::new();
+        let text = RichText::new()
+            .span("plain ")
+            .span(Span::new("bold").weight(FontWeight::Bold).font_size(20.))
+            .span(Span::new(" italic").italic(true));
+        let overrides = text.overrides(&world).0;
+        assert_eq!(overrides.len(), 3);
+        assert_eq!(overrides[0], InheritableFontStyles::default());
+        assert_eq!(overrides[1].weight, Some(FontWeight::Bold));
+        assert_eq!(overrides[1].font_size, Some(20.));
+        assert_eq!(overrides[1].italic, None);
+        assert_eq!(overrides[2].italic, Some(true));
+        assert_eq!(overrides[2].weight, None);
+    }
+
+    #[test]
+    fn test_rebuild() {
+        let mut world = World::new();
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let mut cx = Cx::new(&mut world, owner, &mut scope);
+
+        let text = RichText::new()
+            .span("count: ")
+            .span(Span::new("1").weight(FontWeight::Bold));
+        let mut state = text.build(&mut cx);
+        let entity = state;
+        let sections = |world: &World| -> Vec<String> {
+            world
+                .get::<Text>(entity)
+                .unwrap()
+                .sections
+                .iter()
+                .map(|s| s.value.clone())
+                .collect()
+        };
+        assert_eq!(sections(cx.world()), ["count: ", "1"]);
+        assert_eq!(
+            cx.world().get::<TextSpanStyles>(entity).unwrap().0[1].weight,
+            Some(FontWeight::Bold)
+        );
+
+        // Changing the text of a span updates that section in place.
+        let text = RichText::new()
+            .span("count: ")
+            .span(Span::new("2").weight(FontWeight::Bold));
+        assert!(!text.rebuild(&mut cx, &mut state));
+        assert_eq!(state, entity);
+        assert_eq!(sections(cx.world()), ["count: ", "2"]);
+
+        // Adding a span and changing a style replaces the sections and the overrides.
+        let text = RichText::new()
+            .span("count: ")
+            .span(Span::new("2").italic(true))
+            .span("!");
+        assert!(!text.rebuild(&mut cx, &mut state));
+        assert_eq!(state, entity);
+        assert_eq!(sections(cx.world()), ["count: ", "2", "!"]);
+        let styles = &cx.world().get::<TextSpanStyles>(entity).unwrap().0;
+        assert_eq!(styles.len(), 3);
+        assert_eq!(styles[1].weight, None);
+        assert_eq!(styles[1].italic, Some(true));
+
+        text.raze(cx.world_mut(), &mut state);
+        assert!(world.get_entity(entity).is_none());
+    }
+}
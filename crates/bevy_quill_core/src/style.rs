@@ -1,11 +1,14 @@
+use std::panic::Location;
+
 use bevy::{prelude::Entity, ui};
-use bevy_mod_stylebuilder::{StyleBuilder, StyleTuple};
+use bevy_mod_stylebuilder::{StyleBuilder, StyleBuilderClasses, StyleTuple};
 
 use crate::{effects::EntityEffect, Cx};
 
 /// Inserts a static, pre-constructed bundle into the target entity. No reactivity.
 pub struct ApplyStaticStylesEffect<S: StyleTuple> {
     pub(crate) styles: S,
+    pub(crate) location: &'static Location<'static>,
 }
 
 impl<S: StyleTuple> EntityEffect for ApplyStaticStylesEffect<S> {
@@ -16,7 +19,7 @@ impl<S: StyleTuple> EntityEffect for ApplyStaticStylesEffect<S> {
         if let Some(s) = target.get::<ui::Style>() {
             style.clone_from(s);
         }
-        let mut sb = StyleBuilder::new(&mut target, style);
+        let mut sb = StyleBuilder::new(&mut target, style).with_source(self.location);
         self.styles.apply(&mut sb);
         sb.finish();
     }
@@ -28,6 +31,7 @@ impl<S: StyleTuple> EntityEffect for ApplyStaticStylesEffect<S> {
 pub struct ApplyDynamicStylesEffect<F: Fn(D, &mut StyleBuilder), D: PartialEq + Clone> {
     pub(crate) style_fn: F,
     pub(crate) deps: D,
+    pub(crate) location: &'static Location<'static>,
 }
 
 impl<F: Fn(D, &mut StyleBuilder) + Send + Sync, D: PartialEq + Clone + Send + Sync> EntityEffect
@@ -40,7 +44,7 @@ impl<F: Fn(D, &mut StyleBuilder) + Send + Sync, D: PartialEq + Clone + Send + Sy
         if let Some(s) = target.get::<ui::Style>() {
             style.clone_from(s);
         }
-        let mut sb = StyleBuilder::new(&mut target, style);
+        let mut sb = StyleBuilder::new(&mut target, style).with_source(self.location);
         (self.style_fn)(self.deps.clone(), &mut sb);
         sb.finish();
         self.deps.clone()
@@ -52,3 +56,30 @@ impl<F: Fn(D, &mut StyleBuilder) + Send + Sync, D: PartialEq + Clone + Send + Sy
         }
     }
 }
+
+/// Applies stylesheet classes to the target entity. The classes are re-applied if they change.
+pub struct ApplyClassesEffect {
+    pub(crate) classes: String,
+    pub(crate) location: &'static Location<'static>,
+}
+
+impl EntityEffect for ApplyClassesEffect {
+    type State = String;
+    fn apply(&self, cx: &mut Cx, target: Entity) -> Self::State {
+        let mut target = cx.world_mut().entity_mut(target);
+        let mut style = ui::Style::default();
+        if let Some(s) = target.get::<ui::Style>() {
+            style.clone_from(s);
+        }
+        let mut sb = StyleBuilder::new(&mut target, style).with_source(self.location);
+        sb.class(&self.classes);
+        sb.finish();
+        self.classes.clone()
+    }
+
+    fn reapply(&self, cx: &mut Cx, target: Entity, state: &mut Self::State) {
+        if *state != self.classes {
+            *state = self.apply(cx, target);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{io::Reader, Asset, AssetLoader, AsyncReadExt, LoadContext},
+    reflect::TypePath,
+};
+use thiserror::Error;
+
+use crate::message::{parse_bundle, Message, ParseError};
+
+/// A collection of localized messages for a single locale, loaded from a `.ftl` file.
+#[derive(Asset, TypePath, Debug, Default)]
+pub struct MessageBundle {
+    messages: HashMap<String, Message>,
+}
+
+impl MessageBundle {
+    /// Parse a message bundle from source text.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            messages: parse_bundle(source)?,
+        })
+    }
+
+    /// Look up a message by id.
+    pub fn get(&self, id: &str) -> Option<&Message> {
+        self.messages.get(id)
+    }
+
+    /// Returns true if the bundle contains a message with the given id.
+    pub fn contains(&self, id: &str) -> bool {
+        self.messages.contains_key(id)
+    }
+
+    /// Iterate over the ids of all messages in the bundle.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(|k| k.as_str())
+    }
+}
+
+/// Errors that can occur when loading a [`MessageBundle`].
+#[derive(Debug, Error)]
+pub enum MessageBundleLoaderError {
+    /// The file could not be read.
+    #[error("could not read message bundle: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid UTF-8.
+    #[error("message bundle is not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    /// The file contains a syntax error.
+    #[error("could not parse message bundle: {0}")]
+    Parse(#[from] ParseError),
+}
+
+/// Asset loader for `.ftl` message bundles.
+#[derive(Default)]
+pub struct MessageBundleLoader;
+
+impl AssetLoader for MessageBundleLoader {
+    type Asset = MessageBundle;
+    type Settings = ();
+    type Error = MessageBundleLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes)?;
+        Ok(MessageBundle::parse(&source)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
@@ -0,0 +1,35 @@
+mod bundle;
+mod loc;
+mod localization;
+mod message;
+mod plural;
+
+use bevy::{
+    app::{App, Plugin, Update},
+    asset::AssetApp,
+};
+
+pub use bundle::{MessageBundle, MessageBundleLoader, MessageBundleLoaderError};
+pub use loc::{Loc, UseLocalized};
+pub use localization::{Localization, MissingMessages};
+pub use message::{LocArgs, LocValue, Message, ParseError};
+pub use plural::{plural_category, PluralCategory};
+
+pub mod prelude {
+    pub use crate::loc::{Loc, UseLocalized};
+    pub use crate::localization::Localization;
+    pub use crate::message::LocArgs;
+}
+
+/// Plugin which enables loading of message bundles and reactive localization.
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MessageBundle>()
+            .init_asset_loader::<MessageBundleLoader>()
+            .init_resource::<Localization>()
+            .init_resource::<MissingMessages>()
+            .add_systems(Update, localization::watch_bundles);
+    }
+}
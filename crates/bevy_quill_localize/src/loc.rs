@@ -0,0 +1,74 @@
+use bevy::asset::Assets;
+use bevy_quill_core::{Cx, ViewTemplate};
+
+use crate::{
+    bundle::MessageBundle,
+    localization::{Localization, MissingMessages},
+    message::{LocArgs, LocValue},
+};
+
+/// Methods for looking up localized messages from a reactive context.
+pub trait UseLocalized {
+    /// Hook that returns the localized text for the given message id in the active locale.
+    /// The current context will re-render when the locale changes or message bundles are loaded.
+    fn use_localized(&mut self, id: &str) -> String;
+
+    /// Like [`UseLocalized::use_localized`], but with arguments.
+    fn use_localized_args(&mut self, id: &str, args: &LocArgs) -> String;
+}
+
+impl<'p, 'w> UseLocalized for Cx<'p, 'w> {
+    fn use_localized(&mut self, id: &str) -> String {
+        self.use_localized_args(id, &LocArgs::default())
+    }
+
+    fn use_localized_args(&mut self, id: &str, args: &LocArgs) -> String {
+        let localization = self.use_resource::<Localization>();
+        let assets = self.use_resource_untracked::<Assets<MessageBundle>>();
+        let (text, missing) = localization.format(assets, id, args);
+        // Don't report missing messages while the bundles are still loading.
+        if missing && localization.is_loaded(assets, localization.locale()) {
+            let locale = localization.locale().to_string();
+            self.world_mut()
+                .resource_mut::<MissingMessages>()
+                .report(&locale, id);
+        }
+        text
+    }
+}
+
+/// A view which displays a localized message.
+///
+/// Example:
+/// ```ignore
+/// Loc::new("item-count").arg("count", 3)
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Loc {
+    id: String,
+    args: LocArgs,
+}
+
+impl Loc {
+    /// Construct a new `Loc` for the given message id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            args: LocArgs::default(),
+        }
+    }
+
+    /// Add a named argument to the message.
+    pub fn arg(mut self, name: &str, value: impl Into<LocValue>) -> Self {
+        self.args.set(name, value);
+        self
+    }
+}
+
+impl ViewTemplate for Loc {
+    type View = String;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        cx.use_localized_args(&self.id, &self.args)
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::{
+    asset::{AssetEvent, AssetPath, AssetServer, Assets, Handle},
+    log::warn,
+    prelude::{DetectChangesMut, EventReader, ResMut, Resource},
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    bundle::MessageBundle,
+    message::{LocArgs, Message},
+};
+
+/// Resource which holds the message bundles for each locale, as well as the currently
+/// active locale. Views which display localized messages are re-rendered whenever this
+/// resource changes, which includes changing the active locale or (re-)loading a bundle.
+#[derive(Resource, Debug)]
+pub struct Localization {
+    /// The currently active locale.
+    locale: String,
+
+    /// The locale used when a message is not present in the active locale.
+    default_locale: String,
+
+    /// Message bundles for each locale, searched in order of registration.
+    bundles: HashMap<String, Vec<Handle<MessageBundle>>>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new("en-US")
+    }
+}
+
+impl Localization {
+    /// Construct a new `Localization` with the given default locale, which is also
+    /// initially the active locale.
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        let default_locale = default_locale.into();
+        Self {
+            locale: default_locale.clone(),
+            default_locale,
+            bundles: HashMap::default(),
+        }
+    }
+
+    /// The currently active locale.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Change the active locale.
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.locale = locale.into();
+    }
+
+    /// The fallback locale.
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// Change the fallback locale.
+    pub fn set_default_locale(&mut self, locale: impl Into<String>) {
+        self.default_locale = locale.into();
+    }
+
+    /// Iterate over all locales which have bundles registered.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.bundles.keys().map(|k| k.as_str())
+    }
+
+    /// Register a message bundle for a locale. A locale can have multiple bundles; they are
+    /// searched in the order in which they were added.
+    pub fn add_bundle(&mut self, locale: impl Into<String>, bundle: Handle<MessageBundle>) {
+        self.bundles.entry(locale.into()).or_default().push(bundle);
+    }
+
+    /// Load a message bundle from an asset path and register it for a locale.
+    pub fn load_bundle<'a>(
+        &mut self,
+        server: &AssetServer,
+        locale: impl Into<String>,
+        path: impl Into<AssetPath<'a>>,
+    ) {
+        let handle = server.load(path.into());
+        self.add_bundle(locale, handle);
+    }
+
+    /// True if all of the bundles for the given locale have finished loading.
+    pub fn is_loaded(&self, assets: &Assets<MessageBundle>, locale: &str) -> bool {
+        self.bundles
+            .get(locale)
+            .is_none_or(|bundles| bundles.iter().all(|h| assets.contains(h)))
+    }
+
+    /// Look up a message in the bundles for a specific locale.
+    fn find<'a>(
+        &self,
+        assets: &'a Assets<MessageBundle>,
+        locale: &str,
+        id: &str,
+    ) -> Option<&'a Message> {
+        self.bundles
+            .get(locale)?
+            .iter()
+            .filter_map(|h| assets.get(h))
+            .find_map(|bundle| bundle.get(id))
+    }
+
+    /// Look up a message, first in the active locale and then in the default locale.
+    /// Returns the message and the locale it was found in.
+    fn lookup<'a>(
+        &'a self,
+        assets: &'a Assets<MessageBundle>,
+        id: &str,
+    ) -> Option<(&'a str, &'a Message)> {
+        self.find(assets, &self.locale, id)
+            .map(|m| (self.locale.as_str(), m))
+            .or_else(|| {
+                self.find(assets, &self.default_locale, id)
+                    .map(|m| (self.default_locale.as_str(), m))
+            })
+    }
+
+    /// Format a localized message. Returns the formatted text, and whether the message was
+    /// missing from the active locale. If the message is not found in any locale, the message
+    /// id is returned as the text.
+    pub fn format(
+        &self,
+        assets: &Assets<MessageBundle>,
+        id: &str,
+        args: &LocArgs,
+    ) -> (String, bool) {
+        match self.lookup(assets, id) {
+            Some((locale, message)) => {
+                let text = message.format(locale, args, &|r| {
+                    self.lookup(assets, r).map(|(_, message)| message)
+                });
+                (text, locale != self.locale)
+            }
+            None => (id.to_string(), true),
+        }
+    }
+}
+
+/// Resource which records the message ids which were requested but not present in the active
+/// locale. This is useful for finding untranslated strings.
+#[derive(Resource, Debug, Default)]
+pub struct MissingMessages(HashSet<(String, String)>);
+
+impl MissingMessages {
+    /// Record a missing message. Logs a warning the first time a given message is reported.
+    pub fn report(&mut self, locale: &str, id: &str) {
+        if self.0.insert((locale.to_string(), id.to_string())) {
+            warn!("Missing localized message `{}` for locale `{}`", id, locale);
+        }
+    }
+
+    /// Iterate over the missing messages, as (locale, message id) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(l, id)| (l.as_str(), id.as_str()))
+    }
+
+    /// True if there are no missing messages.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Clear the list of missing messages.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Marks the `Localization` resource as changed whenever one of its bundles is loaded or
+/// hot-reloaded, so that views displaying localized text are re-rendered.
+pub(crate) fn watch_bundles(
+    mut events: EventReader<AssetEvent<MessageBundle>>,
+    mut localization: ResMut<Localization>,
+) {
+    let mut changed = false;
+    for ev in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = ev {
+            changed |= localization
+                .bundles
+                .values()
+                .flatten()
+                .any(|h| h.id() == *id);
+        }
+    }
+    if changed {
+        localization.set_changed();
+    }
+}
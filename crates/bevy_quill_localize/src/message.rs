@@ -0,0 +1,483 @@
+use std::{collections::HashMap, fmt::Write, iter::Peekable, str::Chars};
+
+use thiserror::Error;
+
+use crate::plural::plural_category;
+
+/// A value which can be passed as an argument to a localized message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocValue {
+    /// A string argument.
+    String(String),
+    /// A numeric argument. Numeric arguments can be used to select plural variants.
+    Number(f64),
+}
+
+impl std::fmt::Display for LocValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocValue::String(s) => f.write_str(s),
+            LocValue::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl From<&str> for LocValue {
+    fn from(value: &str) -> Self {
+        LocValue::String(value.to_string())
+    }
+}
+
+impl From<String> for LocValue {
+    fn from(value: String) -> Self {
+        LocValue::String(value)
+    }
+}
+
+macro_rules! impl_number_value {
+    ($($t:ty),*) => {
+        $(impl From<$t> for LocValue {
+            fn from(value: $t) -> Self {
+                LocValue::Number(value as f64)
+            }
+        })*
+    };
+}
+
+impl_number_value!(i32, i64, u32, u64, usize, f32, f64);
+
+/// A list of named arguments to a localized message.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LocArgs(Vec<(String, LocValue)>);
+
+impl LocArgs {
+    /// Construct an empty argument list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of a named argument.
+    pub fn set(&mut self, name: &str, value: impl Into<LocValue>) {
+        let value = value.into();
+        match self.0.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.0.push((name.to_string(), value)),
+        }
+    }
+
+    /// Builder-style version of [`LocArgs::set`].
+    pub fn with(mut self, name: &str, value: impl Into<LocValue>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Look up an argument by name.
+    pub fn get(&self, name: &str) -> Option<&LocValue> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
+/// An error encountered while parsing a message bundle.
+#[derive(Debug, Error, PartialEq)]
+#[error("line {line}: {message}")]
+pub struct ParseError {
+    /// The line number (1-based) of the entry containing the error.
+    pub line: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+/// A parsed message pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message(Vec<Element>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    /// Literal text.
+    Text(String),
+    /// Reference to an argument, `{ $name }`.
+    Arg(String),
+    /// Reference to another message, `{ message-id }`.
+    Ref(String),
+    /// Selection based on the value of an argument.
+    Select {
+        selector: String,
+        variants: Vec<(String, Vec<Element>)>,
+        default: usize,
+    },
+}
+
+/// Maximum depth of message references, to guard against reference cycles.
+const MAX_REF_DEPTH: usize = 8;
+
+impl Message {
+    /// Format the message.
+    ///
+    /// Arguments:
+    /// - `locale`: The locale, used to determine plural categories.
+    /// - `args`: The arguments to the message.
+    /// - `resolve`: Function used to look up references to other messages.
+    pub fn format<'a>(
+        &self,
+        locale: &str,
+        args: &LocArgs,
+        resolve: &dyn Fn(&str) -> Option<&'a Message>,
+    ) -> String {
+        let mut out = String::new();
+        format_elements(&self.0, locale, args, resolve, 0, &mut out);
+        out
+    }
+}
+
+fn format_elements<'a>(
+    elements: &[Element],
+    locale: &str,
+    args: &LocArgs,
+    resolve: &dyn Fn(&str) -> Option<&'a Message>,
+    depth: usize,
+    out: &mut String,
+) {
+    for element in elements {
+        match element {
+            Element::Text(text) => out.push_str(text),
+            Element::Arg(name) => match args.get(name) {
+                Some(value) => {
+                    let _ = write!(out, "{}", value);
+                }
+                None => {
+                    let _ = write!(out, "{{${}}}", name);
+                }
+            },
+            Element::Ref(id) => match resolve(id) {
+                Some(message) if depth < MAX_REF_DEPTH => {
+                    format_elements(&message.0, locale, args, resolve, depth + 1, out);
+                }
+                _ => {
+                    let _ = write!(out, "{{{}}}", id);
+                }
+            },
+            Element::Select {
+                selector,
+                variants,
+                default,
+            } => {
+                let index =
+                    select_variant(args.get(selector), locale, variants).unwrap_or(*default);
+                format_elements(&variants[index].1, locale, args, resolve, depth, out);
+            }
+        }
+    }
+}
+
+fn select_variant(
+    value: Option<&LocValue>,
+    locale: &str,
+    variants: &[(String, Vec<Element>)],
+) -> Option<usize> {
+    match value? {
+        LocValue::String(s) => variants.iter().position(|(key, _)| key == s),
+        LocValue::Number(n) => {
+            // Exact numeric matches take precedence over plural categories.
+            variants
+                .iter()
+                .position(|(key, _)| key.parse::<f64>().is_ok_and(|k| k == *n))
+                .or_else(|| {
+                    let category = plural_category(locale, *n).name();
+                    variants.iter().position(|(key, _)| key == category)
+                })
+        }
+    }
+}
+
+/// Parse the text of a message bundle into a map of message ids to messages.
+pub fn parse_bundle(source: &str) -> Result<HashMap<String, Message>, ParseError> {
+    let mut messages = HashMap::new();
+    let mut lines = source.lines().enumerate().peekable();
+    while let Some((index, line)) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let line_no = index + 1;
+        if line.starts_with(char::is_whitespace) {
+            return Err(ParseError {
+                line: line_no,
+                message: "unexpected indented line".to_string(),
+            });
+        }
+        let Some((id, first)) = line.split_once('=') else {
+            return Err(ParseError {
+                line: line_no,
+                message: format!("expected `=` after message id `{}`", trimmed),
+            });
+        };
+        let id = id.trim();
+        if !is_identifier(id) {
+            return Err(ParseError {
+                line: line_no,
+                message: format!("invalid message id `{}`", id),
+            });
+        }
+
+        // Gather continuation lines, which are indented, or which begin a variant or close
+        // a select expression.
+        let mut value: Vec<&str> = Vec::new();
+        if !first.trim().is_empty() {
+            value.push(first.trim());
+        }
+        while let Some((_, next)) = lines.peek() {
+            if next.trim().is_empty() {
+                lines.next();
+            } else if next.starts_with(|c: char| c.is_whitespace() || matches!(c, '[' | '*' | '}'))
+            {
+                value.push(next.trim());
+                lines.next();
+            } else {
+                break;
+            }
+        }
+
+        let text = value.join("\n");
+        let mut chars = text.chars().peekable();
+        let elements = parse_pattern(&mut chars, false).map_err(|message| ParseError {
+            line: line_no,
+            message: format!("in message `{}`: {}", id, message),
+        })?;
+        if messages.insert(id.to_string(), Message(elements)).is_some() {
+            return Err(ParseError {
+                line: line_no,
+                message: format!("duplicate message id `{}`", id),
+            });
+        }
+    }
+    Ok(messages)
+}
+
+fn is_identifier(id: &str) -> bool {
+    let mut chars = id.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Parse a sequence of text and placeables. If `inline` is true, the pattern ends at the
+/// next newline (used for select variants).
+fn parse_pattern(chars: &mut Peekable<Chars>, inline: bool) -> Result<Vec<Element>, String> {
+    let mut elements = Vec::new();
+    let mut text = String::new();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '\n' if inline => break,
+            '{' => {
+                chars.next();
+                if !text.is_empty() {
+                    elements.push(Element::Text(std::mem::take(&mut text)));
+                }
+                elements.push(parse_placeable(chars)?);
+            }
+            '}' => return Err("unmatched `}`".to_string()),
+            _ => {
+                text.push(c);
+                chars.next();
+            }
+        }
+    }
+    if !text.is_empty() {
+        elements.push(Element::Text(text));
+    }
+    Ok(elements)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_name(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_') {
+        name.push(c);
+    }
+    name
+}
+
+/// Parse the contents of a placeable, after the opening brace.
+fn parse_placeable(chars: &mut Peekable<Chars>) -> Result<Element, String> {
+    skip_whitespace(chars);
+    let expr = match chars.peek() {
+        Some('$') => {
+            chars.next();
+            let name = parse_name(chars);
+            if name.is_empty() {
+                return Err("expected argument name after `$`".to_string());
+            }
+            Element::Arg(name)
+        }
+        Some('"') => {
+            chars.next();
+            let mut literal = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c) => literal.push(c),
+                        None => return Err("unterminated string literal".to_string()),
+                    },
+                    Some(c) => literal.push(c),
+                    None => return Err("unterminated string literal".to_string()),
+                }
+            }
+            Element::Text(literal)
+        }
+        Some(c) if c.is_ascii_alphabetic() => Element::Ref(parse_name(chars)),
+        _ => return Err("expected argument, string literal or message reference".to_string()),
+    };
+
+    skip_whitespace(chars);
+    match chars.next() {
+        Some('}') => Ok(expr),
+        Some('-') if chars.next_if_eq(&'>').is_some() => {
+            let Element::Arg(selector) = expr else {
+                return Err("only arguments can be used as selectors".to_string());
+            };
+            parse_variants(chars, selector)
+        }
+        _ => Err("expected `}`".to_string()),
+    }
+}
+
+/// Parse the variant list of a select expression, after the `->`.
+fn parse_variants(chars: &mut Peekable<Chars>, selector: String) -> Result<Element, String> {
+    let mut variants = Vec::new();
+    let mut default = None;
+    loop {
+        skip_whitespace(chars);
+        let is_default = match chars.next() {
+            Some('}') => break,
+            Some('*') => {
+                if chars.next() != Some('[') {
+                    return Err("expected `[` after `*`".to_string());
+                }
+                true
+            }
+            Some('[') => false,
+            _ => return Err("expected variant key or `}`".to_string()),
+        };
+        let mut key = String::new();
+        loop {
+            match chars.next() {
+                Some(']') => break,
+                Some(c) => key.push(c),
+                None => return Err("unterminated variant key".to_string()),
+            }
+        }
+        if is_default {
+            if default.is_some() {
+                return Err("multiple default variants".to_string());
+            }
+            default = Some(variants.len());
+        }
+        // Skip spaces (but not newlines) before the variant value.
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        let mut value = parse_pattern(chars, true)?;
+        if let Some(Element::Text(text)) = value.last_mut() {
+            let len = text.trim_end().len();
+            text.truncate(len);
+        }
+        variants.push((key.trim().to_string(), value));
+    }
+    match default {
+        Some(default) => Ok(Element::Select {
+            selector,
+            variants,
+            default,
+        }),
+        None => Err("select expression has no default variant".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str, id: &str, locale: &str, args: &LocArgs) -> String {
+        let messages = parse_bundle(source).unwrap();
+        messages[id].format(locale, args, &|r| messages.get(r))
+    }
+
+    #[test]
+    fn test_simple_message() {
+        let source = "# comment\nhello = Hello, world!\n";
+        assert_eq!(
+            format(source, "hello", "en", &LocArgs::new()),
+            "Hello, world!"
+        );
+    }
+
+    #[test]
+    fn test_arguments() {
+        let source = "greeting = Hello, { $name }!";
+        let args = LocArgs::new().with("name", "Ada");
+        assert_eq!(format(source, "greeting", "en", &args), "Hello, Ada!");
+        assert_eq!(
+            format(source, "greeting", "en", &LocArgs::new()),
+            "Hello, {$name}!"
+        );
+    }
+
+    #[test]
+    fn test_multiline() {
+        let source = "text =\n    First line\n    second line\nnext = x";
+        assert_eq!(
+            format(source, "text", "en", &LocArgs::new()),
+            "First line\nsecond line"
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let source = "app = Vortex\ntitle = { app } Editor\nlit = {\"{\"}";
+        assert_eq!(
+            format(source, "title", "en", &LocArgs::new()),
+            "Vortex Editor"
+        );
+        assert_eq!(format(source, "lit", "en", &LocArgs::new()), "{");
+    }
+
+    #[test]
+    fn test_reference_cycle() {
+        let source = "a = { b }\nb = { a }";
+        // Should terminate rather than recursing forever.
+        assert!(format(source, "a", "en", &LocArgs::new()).ends_with('}'));
+    }
+
+    #[test]
+    fn test_plurals() {
+        let source = r#"items = { $n ->
+    [0] No items
+    [one] One item
+   *[other] { $n } items
+}"#;
+        let fmt =
+            |n: i32, locale: &str| format(source, "items", locale, &LocArgs::new().with("n", n));
+        assert_eq!(fmt(0, "en"), "No items");
+        assert_eq!(fmt(1, "en"), "One item");
+        assert_eq!(fmt(5, "en"), "5 items");
+        assert_eq!(fmt(1, "ja"), "1 items");
+    }
+
+    #[test]
+    fn test_string_selector() {
+        let source = "pronoun = { $gender ->\n  [female] she\n  [male] he\n *[other] they\n}";
+        let fmt = |g: &str| format(source, "pronoun", "en", &LocArgs::new().with("gender", g));
+        assert_eq!(fmt("female"), "she");
+        assert_eq!(fmt("unknown"), "they");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse_bundle("no equals").unwrap_err().line, 1);
+        assert!(parse_bundle("a = { $n ->\n [one] x\n}").is_err());
+        assert!(parse_bundle("a = { $n").is_err());
+        assert!(parse_bundle("a = x\na = y").is_err());
+        assert!(parse_bundle("a = }").is_err());
+    }
+}
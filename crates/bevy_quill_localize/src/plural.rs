@@ -0,0 +1,150 @@
+/// CLDR plural category of a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// The name of the category, as used in variant keys.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// Return the cardinal plural category of `n` for the given locale. Only the language
+/// part of the locale identifier (e.g. `ru` in `ru-RU`) is considered. This covers the
+/// rules for the most common languages; languages which are not known use the English rules.
+pub fn plural_category(locale: &str, n: f64) -> PluralCategory {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let n = n.abs();
+    let is_int = n.fract() == 0.;
+    let i = n.trunc() as u64;
+    match language.as_str() {
+        // No plural forms.
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" => PluralCategory::Other,
+
+        // 0 and 1 are singular.
+        "fr" | "pt" | "hi" => {
+            if i <= 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        // East Slavic.
+        "ru" | "uk" | "be" => {
+            if !is_int {
+                PluralCategory::Other
+            } else if i % 10 == 1 && i % 100 != 11 {
+                PluralCategory::One
+            } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+
+        "pl" => {
+            if !is_int {
+                PluralCategory::Other
+            } else if i == 1 {
+                PluralCategory::One
+            } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+
+        "cs" | "sk" => {
+            if !is_int {
+                PluralCategory::Many
+            } else if i == 1 {
+                PluralCategory::One
+            } else if (2..=4).contains(&i) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        "ar" => {
+            if !is_int {
+                PluralCategory::Other
+            } else if i == 0 {
+                PluralCategory::Zero
+            } else if i == 1 {
+                PluralCategory::One
+            } else if i == 2 {
+                PluralCategory::Two
+            } else if (3..=10).contains(&(i % 100)) {
+                PluralCategory::Few
+            } else if (11..=99).contains(&(i % 100)) {
+                PluralCategory::Many
+            } else {
+                PluralCategory::Other
+            }
+        }
+
+        // English, German, Spanish, Italian, etc.
+        _ => {
+            if is_int && i == 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english() {
+        assert_eq!(plural_category("en-US", 0.), PluralCategory::Other);
+        assert_eq!(plural_category("en-US", 1.), PluralCategory::One);
+        assert_eq!(plural_category("en", 1.5), PluralCategory::Other);
+        assert_eq!(plural_category("de", 2.), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_french() {
+        assert_eq!(plural_category("fr", 0.), PluralCategory::One);
+        assert_eq!(plural_category("fr-CA", 1.), PluralCategory::One);
+        assert_eq!(plural_category("fr", 2.), PluralCategory::Other);
+    }
+
+    #[test]
+    fn test_russian() {
+        assert_eq!(plural_category("ru", 1.), PluralCategory::One);
+        assert_eq!(plural_category("ru", 21.), PluralCategory::One);
+        assert_eq!(plural_category("ru", 11.), PluralCategory::Many);
+        assert_eq!(plural_category("ru", 3.), PluralCategory::Few);
+        assert_eq!(plural_category("ru", 13.), PluralCategory::Many);
+        assert_eq!(plural_category("ru_RU", 5.), PluralCategory::Many);
+    }
+
+    #[test]
+    fn test_japanese() {
+        assert_eq!(plural_category("ja", 1.), PluralCategory::Other);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Disabled, IsDisabled};
+use super::{Disabled, IsDisabled, Tooltip};
 use crate::{
     colors,
     cursor::StyleBuilderCursor,
@@ -19,7 +19,7 @@ use bevy::{
 use bevy_mod_picking::{events::PointerCancel, prelude::*};
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::{
-    Callback, Cx, Element, IntoViewChild, RunCallback, View, ViewChild, ViewTemplate,
+    Callback, Cond, Cx, Element, IntoViewChild, RunCallback, View, ViewChild, ViewTemplate,
 };
 
 /// The variant determines the button's color scheme
@@ -93,6 +93,9 @@ pub struct Button {
 
     /// If true, render the button in a 'minimal' style with no background and reduced padding.
     pub minimal: bool,
+
+    /// Content of the tooltip shown when hovering over the button.
+    pub tooltip: Option<ViewChild>,
 }
 
 impl Button {
@@ -171,6 +174,12 @@ impl Button {
         self.autofocus = autofocus;
         self
     }
+
+    /// Set the tooltip shown when hovering over the button.
+    pub fn tooltip(mut self, tooltip: impl IntoViewChild) -> Self {
+        self.tooltip = Some(tooltip.into_view_child());
+        self
+    }
 }
 
 impl ViewTemplate for Button {
@@ -318,6 +327,11 @@ impl ViewTemplate for Button {
                         focused,
                     ),
                 self.children.clone(),
+                Cond::new(
+                    self.tooltip.is_some(),
+                    Tooltip::new(id).content(self.tooltip.clone().unwrap_or_default()),
+                    (),
+                ),
             ))
     }
 }
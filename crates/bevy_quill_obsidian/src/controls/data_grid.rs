@@ -0,0 +1,971 @@
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode,
+    },
+    prelude::*,
+    ui,
+};
+use bevy_mod_picking::{events::PointerCancel, prelude::*};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    focus::{KeyPressEvent, TabIndex},
+    hooks::{UseIsFocus, UseIsHover},
+    scrolling::ScrollArea,
+    text_editing::DOUBLE_CLICK_TIME,
+    typography,
+};
+
+use super::{
+    selection::{activate, handle_selection_key, press_row, SelectionCursor, SelectionProps},
+    virtual_view::{VirtualLayout, VirtualViewProps, VirtualWindow},
+    Icon, ItemBuilder, ListSelectionMode, ScrollView,
+};
+
+/// Height of the header row, in pixels.
+const HEADER_HEIGHT: f32 = 24.;
+
+fn style_data_grid(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .background_color(colors::U1)
+        .border_radius(5.0)
+        .padding(3);
+}
+
+fn style_grid_scroll(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).min_height(0);
+}
+
+fn style_grid_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::FlexStart);
+}
+
+fn style_header(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .position(ui::PositionType::Relative)
+        .height(HEADER_HEIGHT)
+        .flex_shrink(0.)
+        .z_index(1)
+        .background_color(colors::U2);
+}
+
+fn style_header_cell(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .flex_shrink(0.)
+        .padding((6, 0))
+        .column_gap(4)
+        .overflow(ui::OverflowAxis::Clip)
+        .color(colors::DIM);
+}
+
+fn style_header_label(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).overflow(ui::OverflowAxis::Clip);
+}
+
+fn style_resize_handle(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(0)
+        .bottom(0)
+        .right(0)
+        .width(5)
+        .cursor(CursorIcon::ColResize);
+}
+
+fn style_body(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative).flex_shrink(0.);
+}
+
+fn style_row(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .left(0)
+        .color(colors::FOREGROUND);
+}
+
+fn style_cell(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .flex_shrink(0.)
+        .padding((6, 0))
+        .overflow(ui::OverflowAxis::Clip);
+}
+
+/// The order in which a [`DataGrid`] is sorted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortDirection {
+    /// Smallest values first.
+    Ascending,
+
+    /// Largest values first.
+    Descending,
+}
+
+/// A column of a [`DataGrid`].
+#[derive(Clone, PartialEq)]
+pub struct DataColumn {
+    /// The content of the column's header.
+    pub header: ViewChild,
+
+    /// Builds the view for the column's cell in a given row.
+    pub cell: ItemBuilder,
+
+    /// The initial width of the column, in pixels.
+    pub width: f32,
+
+    /// The smallest width the column can be resized to.
+    pub min_width: f32,
+
+    /// Whether clicking the header sorts the grid by this column.
+    pub sortable: bool,
+}
+
+impl DataColumn {
+    /// Create a new column with the given header.
+    pub fn new(header: impl IntoViewChild) -> Self {
+        Self {
+            header: header.into_view_child(),
+            cell: ItemBuilder::default(),
+            width: 100.,
+            min_width: 24.,
+            sortable: false,
+        }
+    }
+
+    /// Set the function which builds the view for a cell, given the row index.
+    pub fn cell<V: IntoViewChild, F: Fn(usize) -> V + Send + Sync + 'static>(
+        mut self,
+        cell_fn: F,
+    ) -> Self {
+        self.cell = ItemBuilder::new(cell_fn);
+        self
+    }
+
+    /// Set the builder for the view of a cell. Builders are compared by identity, so unlike
+    /// [`cell`](Self::cell), this lets the builder be kept between renders, for example using
+    /// [`Cx::create_memo`], so that the visible rows aren't rebuilt each time.
+    pub fn cell_builder(mut self, cell: ItemBuilder) -> Self {
+        self.cell = cell;
+        self
+    }
+
+    /// Set the initial width of the column.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the smallest width the column can be resized to.
+    pub fn min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// Set whether the grid can be sorted by this column.
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+/// A table of rows and resizable columns, which only builds the rows that are visible.
+///
+/// Like [`VirtualListView`](super::VirtualListView), the grid doesn't hold the data: each column
+/// builds its cells from the row's position in the display order. The caller also owns the sort
+/// order and the selection. Clicking a sortable header calls `on_sort`, after which the caller
+/// should supply the rows in the new order. Rows are identified by a key supplied by the caller,
+/// as with [`ListRow`](super::ListRow), so the selection and cursor follow the rows when they
+/// are re-sorted. Column widths start at [`DataColumn::width`] and are then tracked by the grid
+/// as the user drags the column edges.
+#[derive(Clone, PartialEq)]
+pub struct DataGrid {
+    /// The columns of the grid.
+    pub columns: Vec<DataColumn>,
+
+    /// The keys of the rows, in display order. Each key must be unique within the grid.
+    pub rows: Vec<u64>,
+
+    /// The height of each row.
+    pub row_height: f32,
+
+    /// The column the rows are sorted by, which is marked in the header.
+    pub sort: Option<(usize, SortDirection)>,
+
+    /// Keys of the selected rows.
+    pub selection: Vec<u64>,
+
+    /// Whether rows can be selected, and how many at once.
+    pub selection_mode: ListSelectionMode,
+
+    /// The number of rows to build above and below the visible region.
+    pub overscan: usize,
+
+    /// Additional styles to be applied to the grid.
+    pub style: StyleHandle,
+
+    /// The tab index of the grid (default 0). Only used if rows can be selected.
+    pub tab_index: i32,
+
+    /// Callback called with the column and direction when a sortable header is clicked.
+    pub on_sort: Option<Callback<(usize, SortDirection)>>,
+
+    /// Callback called with the new selection when the user changes it.
+    pub on_selection_change: Option<Callback<Vec<u64>>>,
+
+    /// Callback called with the key of a row when it is double-clicked, or when Enter is
+    /// pressed while it has the cursor.
+    pub on_activate: Option<Callback<u64>>,
+}
+
+impl Default for DataGrid {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            row_height: 22.,
+            sort: None,
+            selection: Vec::new(),
+            selection_mode: ListSelectionMode::None,
+            overscan: 4,
+            style: StyleHandle::default(),
+            tab_index: 0,
+            on_sort: None,
+            on_selection_change: None,
+            on_activate: None,
+        }
+    }
+}
+
+impl DataGrid {
+    /// Create a new data grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the columns of the grid.
+    pub fn columns(mut self, columns: Vec<DataColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the keys of the rows, in display order.
+    pub fn rows(mut self, rows: Vec<u64>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// Set the height of each row.
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Set the column the rows are sorted by.
+    pub fn sort(mut self, sort: Option<(usize, SortDirection)>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Set the keys of the selected rows.
+    pub fn selection(mut self, selection: Vec<u64>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Set whether rows can be selected.
+    pub fn selection_mode(mut self, selection_mode: ListSelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Set the number of rows to build above and below the visible region.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Set additional styles to be applied to the grid.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the grid.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set the callback called when a sortable header is clicked.
+    pub fn on_sort(mut self, on_sort: Callback<(usize, SortDirection)>) -> Self {
+        self.on_sort = Some(on_sort);
+        self
+    }
+
+    /// Set the callback called when the selection changes.
+    pub fn on_selection_change(mut self, on_selection_change: Callback<Vec<u64>>) -> Self {
+        self.on_selection_change = Some(on_selection_change);
+        self
+    }
+
+    /// Set the callback called when a row is activated.
+    pub fn on_activate(mut self, on_activate: Callback<u64>) -> Self {
+        self.on_activate = Some(on_activate);
+        self
+    }
+}
+
+/// Properties of a data grid which are needed by the event handlers.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct DataGridProps {
+    rows: Vec<u64>,
+    row_height: f32,
+    widths: Vec<f32>,
+    min_widths: Vec<f32>,
+    sort: Option<(usize, SortDirection)>,
+    selection: Vec<u64>,
+    mode: ListSelectionMode,
+    on_sort: Option<Callback<(usize, SortDirection)>>,
+    on_selection_change: Option<Callback<Vec<u64>>>,
+    on_activate: Option<Callback<u64>>,
+}
+
+impl SelectionProps for DataGridProps {
+    type Key = u64;
+
+    fn mode(&self) -> ListSelectionMode {
+        self.mode
+    }
+
+    fn selection(&self) -> &[u64] {
+        &self.selection
+    }
+
+    fn on_selection_change(&self) -> Option<Callback<Vec<u64>>> {
+        self.on_selection_change
+    }
+
+    fn on_activate(&self) -> Option<Callback<u64>> {
+        self.on_activate
+    }
+
+    fn keys(world: &World, grid: Entity) -> Vec<u64> {
+        world
+            .get::<DataGridProps>(grid)
+            .map_or(Vec::new(), |props| props.rows.clone())
+    }
+
+    fn reveal(world: &mut World, grid: Entity, key: u64) {
+        let row = world
+            .get::<DataGridProps>(grid)
+            .and_then(|props| props.rows.iter().position(|k| *k == key));
+        if let Some(row) = row {
+            scroll_to_row(world, grid, row);
+        }
+    }
+}
+
+/// Widths of the columns which the user has resized.
+#[derive(Component, Default)]
+struct DataGridWidths(Vec<Option<f32>>);
+
+/// Interaction state of a data grid, stored on the root entity.
+#[derive(Component)]
+pub(crate) struct DataGridState {
+    scroll: Entity,
+    /// Time and key of the last click, used to detect double-clicks.
+    last_click: Option<(f32, u64)>,
+    /// The column being resized, and its width when the drag started.
+    resizing: Option<(usize, f32)>,
+}
+
+/// Marks the header row of a data grid, which is kept at the top of the scroll area.
+#[derive(Component, Clone)]
+pub(crate) struct DataGridHeader {
+    scroll: Entity,
+}
+
+impl ViewTemplate for DataGrid {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let scroll = cx.create_entity();
+        let header = cx.create_entity();
+        let selectable = self.selection_mode != ListSelectionMode::None;
+        let focused = cx.is_focus_visible(id);
+
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<DataGridState>() {
+            entt.insert((
+                DataGridState {
+                    scroll,
+                    last_click: None,
+                    resizing: None,
+                },
+                DataGridWidths::default(),
+                SelectionCursor::<u64>::default(),
+            ));
+        }
+        let mut entt = cx.world_mut().entity_mut(scroll);
+        if !entt.contains::<VirtualWindow>() {
+            entt.insert(VirtualWindow::default());
+        }
+        cx.create_effect(
+            move |world, props: VirtualViewProps| {
+                world.entity_mut(scroll).insert(props);
+            },
+            VirtualViewProps {
+                layout: VirtualLayout::Cells {
+                    count: self.rows.len(),
+                    size: Vec2::new(0., self.row_height),
+                    gap: 0.,
+                },
+                overscan: self.overscan,
+            },
+        );
+
+        let widths = column_widths(
+            &self.columns,
+            cx.use_component::<DataGridWidths>(id)
+                .map_or(&[], |widths| widths.0.as_slice()),
+        );
+        let total_width: f32 = widths.iter().sum();
+        let window = cx
+            .use_component::<VirtualWindow>(scroll)
+            .cloned()
+            .unwrap_or_default();
+        let rows: Vec<(usize, u64)> = window
+            .range
+            .clone()
+            .filter_map(|row| self.rows.get(row).map(|key| (row, *key)))
+            .collect();
+        let cells: Vec<(f32, ItemBuilder)> = widths
+            .iter()
+            .zip(self.columns.iter())
+            .map(|(width, column)| (*width, column.cell.clone()))
+            .collect();
+        let headers: Vec<HeaderCell> = self
+            .columns
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(index, (column, width))| HeaderCell {
+                grid: id,
+                column: index,
+                width: *width,
+                header: column.header.clone(),
+                sortable: column.sortable,
+                sort: self
+                    .sort
+                    .filter(|(sorted, _)| *sorted == index)
+                    .map(|(_, direction)| direction),
+            })
+            .collect();
+        let row_height = self.row_height;
+        let tab_index = self.tab_index;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("DataGrid")
+            .style((
+                typography::text_default,
+                style_data_grid,
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .insert_dyn(
+                |props: DataGridProps| {
+                    let mut builder = NodeBuilder::new(Role::Grid);
+                    builder.set_row_count(props.rows.len());
+                    if props.mode == ListSelectionMode::Multiple {
+                        builder.set_multiselectable();
+                    }
+                    (props, AccessibilityNode::from(builder))
+                },
+                DataGridProps {
+                    rows: self.rows.clone(),
+                    row_height: self.row_height,
+                    widths: widths.clone(),
+                    min_widths: self.columns.iter().map(|column| column.min_width).collect(),
+                    sort: self.sort,
+                    selection: self.selection.clone(),
+                    mode: self.selection_mode,
+                    on_sort: self.on_sort,
+                    on_selection_change: self.on_selection_change,
+                    on_activate: self.on_activate,
+                },
+            )
+            .insert_if(selectable, move || TabIndex(tab_index))
+            .insert_dyn(
+                move |_| {
+                    On::<KeyPressEvent>::run(move |world: &mut World| {
+                        let event = world.resource::<ListenerInput<KeyPressEvent>>();
+                        let (key, shift, ctrl) = (event.key_code, event.shift, event.ctrl);
+                        if handle_key(world, id, key, shift, ctrl) {
+                            world
+                                .resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .stop_propagation();
+                        }
+                    })
+                },
+                (),
+            )
+            .children(
+                ScrollView::new()
+                    .entity(Some(scroll))
+                    .scroll_enable_x(true)
+                    .scroll_enable_y(true)
+                    .style(style_grid_scroll)
+                    .content_style(style_grid_content)
+                    .children((
+                        Element::<NodeBundle>::for_entity(header)
+                            .named("DataGrid::Header")
+                            .style(style_header)
+                            .style_dyn(
+                                |width, sb| {
+                                    sb.width(width);
+                                },
+                                total_width,
+                            )
+                            .insert(DataGridHeader { scroll })
+                            .children(For::index(&headers, |header, _| header.clone())),
+                        Element::<NodeBundle>::new()
+                            .named("DataGrid::Body")
+                            .style(style_body)
+                            .style_dyn(
+                                |(width, height), sb| {
+                                    sb.width(width).height(height);
+                                },
+                                (total_width, self.rows.len() as f32 * row_height),
+                            )
+                            .children(For::keyed(
+                                &rows,
+                                |(_, key)| *key,
+                                move |(row, key)| DataGridRow {
+                                    grid: id,
+                                    row: *row,
+                                    key: *key,
+                                    row_height,
+                                    width: total_width,
+                                    cells: cells.clone(),
+                                },
+                            )),
+                    )),
+            )
+    }
+}
+
+/// A cell of the header row, with the sort indicator and resize handle.
+#[derive(Clone, PartialEq)]
+struct HeaderCell {
+    grid: Entity,
+    column: usize,
+    width: f32,
+    header: ViewChild,
+    sortable: bool,
+    sort: Option<SortDirection>,
+}
+
+impl ViewTemplate for HeaderCell {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let handle = cx.create_entity();
+        let grid = self.grid;
+        let column = self.column;
+        let sortable = self.sortable;
+        let hovering = cx.is_hovered(id) && sortable;
+        let resizing = cx
+            .use_component::<DataGridState>(grid)
+            .is_some_and(|state| state.resizing.is_some_and(|(c, _)| c == column));
+        let hovering_handle = cx.is_hovered(handle) || resizing;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("DataGrid::HeaderCell")
+            .style((typography::text_strong, style_header_cell))
+            .style_dyn(
+                |(width, hovering), sb| {
+                    sb.width(width).background_color(if hovering {
+                        colors::U2.lighter(0.03)
+                    } else {
+                        colors::TRANSPARENT
+                    });
+                },
+                (self.width, hovering),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        AccessibilityNode::from(NodeBuilder::new(Role::ColumnHeader)),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let Some(props) = world.get::<DataGridProps>(grid) else {
+                                return;
+                            };
+                            if let (true, Some(on_sort)) = (sortable, props.on_sort) {
+                                let sort = next_sort(props.sort, column);
+                                world.run_callback(on_sort, sort);
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .style(style_header_label)
+                    .children(self.header.clone()),
+                Cond::new(
+                    self.sort.is_some(),
+                    Icon::new(match self.sort {
+                        Some(SortDirection::Ascending) => {
+                            "embedded://bevy_quill_obsidian/assets/icons/chevron_up.png"
+                        }
+                        _ => "embedded://bevy_quill_obsidian/assets/icons/chevron_down.png",
+                    })
+                    .size(Vec2::splat(12.))
+                    .color(Color::from(colors::DIM)),
+                    (),
+                ),
+                Element::<NodeBundle>::for_entity(handle)
+                    .named("DataGrid::ResizeHandle")
+                    .style(style_resize_handle)
+                    .style_dyn(
+                        |active, sb| {
+                            sb.background_color(if active {
+                                colors::U3
+                            } else {
+                                colors::TRANSPARENT
+                            });
+                        },
+                        hovering_handle,
+                    )
+                    .insert_dyn(
+                        move |_| {
+                            (
+                                On::<Pointer<DragStart>>::run(move |world: &mut World| {
+                                    let Some(width) = world
+                                        .get::<DataGridProps>(grid)
+                                        .and_then(|props| props.widths.get(column).copied())
+                                    else {
+                                        return;
+                                    };
+                                    world.get_mut::<DataGridState>(grid).unwrap().resizing =
+                                        Some((column, width));
+                                }),
+                                On::<Pointer<Drag>>::run(move |world: &mut World| {
+                                    let distance =
+                                        world.resource::<ListenerInput<Pointer<Drag>>>().distance;
+                                    let Some((_, start)) =
+                                        world.get::<DataGridState>(grid).unwrap().resizing
+                                    else {
+                                        return;
+                                    };
+                                    let min_width = world
+                                        .get::<DataGridProps>(grid)
+                                        .and_then(|props| props.min_widths.get(column).copied())
+                                        .unwrap_or(0.);
+                                    let width = (start + distance.x).max(min_width);
+                                    let mut widths = world.get_mut::<DataGridWidths>(grid).unwrap();
+                                    if widths.0.len() <= column {
+                                        widths.0.resize(column + 1, None);
+                                    }
+                                    if widths.0[column] != Some(width) {
+                                        widths.0[column] = Some(width);
+                                    }
+                                }),
+                                On::<Pointer<DragEnd>>::run(move |world: &mut World| {
+                                    world.get_mut::<DataGridState>(grid).unwrap().resizing = None;
+                                }),
+                                On::<Pointer<PointerCancel>>::run(move |world: &mut World| {
+                                    world.get_mut::<DataGridState>(grid).unwrap().resizing = None;
+                                }),
+                                // Don't sort when the drag ends over the header.
+                                On::<Pointer<Click>>::run(move |world: &mut World| {
+                                    world
+                                        .resource_mut::<ListenerInput<Pointer<Click>>>()
+                                        .stop_propagation();
+                                }),
+                            )
+                        },
+                        (),
+                    ),
+            ))
+    }
+}
+
+/// A row of a data grid, keyed by the row's key so that it keeps its entity while it stays
+/// visible.
+#[derive(Clone, PartialEq)]
+struct DataGridRow {
+    grid: Entity,
+    /// Position of the row in the display order.
+    row: usize,
+    key: u64,
+    row_height: f32,
+    width: f32,
+    cells: Vec<(f32, ItemBuilder)>,
+}
+
+impl ViewTemplate for DataGridRow {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let grid = self.grid;
+        let row = self.row;
+        let key = self.key;
+        let (selectable, selected) = match cx.use_component::<DataGridProps>(grid) {
+            Some(props) => (
+                props.mode != ListSelectionMode::None,
+                props.selection.contains(&key),
+            ),
+            None => (false, false),
+        };
+        let cursor = selectable
+            && cx
+                .use_component::<SelectionCursor<u64>>(grid)
+                .is_some_and(|cursor| cursor.cursor == Some(key));
+        let focused = cx.is_focus_within(grid);
+        let hovering = cx.is_hovered(id) && selectable;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("DataGrid::Row")
+            .style(style_row)
+            .style_dyn(
+                |(top, width, height), sb| {
+                    sb.top(top).width(width).height(height);
+                },
+                (row as f32 * self.row_height, self.width, self.row_height),
+            )
+            .insert_dyn(
+                |selected| {
+                    let mut builder = NodeBuilder::new(Role::Row);
+                    builder.set_selected(selected);
+                    AccessibilityNode::from(builder)
+                },
+                selected,
+            )
+            .insert_dyn(
+                move |key| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            let selectable = world
+                                .get::<DataGridProps>(grid)
+                                .is_some_and(|props| props.mode != ListSelectionMode::None);
+                            if !selectable {
+                                return;
+                            }
+                            press_row::<DataGridProps>(world, grid, key, false);
+                        }),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let now = world.resource::<Time>().elapsed_seconds();
+                            let Some(mut state) = world.get_mut::<DataGridState>(grid) else {
+                                return;
+                            };
+                            let double_click = state.last_click.is_some_and(|(time, prev)| {
+                                prev == key && now - time < DOUBLE_CLICK_TIME
+                            });
+                            if !double_click {
+                                state.last_click = Some((now, key));
+                                return;
+                            }
+                            state.last_click = None;
+                            activate::<DataGridProps>(world, grid, key);
+                        }),
+                    )
+                },
+                key,
+            )
+            .style_dyn(
+                |(selected, focused, hovering, odd), sb| {
+                    let color = match (selected, focused, hovering) {
+                        (true, true, _) => colors::TEXT_SELECT,
+                        (true, false, _) => colors::U3,
+                        (false, _, true) => colors::U1.lighter(0.03),
+                        (false, _, false) if odd => colors::U1.lighter(0.01),
+                        (false, _, false) => colors::TRANSPARENT,
+                    };
+                    sb.background_color(color);
+                },
+                (selected, focused, hovering, row % 2 == 1),
+            )
+            .style_dyn(
+                |cursor, sb| {
+                    if cursor {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_width(2)
+                            .outline_offset(-2);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                cursor && focused,
+            )
+            .children(For::index(&self.cells, move |(width, cell), _| {
+                DataGridCell {
+                    width: *width,
+                    content: cell.build(row),
+                }
+            }))
+    }
+}
+
+/// A cell of a data grid row.
+#[derive(Clone, PartialEq)]
+struct DataGridCell {
+    width: f32,
+    content: ViewChild,
+}
+
+impl ViewTemplate for DataGridCell {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .named("DataGrid::Cell")
+            .style(style_cell)
+            .style_dyn(
+                |width, sb| {
+                    sb.width(width);
+                },
+                self.width,
+            )
+            .children(self.content.clone())
+    }
+}
+
+/// The width of each column: the width the user resized it to, or its initial width.
+fn column_widths(columns: &[DataColumn], resized: &[Option<f32>]) -> Vec<f32> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            resized
+                .get(index)
+                .copied()
+                .flatten()
+                .unwrap_or(column.width)
+                .max(column.min_width)
+        })
+        .collect()
+}
+
+/// The sort order after clicking the header of `column`: clicking the sorted column reverses
+/// the order, and clicking another column sorts by it in ascending order.
+fn next_sort(sort: Option<(usize, SortDirection)>, column: usize) -> (usize, SortDirection) {
+    match sort {
+        Some((sorted, SortDirection::Ascending)) if sorted == column => {
+            (column, SortDirection::Descending)
+        }
+        _ => (column, SortDirection::Ascending),
+    }
+}
+
+/// Scroll the grid so that `row` is visible below the header. Since rows outside the visible
+/// region aren't built, this works from the row height rather than the row's layout.
+fn scroll_to_row(world: &mut World, grid: Entity, row: usize) {
+    let scroll = world.get::<DataGridState>(grid).unwrap().scroll;
+    let row_height = world.get::<DataGridProps>(grid).unwrap().row_height;
+    let Some(mut area) = world.get_mut::<ScrollArea>(scroll) else {
+        return;
+    };
+    let top = row as f32 * row_height;
+    let visible = area.visible_size.y - HEADER_HEIGHT;
+    let left = area.scroll_left;
+    if top < area.scroll_top {
+        area.scroll_to(left, top);
+    } else if top + row_height > area.scroll_top + visible {
+        area.scroll_to(left, top + row_height - visible);
+    }
+}
+
+/// Handle navigation keys. Returns true if the key was used.
+fn handle_key(world: &mut World, grid: Entity, key: KeyCode, shift: bool, ctrl: bool) -> bool {
+    let Some(row_height) = world
+        .get::<DataGridProps>(grid)
+        .map(|props| props.row_height)
+    else {
+        return false;
+    };
+    let scroll = world.get::<DataGridState>(grid).unwrap().scroll;
+    let visible = world
+        .get::<ScrollArea>(scroll)
+        .map_or(0., |area| area.visible_size.y - HEADER_HEIGHT);
+    let page = ((visible / row_height.max(1.)) as usize).max(1);
+    handle_selection_key::<DataGridProps>(world, grid, key, shift, ctrl, page)
+}
+
+/// Keeps the header rows of data grids at the top of the scroll area as it scrolls vertically.
+pub(crate) fn update_data_grid_headers(
+    mut headers: Query<(&DataGridHeader, &mut Style)>,
+    scroll_areas: Query<&ScrollArea>,
+) {
+    for (header, mut style) in headers.iter_mut() {
+        let Ok(scroll) = scroll_areas.get(header.scroll) else {
+            continue;
+        };
+        let top = ui::Val::Px(scroll.scroll_top);
+        if style.top != top {
+            style.top = top;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_widths() {
+        let columns = vec![
+            DataColumn::new("Name").width(120.),
+            DataColumn::new("Size").width(60.).min_width(40.),
+            DataColumn::new("Kind"),
+        ];
+        assert_eq!(column_widths(&columns, &[]), vec![120., 60., 100.]);
+        assert_eq!(
+            column_widths(&columns, &[None, Some(10.), Some(150.)]),
+            vec![120., 40., 150.]
+        );
+    }
+
+    #[test]
+    fn test_next_sort() {
+        assert_eq!(next_sort(None, 2), (2, SortDirection::Ascending));
+        assert_eq!(
+            next_sort(Some((2, SortDirection::Ascending)), 2),
+            (2, SortDirection::Descending)
+        );
+        assert_eq!(
+            next_sort(Some((2, SortDirection::Descending)), 2),
+            (2, SortDirection::Ascending)
+        );
+        assert_eq!(
+            next_sort(Some((1, SortDirection::Descending)), 2),
+            (2, SortDirection::Ascending)
+        );
+    }
+}
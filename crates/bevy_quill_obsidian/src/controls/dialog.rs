@@ -28,7 +28,6 @@ fn style_dialog_overlay(ss: &mut StyleBuilder) {
         .top(0)
         .right(0)
         .bottom(0)
-        .z_index(100)
         .background_color(colors::U1.with_alpha(0.0));
 }
 
@@ -214,7 +213,8 @@ impl ViewTemplate for Dialog {
                             )
                             .children(children.clone()),
                     ),
-            ),
+            )
+            .into(OverlayLayer::Modals),
             (),
         )
     }
@@ -4,7 +4,7 @@ use crate::{
     colors,
     cursor::StyleBuilderCursor,
     focus::{KeyPressEvent, TabIndex},
-    hooks::{UseIsFocus, UseIsHover},
+    hooks::UseIsHover,
     size::Size,
 };
 use bevy::{
@@ -26,7 +26,12 @@ fn style_toggle(ss: &mut StyleBuilder) {
         .align_items(ui::AlignItems::Center)
         .align_content(ui::AlignContent::Center)
         .color(colors::FOREGROUND)
-        .cursor(CursorIcon::Pointer);
+        .cursor(CursorIcon::Pointer)
+        .on_focus_visible(|ss| {
+            ss.outline_color(colors::FOCUS)
+                .outline_width(2)
+                .outline_offset(2);
+        });
 }
 
 /// A widget which displays small toggleable chevron that can be used to control whether
@@ -109,7 +114,6 @@ impl ViewTemplate for DisclosureToggle {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let id = cx.create_entity();
         let hovering = cx.is_hovered(id);
-        let focused = cx.is_focus_visible(id);
         let on_change = self.on_change;
 
         Element::<NodeBundle>::for_entity(id)
@@ -168,21 +172,6 @@ impl ViewTemplate for DisclosureToggle {
                 },
                 self.expanded,
             )
-            .style_dyn(
-                move |focused, sb| {
-                    match focused {
-                        true => {
-                            sb.outline_color(colors::FOCUS)
-                                .outline_width(2)
-                                .outline_offset(2);
-                        }
-                        false => {
-                            sb.outline_color(Option::<Color>::None);
-                        }
-                    };
-                },
-                focused,
-            )
             .children(
                 Icon::new("embedded://bevy_quill_obsidian/assets/icons/chevron_right.png")
                     .color({
@@ -36,6 +36,9 @@ pub struct IconButton {
 
     /// If true, render the button in a 'minimal' style with no background and reduced padding.
     pub minimal: bool,
+
+    /// Content of the tooltip shown when hovering over the button.
+    pub tooltip: Option<ViewChild>,
 }
 
 impl IconButton {
@@ -100,6 +103,12 @@ impl IconButton {
         self.autofocus = autofocus;
         self
     }
+
+    /// Set the tooltip shown when hovering over the button.
+    pub fn tooltip(mut self, tooltip: impl IntoViewChild) -> Self {
+        self.tooltip = Some(tooltip.into_view_child());
+        self
+    }
 }
 
 impl ViewTemplate for IconButton {
@@ -120,6 +129,7 @@ impl ViewTemplate for IconButton {
             autofocus: self.autofocus,
             minimal: self.minimal,
             corners: self.corners,
+            tooltip: self.tooltip.clone(),
             ..default()
         }
         .children(
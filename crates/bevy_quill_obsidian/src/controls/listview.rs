@@ -1,17 +1,41 @@
-use bevy::ui;
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode,
+    },
+    prelude::*,
+    ui,
+};
+use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
 
-use crate::colors;
+use crate::{
+    colors,
+    focus::{KeyPressEvent, TabIndex},
+    hooks::{UseIsFocus, UseIsHover},
+    text_editing::DOUBLE_CLICK_TIME,
+};
 
-use super::ScrollView;
+use super::{
+    selection::{
+        activate, handle_selection_key, press_row, ScrollToCursor, SelectionCursor, SelectionProps,
+    },
+    ScrollView,
+};
 
 fn style_listview(ss: &mut StyleBuilder) {
-    ss.background_color(colors::U1)
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .background_color(colors::U1)
         .border_radius(5.0)
         .padding(3);
 }
 
+fn style_listview_scroll(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).min_height(0);
+}
+
 fn style_listview_inner(ss: &mut StyleBuilder) {
     ss.display(ui::Display::Flex)
         .flex_direction(ui::FlexDirection::Column)
@@ -20,14 +44,57 @@ fn style_listview_inner(ss: &mut StyleBuilder) {
         .justify_self(ui::JustifySelf::Stretch);
 }
 
+fn style_list_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .border_radius(3.0);
+}
+
+/// How many rows of a [`ListView`] can be selected at once.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ListSelectionMode {
+    /// Rows can't be selected, and the list doesn't take keyboard focus.
+    #[default]
+    None,
+
+    /// Clicking a row selects it, and deselects the others.
+    Single,
+
+    /// Ctrl-click toggles rows in and out of the selection, and shift-click selects a range.
+    Multiple,
+}
+
 /// A scrollable list of items.
+///
+/// If a selection mode is set, rows wrapped in a [`ListRow`] can be selected with the mouse or
+/// the keyboard: the arrow, page up / down, home and end keys move the cursor, and holding
+/// shift or ctrl extends the selection or moves the cursor on its own. As with
+/// [`TreeView`](super::TreeView), the caller owns the selection, and is told about changes via
+/// `on_selection_change`.
 #[derive(Clone, PartialEq, Default)]
 pub struct ListView {
     /// Additional styles to be applied to the list view.
     pub style: StyleHandle,
 
-    /// The content of the dialog header.
+    /// The content of the list.
     pub children: ViewChild,
+
+    /// Keys of the selected rows.
+    pub selection: Vec<u64>,
+
+    /// Whether rows can be selected, and how many at once.
+    pub selection_mode: ListSelectionMode,
+
+    /// The tab index of the list view (default 0). Only used if rows can be selected.
+    pub tab_index: i32,
+
+    /// Callback called with the new selection when the user changes it.
+    pub on_selection_change: Option<Callback<Vec<u64>>>,
+
+    /// Callback called with the key of a row when it is double-clicked, or when Enter is
+    /// pressed while it has the cursor.
+    pub on_activate: Option<Callback<u64>>,
 }
 
 impl ListView {
@@ -47,15 +114,379 @@ impl ListView {
         self.children = children.into_view_child();
         self
     }
+
+    /// Set the keys of the selected rows.
+    pub fn selection(mut self, selection: Vec<u64>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Set whether rows can be selected.
+    pub fn selection_mode(mut self, selection_mode: ListSelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Set the tab index of the list view.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set the callback called when the selection changes.
+    pub fn on_selection_change(mut self, on_selection_change: Callback<Vec<u64>>) -> Self {
+        self.on_selection_change = Some(on_selection_change);
+        self
+    }
+
+    /// Set the callback called when a row is activated.
+    pub fn on_activate(mut self, on_activate: Callback<u64>) -> Self {
+        self.on_activate = Some(on_activate);
+        self
+    }
+}
+
+/// Properties of a list view which are needed by the event handlers.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct ListViewProps {
+    selection: Vec<u64>,
+    mode: ListSelectionMode,
+    on_selection_change: Option<Callback<Vec<u64>>>,
+    on_activate: Option<Callback<u64>>,
+}
+
+impl SelectionProps for ListViewProps {
+    type Key = u64;
+
+    fn mode(&self) -> ListSelectionMode {
+        self.mode
+    }
+
+    fn selection(&self) -> &[u64] {
+        &self.selection
+    }
+
+    fn on_selection_change(&self) -> Option<Callback<Vec<u64>>> {
+        self.on_selection_change
+    }
+
+    fn on_activate(&self) -> Option<Callback<u64>> {
+        self.on_activate
+    }
+
+    fn keys(world: &World, list: Entity) -> Vec<u64> {
+        list_rows(world, list).iter().map(|(_, key)| *key).collect()
+    }
+
+    fn reveal(world: &mut World, list: Entity, _key: u64) {
+        if let Some(mut state) = world.get_mut::<ListViewState>(list) {
+            state.scroll_to_cursor = true;
+        }
+    }
+}
+
+/// Interaction state of a list view, stored on the root entity.
+#[derive(Component)]
+pub(crate) struct ListViewState {
+    scroll: Entity,
+    /// Time and key of the last click, used to detect double-clicks.
+    last_click: Option<(f32, u64)>,
+    /// True if the cursor has moved and should be scrolled into view.
+    scroll_to_cursor: bool,
+}
+
+/// Lets [`ListRow`]s find the list view they belong to.
+#[derive(Component, Clone, Copy)]
+struct ListViewContext(Entity);
+
+/// Identifies the list view and key of a row entity.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct ListViewRow {
+    list: Entity,
+    key: u64,
+}
+
+impl ScrollToCursor for ListViewState {
+    type Key = u64;
+    type Row = ListViewRow;
+
+    fn row(row: &ListViewRow) -> (Entity, &u64) {
+        (row.list, &row.key)
+    }
+
+    fn scroll_area(&self) -> Entity {
+        self.scroll
+    }
+
+    fn scroll_to_cursor(&self) -> bool {
+        self.scroll_to_cursor
+    }
+
+    fn clear_scroll_to_cursor(&mut self) {
+        self.scroll_to_cursor = false;
+    }
 }
 
 impl ViewTemplate for ListView {
-    type View = ScrollView;
-    fn create(&self, _cx: &mut Cx) -> Self::View {
-        ScrollView::new()
-            .children(self.children.clone())
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let scroll = cx.create_entity();
+        let selectable = self.selection_mode != ListSelectionMode::None;
+        let focused = cx.is_focus_visible(id);
+        cx.insert(ListViewContext(id));
+
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<ListViewState>() {
+            entt.insert((
+                ListViewState {
+                    scroll,
+                    last_click: None,
+                    scroll_to_cursor: false,
+                },
+                SelectionCursor::<u64>::default(),
+            ));
+        }
+
+        let tab_index = self.tab_index;
+        Element::<NodeBundle>::for_entity(id)
+            .named("ListView")
             .style((style_listview, self.style.clone()))
-            .content_style(style_listview_inner)
-            .scroll_enable_y(true)
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .insert_dyn(
+                |props: ListViewProps| {
+                    let role = match props.mode {
+                        ListSelectionMode::None => Role::List,
+                        _ => Role::ListBox,
+                    };
+                    let mut builder = NodeBuilder::new(role);
+                    if props.mode == ListSelectionMode::Multiple {
+                        builder.set_multiselectable();
+                    }
+                    (props, AccessibilityNode::from(builder))
+                },
+                ListViewProps {
+                    selection: self.selection.clone(),
+                    mode: self.selection_mode,
+                    on_selection_change: self.on_selection_change,
+                    on_activate: self.on_activate,
+                },
+            )
+            .insert_if(selectable, move || TabIndex(tab_index))
+            .insert_dyn(
+                move |_| {
+                    On::<KeyPressEvent>::run(move |world: &mut World| {
+                        let event = world.resource::<ListenerInput<KeyPressEvent>>();
+                        let (key, shift, ctrl) = (event.key_code, event.shift, event.ctrl);
+                        if handle_key(world, id, key, shift, ctrl) {
+                            world
+                                .resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .stop_propagation();
+                        }
+                    })
+                },
+                (),
+            )
+            .children(
+                ScrollView::new()
+                    .entity(Some(scroll))
+                    .children(self.children.clone())
+                    .style(style_listview_scroll)
+                    .content_style(style_listview_inner)
+                    .scroll_enable_y(true),
+            )
+    }
+}
+
+/// A row of a [`ListView`] which can be selected and activated.
+#[derive(Clone, PartialEq, Default)]
+pub struct ListRow {
+    /// Identifies the row in the list's selection. Must be unique within the list.
+    pub key: u64,
+
+    /// Additional styles to be applied to the row.
+    pub style: StyleHandle,
+
+    /// The content of the row.
+    pub children: ViewChild,
+}
+
+impl ListRow {
+    /// Create a new list row with the given key.
+    pub fn new(key: u64) -> Self {
+        Self { key, ..default() }
+    }
+
+    /// Set additional styles to be applied to the row.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
     }
+
+    /// Set the child views for this element.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for ListRow {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let key = self.key;
+        let list = cx
+            .use_inherited_component::<ListViewContext>()
+            .map(|context| context.0)
+            .unwrap_or(Entity::PLACEHOLDER);
+        let (mode, selected) = match cx.use_component::<ListViewProps>(list) {
+            Some(props) => (props.mode, props.selection.contains(&key)),
+            None => (ListSelectionMode::None, false),
+        };
+        let selectable = mode != ListSelectionMode::None;
+        let cursor = selectable
+            && cx
+                .use_component::<SelectionCursor<u64>>(list)
+                .is_some_and(|cursor| cursor.cursor == Some(key));
+        let focused = cx.is_focus_within(list);
+        let hovering = cx.is_hovered(id);
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("ListView::Row")
+            .style((style_list_row, self.style.clone()))
+            .insert_dyn(
+                move |(selectable, selected)| {
+                    (
+                        ListViewRow { list, key },
+                        AccessibilityNode::from(if selectable {
+                            let mut builder = NodeBuilder::new(Role::ListBoxOption);
+                            builder.set_selected(selected);
+                            builder
+                        } else {
+                            NodeBuilder::new(Role::ListItem)
+                        }),
+                    )
+                },
+                (selectable, selected),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            let selectable = world
+                                .get::<ListViewProps>(list)
+                                .is_some_and(|props| props.mode != ListSelectionMode::None);
+                            if !selectable {
+                                return;
+                            }
+                            press_row::<ListViewProps>(world, list, key, false);
+                        }),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let now = world.resource::<Time>().elapsed_seconds();
+                            let Some(mut state) = world.get_mut::<ListViewState>(list) else {
+                                return;
+                            };
+                            let double_click = state.last_click.is_some_and(|(time, prev)| {
+                                prev == key && now - time < DOUBLE_CLICK_TIME
+                            });
+                            if !double_click {
+                                state.last_click = Some((now, key));
+                                return;
+                            }
+                            state.last_click = None;
+                            activate::<ListViewProps>(world, list, key);
+                        }),
+                    )
+                },
+                (),
+            )
+            .style_dyn(
+                |(selected, focused, hovering), sb| {
+                    let color = match (selected, focused, hovering) {
+                        (true, true, _) => colors::TEXT_SELECT,
+                        (true, false, _) => colors::U3,
+                        (false, _, true) => colors::U1.lighter(0.03),
+                        (false, _, false) => colors::TRANSPARENT,
+                    };
+                    sb.background_color(color);
+                },
+                (selected, focused, hovering && selectable),
+            )
+            .style_dyn(
+                |cursor, sb| {
+                    if cursor {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_width(2)
+                            .outline_offset(-2);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                cursor && focused,
+            )
+            .children(self.children.clone())
+    }
+}
+
+/// Keys of the rows of a list view, in display order, along with their entities.
+fn list_rows(world: &World, list: Entity) -> Vec<(Entity, u64)> {
+    fn visit(world: &World, list: Entity, entity: Entity, out: &mut Vec<(Entity, u64)>) {
+        if let Some(row) = world.get::<ListViewRow>(entity) {
+            if row.list == list {
+                out.push((entity, row.key));
+            }
+            return;
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            for child in children.iter() {
+                visit(world, list, *child, out);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    if let Some(state) = world.get::<ListViewState>(list) {
+        visit(world, list, state.scroll, &mut out);
+    }
+    out
+}
+
+/// Number of rows which fit in the visible part of the list, based on the height of `row`.
+fn page_size(world: &World, list: Entity, row: Entity) -> usize {
+    let scroll = world.get::<ListViewState>(list).unwrap().scroll;
+    let visible = world.get::<Node>(scroll).map_or(0., |node| node.size().y);
+    let height = world.get::<Node>(row).map_or(0., |node| node.size().y);
+    if height <= 0. {
+        return 1;
+    }
+    ((visible / height) as usize).max(1)
+}
+
+/// Handle navigation keys. Returns true if the key was used.
+fn handle_key(world: &mut World, list: Entity, key: KeyCode, shift: bool, ctrl: bool) -> bool {
+    let rows = list_rows(world, list);
+    let Some(first) = rows.first() else {
+        return false;
+    };
+    let cursor = world
+        .get::<SelectionCursor<u64>>(list)
+        .and_then(|cursor| cursor.cursor);
+    let row = rows
+        .iter()
+        .find(|(_, k)| Some(*k) == cursor)
+        .unwrap_or(first)
+        .0;
+    let page = page_size(world, list, row);
+    handle_selection_key::<ListViewProps>(world, list, key, shift, ctrl, page)
 }
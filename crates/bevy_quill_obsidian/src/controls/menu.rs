@@ -2,16 +2,19 @@ use crate::{
     colors,
     floating::{FloatAlign, FloatPosition, FloatSide, Floating},
     focus::{AutoFocus, KeyPressEvent, NavAction, TabGroup, TabIndex, TabNavigation},
-    hooks::{BistableTransitionState, CreateBistableTransition, UseIsFocus, UseIsHover},
+    hooks::{
+        is_focus::is_descendant, BistableTransitionState, CreateBistableTransition, UseIsFocus,
+        UseIsHover,
+    },
     size::Size,
     typography, RoundedCorners,
 };
 use bevy::{
     a11y::{
-        accesskit::{HasPopup, NodeBuilder, Role},
+        accesskit::{HasPopup, NodeBuilder, Role, Toggled},
         AccessibilityNode, Focus,
     },
-    ecs::system::SystemState,
+    ecs::{system::SystemState, world::Command},
     prelude::*,
     ui,
 };
@@ -27,6 +30,33 @@ use super::{
 #[derive(Component)]
 struct MenuAnchor(Entity);
 
+/// View context component which stores the popup element id, and which of the popup's submenus
+/// is open, so that menu items can close the submenus of their siblings.
+#[derive(Component, Clone, Copy)]
+struct MenuPopupContext {
+    popup: Entity,
+    submenu: Mutable<Option<Entity>>,
+}
+
+/// View context component inserted by a [`SubMenu`] for the popup it opens.
+#[derive(Component, Clone, Copy)]
+struct SubMenuContext {
+    /// The submenu item in the parent popup.
+    item: Entity,
+    /// Context of the parent popup.
+    parent: MenuPopupContext,
+    /// Whether the popup should take focus, which is true when opened with the keyboard.
+    autofocus: Mutable<bool>,
+}
+
+impl SubMenuContext {
+    /// Close the submenu and return focus to the submenu item.
+    fn close(&self, world: &mut World) {
+        self.parent.submenu.set(world, None);
+        world.resource_mut::<Focus>().0 = Some(self.item);
+    }
+}
+
 #[derive(Clone, Event, EntityEvent)]
 #[can_bubble]
 pub(crate) struct MenuCloseEvent {
@@ -45,7 +75,6 @@ fn style_menu_barrier(ss: &mut StyleBuilder) {
         .top(0)
         .right(0)
         .bottom(0)
-        .z_index(100)
         .background_color(colors::U2.with_alpha(0.0));
 }
 
@@ -326,27 +355,25 @@ impl ViewTemplate for MenuButton {
                                 .style(style_menu_barrier)
                                 .insert_dyn(
                                     move |_| {
-                                        (
-                                            On::<Pointer<Click>>::run(move |world: &mut World| {
-                                                let disabled =
-                                                    world.entity(id_anchor).contains::<Disabled>();
-                                                if !disabled {
-                                                    let mut event = world
+                                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                                            let disabled =
+                                                world.entity(id_anchor).contains::<Disabled>();
+                                            if !disabled {
+                                                let mut event = world
                                                 .get_resource_mut::<ListenerInput<Pointer<Click>>>()
                                                 .unwrap();
-                                                    event.stop_propagation();
-                                                    open.update(world, |mut state| {
-                                                        *state = !*state;
-                                                    });
-                                                }
-                                            }),
-                                            ZIndex::Global(100),
-                                        )
+                                                event.stop_propagation();
+                                                open.update(world, |mut state| {
+                                                    *state = !*state;
+                                                });
+                                            }
+                                        })
                                     },
                                     (),
                                 )
                                 .children(popup.clone()),
                         )
+                        .into(OverlayLayer::Menus)
                     },
                     (),
                 ),
@@ -354,6 +381,214 @@ impl ViewTemplate for MenuButton {
     }
 }
 
+/// Marks an entity which has a context menu.
+#[derive(Component)]
+pub(crate) struct ContextMenuTarget;
+
+/// The target of the context menu which is currently open, and the pointer position at which
+/// it was opened.
+#[derive(Resource, Default)]
+pub(crate) struct ActiveContextMenu(Option<(Entity, Vec2)>);
+
+/// Records, on the view entity of a context menu, which target it is currently attached to.
+#[derive(Component)]
+struct ContextMenuOwner(Entity);
+
+/// Removes the context menu marker from the target once the context menu which added it has been
+/// despawned, or has been moved to a different target.
+struct ReleaseContextMenuTarget {
+    /// The view entity of the context menu.
+    owner: Entity,
+    target: Entity,
+}
+
+impl Command for ReleaseContextMenuTarget {
+    fn apply(self, world: &mut World) {
+        // Cleanups also run before each reaction, in which case the context menu is usually
+        // still attached to the same target.
+        if world
+            .get::<ContextMenuOwner>(self.owner)
+            .is_some_and(|current| current.0 == self.target)
+        {
+            return;
+        }
+        if let Some(mut entt) = world.get_entity_mut(self.target) {
+            entt.remove::<ContextMenuTarget>();
+        }
+        close_context_menu(world, self.target);
+    }
+}
+
+fn close_context_menu(world: &mut World, target: Entity) {
+    let mut active = world.resource_mut::<ActiveContextMenu>();
+    if active.0.is_some_and(|(t, _)| t == target) {
+        active.0 = None;
+    }
+}
+
+/// A menu which pops up at the pointer position when the target element, or one of its
+/// descendants, is clicked with the secondary mouse button.
+///
+/// Like [`Tooltip`](super::Tooltip), the context menu can be placed anywhere in the view tree;
+/// it only needs the entity id of the target. The popup is normally a [`MenuPopup`].
+#[derive(Clone, PartialEq)]
+pub struct ContextMenu {
+    /// The element which opens the menu when right-clicked.
+    pub target: Entity,
+
+    /// The popup to display.
+    pub popup: ViewChild,
+
+    /// Callback which is called when the menu opens or closes.
+    pub on_state_change: Option<Callback<bool>>,
+}
+
+impl ContextMenu {
+    /// Create a new context menu for the given target element.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            popup: ViewChild::default(),
+            on_state_change: None,
+        }
+    }
+
+    /// Set the popup to display.
+    pub fn popup(mut self, popup: impl IntoViewChild) -> Self {
+        self.popup = popup.into_view_child();
+        self
+    }
+
+    /// Set a callback to be called when the menu opens or closes.
+    pub fn on_state_change(mut self, callback: Callback<bool>) -> Self {
+        self.on_state_change = Some(callback);
+        self
+    }
+}
+
+impl ViewTemplate for ContextMenu {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let target = self.target;
+        let owner = cx.owner();
+        let previous = cx
+            .world()
+            .get::<ContextMenuOwner>(owner)
+            .map(|current| current.0);
+        cx.insert(ContextMenuOwner(target));
+        if let Some(previous) = previous.filter(|previous| *previous != target) {
+            ReleaseContextMenuTarget {
+                owner,
+                target: previous,
+            }
+            .apply(cx.world_mut());
+        }
+        let mut entt = cx.world_mut().entity_mut(target);
+        if !entt.contains::<ContextMenuTarget>() {
+            entt.insert(ContextMenuTarget);
+        }
+        cx.on_cleanup(move |world| {
+            world
+                .commands()
+                .add(ReleaseContextMenuTarget { owner, target });
+        });
+        let position = cx
+            .use_resource::<ActiveContextMenu>()
+            .0
+            .filter(|(t, _)| *t == target)
+            .map(|(_, position)| position);
+        let id_anchor = cx.create_entity();
+        let on_state_change = self.on_state_change;
+
+        cx.create_effect_ext(
+            |world, open| {
+                if let Some(cb) = on_state_change {
+                    world.run_callback(cb, open)
+                }
+            },
+            position.is_some(),
+            EffectOptions {
+                run_immediately: false,
+            },
+        );
+
+        cx.insert(MenuAnchor(id_anchor));
+        cx.insert(On::<MenuCloseEvent>::run(move |world: &mut World| {
+            let mut event = world
+                .get_resource_mut::<ListenerInput<MenuCloseEvent>>()
+                .unwrap();
+            event.stop_propagation();
+            close_context_menu(world, target);
+        }));
+
+        Cond::new(
+            position.is_some(),
+            Portal::new(
+                Element::<NodeBundle>::new()
+                    .named("ContextMenu")
+                    .style(style_menu_barrier)
+                    .insert_dyn(
+                        move |_| {
+                            On::<Pointer<Click>>::run(move |world: &mut World| {
+                                let mut event = world
+                                    .get_resource_mut::<ListenerInput<Pointer<Click>>>()
+                                    .unwrap();
+                                event.stop_propagation();
+                                close_context_menu(world, target);
+                            })
+                        },
+                        (),
+                    )
+                    .children((
+                        // Zero-sized element at the pointer position, which the popup floats next to.
+                        Element::<NodeBundle>::for_entity(id_anchor)
+                            .named("ContextMenu::Anchor")
+                            .style_dyn(
+                                |position, sb| {
+                                    sb.position(ui::PositionType::Absolute)
+                                        .left(position.x)
+                                        .top(position.y);
+                                },
+                                position.unwrap_or_default(),
+                            ),
+                        self.popup.clone(),
+                    )),
+            )
+            .into(OverlayLayer::Menus),
+            (),
+        )
+    }
+}
+
+/// Opens the context menu of the nearest target under the pointer when the secondary button
+/// is pressed.
+pub(crate) fn open_context_menus(
+    mut events: EventReader<Pointer<Down>>,
+    targets: Query<(), With<ContextMenuTarget>>,
+    parents: Query<&Parent>,
+    mut active: ResMut<ActiveContextMenu>,
+) {
+    // Forget the menu if its target has been despawned.
+    if active
+        .0
+        .is_some_and(|(target, _)| !targets.contains(target))
+    {
+        active.0 = None;
+    }
+    for event in events.read() {
+        if event.button != PointerButton::Secondary {
+            continue;
+        }
+        if let Some(target) = std::iter::once(event.target)
+            .chain(parents.iter_ancestors(event.target))
+            .find(|e| targets.contains(*e))
+        {
+            active.0 = Some((target, event.pointer_location.position));
+        }
+    }
+}
+
 fn style_popup(ss: &mut StyleBuilder) {
     ss.background_color(colors::U1)
         .border_radius(4.0)
@@ -431,10 +666,25 @@ impl ViewTemplate for MenuPopup {
     fn create(&self, cx: &mut Cx) -> Self::View {
         // Adds a delay to ensure the menu items are created before setting focus.
         let state = cx.create_bistable_transition(true, 0.01);
-        let context = cx.use_inherited_component::<MenuAnchor>().unwrap();
+        // A popup placed outside of a menu button, context menu or submenu has nothing to float
+        // next to, so it is left where the layout puts it. The context components may not have
+        // been registered yet in that case, which is required in order to track them.
+        cx.world_mut().init_component::<MenuAnchor>();
+        cx.world_mut().init_component::<SubMenuContext>();
+        let anchor = cx
+            .use_inherited_component::<MenuAnchor>()
+            .map(|anchor| anchor.0);
+        if anchor.is_none() {
+            warn!("MenuPopup has no anchor, it should be placed within a MenuButton, ContextMenu or SubMenu");
+        }
+        let submenu_context = cx.use_inherited_component::<SubMenuContext>().copied();
+        let autofocus = submenu_context.is_none_or(|sub| sub.autofocus.get(cx));
         let owner_id = cx.owner();
+        let id = cx.create_entity();
+        let submenu = cx.create_mutable::<Option<Entity>>(None);
+        cx.insert(MenuPopupContext { popup: id, submenu });
 
-        Element::<NodeBundle>::new()
+        Element::<NodeBundle>::for_entity(id)
             .named("MenuPopup")
             .style((typography::text_default, style_popup, self.style.clone()))
             .insert(TabGroup {
@@ -443,7 +693,8 @@ impl ViewTemplate for MenuPopup {
             })
             .insert_dyn(
                 |(side, align, anchor)| Floating {
-                    anchor,
+                    // The floating system skips elements whose anchor doesn't exist.
+                    anchor: anchor.unwrap_or(Entity::PLACEHOLDER),
                     position: vec![
                         FloatPosition {
                             side,
@@ -459,7 +710,7 @@ impl ViewTemplate for MenuPopup {
                         },
                     ],
                 },
-                (self.side, self.align, context.0),
+                (self.side, self.align, anchor),
             )
             .insert_dyn(
                 move |_| {
@@ -481,7 +732,18 @@ impl ViewTemplate for MenuPopup {
                                 match event.key_code {
                                     KeyCode::Escape => {
                                         event.stop_propagation();
-                                        world.send_event(MenuCloseEvent { target: owner_id });
+                                        match submenu_context {
+                                            Some(sub) => sub.close(world),
+                                            None => {
+                                                world.send_event(MenuCloseEvent {
+                                                    target: owner_id,
+                                                });
+                                            }
+                                        }
+                                    }
+                                    KeyCode::ArrowLeft if submenu_context.is_some() => {
+                                        event.stop_propagation();
+                                        submenu_context.unwrap().close(world);
                                     }
                                     KeyCode::ArrowUp => {
                                         event.stop_propagation();
@@ -509,15 +771,15 @@ impl ViewTemplate for MenuPopup {
             )
             .children(self.children.clone())
             .effect(
-                move |cx, ent, state| {
-                    if state == BistableTransitionState::Entered {
+                move |cx, ent, (state, autofocus)| {
+                    if state == BistableTransitionState::Entered && autofocus {
                         let mut st: SystemState<(ResMut<Focus>, TabNavigation)> =
                             SystemState::new(cx.world_mut());
                         let (mut focus, nav) = st.get_mut(cx.world_mut());
                         focus.0 = nav.navigate(Some(ent), NavAction::First);
                     }
                 },
-                state,
+                (state, autofocus),
             )
     }
 }
@@ -532,6 +794,28 @@ fn style_menu_item(ss: &mut StyleBuilder) {
         .margin((2, 0));
 }
 
+fn style_menu_item_indicator(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .justify_content(ui::JustifyContent::Center)
+        .align_items(ui::AlignItems::Center)
+        .width(16)
+        .margin_right(4)
+        .flex_shrink(0.);
+}
+
+fn style_menu_item_accelerator(ss: &mut StyleBuilder) {
+    ss.margin_left(24).color(colors::DIM);
+}
+
+fn menu_item_bg_color(pressed: bool, hovering: bool, focused: bool) -> Srgba {
+    match (pressed || focused, hovering) {
+        (true, true) => colors::U1.lighter(0.03),
+        (true, false) => colors::U1.lighter(0.02),
+        (false, true) => colors::U1.lighter(0.01),
+        (false, false) => Srgba::NONE,
+    }
+}
+
 /// UI component representing a menu item.
 #[derive(Default, Clone, PartialEq)]
 pub struct MenuItem {
@@ -544,13 +828,23 @@ pub struct MenuItem {
     /// Whether the menu item is checked.
     pub checked: bool,
 
+    /// If true, the item is one of a group of mutually exclusive choices, and is shown with a
+    /// dot rather than a check mark when checked.
+    pub radio: bool,
+
+    /// Asset path of an icon to display before the label. The check mark takes its place when
+    /// the item is checked.
+    pub icon: Option<String>,
+
+    /// Keyboard accelerator hint, such as "Ctrl+S", displayed at the right edge of the item.
+    /// This is only a label: the accelerator key itself must be handled by the application.
+    pub accelerator: Option<String>,
+
     /// Whether the menu item is disabled.
     pub disabled: bool,
 
     /// Callback called when clicked
     pub on_click: Option<Callback>,
-    // icon
-    // shortcut
 }
 
 impl MenuItem {
@@ -577,6 +871,24 @@ impl MenuItem {
         self
     }
 
+    /// Set whether the menu item is displayed as a radio item.
+    pub fn radio(mut self, radio: bool) -> Self {
+        self.radio = radio;
+        self
+    }
+
+    /// Set the icon of the menu item.
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Set the keyboard accelerator hint of the menu item.
+    pub fn accelerator(mut self, accelerator: &str) -> Self {
+        self.accelerator = Some(accelerator.to_string());
+        self
+    }
+
     /// Set the disabled state of the menu item.
     pub fn disabled(mut self, disabled: bool) -> Self {
         self.disabled = disabled;
@@ -601,6 +913,24 @@ impl ViewTemplate for MenuItem {
         let focused = cx.is_focused(id);
         let on_click = self.on_click;
 
+        // Hovering over an item closes any submenu opened by one of its siblings.
+        let parent = cx.use_inherited_component::<MenuPopupContext>().copied();
+        cx.create_effect(
+            move |world, hovering| {
+                if let (true, Some(parent)) = (hovering, parent) {
+                    parent.submenu.set(world, None);
+                }
+            },
+            hovering,
+        );
+
+        let indicator = match (self.checked, self.radio, &self.icon) {
+            (true, true, _) => Some("embedded://bevy_quill_obsidian/assets/icons/disc.png"),
+            (true, false, _) => Some("embedded://bevy_quill_obsidian/assets/icons/checkmark.png"),
+            (false, _, Some(icon)) => Some(icon.as_str()),
+            (false, _, None) => None,
+        };
+
         Element::<NodeBundle>::for_entity(id)
             .named("MenuItem")
             .insert_if(self.disabled, || Disabled)
@@ -609,7 +939,6 @@ impl ViewTemplate for MenuItem {
                 move |_| {
                     (
                         TabIndex(0),
-                        AccessibilityNode::from(NodeBuilder::new(Role::Button)),
                         On::<Pointer<Click>>::run(move |world: &mut World| {
                             let disabled = world.entity(id).contains::<Disabled>();
                             let mut st: SystemState<(EventWriter<MenuCloseEvent>, ResMut<Focus>)> =
@@ -677,19 +1006,263 @@ impl ViewTemplate for MenuItem {
                 },
                 (),
             )
+            .insert_dyn(
+                |(radio, checked)| {
+                    AccessibilityNode::from(match (radio, checked) {
+                        (true, _) => {
+                            let mut builder = NodeBuilder::new(Role::MenuItemRadio);
+                            builder.set_toggled(if checked {
+                                Toggled::True
+                            } else {
+                                Toggled::False
+                            });
+                            builder
+                        }
+                        (false, true) => {
+                            let mut builder = NodeBuilder::new(Role::MenuItemCheckBox);
+                            builder.set_toggled(Toggled::True);
+                            builder
+                        }
+                        (false, false) => NodeBuilder::new(Role::MenuItem),
+                    })
+                },
+                (self.radio, self.checked),
+            )
             .style_dyn(
-                move |(is_pressed, is_hovering, is_focused), sb| {
-                    let color = match (is_pressed || is_focused, is_hovering) {
-                        (true, true) => colors::U1.lighter(0.03),
-                        (true, false) => colors::U1.lighter(0.02),
-                        (false, true) => colors::U1.lighter(0.01),
-                        (false, false) => Srgba::NONE,
-                    };
-                    sb.background_color(color);
+                move |(is_pressed, is_hovering, is_focused, is_disabled), sb| {
+                    sb.background_color(menu_item_bg_color(is_pressed, is_hovering, is_focused))
+                        .color(if is_disabled {
+                            colors::DIM
+                        } else {
+                            colors::FOREGROUND
+                        });
                 },
-                (pressed.get(cx), hovering, focused),
+                (pressed.get(cx), hovering, focused, self.disabled),
             )
-            .children(self.label.clone())
+            .children((
+                Element::<NodeBundle>::new()
+                    .named("MenuItem::Indicator")
+                    .style(style_menu_item_indicator)
+                    .children(Cond::new(
+                        indicator.is_some(),
+                        Icon::new(indicator.unwrap_or_default()),
+                        (),
+                    )),
+                self.label.clone(),
+                Cond::new(
+                    self.accelerator.is_some(),
+                    (
+                        Spacer,
+                        Element::<NodeBundle>::new()
+                            .named("MenuItem::Accelerator")
+                            .style(style_menu_item_accelerator)
+                            .children(self.accelerator.clone().unwrap_or_default()),
+                    ),
+                    (),
+                ),
+            ))
+    }
+}
+
+/// A menu item which opens a nested menu, either when hovered or when the right arrow key is
+/// pressed. The nested menu is placed to the right of the item, or to the left if there is
+/// not enough room. Pressing the left arrow or escape key closes it again.
+#[derive(Default, Clone, PartialEq)]
+pub struct SubMenu {
+    /// The label of the submenu item.
+    pub label: ViewChild,
+
+    /// Asset path of an icon to display before the label.
+    pub icon: Option<String>,
+
+    /// The items of the nested menu.
+    pub children: ViewChild,
+
+    /// Additional styles to apply to the submenu item.
+    pub style: StyleHandle,
+
+    /// Whether the submenu is disabled.
+    pub disabled: bool,
+}
+
+impl SubMenu {
+    /// Create a new submenu.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the label of the submenu item.
+    pub fn label(mut self, label: impl IntoViewChild) -> Self {
+        self.label = label.into_view_child();
+        self
+    }
+
+    /// Set the icon of the submenu item.
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+        self
+    }
+
+    /// Set the items of the nested menu.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+
+    /// Set additional styles to apply to the submenu item.
+    pub fn style(mut self, style: StyleHandle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the disabled state of the submenu.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl ViewTemplate for SubMenu {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        // Outside of a popup, the submenu keeps track of its own open state.
+        let own_submenu = cx.create_mutable::<Option<Entity>>(None);
+        let parent = cx
+            .use_inherited_component::<MenuPopupContext>()
+            .copied()
+            .unwrap_or_else(|| {
+                warn!("SubMenu should be placed within a MenuPopup");
+                MenuPopupContext {
+                    popup: id,
+                    submenu: own_submenu,
+                }
+            });
+        let open = parent.submenu.get(cx) == Some(id);
+        let autofocus = cx.create_mutable::<bool>(false);
+        let hovering = cx.is_hovered(id);
+        let focused = cx.is_focused(id);
+        let disabled = self.disabled;
+
+        cx.insert(MenuAnchor(id));
+        cx.insert(SubMenuContext {
+            item: id,
+            parent,
+            autofocus,
+        });
+
+        cx.create_effect(
+            move |world, hovering| {
+                if hovering && !disabled {
+                    autofocus.set(world, false);
+                    parent.submenu.set(world, Some(id));
+                }
+            },
+            hovering,
+        );
+
+        // If the submenu closes while it has focus, because the pointer moved to a sibling item,
+        // return focus to the parent menu.
+        cx.create_effect_ext(
+            move |world, open| {
+                if let (false, Some(focus)) = (open, world.resource::<Focus>().0) {
+                    if !is_descendant(world, &focus, &parent.popup) {
+                        world.resource_mut::<Focus>().0 = Some(id);
+                    }
+                }
+            },
+            open,
+            EffectOptions {
+                run_immediately: false,
+            },
+        );
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("SubMenu")
+            .insert_if(self.disabled, || Disabled)
+            .style((style_menu_item, self.style.clone()))
+            .insert_dyn(
+                move |_| {
+                    (
+                        TabIndex(0),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let disabled = world.entity(id).contains::<Disabled>();
+                            if !disabled {
+                                world.resource_mut::<Focus>().0 = Some(id);
+                                parent.submenu.set(world, Some(id));
+                            }
+                        }),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            let disabled = world.entity(id).contains::<Disabled>();
+                            if !disabled {
+                                let mut event =
+                                    world.resource_mut::<ListenerInput<KeyPressEvent>>();
+                                if !event.repeat
+                                    && matches!(
+                                        event.key_code,
+                                        KeyCode::ArrowRight | KeyCode::Enter | KeyCode::Space
+                                    )
+                                {
+                                    event.stop_propagation();
+                                    autofocus.set(world, true);
+                                    parent.submenu.set(world, Some(id));
+                                }
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .insert_dyn(
+                |open| {
+                    AccessibilityNode::from({
+                        let mut builder = NodeBuilder::new(Role::MenuItem);
+                        builder.set_has_popup(HasPopup::Menu);
+                        builder.set_expanded(open);
+                        builder
+                    })
+                },
+                open,
+            )
+            .style_dyn(
+                move |(open, is_hovering, is_focused, is_disabled), sb| {
+                    sb.background_color(menu_item_bg_color(open, is_hovering, is_focused))
+                        .color(if is_disabled {
+                            colors::DIM
+                        } else {
+                            colors::FOREGROUND
+                        });
+                },
+                (open, hovering, focused, self.disabled),
+            )
+            .children((
+                Element::<NodeBundle>::new()
+                    .named("SubMenu::Indicator")
+                    .style(style_menu_item_indicator)
+                    .children(Cond::new(
+                        self.icon.is_some(),
+                        Icon::new(self.icon.as_deref().unwrap_or_default()),
+                        (),
+                    )),
+                self.label.clone(),
+                Spacer,
+                Icon::new("embedded://bevy_quill_obsidian/assets/icons/chevron_right.png")
+                    .color(colors::DIM)
+                    .style(|ss: &mut StyleBuilder| {
+                        ss.margin_left(24);
+                    }),
+                Cond::new(
+                    open,
+                    Portal::new(
+                        MenuPopup::new()
+                            .side(FloatSide::Right)
+                            .children(self.children.clone()),
+                    )
+                    .into(OverlayLayer::Menus),
+                    (),
+                ),
+            ))
     }
 }
 
@@ -709,3 +1282,271 @@ impl ViewTemplate for MenuDivider {
             .style(style_menu_divider)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::keyboard::KeyboardInput;
+    use bevy_mod_picking::{backend::HitData, pointer::Location};
+
+    use super::*;
+    use crate::{
+        focus::KeyboardInputPlugin, hooks::is_hover::Hovering, hooks::BistableTransitionPlugin,
+    };
+
+    /// An element with a context menu, which contains a pair of radio items and a submenu.
+    #[derive(Clone, PartialEq)]
+    struct TestMenu;
+
+    impl ViewTemplate for TestMenu {
+        type View = impl View;
+
+        fn create(&self, cx: &mut Cx) -> Self::View {
+            let target = cx.create_entity();
+            let choice = cx.create_mutable::<usize>(0);
+            let current = choice.get(cx);
+            let choose_a = cx.create_callback(move |world: &mut World| choice.set(world, 0));
+            let choose_b = cx.create_callback(move |world: &mut World| choice.set(world, 1));
+            Element::<NodeBundle>::for_entity(target)
+                .named("Target")
+                .children(
+                    ContextMenu::new(target).popup(
+                        MenuPopup::new().children((
+                            MenuItem::new()
+                                .label("A")
+                                .radio(true)
+                                .checked(current == 0)
+                                .on_click(choose_a),
+                            MenuItem::new()
+                                .label("B")
+                                .radio(true)
+                                .checked(current == 1)
+                                .on_click(choose_b),
+                            SubMenu::new()
+                                .label("More")
+                                .children((MenuItem::new().label("C"), MenuItem::new().label("D"))),
+                        )),
+                    ),
+                )
+        }
+    }
+
+    fn test_app(root: impl View) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            QuillPlugin,
+            KeyboardInputPlugin,
+            BistableTransitionPlugin,
+            EventListenerPlugin::<MenuCloseEvent>::default(),
+        ))
+        .init_asset::<Image>()
+        .init_asset::<Font>()
+        .init_resource::<Focus>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ActiveContextMenu>()
+        .add_event::<KeyboardInput>()
+        .add_event::<MenuCloseEvent>()
+        .add_event::<Pointer<Down>>()
+        .add_systems(Update, open_context_menus);
+        // Normally registered by the UI plugin; portals track it.
+        app.world_mut().init_component::<TargetCamera>();
+        app.world_mut().spawn(root.to_root());
+        app.update();
+        app
+    }
+
+    /// Find the entities with the given name.
+    fn named(app: &mut App, name: &str) -> Vec<Entity> {
+        let mut query = app.world_mut().query::<(Entity, &Name)>();
+        query
+            .iter(app.world())
+            .filter(|(_, n)| n.as_str() == name)
+            .map(|(e, _)| e)
+            .collect()
+    }
+
+    /// Find the menu item or submenu with the given label.
+    fn item(app: &mut App, label: &str) -> Entity {
+        let mut texts = app.world_mut().query::<(Entity, &Text)>();
+        let text = texts
+            .iter(app.world())
+            .find(|(_, text)| text.sections[0].value == label)
+            .map(|(e, _)| e)
+            .unwrap();
+        let mut parents = app.world_mut().query::<&Parent>();
+        let mut entity = text;
+        loop {
+            entity = parents.get(app.world(), entity).unwrap().get();
+            let name = app.world().get::<Name>(entity).unwrap();
+            if matches!(name.as_str(), "MenuItem" | "SubMenu") {
+                return entity;
+            }
+        }
+    }
+
+    fn focus(app: &App) -> Option<Entity> {
+        app.world().resource::<Focus>().0
+    }
+
+    fn press(app: &mut App, key_code: KeyCode) {
+        let target = focus(app).unwrap();
+        app.world_mut()
+            .send_event(KeyPressEvent::new(target, key_code));
+        app.update();
+    }
+
+    /// Update until the popups have taken focus, which happens after a short delay.
+    fn settle(app: &mut App) {
+        for _ in 0..10 {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            app.update();
+        }
+    }
+
+    fn open_context_menu(app: &mut App) {
+        let target = named(app, "Target")[0];
+        app.world_mut().send_event(Pointer::new(
+            PointerId::Mouse,
+            Location {
+                target: bevy::render::camera::NormalizedRenderTarget::Image(Handle::default()),
+                position: Vec2::new(10., 20.),
+            },
+            target,
+            Down {
+                button: PointerButton::Secondary,
+                hit: HitData::new(target, 0., None, None),
+            },
+        ));
+        app.update();
+        settle(app);
+    }
+
+    #[test]
+    fn test_context_menu_open_close() {
+        let mut app = test_app(TestMenu);
+        assert!(named(&mut app, "ContextMenu").is_empty());
+
+        open_context_menu(&mut app);
+        assert_eq!(named(&mut app, "ContextMenu").len(), 1);
+        assert_eq!(named(&mut app, "MenuPopup").len(), 1);
+        // The first item takes focus.
+        assert_eq!(focus(&app), Some(item(&mut app, "A")));
+
+        press(&mut app, KeyCode::Escape);
+        app.update();
+        assert!(named(&mut app, "ContextMenu").is_empty());
+        assert!(app.world().resource::<ActiveContextMenu>().0.is_none());
+    }
+
+    #[test]
+    fn test_submenu_hover_and_arrow_navigation() {
+        let mut app = test_app(TestMenu);
+        open_context_menu(&mut app);
+        let submenu = item(&mut app, "More");
+        let a = item(&mut app, "A");
+        let b = item(&mut app, "B");
+
+        // Hovering opens the submenu without moving focus.
+        app.world_mut().get_mut::<Hovering>(submenu).unwrap().0 = true;
+        settle(&mut app);
+        assert_eq!(named(&mut app, "MenuPopup").len(), 2);
+        assert_eq!(focus(&app), Some(a));
+
+        // Hovering a sibling item closes it again.
+        app.world_mut().get_mut::<Hovering>(submenu).unwrap().0 = false;
+        app.world_mut().get_mut::<Hovering>(b).unwrap().0 = true;
+        settle(&mut app);
+        assert_eq!(named(&mut app, "MenuPopup").len(), 1);
+        app.world_mut().get_mut::<Hovering>(b).unwrap().0 = false;
+
+        // Arrow keys move through the items, and the right arrow opens the submenu and focuses
+        // its first item.
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focus(&app), Some(b));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focus(&app), Some(submenu));
+        press(&mut app, KeyCode::ArrowRight);
+        settle(&mut app);
+        let c = item(&mut app, "C");
+        let d = item(&mut app, "D");
+        assert_eq!(focus(&app), Some(c));
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focus(&app), Some(d));
+        // Navigation wraps around within the submenu.
+        press(&mut app, KeyCode::ArrowDown);
+        assert_eq!(focus(&app), Some(c));
+
+        // The left arrow closes the submenu and returns focus to the submenu item.
+        press(&mut app, KeyCode::ArrowLeft);
+        settle(&mut app);
+        assert_eq!(named(&mut app, "MenuPopup").len(), 1);
+        assert_eq!(focus(&app), Some(submenu));
+    }
+
+    #[test]
+    fn test_radio_items() {
+        let mut app = test_app(TestMenu);
+        let toggled = |app: &mut App| {
+            ["A", "B"].map(|label| {
+                let item = item(app, label);
+                let node = app.world().get::<AccessibilityNode>(item).unwrap();
+                assert_eq!(node.0.role(), Role::MenuItemRadio);
+                node.0.toggled()
+            })
+        };
+
+        open_context_menu(&mut app);
+        assert_eq!(
+            toggled(&mut app),
+            [Some(Toggled::True), Some(Toggled::False)]
+        );
+
+        // Choosing the second item checks it and closes the menu.
+        press(&mut app, KeyCode::ArrowDown);
+        press(&mut app, KeyCode::Enter);
+        app.update();
+        assert!(named(&mut app, "ContextMenu").is_empty());
+
+        open_context_menu(&mut app);
+        assert_eq!(
+            toggled(&mut app),
+            [Some(Toggled::False), Some(Toggled::True)]
+        );
+    }
+
+    #[test]
+    fn test_release_context_menu_target() {
+        let mut app = test_app(());
+        let target = app.world_mut().spawn(NodeBundle::default()).id();
+        let root = app
+            .world_mut()
+            .spawn(ContextMenu::new(target).popup(MenuPopup::new()).to_root())
+            .id();
+        app.update();
+        assert!(app.world().get::<ContextMenuTarget>(target).is_some());
+        app.world_mut().resource_mut::<ActiveContextMenu>().0 = Some((target, Vec2::ZERO));
+
+        // Razing the context menu removes the marker from the target, and forgets its menu.
+        app.world_mut().entity_mut(root).despawn_recursive();
+        app.world_mut().flush();
+        assert!(app.world().get::<ContextMenuTarget>(target).is_none());
+        assert!(app.world().resource::<ActiveContextMenu>().0.is_none());
+    }
+
+    #[test]
+    fn test_orphaned_menus() {
+        // A popup or submenu placed outside of a menu still works, rather than panicking.
+        let mut app = test_app((
+            MenuPopup::new().children(MenuItem::new().label("A")),
+            SubMenu::new()
+                .label("Orphan")
+                .children(MenuItem::new().label("B")),
+        ));
+        assert_eq!(named(&mut app, "MenuPopup").len(), 1);
+        let submenu = item(&mut app, "Orphan");
+        app.world_mut().get_mut::<Hovering>(submenu).unwrap().0 = true;
+        settle(&mut app);
+        assert_eq!(named(&mut app, "MenuPopup").len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 mod button;
 mod checkbox;
 mod color_edit;
+mod data_grid;
 mod dialog;
 mod disabled;
 mod disclosure_toggle;
@@ -10,24 +11,32 @@ mod icon_button;
 mod listview;
 mod menu;
 mod scrollview;
+mod selection;
 mod slider;
 mod spacer;
 mod spinbox;
 mod splitter;
 mod swatch;
 mod swatch_grid;
+mod tabs;
+mod text_area;
+mod text_input;
 mod tool_palette;
+mod tooltip;
+mod tree_view;
+mod virtual_view;
 
 pub use button::*;
 pub use checkbox::*;
 pub use color_edit::{ColorEdit, ColorEditState, ColorMode, RecentColors};
+pub use data_grid::{DataColumn, DataGrid, SortDirection};
 pub use dialog::*;
 pub use disabled::*;
 pub use disclosure_toggle::*;
 pub use gradient_slider::*;
 pub use icon::*;
 pub use icon_button::*;
-pub use listview::ListView;
+pub use listview::{ListRow, ListSelectionMode, ListView};
 pub use menu::*;
 pub use scrollview::*;
 pub use slider::*;
@@ -36,4 +45,20 @@ pub use spinbox::*;
 pub use splitter::*;
 pub use swatch::Swatch;
 pub use swatch_grid::SwatchGrid;
+pub use tabs::{TabBar, TabItem, TabPanel};
+pub use text_area::{TabBehavior, TextArea};
+pub use text_input::TextInput;
 pub use tool_palette::*;
+pub use tooltip::{Tooltip, TooltipSettings};
+pub use tree_view::{TreeChildren, TreeMove, TreeNode, TreeSelectionMode, TreeView};
+pub use virtual_view::{ItemBuilder, RowHeights, VirtualGridView, VirtualListView};
+
+pub(crate) use data_grid::update_data_grid_headers;
+pub(crate) use listview::ListViewState;
+pub(crate) use selection::scroll_views_to_cursor;
+pub(crate) use tabs::update_tab_bars;
+pub(crate) use text_area::{commit_text_areas_on_blur, update_text_area_layout};
+pub(crate) use text_input::{commit_text_inputs_on_blur, update_text_input_layout};
+pub(crate) use tooltip::{update_tooltips, ActiveTooltip, TooltipTimer};
+pub(crate) use tree_view::TreeViewState;
+pub(crate) use virtual_view::update_virtual_windows;
@@ -0,0 +1,422 @@
+use bevy::{a11y::Focus, prelude::*};
+use bevy_quill_core::*;
+
+use crate::{focus::ctrl_pressed, scrolling::ScrollArea};
+
+use super::ListSelectionMode;
+
+/// Implemented by the props component of a view whose rows can be selected, such as
+/// [`ListView`](super::ListView), [`DataGrid`](super::DataGrid) and
+/// [`TreeView`](super::TreeView), so that they can share the rules for how clicks and keys
+/// change the cursor and the selection. Rows are identified by a key of type `Key`.
+pub(crate) trait SelectionProps: Component + Sized {
+    /// Identifies a row of the view.
+    type Key: Copy + PartialEq + Send + Sync + 'static;
+
+    /// Whether rows can be selected, and how many at once.
+    fn mode(&self) -> ListSelectionMode;
+
+    /// Keys of the selected rows.
+    fn selection(&self) -> &[Self::Key];
+
+    /// Callback called with the new selection when the user changes it.
+    fn on_selection_change(&self) -> Option<Callback<Vec<Self::Key>>>;
+
+    /// Callback called with the key of a row when it is activated.
+    fn on_activate(&self) -> Option<Callback<Self::Key>> {
+        None
+    }
+
+    /// Keys of the selectable rows of the view, in display order.
+    fn keys(world: &World, view: Entity) -> Vec<Self::Key>;
+
+    /// Called after the cursor moves to `key`, to scroll it into view.
+    fn reveal(world: &mut World, view: Entity, key: Self::Key);
+}
+
+/// The keyboard cursor of a selectable view, and the fixed end of a range selection. Stored on
+/// the root entity of the view; rows redraw when it changes.
+#[derive(Component, PartialEq)]
+pub(crate) struct SelectionCursor<K: Send + Sync + 'static> {
+    pub(crate) cursor: Option<K>,
+    pub(crate) anchor: Option<K>,
+}
+
+impl<K: Send + Sync + 'static> Default for SelectionCursor<K> {
+    fn default() -> Self {
+        Self {
+            cursor: None,
+            anchor: None,
+        }
+    }
+}
+
+/// Implemented by the state component of a selectable view whose rows are scrolled into view as
+/// the cursor moves, so that they can share [`scroll_views_to_cursor`].
+pub(crate) trait ScrollToCursor: Component {
+    /// Identifies a row of the view.
+    type Key: PartialEq + Send + Sync + 'static;
+
+    /// Component on each row entity, which identifies the view and key of the row.
+    type Row: Component;
+
+    /// The view entity and key of a row.
+    fn row(row: &Self::Row) -> (Entity, &Self::Key);
+
+    /// The scroll area containing the rows.
+    fn scroll_area(&self) -> Entity;
+
+    /// True if the cursor has moved and should be scrolled into view.
+    fn scroll_to_cursor(&self) -> bool;
+
+    /// Called once the cursor has been scrolled into view, or there is no cursor.
+    fn clear_scroll_to_cursor(&mut self);
+}
+
+/// Move the cursor to `key`, and update the selection: extending it from the anchor if `extend`
+/// is true, or otherwise replacing it with `key` unless `keep` is true. Unless extending, the
+/// anchor moves to `key` if `set_anchor` is true.
+pub(crate) fn move_cursor<P: SelectionProps>(
+    world: &mut World,
+    view: Entity,
+    key: P::Key,
+    extend: bool,
+    keep: bool,
+    set_anchor: bool,
+) {
+    let Some(mut cursor) = world.get_mut::<SelectionCursor<P::Key>>(view) else {
+        return;
+    };
+    let anchor = cursor.anchor;
+    cursor.cursor = Some(key);
+    if set_anchor && !extend {
+        cursor.anchor = Some(key);
+    }
+    P::reveal(world, view, key);
+    if keep && !extend {
+        return;
+    }
+    let Some(props) = world.get::<P>(view) else {
+        return;
+    };
+    let selection = match (props.mode(), extend) {
+        (ListSelectionMode::None, _) => return,
+        (ListSelectionMode::Multiple, true) => {
+            range(&P::keys(world, view), anchor.unwrap_or(key), key)
+        }
+        _ => vec![key],
+    };
+    select::<P>(world, view, selection);
+}
+
+/// Add `key` to the selection, or remove it if it is already selected.
+pub(crate) fn toggle_selected<P: SelectionProps>(world: &mut World, view: Entity, key: P::Key) {
+    let Some(props) = world.get::<P>(view) else {
+        return;
+    };
+    let mut selection = props.selection().to_vec();
+    match props.mode() {
+        ListSelectionMode::None => return,
+        ListSelectionMode::Single => selection = vec![key],
+        ListSelectionMode::Multiple => match selection.iter().position(|k| *k == key) {
+            Some(index) => {
+                selection.remove(index);
+            }
+            None => selection.push(key),
+        },
+    }
+    select::<P>(world, view, selection);
+}
+
+fn select<P: SelectionProps>(world: &mut World, view: Entity, selection: Vec<P::Key>) {
+    let Some(props) = world.get::<P>(view) else {
+        return;
+    };
+    if props.selection() == selection.as_slice() {
+        return;
+    }
+    if let Some(on_selection_change) = props.on_selection_change() {
+        world.run_callback(on_selection_change, selection);
+    }
+}
+
+/// Call the view's `on_activate` callback for `key`. Returns false if there is none.
+pub(crate) fn activate<P: SelectionProps>(world: &mut World, view: Entity, key: P::Key) -> bool {
+    let on_activate = world.get::<P>(view).and_then(|props| props.on_activate());
+    if let Some(on_activate) = on_activate {
+        world.run_callback(on_activate, key);
+    }
+    on_activate.is_some()
+}
+
+/// Handle a pointer press on the row `key`: focus the view and move the cursor, extending the
+/// selection if shift is held or toggling the row if ctrl is held. If `keep_selected` is true,
+/// pressing on a row which is already selected leaves the selection alone, so that it can be
+/// dragged.
+pub(crate) fn press_row<P: SelectionProps>(
+    world: &mut World,
+    view: Entity,
+    key: P::Key,
+    keep_selected: bool,
+) {
+    world.resource_mut::<Focus>().0 = Some(view);
+    let keys = world.resource::<ButtonInput<KeyCode>>();
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = ctrl_pressed(keys);
+    let keep = keep_selected
+        && !shift
+        && !ctrl
+        && world
+            .get::<P>(view)
+            .is_some_and(|props| props.selection().contains(&key));
+    move_cursor::<P>(world, view, key, shift, ctrl || keep, true);
+    if ctrl && !shift {
+        toggle_selected::<P>(world, view, key);
+    }
+}
+
+/// Handle the keys which select, activate and move between rows: space, enter, the up and down
+/// arrows, page up / down, home and end. `page` is the number of rows which fit in the visible
+/// part of the view. Returns true if the key was used.
+pub(crate) fn handle_selection_key<P: SelectionProps>(
+    world: &mut World,
+    view: Entity,
+    key: KeyCode,
+    shift: bool,
+    ctrl: bool,
+    page: usize,
+) -> bool {
+    let keys = P::keys(world, view);
+    let cursor = world
+        .get::<SelectionCursor<P::Key>>(view)
+        .and_then(|cursor| cursor.cursor);
+    let current = cursor.and_then(|cursor| keys.iter().position(|k| *k == cursor));
+    match key {
+        KeyCode::Space => {
+            let Some(index) = current else {
+                return false;
+            };
+            if ctrl {
+                toggle_selected::<P>(world, view, keys[index]);
+            } else {
+                move_cursor::<P>(world, view, keys[index], shift, false, true);
+            }
+            true
+        }
+        KeyCode::Enter | KeyCode::NumpadEnter => match current {
+            Some(index) => activate::<P>(world, view, keys[index]),
+            None => false,
+        },
+        _ => {
+            let Some(index) = step(keys.len(), current, key, page) else {
+                return false;
+            };
+            move_cursor::<P>(world, view, keys[index], shift, ctrl, !ctrl);
+            true
+        }
+    }
+}
+
+/// Scrolls views so that the row with the cursor is visible, after the cursor moves.
+pub(crate) fn scroll_views_to_cursor<S: ScrollToCursor>(
+    mut views: Query<(Entity, &mut S, &SelectionCursor<S::Key>)>,
+    rows: Query<(&S::Row, &Node, &GlobalTransform)>,
+    mut scroll_areas: Query<(&mut ScrollArea, &Node, &GlobalTransform)>,
+) {
+    for (entity, mut state, cursor) in views.iter_mut() {
+        if !state.scroll_to_cursor() {
+            continue;
+        }
+        let Some(ref cursor) = cursor.cursor else {
+            state.clear_scroll_to_cursor();
+            continue;
+        };
+        // Wait until the row has been laid out.
+        let Some(row_rect) = rows
+            .iter()
+            .find(|(row, node, _)| S::row(row) == (entity, cursor) && node.size().y > 0.)
+            .map(|(_, node, transform)| node.logical_rect(transform))
+        else {
+            continue;
+        };
+        state.clear_scroll_to_cursor();
+        let Ok((mut scroll, node, transform)) = scroll_areas.get_mut(state.scroll_area()) else {
+            continue;
+        };
+        let visible = node.logical_rect(transform);
+        let left = scroll.scroll_left;
+        if row_rect.min.y < visible.min.y {
+            let top = scroll.scroll_top - (visible.min.y - row_rect.min.y);
+            scroll.scroll_to(left, top);
+        } else if row_rect.max.y > visible.max.y {
+            let top = scroll.scroll_top + (row_rect.max.y - visible.max.y);
+            scroll.scroll_to(left, top);
+        }
+    }
+}
+
+/// Keys between `from` and `to` inclusive, in display order.
+pub(crate) fn range<K: Copy + PartialEq>(keys: &[K], from: K, to: K) -> Vec<K> {
+    let position = |key| keys.iter().position(|k| *k == key);
+    let (Some(a), Some(b)) = (position(from), position(to)) else {
+        return vec![to];
+    };
+    keys[a.min(b)..=a.max(b)].to_vec()
+}
+
+/// Index of the row that a navigation key moves the cursor to, given the number of rows, the
+/// row with the cursor, and how many rows fit in a page. Returns `None` for other keys.
+pub(crate) fn step(
+    count: usize,
+    current: Option<usize>,
+    key: KeyCode,
+    page: usize,
+) -> Option<usize> {
+    let last = count.checked_sub(1)?;
+    Some(match (key, current) {
+        (KeyCode::ArrowUp, Some(index)) => index.saturating_sub(1),
+        (KeyCode::ArrowDown, Some(index)) => (index + 1).min(last),
+        (KeyCode::PageUp, Some(index)) => index.saturating_sub(page),
+        (KeyCode::PageDown, Some(index)) => (index + page).min(last),
+        (KeyCode::ArrowUp | KeyCode::End | KeyCode::PageDown, _) => last,
+        (KeyCode::ArrowDown | KeyCode::Home | KeyCode::PageUp, _) => 0,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A selectable view whose rows are given by `keys`, with the selection recorded by the
+    /// `on_selection_change` callback.
+    #[derive(Component)]
+    struct TestProps {
+        keys: Vec<u32>,
+        selection: Vec<u32>,
+        mode: ListSelectionMode,
+        on_selection_change: Option<Callback<Vec<u32>>>,
+    }
+
+    impl SelectionProps for TestProps {
+        type Key = u32;
+
+        fn mode(&self) -> ListSelectionMode {
+            self.mode
+        }
+
+        fn selection(&self) -> &[u32] {
+            &self.selection
+        }
+
+        fn on_selection_change(&self) -> Option<Callback<Vec<u32>>> {
+            self.on_selection_change
+        }
+
+        fn keys(world: &World, view: Entity) -> Vec<u32> {
+            world.get::<TestProps>(view).unwrap().keys.clone()
+        }
+
+        fn reveal(_world: &mut World, _view: Entity, _key: u32) {}
+    }
+
+    #[derive(Resource)]
+    struct TestView(Entity);
+
+    fn new_view(world: &mut World, mode: ListSelectionMode) -> Entity {
+        let owner = world.spawn_empty().id();
+        let mut scope = TrackingScope::new(world.change_tick());
+        let on_selection_change = Cx::new(world, owner, &mut scope).create_callback(
+            |selection: In<Vec<u32>>, view: Res<TestView>, mut props: Query<&mut TestProps>| {
+                props.get_mut(view.0).unwrap().selection = selection.0;
+            },
+        );
+        let view = world
+            .spawn((
+                TestProps {
+                    keys: vec![10, 20, 30, 40, 50],
+                    selection: Vec::new(),
+                    mode,
+                    on_selection_change: Some(on_selection_change),
+                },
+                SelectionCursor::<u32>::default(),
+            ))
+            .id();
+        world.insert_resource(TestView(view));
+        view
+    }
+
+    fn selection(world: &World, view: Entity) -> Vec<u32> {
+        world.get::<TestProps>(view).unwrap().selection.clone()
+    }
+
+    #[test]
+    fn test_range() {
+        let keys = [10, 20, 30, 40];
+        assert_eq!(range(&keys, 30, 10), vec![10, 20, 30]);
+        assert_eq!(range(&keys, 20, 40), vec![20, 30, 40]);
+        assert_eq!(range(&keys, 20, 20), vec![20]);
+        assert_eq!(range(&keys, 99, 30), vec![30]);
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(step(10, Some(3), KeyCode::ArrowUp, 4), Some(2));
+        assert_eq!(step(10, Some(0), KeyCode::ArrowUp, 4), Some(0));
+        assert_eq!(step(10, Some(9), KeyCode::ArrowDown, 4), Some(9));
+        assert_eq!(step(10, Some(3), KeyCode::PageDown, 4), Some(7));
+        assert_eq!(step(10, Some(8), KeyCode::PageDown, 4), Some(9));
+        assert_eq!(step(10, Some(2), KeyCode::PageUp, 4), Some(0));
+        assert_eq!(step(10, Some(5), KeyCode::Home, 4), Some(0));
+        assert_eq!(step(10, Some(5), KeyCode::End, 4), Some(9));
+        // With no cursor, moving down starts at the top and moving up at the bottom.
+        assert_eq!(step(10, None, KeyCode::ArrowDown, 4), Some(0));
+        assert_eq!(step(10, None, KeyCode::ArrowUp, 4), Some(9));
+        assert_eq!(step(0, None, KeyCode::ArrowDown, 4), None);
+        assert_eq!(step(10, Some(5), KeyCode::KeyA, 4), None);
+    }
+
+    #[test]
+    fn test_keyboard_selection() {
+        let mut world = World::new();
+        let view = new_view(&mut world, ListSelectionMode::Multiple);
+        let key = |world: &mut World, key, shift, ctrl| {
+            handle_selection_key::<TestProps>(world, view, key, shift, ctrl, 2)
+        };
+
+        assert!(key(&mut world, KeyCode::ArrowDown, false, false));
+        assert_eq!(selection(&world, view), vec![10]);
+        key(&mut world, KeyCode::PageDown, true, false);
+        assert_eq!(selection(&world, view), vec![10, 20, 30]);
+
+        // Ctrl moves the cursor without changing the selection, and ctrl-space toggles.
+        key(&mut world, KeyCode::ArrowDown, false, true);
+        assert_eq!(selection(&world, view), vec![10, 20, 30]);
+        key(&mut world, KeyCode::Space, false, true);
+        assert_eq!(selection(&world, view), vec![10, 20, 30, 40]);
+        key(&mut world, KeyCode::Space, false, true);
+        assert_eq!(selection(&world, view), vec![10, 20, 30]);
+
+        // Without an `on_activate` callback, enter isn't used.
+        assert!(!key(&mut world, KeyCode::Enter, false, false));
+        assert!(!key(&mut world, KeyCode::KeyA, false, false));
+    }
+
+    #[test]
+    fn test_single_selection() {
+        let mut world = World::new();
+        let view = new_view(&mut world, ListSelectionMode::Single);
+        move_cursor::<TestProps>(&mut world, view, 20, false, false, true);
+        move_cursor::<TestProps>(&mut world, view, 40, true, false, true);
+        assert_eq!(selection(&world, view), vec![40]);
+        toggle_selected::<TestProps>(&mut world, view, 10);
+        assert_eq!(selection(&world, view), vec![10]);
+
+        let mut props = world.get_mut::<TestProps>(view).unwrap();
+        props.mode = ListSelectionMode::None;
+        move_cursor::<TestProps>(&mut world, view, 30, false, false, true);
+        assert_eq!(selection(&world, view), vec![10]);
+        let cursor = world.get::<SelectionCursor<u32>>(view).unwrap();
+        assert_eq!(cursor.cursor, Some(30));
+    }
+}
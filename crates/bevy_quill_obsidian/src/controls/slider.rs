@@ -7,7 +7,7 @@ use bevy_quill_core::*;
 
 use crate::{
     colors, cursor::StyleBuilderCursor, hooks::UseElementRect, materials::SliderRectMaterial,
-    RoundedCorners,
+    typography, RoundedCorners,
 };
 
 use super::{IconButton, Spacer};
@@ -49,7 +49,8 @@ fn style_label(ss: &mut StyleBuilder) {
         .align_items(ui::AlignItems::Center)
         .justify_content(ui::JustifyContent::Center)
         .height(ui::Val::Percent(100.))
-        .font("embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Medium.ttf")
+        .font_family(typography::FONT_FAMILY)
+        .font_weight(FontWeight::Medium)
         .font_size(16)
         .padding((6, 0))
         .color(colors::FOREGROUND);
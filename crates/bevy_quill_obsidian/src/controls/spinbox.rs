@@ -1,10 +1,18 @@
-use super::IconButton;
-use crate::{colors, cursor::StyleBuilderCursor, hooks::UseElementRect, RoundedCorners};
-use bevy::{prelude::*, ui};
+use super::{IconButton, TextInput};
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    expression::evaluate,
+    focus::{KeyPressEvent, TabIndex},
+    hooks::{is_focus::is_descendant, UseElementRect, UseIsFocus},
+    text_editing::DOUBLE_CLICK_TIME,
+    typography, RoundedCorners,
+};
+use bevy::{a11y::Focus, prelude::*, ui};
 use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill_core::*;
-use std::ops::RangeInclusive;
+use std::{f64::consts, ops::RangeInclusive};
 
 #[derive(Clone, PartialEq, Default, Copy)]
 enum DragType {
@@ -18,6 +26,8 @@ struct DragState {
     dragging: DragType,
     offset: f32,
     was_dragged: bool,
+    /// Time of the last click on the label, used to detect double-clicks.
+    last_click: Option<f32>,
 }
 
 fn style_spinbox(ss: &mut StyleBuilder) {
@@ -46,17 +56,77 @@ fn style_spinbox_label(ss: &mut StyleBuilder) {
         .align_items(ui::AlignItems::Center)
         .justify_content(ui::JustifyContent::FlexEnd)
         .height(ui::Val::Percent(100.))
-        .font("embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Medium.ttf")
+        .font_family(typography::FONT_FAMILY)
+        .font_weight(FontWeight::Medium)
         .font_size(16)
         .overflow(ui::OverflowAxis::Hidden)
         .padding((3, 0))
         .color(colors::FOREGROUND);
 }
 
+fn style_spinbox_input(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .top(0)
+        .bottom(0)
+        .right(0)
+        .height(ui::Val::Auto)
+        .min_width(0)
+        .padding((3, 0));
+}
+
 fn style_spinbox_button(ss: &mut StyleBuilder) {
     ss.height(20.).padding(0).max_width(12).flex_grow(0.2);
 }
 
+/// Units which can be typed after numbers when entering a value into a [`SpinBox`]. Each
+/// variant names the unit of the spinbox value; typed quantities are converted to it, so that
+/// `50cm` enters `0.5` into a spinbox measured in meters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpinBoxUnits {
+    /// No unit suffixes are accepted.
+    #[default]
+    None,
+    /// Lengths, with the value in meters: `mm`, `cm`, `m`, `km`, `in` and `ft`.
+    Meters,
+    /// Angles, with the value in degrees: `deg` (or `°`), `rad` and `turn`.
+    Degrees,
+    /// Angles, with the value in radians: `deg` (or `°`), `rad` and `turn`.
+    Radians,
+    /// Durations, with the value in seconds: `ms`, `s`, `min` and `h`.
+    Seconds,
+}
+
+impl SpinBoxUnits {
+    /// The unit suffixes, and the value of each in terms of the spinbox unit.
+    fn suffixes(self) -> &'static [(&'static str, f64)] {
+        match self {
+            SpinBoxUnits::None => &[],
+            SpinBoxUnits::Meters => &[
+                ("mm", 0.001),
+                ("cm", 0.01),
+                ("m", 1.),
+                ("km", 1000.),
+                ("in", 0.0254),
+                ("ft", 0.3048),
+            ],
+            SpinBoxUnits::Degrees => &[
+                ("deg", 1.),
+                ("°", 1.),
+                ("rad", 180. / consts::PI),
+                ("turn", 360.),
+            ],
+            SpinBoxUnits::Radians => &[
+                ("deg", consts::PI / 180.),
+                ("°", consts::PI / 180.),
+                ("rad", 1.),
+                ("turn", consts::TAU),
+            ],
+            SpinBoxUnits::Seconds => &[("ms", 0.001), ("s", 1.), ("min", 60.), ("h", 3600.)],
+        }
+    }
+}
+
 /// Component used to hold the spinbox params so that they can be accessed by the callbacks
 /// without capturing.
 #[derive(Component, Copy, Clone)]
@@ -66,6 +136,18 @@ struct SpinBoxState {
     max: f32,
     precision: usize,
     step: f32,
+    units: SpinBoxUnits,
+}
+
+impl SpinBoxState {
+    /// Evaluate text typed into the spinbox, rounding the result to the spinbox precision.
+    /// Returns `None` if the text is not a valid expression, or the result is out of range.
+    fn parse(&self, text: &str) -> Option<f32> {
+        let value = evaluate(text, self.value as f64, self.units.suffixes())?;
+        let rounding = f64::powi(10., self.precision as i32);
+        let value = ((value * rounding).round() / rounding) as f32;
+        (self.min..=self.max).contains(&value).then_some(value)
+    }
 }
 
 /// A numeric spinbox. This is a widget that allows the user to input a number by typing, using
@@ -73,6 +155,12 @@ struct SpinBoxState {
 /// * The range of values is large or unbounded, making it difficult to select a specific value
 ///   with a slider.
 /// * There is limited horizontal space available.
+///
+/// Double-clicking the value, or pressing Enter while the spinbox is focused, switches to
+/// typing. The typed text may be an arithmetic expression such as `2*pi` or `1/3`, with unit
+/// suffixes (see [`SpinBoxUnits`]); a leading `+=`, `-=`, `*=` or `/=` applies the expression
+/// to the current value. Enter commits the value if it is valid and within range, and Escape
+/// cancels.
 #[derive(Clone, PartialEq)]
 pub struct SpinBox {
     /// Current slider value.
@@ -97,9 +185,15 @@ pub struct SpinBox {
     /// formatter will be used.
     pub formatted_value: Option<String>,
 
+    /// Units which may be typed after numbers when entering a value.
+    pub units: SpinBoxUnits,
+
     /// Style handle for slider root element.
     pub style: StyleHandle,
 
+    /// The tab index of the spinbox (default 0).
+    pub tab_index: i32,
+
     /// Callback called when value changes
     pub on_change: Option<Callback<f32>>,
 }
@@ -160,6 +254,18 @@ impl SpinBox {
         self
     }
 
+    /// Set the units which may be typed after numbers when entering a value.
+    pub fn units(mut self, units: SpinBoxUnits) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Set the tab index of the spinbox.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
     /// Set the style handle for the spinbox root element.
     pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
         self.style = style.into_handle();
@@ -183,7 +289,9 @@ impl Default for SpinBox {
             step: 1.,
             disabled: false,
             formatted_value: None,
+            units: SpinBoxUnits::None,
             style: StyleHandle::default(),
+            tab_index: 0,
             on_change: None,
         }
     }
@@ -194,8 +302,13 @@ impl ViewTemplate for SpinBox {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let spinbox_id = cx.create_entity();
         let rect = cx.use_element_rect(spinbox_id);
-        let show_buttons = rect.width() >= 48.;
+        let editing = cx.create_mutable(false);
+        let invalid = cx.create_mutable(false);
+        let is_editing = editing.get(cx);
+        let focused = cx.is_focused(spinbox_id);
+        let show_buttons = rect.width() >= 48. && !is_editing;
         let on_change = self.on_change;
+        let disabled = self.disabled;
 
         let dec_disabled = self.value <= self.min;
         let dec_click = cx.create_callback(move |world: &mut World| {
@@ -216,6 +329,39 @@ impl ViewTemplate for SpinBox {
             }
         });
 
+        // Commit the typed value when Enter is pressed or the text input loses focus. If the
+        // value is invalid, keep editing, unless the focus has moved elsewhere.
+        let on_commit = cx.create_callback(move |text: In<String>, world: &mut World| {
+            if !editing.get(world) {
+                return;
+            }
+            let state = *world.get::<SpinBoxState>(spinbox_id).unwrap();
+            let focus_within = world
+                .resource::<Focus>()
+                .0
+                .is_some_and(|focus| is_descendant(world, &focus, &spinbox_id));
+            match state.parse(&text) {
+                Some(value) => {
+                    editing.set(world, false);
+                    invalid.set(world, false);
+                    if focus_within {
+                        world.resource_mut::<Focus>().0 = Some(spinbox_id);
+                    }
+                    if let Some(on_change) = on_change {
+                        world.run_callback(on_change, value);
+                    }
+                }
+                None if focus_within => invalid.set(world, true),
+                None => {
+                    editing.set(world, false);
+                    invalid.set(world, false);
+                }
+            }
+        });
+        let on_edit = cx.create_callback(move |_: In<String>, world: &mut World| {
+            invalid.set(world, false);
+        });
+
         // Ensure DragState component exists before rendering.
         let mut entt = cx.world_mut().entity_mut(spinbox_id);
         if !entt.contains::<DragState>() {
@@ -223,20 +369,75 @@ impl ViewTemplate for SpinBox {
                 dragging: DragType::None,
                 was_dragged: false,
                 offset: 0.,
+                last_click: None,
             });
         }
 
         Element::<NodeBundle>::for_entity(spinbox_id)
             .style((style_spinbox, self.style.clone()))
+            .style_dyn(
+                |(focused, invalid), sb| {
+                    if invalid {
+                        sb.outline_color(colors::X_RED)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                (focused, invalid.get(cx)),
+            )
+            .insert_dyn(
+                |(value, min, max, precision, step, units, tab_index)| {
+                    (
+                        SpinBoxState {
+                            value,
+                            min,
+                            max,
+                            precision,
+                            step,
+                            units,
+                        },
+                        TabIndex(tab_index),
+                    )
+                },
+                (
+                    self.value,
+                    self.min,
+                    self.max,
+                    self.precision,
+                    self.step,
+                    self.units,
+                    self.tab_index,
+                ),
+            )
             .insert_dyn(
-                |(value, min, max, precision, step)| SpinBoxState {
-                    value,
-                    min,
-                    max,
-                    precision,
-                    step,
+                move |disabled| {
+                    On::<KeyPressEvent>::run(move |world: &mut World| {
+                        let key_code = world.resource::<ListenerInput<KeyPressEvent>>().key_code;
+                        match key_code {
+                            KeyCode::Enter | KeyCode::NumpadEnter
+                                if !disabled && !editing.get(world) =>
+                            {
+                                editing.set(world, true);
+                            }
+                            KeyCode::Escape if editing.get(world) => {
+                                editing.set(world, false);
+                                invalid.set(world, false);
+                                world.resource_mut::<Focus>().0 = Some(spinbox_id);
+                            }
+                            _ => return,
+                        }
+                        world
+                            .resource_mut::<ListenerInput<KeyPressEvent>>()
+                            .stop_propagation();
+                    })
                 },
-                (self.value, self.min, self.max, self.precision, self.step),
+                disabled,
             )
             .children((Element::<NodeBundle>::new()
                 .named("SpinBox")
@@ -259,6 +460,24 @@ impl ViewTemplate for SpinBox {
                         .insert_dyn(
                             move |_| {
                                 (
+                                    On::<Pointer<Down>>::run(move |world: &mut World| {
+                                        world.resource_mut::<Focus>().0 = Some(spinbox_id);
+                                        if disabled {
+                                            return;
+                                        }
+                                        let now = world.resource::<Time>().elapsed_seconds();
+                                        let mut ds =
+                                            world.get_mut::<DragState>(spinbox_id).unwrap();
+                                        if ds
+                                            .last_click
+                                            .is_some_and(|time| now - time < DOUBLE_CLICK_TIME)
+                                        {
+                                            ds.last_click = None;
+                                            editing.set(world, true);
+                                        } else {
+                                            ds.last_click = Some(now);
+                                        }
+                                    }),
                                     On::<Pointer<DragStart>>::run(move |world: &mut World| {
                                         // Save initial value to use as drag offset.
                                         let mut event = world
@@ -267,27 +486,27 @@ impl ViewTemplate for SpinBox {
                                         event.stop_propagation();
                                         let mut entt = world.entity_mut(spinbox_id);
                                         let value = entt.get::<SpinBoxState>().unwrap().value;
+                                        let ds = *entt.get::<DragState>().unwrap();
                                         entt.insert(DragState {
                                             dragging: DragType::Dragging,
                                             offset: value,
                                             was_dragged: false,
+                                            ..ds
                                         });
                                     }),
                                     On::<Pointer<DragEnd>>::run(move |world: &mut World| {
-                                        let entt = world.entity(spinbox_id);
-                                        let ds = entt.get::<DragState>().unwrap();
+                                        let mut entt = world.entity_mut(spinbox_id);
+                                        let ds = *entt.get::<DragState>().unwrap();
                                         if ds.dragging == DragType::Dragging {
-                                            if !ds.was_dragged {
-                                                // We want to know if it was a click or a drag.
-                                                // This will let us display a text input field later.
-                                                // Once we have text input fields.
-                                                println!("was not dragged");
-                                            }
-                                            let mut entt = world.entity_mut(spinbox_id);
                                             let state = entt.get::<SpinBoxState>().unwrap();
                                             entt.insert(DragState {
                                                 dragging: DragType::None,
                                                 offset: state.value,
+                                                // A drag is not the first click of a double-click.
+                                                last_click: match ds.was_dragged {
+                                                    true => None,
+                                                    false => ds.last_click,
+                                                },
                                                 was_dragged: false,
                                             });
                                         }
@@ -337,6 +556,16 @@ impl ViewTemplate for SpinBox {
                             Some(ref formatted_value) => formatted_value.clone(),
                             None => format!("{:.*}", self.precision, self.value),
                         }),
+                    Cond::new(
+                        is_editing,
+                        TextInput::new()
+                            .value(format!("{:.*}", self.precision, self.value))
+                            .style(style_spinbox_input)
+                            .auto_focus(true)
+                            .on_change(on_edit)
+                            .on_commit(on_commit),
+                        (),
+                    ),
                     Cond::new(
                         show_buttons,
                         IconButton::new(
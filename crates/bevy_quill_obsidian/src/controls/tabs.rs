@@ -0,0 +1,821 @@
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    ecs::system::SystemState,
+    prelude::*,
+    ui,
+};
+use bevy_mod_picking::{events::PointerCancel, prelude::*};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    floating::FloatAlign,
+    focus::{KeyPressEvent, NavAction, TabIndex, TabNavigation},
+    hooks::{is_focus::is_descendant, UseIsFocus, UseIsHover},
+    size::Size,
+    typography,
+};
+
+use super::{IconButton, MenuButton, MenuItem, MenuPopup};
+
+fn style_tab_bar(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Stretch)
+        .background_color(colors::U1)
+        .min_height(Size::Md.height());
+}
+
+fn style_tab_viewport(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .flex_grow(1.)
+        .min_width(0)
+        .overflow(ui::OverflowAxis::Clip);
+}
+
+fn style_tab_strip(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Stretch)
+        .position(ui::PositionType::Relative)
+        .flex_shrink(0.)
+        .gap(1);
+}
+
+fn style_tab(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .gap(4)
+        .padding((10, 0))
+        .border_radius(BorderRadius::top(ui::Val::Px(4.)))
+        .color(colors::FOREGROUND)
+        .cursor(CursorIcon::Pointer);
+}
+
+fn style_tab_close(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex).margin_right(-6);
+}
+
+fn style_drop_indicator(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(0)
+        .bottom(0)
+        .width(2)
+        .background_color(colors::ACCENT)
+        .display(ui::Display::None);
+}
+
+fn style_tab_panel(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .background_color(colors::U3)
+        .padding(6);
+}
+
+/// Component which identifies a tab within a [`TabBar`].
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct TabBarTab {
+    bar: Entity,
+    index: usize,
+    closable: bool,
+}
+
+/// Entities which make up a tab bar, and the index of the selected tab.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) struct TabBarLayout {
+    viewport: Entity,
+    strip: Entity,
+    indicator: Entity,
+    selected: usize,
+}
+
+/// System state for the key handler of a tab bar, cached by the listener between events.
+type TabKeyState = SystemState<(
+    ResMut<'static, ListenerInput<KeyPressEvent>>,
+    ResMut<'static, Focus>,
+    TabNavigation<'static, 'static>,
+    Query<'static, 'static, &'static TabBarTab>,
+)>;
+
+/// A tab being dragged to a new position.
+#[derive(Clone, Copy)]
+struct TabDrag {
+    /// Index of the tab being dragged.
+    from: usize,
+    /// Horizontal position of the pointer.
+    x: f32,
+}
+
+/// Scroll and drag state of a tab bar, stored on the root entity.
+#[derive(Component, Default)]
+pub(crate) struct TabBarState {
+    /// Horizontal scroll offset of the tab strip.
+    offset: f32,
+    drag: Option<TabDrag>,
+}
+
+/// Whether the tabs of a tab bar are too wide to fit. Kept separate from [`TabBarState`] so
+/// that the view only re-renders when this changes.
+#[derive(Component, Default, PartialEq)]
+pub(crate) struct TabBarOverflow(bool);
+
+/// A tab within a [`TabBar`].
+#[derive(Clone, PartialEq, Default)]
+pub struct TabItem {
+    /// The label of the tab.
+    pub label: String,
+
+    /// Whether the tab displays a close button.
+    pub closable: bool,
+}
+
+impl TabItem {
+    /// Create a new tab with the given label.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            closable: false,
+        }
+    }
+
+    /// Set whether the tab displays a close button.
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+/// A horizontal row of tabs, one of which is selected.
+///
+/// The left and right arrow keys move between tabs, selecting them as they go, and Delete
+/// closes the focused tab if it is closable. Tabs can be dragged to a new position. When the
+/// tabs don't fit, the selected tab is scrolled into view and a drop-down menu listing all of
+/// the tabs is shown at the end of the bar.
+///
+/// The tab bar doesn't own the list of tabs: the callbacks report what the user did, and it's
+/// up to the caller to update `tabs` and `selected` to match.
+#[derive(Clone, PartialEq, Default)]
+pub struct TabBar {
+    /// The tabs to display.
+    pub tabs: Vec<TabItem>,
+
+    /// Index of the selected tab.
+    pub selected: usize,
+
+    /// Additional styles to be applied to the tab bar.
+    pub style: StyleHandle,
+
+    /// Callback called with the index of a tab when it is selected.
+    pub on_select: Option<Callback<usize>>,
+
+    /// Callback called with the index of a tab when its close button is clicked.
+    pub on_close: Option<Callback<usize>>,
+
+    /// Callback called with `(from, to)` when a tab is dragged to a new position. `to` is the
+    /// index of the tab after it has been removed from `from` and re-inserted.
+    pub on_reorder: Option<Callback<(usize, usize)>>,
+}
+
+impl TabBar {
+    /// Create a new tab bar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the tabs to display.
+    pub fn tabs(mut self, tabs: Vec<TabItem>) -> Self {
+        self.tabs = tabs;
+        self
+    }
+
+    /// Set the index of the selected tab.
+    pub fn selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Set additional styles to be applied to the tab bar.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the callback called when a tab is selected.
+    pub fn on_select(mut self, on_select: Callback<usize>) -> Self {
+        self.on_select = Some(on_select);
+        self
+    }
+
+    /// Set the callback called when a tab is closed.
+    pub fn on_close(mut self, on_close: Callback<usize>) -> Self {
+        self.on_close = Some(on_close);
+        self
+    }
+
+    /// Set the callback called when a tab is dragged to a new position.
+    pub fn on_reorder(mut self, on_reorder: Callback<(usize, usize)>) -> Self {
+        self.on_reorder = Some(on_reorder);
+        self
+    }
+}
+
+impl ViewTemplate for TabBar {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let viewport = cx.create_entity();
+        let strip = cx.create_entity();
+        let indicator = cx.create_entity();
+
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<TabBarState>() {
+            entt.insert((TabBarState::default(), TabBarOverflow::default()));
+        }
+        let overflow = cx.use_component::<TabBarOverflow>(id).is_some_and(|o| o.0);
+
+        let selected = self.selected;
+        let on_select = self.on_select;
+        let on_close = self.on_close;
+        let on_reorder = self.on_reorder;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TabBar")
+            .style((typography::text_default, style_tab_bar, self.style.clone()))
+            .insert(AccessibilityNode::from(NodeBuilder::new(Role::TabList)))
+            .insert_dyn(
+                move |selected| TabBarLayout {
+                    viewport,
+                    strip,
+                    indicator,
+                    selected,
+                },
+                selected,
+            )
+            .insert_dyn(
+                move |(on_select, on_close)| {
+                    On::<KeyPressEvent>::run(move |world: &mut World, st: &mut TabKeyState| {
+                        let (mut event, mut focus, nav, tabs) = st.get_mut(world);
+                        let Some(current) = focus.0.and_then(|e| tabs.get(e).ok()).copied() else {
+                            return;
+                        };
+                        if current.bar != id {
+                            return;
+                        }
+                        let action = match event.key_code {
+                            KeyCode::ArrowLeft => NavAction::Previous,
+                            KeyCode::ArrowRight => NavAction::Next,
+                            KeyCode::Home => NavAction::First,
+                            KeyCode::End => NavAction::Last,
+                            KeyCode::Delete if current.closable && !event.repeat => {
+                                event.stop_propagation();
+                                if let Some(on_close) = on_close {
+                                    world.run_callback(on_close, current.index);
+                                }
+                                return;
+                            }
+                            _ => return,
+                        };
+                        event.stop_propagation();
+                        let Some(next) = nav.navigate_within(strip, focus.0, action) else {
+                            return;
+                        };
+                        focus.0 = Some(next);
+                        if let (Ok(tab), Some(on_select)) = (tabs.get(next), on_select) {
+                            let index = tab.index;
+                            world.run_callback(on_select, index);
+                        }
+                    })
+                },
+                (on_select, on_close),
+            )
+            .children((
+                Element::<NodeBundle>::for_entity(viewport)
+                    .named("TabBar::Viewport")
+                    .style(style_tab_viewport)
+                    .children(
+                        Element::<NodeBundle>::for_entity(strip)
+                            .named("TabBar::Strip")
+                            .style(style_tab_strip)
+                            .children((
+                                For::index(&self.tabs, move |tab, index| TabButton {
+                                    bar: id,
+                                    index,
+                                    label: tab.label.clone(),
+                                    closable: tab.closable,
+                                    selected: index == selected,
+                                    on_select,
+                                    on_close,
+                                    on_reorder,
+                                }),
+                                Element::<NodeBundle>::for_entity(indicator)
+                                    .named("TabBar::DropIndicator")
+                                    .style(style_drop_indicator),
+                            )),
+                    ),
+                Cond::new(
+                    overflow,
+                    MenuButton::new()
+                        .minimal(true)
+                        .style(|ss: &mut StyleBuilder| {
+                            ss.padding((4, 0)).flex_shrink(0.);
+                        })
+                        .popup(MenuPopup::new().align(FloatAlign::End).children(For::index(
+                            &self.tabs,
+                            move |tab, index| TabMenuItem {
+                                label: tab.label.clone(),
+                                index,
+                                selected: index == selected,
+                                on_select,
+                            },
+                        ))),
+                    (),
+                ),
+            ))
+    }
+}
+
+/// A single tab within a tab bar.
+#[derive(Clone, PartialEq)]
+struct TabButton {
+    bar: Entity,
+    index: usize,
+    label: String,
+    closable: bool,
+    selected: bool,
+    on_select: Option<Callback<usize>>,
+    on_close: Option<Callback<usize>>,
+    on_reorder: Option<Callback<(usize, usize)>>,
+}
+
+impl ViewTemplate for TabButton {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let close = cx.create_entity();
+        let hovering = cx.is_hovered(id);
+        let focused = cx.is_focus_visible(id);
+        let bar = self.bar;
+        let index = self.index;
+        let on_close = self.on_close;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TabBar::Tab")
+            .style(style_tab)
+            .insert_dyn(
+                move |(closable, selected)| {
+                    (
+                        TabBarTab {
+                            bar,
+                            index,
+                            closable,
+                        },
+                        TabIndex(if selected { 0 } else { -1 }),
+                        AccessibilityNode::from({
+                            let mut builder = NodeBuilder::new(Role::Tab);
+                            builder.set_selected(selected);
+                            builder
+                        }),
+                    )
+                },
+                (self.closable, self.selected),
+            )
+            .insert_dyn(
+                move |(on_select, on_reorder)| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            let target = world.resource::<ListenerInput<Pointer<Down>>>().target;
+                            if is_descendant(world, &target, &close) {
+                                return;
+                            }
+                            world.resource_mut::<Focus>().0 = Some(id);
+                            if let Some(on_select) = on_select {
+                                world.run_callback(on_select, index);
+                            }
+                        }),
+                        On::<Pointer<DragStart>>::run(move |world: &mut World| {
+                            let event = world.resource::<ListenerInput<Pointer<DragStart>>>();
+                            let (target, x) = (event.target, event.pointer_location.position.x);
+                            if is_descendant(world, &target, &close) {
+                                return;
+                            }
+                            if let Some(mut state) = world.get_mut::<TabBarState>(bar) {
+                                state.drag = Some(TabDrag { from: index, x });
+                            }
+                        }),
+                        On::<Pointer<Drag>>::run(move |world: &mut World| {
+                            let x = world
+                                .resource::<ListenerInput<Pointer<Drag>>>()
+                                .pointer_location
+                                .position
+                                .x;
+                            if let Some(mut state) = world.get_mut::<TabBarState>(bar) {
+                                if let Some(drag) = state.drag.as_mut() {
+                                    drag.x = x;
+                                }
+                            }
+                        }),
+                        On::<Pointer<DragEnd>>::run(move |world: &mut World| {
+                            let Some(drag) = world
+                                .get_mut::<TabBarState>(bar)
+                                .and_then(|mut state| state.drag.take())
+                            else {
+                                return;
+                            };
+                            let (origin, rects) = tab_rects(world, bar);
+                            let slot = drop_slot(&rects, drag.x - origin);
+                            // Dropping next to the tab's current position doesn't move it.
+                            let to = if slot > drag.from { slot - 1 } else { slot };
+                            if to != drag.from {
+                                if let Some(on_reorder) = on_reorder {
+                                    world.run_callback(on_reorder, (drag.from, to));
+                                }
+                            }
+                        }),
+                        On::<Pointer<PointerCancel>>::run(move |world: &mut World| {
+                            if let Some(mut state) = world.get_mut::<TabBarState>(bar) {
+                                state.drag = None;
+                            }
+                        }),
+                    )
+                },
+                (self.on_select, self.on_reorder),
+            )
+            .style_dyn(
+                move |(selected, hovering), sb| {
+                    let color = match (selected, hovering) {
+                        (true, _) => colors::U3,
+                        (false, true) => colors::U2.lighter(0.02),
+                        (false, false) => colors::U2,
+                    };
+                    sb.background_color(color);
+                },
+                (self.selected, hovering),
+            )
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_width(2)
+                            .outline_offset(-2);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .children((
+                self.label.clone(),
+                Cond::new(
+                    self.closable,
+                    Element::<NodeBundle>::for_entity(close)
+                        .named("TabBar::Close")
+                        .style(style_tab_close)
+                        .children(
+                            IconButton::new(
+                                "embedded://bevy_quill_obsidian/assets/icons/close.png",
+                            )
+                            .size(Size::Xs)
+                            .minimal(true)
+                            .tab_index(-1)
+                            .on_click(cx.create_callback(
+                                move |world: &mut World| {
+                                    if let Some(on_close) = on_close {
+                                        world.run_callback(on_close, index);
+                                    }
+                                },
+                            )),
+                        ),
+                    (),
+                ),
+            ))
+    }
+}
+
+/// An entry in the overflow menu of a tab bar.
+#[derive(Clone, PartialEq)]
+struct TabMenuItem {
+    label: String,
+    index: usize,
+    selected: bool,
+    on_select: Option<Callback<usize>>,
+}
+
+impl ViewTemplate for TabMenuItem {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let index = self.index;
+        let on_select = self.on_select;
+        MenuItem::new()
+            .label(self.label.clone())
+            .checked(self.selected)
+            .on_click(cx.create_callback(move |world: &mut World| {
+                if let Some(on_select) = on_select {
+                    world.run_callback(on_select, index);
+                }
+            }))
+    }
+}
+
+/// Horizontal extents of the tabs in a tab bar, relative to the start of the tab strip, along
+/// with the position of the start of the strip.
+fn tab_rects(world: &World, bar: Entity) -> (f32, Vec<(f32, f32)>) {
+    let Some(layout) = world.get::<TabBarLayout>(bar) else {
+        return (0., Vec::new());
+    };
+    let Some(strip_x) = element_rect(world, layout.strip).map(|rect| rect.min.x) else {
+        return (0., Vec::new());
+    };
+    let rects = world
+        .get::<Children>(layout.strip)
+        .into_iter()
+        .flat_map(|children| children.iter())
+        .filter(|child| world.get::<TabBarTab>(**child).is_some())
+        .filter_map(|child| element_rect(world, *child))
+        .map(|rect| (rect.min.x - strip_x, rect.max.x - strip_x))
+        .collect();
+    (strip_x, rects)
+}
+
+fn element_rect(world: &World, entity: Entity) -> Option<Rect> {
+    let node = world.get::<Node>(entity)?;
+    let transform = world.get::<GlobalTransform>(entity)?;
+    Some(node.logical_rect(transform))
+}
+
+/// Index of the gap between tabs nearest to `x`, where 0 is before the first tab.
+fn drop_slot(rects: &[(f32, f32)], x: f32) -> usize {
+    rects
+        .iter()
+        .take_while(|(left, right)| x > (left + right) * 0.5)
+        .count()
+}
+
+/// Keeps the selected tab scrolled into view, detects overflow, and positions the drop
+/// indicator while a tab is being dragged.
+pub(crate) fn update_tab_bars(world: &mut World) {
+    let bars: Vec<(Entity, TabBarLayout)> = world
+        .query::<(Entity, &TabBarLayout)>()
+        .iter(world)
+        .map(|(entity, layout)| (entity, *layout))
+        .collect();
+    for (bar, layout) in bars {
+        let (Some(viewport), Some(strip)) = (
+            element_rect(world, layout.viewport),
+            element_rect(world, layout.strip),
+        ) else {
+            continue;
+        };
+        let (origin, rects) = tab_rects(world, bar);
+        let visible = viewport.width();
+        let Some(mut state) = world.get_mut::<TabBarState>(bar) else {
+            continue;
+        };
+
+        // Scroll so that the selected tab is visible.
+        let mut offset = state.offset;
+        if let Some((left, right)) = rects.get(layout.selected) {
+            if right - offset > visible {
+                offset = right - visible;
+            }
+            if *left < offset {
+                offset = *left;
+            }
+        }
+        offset = offset.min(strip.width() - visible).max(0.);
+        if state.offset != offset {
+            state.offset = offset;
+        }
+
+        // Position the drop indicator at the gap nearest to the pointer.
+        let indicator =
+            state
+                .drag
+                .map(|drag| match rects.get(drop_slot(&rects, drag.x - origin)) {
+                    Some((left, _)) => *left,
+                    None => rects.last().map_or(0., |(_, right)| *right),
+                });
+
+        let overflow = TabBarOverflow(strip.width() > visible + 0.5);
+        if let Some(mut current) = world.get_mut::<TabBarOverflow>(bar) {
+            current.set_if_neq(overflow);
+        }
+        if let Some(mut style) = world.get_mut::<Style>(layout.strip) {
+            let left = ui::Val::Px(-offset);
+            if style.left != left {
+                style.left = left;
+            }
+        }
+        if let Some(mut style) = world.get_mut::<Style>(layout.indicator) {
+            let (display, left) = match indicator {
+                Some(x) => (ui::Display::Flex, ui::Val::Px(x - 1.)),
+                None => (ui::Display::None, style.left),
+            };
+            if style.display != display || style.left != left {
+                style.display = display;
+                style.left = left;
+            }
+        }
+    }
+}
+
+/// A container for the content of the selected tab, displayed below a [`TabBar`].
+#[derive(Clone, PartialEq, Default)]
+pub struct TabPanel {
+    /// Additional styles to be applied to the panel.
+    pub style: StyleHandle,
+
+    /// The content of the panel.
+    pub children: ViewChild,
+}
+
+impl TabPanel {
+    /// Create a new tab panel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set additional styles to be applied to the panel.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the content of the panel.
+    pub fn children(mut self, children: impl IntoViewChild) -> Self {
+        self.children = children.into_view_child();
+        self
+    }
+}
+
+impl ViewTemplate for TabPanel {
+    type View = impl View;
+
+    fn create(&self, _cx: &mut Cx) -> Self::View {
+        Element::<NodeBundle>::new()
+            .named("TabPanel")
+            .style((style_tab_panel, self.style.clone()))
+            .insert(AccessibilityNode::from(NodeBuilder::new(Role::TabPanel)))
+            .children(self.children.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::keyboard::KeyboardInput;
+
+    use super::*;
+    use crate::{focus::KeyboardInputPlugin, hooks::BistableTransitionPlugin};
+
+    /// Callbacks received from the tab bar.
+    #[derive(Resource, Default)]
+    struct Log(Vec<String>);
+
+    /// A tab bar whose first and last tabs are closable.
+    #[derive(Clone, PartialEq)]
+    struct TestTabs;
+
+    impl ViewTemplate for TestTabs {
+        type View = impl View;
+
+        fn create(&self, cx: &mut Cx) -> Self::View {
+            let selected = cx.create_mutable::<usize>(0);
+            let on_select = cx.create_callback(move |index: In<usize>, world: &mut World| {
+                selected.set(world, *index);
+                world
+                    .resource_mut::<Log>()
+                    .0
+                    .push(format!("select {}", *index));
+            });
+            let on_close = cx.create_callback(move |index: In<usize>, world: &mut World| {
+                world
+                    .resource_mut::<Log>()
+                    .0
+                    .push(format!("close {}", *index));
+            });
+            TabBar::new()
+                .tabs(vec![
+                    TabItem::new("A").closable(true),
+                    TabItem::new("B"),
+                    TabItem::new("C").closable(true),
+                ])
+                .selected(selected.get(cx))
+                .on_select(on_select)
+                .on_close(on_close)
+        }
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            QuillPlugin,
+            KeyboardInputPlugin,
+            BistableTransitionPlugin,
+        ))
+        .init_asset::<Image>()
+        .init_asset::<Font>()
+        .init_resource::<Focus>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<Log>()
+        .add_event::<KeyboardInput>();
+        app.world_mut().spawn(TestTabs.to_root());
+        app.update();
+        app
+    }
+
+    /// The tabs of the tab bar, in order.
+    fn tabs(app: &mut App) -> Vec<Entity> {
+        let mut query = app.world_mut().query::<(Entity, &TabBarTab)>();
+        let mut tabs: Vec<(usize, Entity)> = query
+            .iter(app.world())
+            .map(|(e, tab)| (tab.index, e))
+            .collect();
+        tabs.sort();
+        tabs.into_iter().map(|(_, e)| e).collect()
+    }
+
+    /// Press a key on the focused tab, and return the callbacks it triggered.
+    fn press(app: &mut App, key_code: KeyCode) -> Vec<String> {
+        let target = app.world().resource::<Focus>().0.unwrap();
+        app.world_mut()
+            .send_event(KeyPressEvent::new(target, key_code));
+        app.update();
+        std::mem::take(&mut app.world_mut().resource_mut::<Log>().0)
+    }
+
+    fn focus(app: &App) -> Option<Entity> {
+        app.world().resource::<Focus>().0
+    }
+
+    #[test]
+    fn test_arrow_keys() {
+        let mut app = test_app();
+        let tabs = tabs(&mut app);
+        assert_eq!(tabs.len(), 3);
+        app.world_mut().resource_mut::<Focus>().0 = Some(tabs[0]);
+
+        assert_eq!(press(&mut app, KeyCode::ArrowRight), ["select 1"]);
+        assert_eq!(focus(&app), Some(tabs[1]));
+        assert_eq!(press(&mut app, KeyCode::ArrowRight), ["select 2"]);
+        assert_eq!(focus(&app), Some(tabs[2]));
+
+        // Navigation wraps around at either end.
+        assert_eq!(press(&mut app, KeyCode::ArrowRight), ["select 0"]);
+        assert_eq!(focus(&app), Some(tabs[0]));
+        assert_eq!(press(&mut app, KeyCode::ArrowLeft), ["select 2"]);
+        assert_eq!(focus(&app), Some(tabs[2]));
+
+        // Other keys are ignored.
+        assert!(press(&mut app, KeyCode::ArrowDown).is_empty());
+        assert_eq!(focus(&app), Some(tabs[2]));
+    }
+
+    #[test]
+    fn test_home_end_keys() {
+        let mut app = test_app();
+        let tabs = tabs(&mut app);
+        app.world_mut().resource_mut::<Focus>().0 = Some(tabs[1]);
+
+        assert_eq!(press(&mut app, KeyCode::End), ["select 2"]);
+        assert_eq!(focus(&app), Some(tabs[2]));
+        assert_eq!(press(&mut app, KeyCode::Home), ["select 0"]);
+        assert_eq!(focus(&app), Some(tabs[0]));
+    }
+
+    #[test]
+    fn test_delete_key() {
+        let mut app = test_app();
+        let tabs = tabs(&mut app);
+
+        // Closable tabs are closed, without moving the focus.
+        app.world_mut().resource_mut::<Focus>().0 = Some(tabs[2]);
+        assert_eq!(press(&mut app, KeyCode::Delete), ["close 2"]);
+        assert_eq!(focus(&app), Some(tabs[2]));
+
+        // Other tabs ignore the key.
+        app.world_mut().resource_mut::<Focus>().0 = Some(tabs[1]);
+        assert!(press(&mut app, KeyCode::Delete).is_empty());
+    }
+
+    #[test]
+    fn test_drop_slot() {
+        let rects = [(0., 40.), (41., 101.), (102., 132.)];
+        assert_eq!(drop_slot(&rects, -5.), 0);
+        assert_eq!(drop_slot(&rects, 19.), 0);
+        assert_eq!(drop_slot(&rects, 21.), 1);
+        assert_eq!(drop_slot(&rects, 80.), 2);
+        assert_eq!(drop_slot(&rects, 120.), 3);
+        assert_eq!(drop_slot(&[], 10.), 0);
+    }
+}
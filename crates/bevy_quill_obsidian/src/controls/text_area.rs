@@ -0,0 +1,863 @@
+use bevy::{a11y::Focus, prelude::*, text::BreakLineOn, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    focus::{CaptureTab, KeyCharEvent, KeyPressEvent, TabIndex},
+    hooks::UseIsFocus,
+    scrolling::ScrollArea,
+    text_editing::{
+        char_offsets, line_at, line_end, line_height, offset_on_line, position_on_line, wrap_lines,
+        EditHistory, EditKind, TextClipboard, TextEditState, VisualLine, DOUBLE_CLICK_TIME,
+    },
+    typography,
+};
+
+use super::{Disabled, IsDisabled, ScrollView};
+
+fn style_text_area(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .height(96)
+        .min_width(32)
+        .padding(2)
+        .background_color(colors::U1)
+        .border_radius(5)
+        .line_break(BreakLineOn::NoWrap)
+        .color(colors::FOREGROUND)
+        .cursor(CursorIcon::Text);
+}
+
+fn style_scroll_view(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).min_height(0);
+}
+
+fn style_scroll_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::FlexStart)
+        .border(0)
+        .padding((2, 0));
+}
+
+fn style_gutter(ss: &mut StyleBuilder) {
+    ss.flex_shrink(0.)
+        .min_width(16)
+        .padding_right(6)
+        .text_justify(JustifyText::Right)
+        .color(colors::DIM.with_alpha(0.5));
+}
+
+fn style_content(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative).flex_grow(1.);
+}
+
+fn style_text(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute).left(0).top(0);
+}
+
+fn style_placeholder(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .top(0)
+        .color(colors::DIM.with_alpha(0.5));
+}
+
+fn style_caret(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .width(1)
+        .background_color(colors::FOREGROUND);
+}
+
+fn style_selection(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .background_color(colors::TEXT_SELECT);
+}
+
+/// What the Tab key does in a [`TextArea`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TabBehavior {
+    /// Tab moves the focus to the next widget, as in other controls.
+    #[default]
+    MoveFocus,
+    /// Tab inserts the given number of spaces. Shift+Tab still moves the focus.
+    InsertSpaces(usize),
+}
+
+/// Editing state and layout of a text area, stored on the root entity.
+#[derive(Component, Default)]
+pub(crate) struct TextAreaState {
+    edit: TextEditState,
+    history: EditHistory,
+    /// The text as of the last layout, which `lines` refers to.
+    laid_out: String,
+    /// The wrap width and font size of the last layout.
+    layout_params: (f32, f32),
+    lines: Vec<VisualLine>,
+    line_height: f32,
+    /// Horizontal position which the caret tries to keep when moving between lines.
+    goal_x: Option<f32>,
+    /// True if the caret has moved and should be scrolled into view.
+    reveal: bool,
+    /// Whether the text area had focus as of the last update; used to commit on blur.
+    focused: bool,
+    /// Time and position of the last click, used to detect double-clicks.
+    last_click: Option<(f32, usize)>,
+}
+
+impl TextAreaState {
+    /// True if the layout is up to date with the text, so that positions can be mapped to lines.
+    fn has_layout(&self) -> bool {
+        !self.lines.is_empty() && self.laid_out == self.edit.text
+    }
+
+    fn move_caret(&mut self, position: usize, extend: bool) {
+        self.edit.move_to(position, extend);
+        self.history.break_merge();
+        self.reveal = true;
+    }
+
+    /// Move the caret up or down by the given number of lines, keeping its horizontal position.
+    fn move_lines(&mut self, delta: isize, extend: bool) {
+        if !self.has_layout() {
+            return;
+        }
+        let text = &self.edit.text;
+        let line = line_at(&self.lines, self.edit.caret);
+        let x = *self
+            .goal_x
+            .get_or_insert_with(|| offset_on_line(&self.lines, line, self.edit.caret, text));
+        let target = line as isize + delta;
+        let position = if target < 0 {
+            0
+        } else if target as usize >= self.lines.len() {
+            text.len()
+        } else {
+            position_on_line(&self.lines, target as usize, x, text)
+        };
+        self.move_caret(position, extend);
+    }
+
+    /// Move the caret to the start or end of its line.
+    fn move_line_edge(&mut self, end: bool, extend: bool) {
+        if !self.has_layout() {
+            return;
+        }
+        let line = line_at(&self.lines, self.edit.caret);
+        let position = if end {
+            line_end(&self.lines, line, &self.edit.text)
+        } else {
+            self.lines[line].range.start
+        };
+        self.move_caret(position, extend);
+    }
+
+    /// Number of lines which fit in the visible area, for page up and page down.
+    fn page_lines(&self, visible_height: f32) -> isize {
+        if self.line_height > 0. {
+            ((visible_height / self.line_height).floor() as isize - 1).max(1)
+        } else {
+            1
+        }
+    }
+}
+
+/// Properties of a text area which are needed by the event handlers and systems.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct TextAreaProps {
+    tab_behavior: TabBehavior,
+    on_change: Option<Callback<String>>,
+    on_commit: Option<Callback<String>>,
+}
+
+/// Entities which make up a text area.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct TextAreaParts {
+    scroll: Entity,
+    gutter: Entity,
+    content: Entity,
+    text: Entity,
+    placeholder: Entity,
+    caret: Entity,
+    /// The selection is drawn as up to three rectangles: the partial first line, the full
+    /// lines in between, and the partial last line.
+    selection: [Entity; 3],
+}
+
+/// A multi-line text editor, with line wrapping and vertical scrolling.
+///
+/// Like [`TextInput`](super::TextInput), the text area keeps its own copy of the text while
+/// editing; `on_change` is called after every edit, and `on_commit` when the user presses
+/// Ctrl+Enter or the text area loses focus. Enter inserts a line break. Edits can be undone
+/// with Ctrl+Z and redone with Ctrl+Y or Ctrl+Shift+Z.
+#[derive(Clone, PartialEq, Default)]
+pub struct TextArea {
+    /// The text to edit.
+    pub value: String,
+
+    /// Text which is shown, dimmed, when the text area is empty.
+    pub placeholder: String,
+
+    /// Whether to show line numbers in a gutter to the left of the text.
+    pub line_numbers: bool,
+
+    /// What the Tab key does.
+    pub tab_behavior: TabBehavior,
+
+    /// Whether the text area is disabled.
+    pub disabled: bool,
+
+    /// Style handle for the root element.
+    pub style: StyleHandle,
+
+    /// The tab index of the text area (default 0).
+    pub tab_index: i32,
+
+    /// Callback called when the text is edited.
+    pub on_change: Option<Callback<String>>,
+
+    /// Callback called when the user presses Ctrl+Enter, or the text area loses focus.
+    pub on_commit: Option<Callback<String>>,
+}
+
+impl TextArea {
+    /// Create a new text area.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text to edit.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    /// Set the text which is shown when the text area is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Set whether to show line numbers.
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Set what the Tab key does.
+    pub fn tab_behavior(mut self, tab_behavior: TabBehavior) -> Self {
+        self.tab_behavior = tab_behavior;
+        self
+    }
+
+    /// Set whether the text area is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set the style of the text area.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the text area.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set the callback called when the text is edited.
+    pub fn on_change(mut self, on_change: Callback<String>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+
+    /// Set the callback called when the user presses Ctrl+Enter, or the text area loses focus.
+    pub fn on_commit(mut self, on_commit: Callback<String>) -> Self {
+        self.on_commit = Some(on_commit);
+        self
+    }
+}
+
+impl ViewTemplate for TextArea {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let parts = TextAreaParts {
+            scroll: cx.create_entity(),
+            gutter: cx.create_entity(),
+            content: cx.create_entity(),
+            text: cx.create_entity(),
+            placeholder: cx.create_entity(),
+            caret: cx.create_entity(),
+            selection: [cx.create_entity(), cx.create_entity(), cx.create_entity()],
+        };
+        let focused = cx.is_focused(id);
+
+        // Ensure the editing state exists before the event handlers need it.
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<TextAreaState>() {
+            entt.insert(TextAreaState::default());
+        }
+        cx.create_effect(
+            move |world, value: String| {
+                if let Some(mut state) = world.get_mut::<TextAreaState>(id) {
+                    // Replacing the text from outside discards the undo history; echoes of
+                    // our own edits leave it alone.
+                    if state.edit.text != value {
+                        state.edit.set_text(&value);
+                        state.history.clear();
+                    }
+                }
+            },
+            self.value.clone(),
+        );
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TextArea")
+            .style((
+                typography::text_default,
+                style_text_area,
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .insert_if(self.disabled, || Disabled)
+            .insert_if(self.tab_behavior != TabBehavior::MoveFocus, || CaptureTab)
+            .insert_dyn(
+                move |(tab_index, props)| (TabIndex(tab_index), props, parts),
+                (
+                    self.tab_index,
+                    TextAreaProps {
+                        tab_behavior: self.tab_behavior,
+                        on_change: self.on_change,
+                        on_commit: self.on_commit,
+                    },
+                ),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            let position = world
+                                .resource::<ListenerInput<Pointer<Down>>>()
+                                .pointer_location
+                                .position;
+                            world.resource_mut::<Focus>().0 = Some(id);
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let extend = world
+                                .resource::<ButtonInput<KeyCode>>()
+                                .any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+                            let now = world.resource::<Time>().elapsed_seconds();
+                            if let Some(index) = hit_test(world, id, position) {
+                                let mut state = world.get_mut::<TextAreaState>(id).unwrap();
+                                let double_click = state.last_click.is_some_and(|(time, prev)| {
+                                    prev == index && now - time < DOUBLE_CLICK_TIME
+                                });
+                                state.goal_x = None;
+                                if double_click && !extend {
+                                    state.edit.select_word(index);
+                                    state.history.break_merge();
+                                    state.last_click = None;
+                                } else {
+                                    state.move_caret(index, extend);
+                                    state.last_click = Some((now, index));
+                                }
+                            }
+                        }),
+                        On::<Pointer<Drag>>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let position = world
+                                .resource::<ListenerInput<Pointer<Drag>>>()
+                                .pointer_location
+                                .position;
+                            if let Some(index) = hit_test(world, id, position) {
+                                let mut state = world.get_mut::<TextAreaState>(id).unwrap();
+                                state.goal_x = None;
+                                state.move_caret(index, true);
+                            }
+                        }),
+                        On::<KeyCharEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                .unwrap();
+                            if event.key.is_control() {
+                                return;
+                            }
+                            event.stop_propagation();
+                            let text = event.key.to_string();
+                            edit_text_area(world, id, EditKind::Insert, |edit, _| {
+                                edit.insert(&text, None)
+                            });
+                        }),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let event = world.resource::<ListenerInput<KeyPressEvent>>();
+                            let (key_code, shift, ctrl) = (event.key_code, event.shift, event.ctrl);
+                            if handle_key(world, id, key_code, shift, ctrl) {
+                                world
+                                    .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                    .unwrap()
+                                    .stop_propagation();
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children(
+                ScrollView::new()
+                    .entity(Some(parts.scroll))
+                    .scroll_enable_y(true)
+                    .style(style_scroll_view)
+                    .content_style(style_scroll_content)
+                    .children((
+                        Cond::new(
+                            self.line_numbers,
+                            Element::<TextBundle>::for_entity(parts.gutter)
+                                .named("TextArea::Gutter")
+                                .style(style_gutter)
+                                .insert_dyn(|_| UseInheritedTextStyles, ()),
+                            (),
+                        ),
+                        Element::<NodeBundle>::for_entity(parts.content)
+                            .named("TextArea::Content")
+                            .style(style_content)
+                            .children((
+                                Element::<NodeBundle>::for_entity(parts.selection[0])
+                                    .style(style_selection),
+                                Element::<NodeBundle>::for_entity(parts.selection[1])
+                                    .style(style_selection),
+                                Element::<NodeBundle>::for_entity(parts.selection[2])
+                                    .style(style_selection),
+                                Element::<TextBundle>::for_entity(parts.text)
+                                    .style(style_text)
+                                    .insert_dyn(|_| UseInheritedTextStyles, ()),
+                                Element::<NodeBundle>::for_entity(parts.placeholder)
+                                    .style(style_placeholder)
+                                    .children(self.placeholder.clone()),
+                                Element::<NodeBundle>::for_entity(parts.caret).style(style_caret),
+                            )),
+                    )),
+            )
+    }
+}
+
+/// Handle a key press; returns true if the key was used.
+fn handle_key(world: &mut World, id: Entity, key_code: KeyCode, shift: bool, ctrl: bool) -> bool {
+    let Some(props) = world.get::<TextAreaProps>(id).cloned() else {
+        return false;
+    };
+    let visible_height = world
+        .get::<TextAreaParts>(id)
+        .and_then(|parts| world.get::<ScrollArea>(parts.scroll))
+        .map_or(0., |scroll| scroll.visible_size.y);
+    let mut state = world.get_mut::<TextAreaState>(id).unwrap();
+    if !matches!(
+        key_code,
+        KeyCode::ArrowUp | KeyCode::ArrowDown | KeyCode::PageUp | KeyCode::PageDown
+    ) {
+        state.goal_x = None;
+    }
+    match key_code {
+        KeyCode::ArrowLeft => {
+            state.edit.move_left(shift, ctrl);
+            state.history.break_merge();
+            state.reveal = true;
+        }
+        KeyCode::ArrowRight => {
+            state.edit.move_right(shift, ctrl);
+            state.history.break_merge();
+            state.reveal = true;
+        }
+        KeyCode::ArrowUp => state.move_lines(-1, shift),
+        KeyCode::ArrowDown => state.move_lines(1, shift),
+        KeyCode::PageUp => {
+            let page = state.page_lines(visible_height);
+            state.move_lines(-page, shift);
+        }
+        KeyCode::PageDown => {
+            let page = state.page_lines(visible_height);
+            state.move_lines(page, shift);
+        }
+        KeyCode::Home if ctrl => state.move_caret(0, shift),
+        KeyCode::End if ctrl => {
+            let end = state.edit.text.len();
+            state.move_caret(end, shift);
+        }
+        KeyCode::Home => state.move_line_edge(false, shift),
+        KeyCode::End => state.move_line_edge(true, shift),
+        KeyCode::Enter | KeyCode::NumpadEnter if ctrl => commit_text_area(world, id),
+        KeyCode::Enter | KeyCode::NumpadEnter => {
+            edit_text_area(world, id, EditKind::Other, |edit, _| {
+                edit.insert("\n", None)
+            });
+        }
+        KeyCode::Tab if !shift => match props.tab_behavior {
+            TabBehavior::MoveFocus => return false,
+            TabBehavior::InsertSpaces(count) => {
+                let spaces = " ".repeat(count);
+                edit_text_area(world, id, EditKind::Insert, |edit, _| {
+                    edit.insert(&spaces, None)
+                });
+            }
+        },
+        KeyCode::Space if !ctrl => {
+            edit_text_area(world, id, EditKind::Insert, |edit, _| {
+                edit.insert(" ", None)
+            });
+        }
+        KeyCode::Backspace => {
+            edit_text_area(world, id, EditKind::Delete, |edit, _| edit.backspace(ctrl));
+        }
+        KeyCode::Delete => {
+            edit_text_area(world, id, EditKind::Delete, |edit, _| edit.delete(ctrl));
+        }
+        KeyCode::KeyA if ctrl => {
+            state.edit.select_all();
+            state.history.break_merge();
+        }
+        KeyCode::KeyC if ctrl => {
+            if state.edit.has_selection() {
+                let text = state.edit.selected_text().to_string();
+                world.resource_mut::<TextClipboard>().0 = text;
+            }
+        }
+        KeyCode::KeyX if ctrl => {
+            edit_text_area(world, id, EditKind::Other, |edit, clipboard| {
+                match edit.cut() {
+                    Some(text) => {
+                        clipboard.0 = text;
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+        KeyCode::KeyV if ctrl => {
+            edit_text_area(world, id, EditKind::Other, |edit, clipboard| {
+                edit.insert(&clipboard.0, None)
+            });
+        }
+        KeyCode::KeyZ if ctrl && shift => redo_text_area(world, id, true),
+        KeyCode::KeyZ if ctrl => redo_text_area(world, id, false),
+        KeyCode::KeyY if ctrl => redo_text_area(world, id, true),
+        _ => return false,
+    }
+    true
+}
+
+/// Apply an edit to the text of a text area, recording it in the undo history and calling
+/// `on_change` if the edit function returns true.
+fn edit_text_area(
+    world: &mut World,
+    id: Entity,
+    kind: EditKind,
+    edit_fn: impl FnOnce(&mut TextEditState, &mut TextClipboard) -> bool,
+) {
+    let changed = world.resource_scope(|world, mut clipboard: Mut<TextClipboard>| {
+        let mut state = world.get_mut::<TextAreaState>(id).unwrap();
+        let before = state.edit.clone();
+        let changed = edit_fn(&mut state.edit, &mut clipboard);
+        if changed {
+            state.history.record(&before, kind);
+            state.reveal = true;
+        }
+        changed
+    });
+    if changed {
+        notify_change(world, id);
+    }
+}
+
+/// Undo the last edit, or redo the last undone edit.
+fn redo_text_area(world: &mut World, id: Entity, redo: bool) {
+    let mut state = world.get_mut::<TextAreaState>(id).unwrap();
+    let state = &mut *state;
+    let changed = if redo {
+        state.history.redo(&mut state.edit)
+    } else {
+        state.history.undo(&mut state.edit)
+    };
+    if changed {
+        state.reveal = true;
+        notify_change(world, id);
+    }
+}
+
+fn notify_change(world: &mut World, id: Entity) {
+    if let Some(on_change) = world.get::<TextAreaProps>(id).and_then(|p| p.on_change) {
+        let text = world.get::<TextAreaState>(id).unwrap().edit.text.clone();
+        world.run_callback(on_change, text);
+    }
+}
+
+fn commit_text_area(world: &mut World, id: Entity) {
+    let Some(on_commit) = world.get::<TextAreaProps>(id).and_then(|p| p.on_commit) else {
+        return;
+    };
+    let text = world.get::<TextAreaState>(id).unwrap().edit.text.clone();
+    world.run_callback(on_commit, text);
+}
+
+/// Find the text position nearest to the given pointer position, or `None` if the pointer is
+/// outside of the scrolling region (for example, over the scrollbar).
+fn hit_test(world: &World, id: Entity, position: Vec2) -> Option<usize> {
+    let state = world.get::<TextAreaState>(id)?;
+    let parts = world.get::<TextAreaParts>(id)?;
+    if !state.has_layout() || state.line_height <= 0. {
+        return None;
+    }
+    let rect = |entity: Entity| {
+        let node = world.get::<Node>(entity)?;
+        let transform = world.get::<GlobalTransform>(entity)?;
+        Some(node.logical_rect(transform))
+    };
+    if !rect(parts.scroll)?.contains(position) {
+        return None;
+    }
+    let local = position - rect(parts.content)?.min;
+    let line = ((local.y / state.line_height).max(0.) as usize).min(state.lines.len() - 1);
+    Some(position_on_line(
+        &state.lines,
+        line,
+        local.x,
+        &state.edit.text,
+    ))
+}
+
+/// A rectangle in the content area, in the form used to position the caret and selection.
+#[derive(Clone, Copy)]
+struct Placement {
+    left: f32,
+    top: f32,
+    /// Width, or `None` to extend to the right edge.
+    width: Option<f32>,
+    height: f32,
+}
+
+fn place(styles: &mut Query<&mut Style>, entity: Entity, placement: Option<Placement>) {
+    if let Ok(mut style) = styles.get_mut(entity) {
+        let mut next = style.clone();
+        match placement {
+            Some(p) => {
+                next.display = ui::Display::Flex;
+                next.left = ui::Val::Px(p.left);
+                next.top = ui::Val::Px(p.top);
+                next.height = ui::Val::Px(p.height);
+                match p.width {
+                    Some(width) => {
+                        next.width = ui::Val::Px(width);
+                        next.right = ui::Val::Auto;
+                    }
+                    None => {
+                        next.width = ui::Val::Auto;
+                        next.right = ui::Val::Px(0.);
+                    }
+                }
+            }
+            None => next.display = ui::Display::None,
+        }
+        style.set_if_neq(next);
+    }
+}
+
+/// Wraps the text of text areas to the width of the content area, and updates the displayed
+/// text, line numbers, caret, selection and scroll position.
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_text_area_layout(
+    focus: Res<Focus>,
+    fonts: Res<Assets<Font>>,
+    mut areas: Query<(Entity, &mut TextAreaState, &TextAreaParts, Has<Disabled>)>,
+    nodes: Query<&Node>,
+    mut texts: Query<&mut Text>,
+    mut styles: Query<&mut Style>,
+    mut scroll_areas: Query<&mut ScrollArea>,
+) {
+    for (entity, mut state, parts, disabled) in areas.iter_mut() {
+        let Ok(mut text) = texts.get_mut(parts.text) else {
+            continue;
+        };
+        if text.sections.is_empty() {
+            text.sections.push(TextSection::default());
+        }
+        let section_style = text.sections[0].style.clone();
+        let Some(font) = fonts.get(&section_style.font) else {
+            continue;
+        };
+
+        // Re-wrap the text if it, the width or the font size has changed.
+        let width = nodes
+            .get(parts.content)
+            .map_or(0., |node| node.size().x)
+            .max(0.);
+        let wrap_width = if width > 0. { width } else { f32::INFINITY };
+        let params = (width, section_style.font_size);
+        if state.laid_out != state.edit.text || state.layout_params != params {
+            let lines = wrap_lines(&state.edit.text, wrap_width, |line| {
+                char_offsets(&font.font, section_style.font_size, line)
+            });
+            let display = lines
+                .iter()
+                .map(|line| &state.edit.text[line.range.clone()])
+                .collect::<Vec<_>>()
+                .join("\n");
+            text.sections[0].value = display;
+            if let Ok(mut gutter) = texts.get_mut(parts.gutter) {
+                let numbers = lines
+                    .iter()
+                    .map(|line| match line.continued {
+                        true => String::new(),
+                        false => (line.line + 1).to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                match gutter.sections.first_mut() {
+                    Some(section) => section.value = numbers,
+                    None => gutter.sections.push(TextSection::from(numbers)),
+                }
+            }
+            let state = &mut *state;
+            state.line_height = line_height(&font.font, section_style.font_size);
+            state.laid_out.clone_from(&state.edit.text);
+            state.layout_params = params;
+            state.lines = lines;
+        }
+
+        let line_height = state.line_height;
+        let text_height = state.lines.len() as f32 * line_height;
+        if let Ok(mut style) = styles.get_mut(parts.content) {
+            let height = ui::Val::Px(text_height);
+            if style.height != height {
+                style.height = height;
+            }
+        }
+        if let Ok(mut style) = styles.get_mut(parts.placeholder) {
+            let display = match state.edit.text.is_empty() {
+                true => ui::Display::Flex,
+                false => ui::Display::None,
+            };
+            if style.display != display {
+                style.display = display;
+            }
+        }
+
+        // Caret and selection.
+        let focused = focus.0 == Some(entity) && !disabled;
+        let text = &state.edit.text;
+        let caret_line = line_at(&state.lines, state.edit.caret);
+        let caret_top = caret_line as f32 * line_height;
+        place(
+            &mut styles,
+            parts.caret,
+            focused.then(|| Placement {
+                left: offset_on_line(&state.lines, caret_line, state.edit.caret, text),
+                top: caret_top,
+                width: Some(1.),
+                height: line_height,
+            }),
+        );
+        let selection = state.edit.selection();
+        let mut rects = [None; 3];
+        if focused && !selection.is_empty() {
+            let first = line_at(&state.lines, selection.start);
+            let last = line_at(&state.lines, selection.end);
+            let start_x = offset_on_line(&state.lines, first, selection.start, text);
+            let end_x = offset_on_line(&state.lines, last, selection.end, text);
+            if first == last {
+                rects[0] = Some(Placement {
+                    left: start_x,
+                    top: first as f32 * line_height,
+                    width: Some(end_x - start_x),
+                    height: line_height,
+                });
+            } else {
+                rects[0] = Some(Placement {
+                    left: start_x,
+                    top: first as f32 * line_height,
+                    width: None,
+                    height: line_height,
+                });
+                if last > first + 1 {
+                    rects[1] = Some(Placement {
+                        left: 0.,
+                        top: (first + 1) as f32 * line_height,
+                        width: None,
+                        height: (last - first - 1) as f32 * line_height,
+                    });
+                }
+                rects[2] = Some(Placement {
+                    left: 0.,
+                    top: last as f32 * line_height,
+                    width: Some(end_x),
+                    height: line_height,
+                });
+            }
+        }
+        for (entity, rect) in parts.selection.iter().zip(rects) {
+            place(&mut styles, *entity, rect);
+        }
+
+        // Scroll the caret into view. The content may not have been laid out at its new
+        // height yet, so keep trying until it has.
+        if state.reveal {
+            if let Ok(mut scroll) = scroll_areas.get_mut(parts.scroll) {
+                let caret_bottom = caret_top + line_height;
+                if caret_top < scroll.scroll_top {
+                    scroll.scroll_top = caret_top;
+                } else if caret_bottom > scroll.scroll_top + scroll.visible_size.y {
+                    scroll.scroll_top = caret_bottom - scroll.visible_size.y;
+                }
+                if scroll.content_size.y >= text_height {
+                    state.reveal = false;
+                }
+            }
+        }
+    }
+}
+
+/// Calls `on_commit` for text areas which have lost focus.
+pub(crate) fn commit_text_areas_on_blur(
+    world: &mut World,
+    query: &mut QueryState<(Entity, &mut TextAreaState)>,
+) {
+    let focus = world.resource::<Focus>().0;
+    let mut blurred: Vec<Entity> = Vec::new();
+    for (entity, mut state) in query.iter_mut(world) {
+        let focused = focus == Some(entity);
+        if state.focused != focused {
+            state.focused = focused;
+            if !focused {
+                blurred.push(entity);
+            }
+        }
+    }
+    for entity in blurred {
+        commit_text_area(world, entity);
+    }
+}
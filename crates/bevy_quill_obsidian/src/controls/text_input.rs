@@ -0,0 +1,582 @@
+use bevy::{a11y::Focus, prelude::*, text::BreakLineOn, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    cursor::StyleBuilderCursor,
+    focus::{AutoFocus, KeyCharEvent, KeyPressEvent, TabIndex},
+    hooks::UseIsFocus,
+    text_editing::{char_offsets, nearest_offset, TextClipboard, TextEditState, DOUBLE_CLICK_TIME},
+    typography,
+};
+
+use super::{Disabled, IsDisabled};
+
+/// Horizontal padding between the frame and the text.
+const PADDING: f32 = 4.;
+
+/// Character used to mask the text of password fields.
+const PASSWORD_CHAR: char = '•';
+
+fn style_text_input(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .height(24)
+        .min_width(32)
+        .padding((PADDING, 0))
+        .background_color(colors::U1)
+        .border_radius(5)
+        .overflow(ui::OverflowAxis::Clip)
+        .line_break(BreakLineOn::NoWrap)
+        .color(colors::FOREGROUND)
+        .cursor(CursorIcon::Text);
+}
+
+fn style_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .flex_shrink(0.)
+        .height(ui::Val::Percent(100.));
+}
+
+fn style_text(ss: &mut StyleBuilder) {
+    ss.flex_shrink(0.);
+}
+
+fn style_placeholder(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .left(0)
+        .color(colors::DIM.with_alpha(0.5));
+}
+
+fn style_caret(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(3)
+        .bottom(3)
+        .width(1)
+        .background_color(colors::FOREGROUND);
+}
+
+fn style_selection(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .top(2)
+        .bottom(2)
+        .background_color(colors::TEXT_SELECT);
+}
+
+/// Editing state of a text input, stored on the root entity.
+#[derive(Component, Default)]
+pub(crate) struct TextInputState {
+    edit: TextEditState,
+    /// Horizontal scroll offset of the content, so that the caret stays visible.
+    scroll: f32,
+    /// Horizontal offset of each character boundary, as of the last layout.
+    offsets: Vec<f32>,
+    /// Whether the input had focus as of the last update; used to commit on blur.
+    focused: bool,
+    /// Time and position of the last click, used to detect double-clicks.
+    last_click: Option<(f32, usize)>,
+}
+
+/// Properties of a text input which are needed by the event handlers and systems.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct TextInputProps {
+    max_length: Option<usize>,
+    password: bool,
+    on_change: Option<Callback<String>>,
+    on_commit: Option<Callback<String>>,
+}
+
+/// Entities which make up a text input.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct TextInputParts {
+    content: Entity,
+    text: Entity,
+    placeholder: Entity,
+    caret: Entity,
+    selection: Entity,
+}
+
+/// A single-line text input field.
+///
+/// The field keeps its own copy of the text while editing; `on_change` is called after every
+/// edit, and `on_commit` when the user presses Enter or the field loses focus. When `value`
+/// changes, the text in the field is replaced.
+#[derive(Clone, PartialEq, Default)]
+pub struct TextInput {
+    /// The text to edit.
+    pub value: String,
+
+    /// Text which is shown, dimmed, when the field is empty.
+    pub placeholder: String,
+
+    /// Maximum number of characters which can be entered.
+    pub max_length: Option<usize>,
+
+    /// If true, the text is masked.
+    pub password: bool,
+
+    /// Whether the text input is disabled.
+    pub disabled: bool,
+
+    /// Style handle for the root element.
+    pub style: StyleHandle,
+
+    /// The tab index of the text input (default 0).
+    pub tab_index: i32,
+
+    /// If true, the text input takes the focus and selects all of its text when it is created.
+    pub auto_focus: bool,
+
+    /// Callback called when the text is edited.
+    pub on_change: Option<Callback<String>>,
+
+    /// Callback called when the user presses Enter, or the text input loses focus.
+    pub on_commit: Option<Callback<String>>,
+}
+
+impl TextInput {
+    /// Create a new text input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text to edit.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    /// Set the text which is shown when the field is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Set the maximum number of characters which can be entered.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Set whether the text is masked.
+    pub fn password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Set whether the text input is disabled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set the style of the text input.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the text input.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set whether the text input takes the focus and selects all of its text when created.
+    pub fn auto_focus(mut self, auto_focus: bool) -> Self {
+        self.auto_focus = auto_focus;
+        self
+    }
+
+    /// Set the callback called when the text is edited.
+    pub fn on_change(mut self, on_change: Callback<String>) -> Self {
+        self.on_change = Some(on_change);
+        self
+    }
+
+    /// Set the callback called when the user presses Enter, or the text input loses focus.
+    pub fn on_commit(mut self, on_commit: Callback<String>) -> Self {
+        self.on_commit = Some(on_commit);
+        self
+    }
+}
+
+impl ViewTemplate for TextInput {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let parts = TextInputParts {
+            content: cx.create_entity(),
+            text: cx.create_entity(),
+            placeholder: cx.create_entity(),
+            caret: cx.create_entity(),
+            selection: cx.create_entity(),
+        };
+        let focused = cx.is_focused(id);
+
+        // Ensure the editing state exists before the event handlers need it.
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<TextInputState>() {
+            let mut state = TextInputState::default();
+            state.edit.set_text(&self.value);
+            if self.auto_focus {
+                state.edit.select_all();
+            }
+            entt.insert(state);
+        }
+        cx.create_effect(
+            move |world, value: String| {
+                if let Some(mut state) = world.get_mut::<TextInputState>(id) {
+                    state.edit.set_text(&value);
+                }
+            },
+            self.value.clone(),
+        );
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TextInput")
+            .style((
+                typography::text_default,
+                style_text_input,
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .insert_if(self.disabled, || Disabled)
+            .insert_if(self.auto_focus, || AutoFocus)
+            .insert_dyn(
+                move |(tab_index, props)| (TabIndex(tab_index), props, parts),
+                (
+                    self.tab_index,
+                    TextInputProps {
+                        max_length: self.max_length,
+                        password: self.password,
+                        on_change: self.on_change,
+                        on_commit: self.on_commit,
+                    },
+                ),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            let position = world
+                                .resource::<ListenerInput<Pointer<Down>>>()
+                                .pointer_location
+                                .position;
+                            world.resource_mut::<Focus>().0 = Some(id);
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let extend = world
+                                .resource::<ButtonInput<KeyCode>>()
+                                .any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+                            let now = world.resource::<Time>().elapsed_seconds();
+                            let password = world
+                                .get::<TextInputProps>(id)
+                                .is_some_and(|props| props.password);
+                            if let Some(index) = hit_test(world, id, position) {
+                                let mut state = world.get_mut::<TextInputState>(id).unwrap();
+                                // Double-click selects a word, unless the text is masked.
+                                let double_click = state.last_click.is_some_and(|(time, prev)| {
+                                    prev == index && now - time < DOUBLE_CLICK_TIME
+                                });
+                                if double_click && !extend && !password {
+                                    state.edit.select_word(index);
+                                    state.last_click = None;
+                                } else {
+                                    state.edit.move_to(index, extend);
+                                    state.last_click = Some((now, index));
+                                }
+                            }
+                        }),
+                        On::<Pointer<Drag>>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let position = world
+                                .resource::<ListenerInput<Pointer<Drag>>>()
+                                .pointer_location
+                                .position;
+                            if let Some(index) = hit_test(world, id, position) {
+                                let mut state = world.get_mut::<TextInputState>(id).unwrap();
+                                state.edit.move_to(index, true);
+                            }
+                        }),
+                        On::<KeyCharEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyCharEvent>>()
+                                .unwrap();
+                            if event.key.is_control() {
+                                return;
+                            }
+                            event.stop_propagation();
+                            let text = event.key.to_string();
+                            edit_text_input(world, id, |edit, props, _| {
+                                edit.insert(&text, props.max_length)
+                            });
+                        }),
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            if world.is_disabled(id) {
+                                return;
+                            }
+                            let mut event = world
+                                .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                .unwrap();
+                            let (key_code, shift, ctrl) = (event.key_code, event.shift, event.ctrl);
+                            if matches!(key_code, KeyCode::Enter | KeyCode::NumpadEnter) {
+                                event.stop_propagation();
+                                commit_text_input(world, id);
+                                return;
+                            }
+                            let mut handled = true;
+                            edit_text_input(world, id, |edit, props, clipboard| {
+                                match key_code {
+                                    KeyCode::ArrowLeft => edit.move_left(shift, ctrl),
+                                    KeyCode::ArrowRight => edit.move_right(shift, ctrl),
+                                    KeyCode::Home | KeyCode::ArrowUp => edit.move_to(0, shift),
+                                    KeyCode::End | KeyCode::ArrowDown => {
+                                        edit.move_to(edit.text.len(), shift)
+                                    }
+                                    KeyCode::Backspace => return edit.backspace(ctrl),
+                                    KeyCode::Delete => return edit.delete(ctrl),
+                                    KeyCode::Space if !ctrl => {
+                                        return edit.insert(" ", props.max_length)
+                                    }
+                                    KeyCode::KeyA if ctrl => edit.select_all(),
+                                    KeyCode::KeyC if ctrl => {
+                                        if edit.has_selection() && !props.password {
+                                            clipboard.0 = edit.selected_text().to_string();
+                                        }
+                                    }
+                                    KeyCode::KeyX if ctrl => {
+                                        if props.password {
+                                            return false;
+                                        }
+                                        if let Some(text) = edit.cut() {
+                                            clipboard.0 = text;
+                                            return true;
+                                        }
+                                    }
+                                    KeyCode::KeyV if ctrl => {
+                                        // Only the first line is pasted into a single-line input.
+                                        let text = clipboard.0.lines().next().unwrap_or_default();
+                                        return edit.insert(text, props.max_length);
+                                    }
+                                    _ => handled = false,
+                                }
+                                false
+                            });
+                            if handled {
+                                world
+                                    .get_resource_mut::<ListenerInput<KeyPressEvent>>()
+                                    .unwrap()
+                                    .stop_propagation();
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children(
+                Element::<NodeBundle>::for_entity(parts.content)
+                    .named("TextInput::Content")
+                    .style(style_content)
+                    .children((
+                        Element::<NodeBundle>::for_entity(parts.selection).style(style_selection),
+                        Element::<TextBundle>::for_entity(parts.text)
+                            .style(style_text)
+                            .insert_dyn(|_| UseInheritedTextStyles, ()),
+                        Element::<NodeBundle>::for_entity(parts.placeholder)
+                            .style(style_placeholder)
+                            .children(self.placeholder.clone()),
+                        Element::<NodeBundle>::for_entity(parts.caret).style(style_caret),
+                    )),
+            )
+    }
+}
+
+/// Apply an edit to the text of a text input, calling `on_change` if the edit function returns
+/// true.
+fn edit_text_input(
+    world: &mut World,
+    id: Entity,
+    edit_fn: impl FnOnce(&mut TextEditState, &TextInputProps, &mut TextClipboard) -> bool,
+) {
+    let Some(props) = world.get::<TextInputProps>(id).cloned() else {
+        return;
+    };
+    let changed = world.resource_scope(|world, mut clipboard: Mut<TextClipboard>| {
+        let mut state = world.get_mut::<TextInputState>(id).unwrap();
+        edit_fn(&mut state.edit, &props, &mut clipboard)
+    });
+    if changed {
+        if let Some(on_change) = props.on_change {
+            let text = world.get::<TextInputState>(id).unwrap().edit.text.clone();
+            world.run_callback(on_change, text);
+        }
+    }
+}
+
+fn commit_text_input(world: &mut World, id: Entity) {
+    let Some(on_commit) = world.get::<TextInputProps>(id).and_then(|p| p.on_commit) else {
+        return;
+    };
+    let text = world.get::<TextInputState>(id).unwrap().edit.text.clone();
+    world.run_callback(on_commit, text);
+}
+
+/// Find the text position nearest to the given pointer position.
+fn hit_test(world: &World, id: Entity, position: Vec2) -> Option<usize> {
+    let state = world.get::<TextInputState>(id)?;
+    let parts = world.get::<TextInputParts>(id)?;
+    let node = world.get::<Node>(parts.content)?;
+    let transform = world.get::<GlobalTransform>(parts.content)?;
+    let x = position.x - node.logical_rect(transform).min.x;
+    Some(state.edit.byte_index(nearest_offset(&state.offsets, x)))
+}
+
+/// Set a style on an entity, only marking it as changed if it differs.
+fn update_style(styles: &mut Query<&mut Style>, entity: Entity, update: impl FnOnce(&mut Style)) {
+    if let Ok(mut style) = styles.get_mut(entity) {
+        let mut next = style.clone();
+        update(&mut next);
+        style.set_if_neq(next);
+    }
+}
+
+/// Updates the displayed text, caret, selection and scroll position of text inputs.
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_text_input_layout(
+    focus: Res<Focus>,
+    fonts: Res<Assets<Font>>,
+    mut inputs: Query<(
+        Entity,
+        &mut TextInputState,
+        &TextInputProps,
+        &TextInputParts,
+        &Node,
+        Has<Disabled>,
+    )>,
+    mut texts: Query<&mut Text>,
+    mut styles: Query<&mut Style>,
+) {
+    for (entity, mut state, props, parts, node, disabled) in inputs.iter_mut() {
+        let display: String = if props.password {
+            state.edit.text.chars().map(|_| PASSWORD_CHAR).collect()
+        } else {
+            state.edit.text.clone()
+        };
+
+        // Update the text, and measure it.
+        let Ok(mut text) = texts.get_mut(parts.text) else {
+            continue;
+        };
+        if text.sections.is_empty() {
+            text.sections.push(TextSection::default());
+        }
+        if text.sections[0].value != display {
+            text.sections[0].value.clone_from(&display);
+        }
+        let section_style = &text.sections[0].style;
+        let offsets = match fonts.get(&section_style.font) {
+            Some(font) => char_offsets(&font.font, section_style.font_size, &display),
+            None => vec![0.; display.chars().count() + 1],
+        };
+        if state.offsets != offsets {
+            state.offsets = offsets;
+        }
+
+        // Scroll so that the caret is visible.
+        let focused = focus.0 == Some(entity) && !disabled;
+        let offset_of = |state: &TextInputState, position: usize| {
+            state.offsets[state.edit.char_index(position)]
+        };
+        let caret_x = offset_of(&state, state.edit.caret);
+        let width = (node.size().x - PADDING * 2.).max(0.);
+        let text_width = state.offsets.last().copied().unwrap_or(0.);
+        let mut scroll = state.scroll;
+        if caret_x - scroll > width {
+            scroll = caret_x - width;
+        } else if caret_x < scroll {
+            scroll = caret_x;
+        }
+        scroll = scroll.min((text_width - width).max(0.)).max(0.);
+        if state.scroll != scroll {
+            state.scroll = scroll;
+        }
+
+        let selection = state.edit.selection();
+        let (sel_start, sel_end) = (
+            offset_of(&state, selection.start),
+            offset_of(&state, selection.end),
+        );
+        update_style(&mut styles, parts.content, |style| {
+            style.left = ui::Val::Px(-scroll);
+        });
+        update_style(&mut styles, parts.placeholder, |style| {
+            style.display = if state.edit.text.is_empty() {
+                ui::Display::Flex
+            } else {
+                ui::Display::None
+            };
+        });
+        update_style(&mut styles, parts.caret, |style| {
+            style.left = ui::Val::Px(caret_x);
+            style.display = if focused {
+                ui::Display::Flex
+            } else {
+                ui::Display::None
+            };
+        });
+        update_style(&mut styles, parts.selection, |style| {
+            style.left = ui::Val::Px(sel_start);
+            style.width = ui::Val::Px(sel_end - sel_start);
+            style.display = if focused && !selection.is_empty() {
+                ui::Display::Flex
+            } else {
+                ui::Display::None
+            };
+        });
+    }
+}
+
+/// Calls `on_commit` for text inputs which have lost focus.
+pub(crate) fn commit_text_inputs_on_blur(
+    world: &mut World,
+    query: &mut QueryState<(Entity, &mut TextInputState)>,
+) {
+    let focus = world.resource::<Focus>().0;
+    let mut blurred: Vec<Entity> = Vec::new();
+    for (entity, mut state) in query.iter_mut(world) {
+        let focused = focus == Some(entity);
+        if state.focused != focused {
+            state.focused = focused;
+            if !focused {
+                blurred.push(entity);
+            }
+        }
+    }
+    for entity in blurred {
+        commit_text_input(world, entity);
+    }
+}
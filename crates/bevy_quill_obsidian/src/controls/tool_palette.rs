@@ -120,6 +120,9 @@ pub struct ToolButton {
 
     /// If true, set focus to this button when it's added to the UI.
     pub(crate) autofocus: bool,
+
+    /// Content of the tooltip shown when hovering over the button.
+    pub(crate) tooltip: Option<ViewChild>,
 }
 
 impl ToolButton {
@@ -186,6 +189,12 @@ impl ToolButton {
         self.autofocus = autofocus;
         self
     }
+
+    /// Set the tooltip shown when hovering over the button.
+    pub fn tooltip(mut self, tooltip: impl IntoViewChild) -> Self {
+        self.tooltip = Some(tooltip.into_view_child());
+        self
+    }
 }
 
 impl Default for ToolButton {
@@ -199,6 +208,7 @@ impl Default for ToolButton {
             tab_index: 0,
             corners: RoundedCorners::None,
             autofocus: false,
+            tooltip: None,
         }
     }
 }
@@ -217,6 +227,7 @@ impl ViewTemplate for ToolButton {
             .autofocus(self.autofocus)
             .corners(self.corners);
         btn.on_click = self.on_click;
+        btn.tooltip.clone_from(&self.tooltip);
         btn
     }
 }
@@ -253,6 +264,9 @@ pub struct ToolIconButton {
 
     /// If true, don't adjust the color of the icon based on selection state.
     pub(crate) no_tint: bool,
+
+    /// Content of the tooltip shown when hovering over the button.
+    pub(crate) tooltip: Option<ViewChild>,
 }
 
 impl ToolIconButton {
@@ -269,6 +283,7 @@ impl ToolIconButton {
             corners: RoundedCorners::None,
             autofocus: false,
             no_tint: false,
+            tooltip: None,
         }
     }
 
@@ -335,6 +350,12 @@ impl ToolIconButton {
         self.no_tint = no_tint;
         self
     }
+
+    /// Set the tooltip shown when hovering over the button.
+    pub fn tooltip(mut self, tooltip: impl IntoViewChild) -> Self {
+        self.tooltip = Some(tooltip.into_view_child());
+        self
+    }
 }
 
 impl ViewTemplate for ToolIconButton {
@@ -366,6 +387,7 @@ impl ViewTemplate for ToolIconButton {
                     .size(self.size),
             );
         btn.on_click = self.on_click;
+        btn.tooltip.clone_from(&self.tooltip);
         btn
     }
 }
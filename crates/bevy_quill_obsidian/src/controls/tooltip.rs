@@ -0,0 +1,383 @@
+use bevy::{ecs::world::Command, prelude::*, ui};
+use bevy_mod_picking::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    floating::{FloatAlign, FloatPosition, FloatSide, Floating},
+    hooks::is_hover::Hovering,
+    typography,
+};
+
+fn style_tooltip(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .max_width(300)
+        .padding((6, 3))
+        .border(1)
+        .border_color(Srgba::BLACK)
+        .border_radius(3.0)
+        .background_color(colors::U1)
+        .color(colors::FOREGROUND);
+}
+
+/// Global settings for tooltips.
+#[derive(Resource, Clone, Debug)]
+pub struct TooltipSettings {
+    /// Seconds the pointer must rest on an element before its tooltip appears.
+    pub delay: f32,
+
+    /// Seconds after a tooltip closes during which hovering another element shows its tooltip
+    /// immediately, so that the user can move along a toolbar without waiting each time.
+    pub warmup: f32,
+}
+
+impl Default for TooltipSettings {
+    fn default() -> Self {
+        Self {
+            delay: 0.6,
+            warmup: 0.5,
+        }
+    }
+}
+
+/// Marks an entity which has a tooltip.
+#[derive(Component)]
+pub(crate) struct TooltipAnchor {
+    /// Whether the `Hovering` component was added by the tooltip, rather than by the anchor's
+    /// own hover tracking, and so should be removed along with the tooltip.
+    owns_hovering: bool,
+}
+
+/// Records, on the view entity of a tooltip, which anchor it is currently attached to.
+#[derive(Component)]
+struct TooltipOwner(Entity);
+
+/// Removes the tooltip's components from the anchor once the tooltip which added them has been
+/// despawned, or has been moved to a different anchor.
+struct ReleaseTooltipAnchor {
+    /// The view entity of the tooltip.
+    owner: Entity,
+    anchor: Entity,
+}
+
+impl Command for ReleaseTooltipAnchor {
+    fn apply(self, world: &mut World) {
+        // Cleanups also run before each reaction, in which case the tooltip is usually still
+        // attached to the same anchor.
+        if world
+            .get::<TooltipOwner>(self.owner)
+            .is_some_and(|current| current.0 == self.anchor)
+        {
+            return;
+        }
+        let Some(mut entt) = world.get_entity_mut(self.anchor) else {
+            return;
+        };
+        if let Some(anchor) = entt.take::<TooltipAnchor>() {
+            if anchor.owns_hovering {
+                entt.remove::<Hovering>();
+            }
+        }
+    }
+}
+
+/// The anchor whose tooltip is currently displayed.
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct ActiveTooltip(Option<Entity>);
+
+/// Tracks how long the pointer has rested on an anchor.
+#[derive(Resource, Default)]
+pub(crate) struct TooltipTimer {
+    hovered: Option<Entity>,
+    /// When the pointer moved onto the hovered anchor.
+    since: f32,
+    /// Tooltips appear without delay until this time.
+    warm_until: f32,
+    /// True if the tooltip was hidden by a key or button press, in which case it stays hidden
+    /// until the pointer moves to a different anchor.
+    dismissed: bool,
+    shown: Option<Entity>,
+}
+
+impl TooltipTimer {
+    /// Advance the timer, returning the anchor whose tooltip should be shown.
+    fn update(
+        &mut self,
+        now: f32,
+        hovered: Option<Entity>,
+        pressed: bool,
+        settings: &TooltipSettings,
+    ) -> Option<Entity> {
+        if hovered != self.hovered {
+            if self.shown.is_some() {
+                self.warm_until = now + settings.warmup;
+            }
+            self.hovered = hovered;
+            self.since = now;
+            self.dismissed = false;
+            self.shown = None;
+        }
+        if pressed && self.hovered.is_some() {
+            self.dismissed = true;
+            self.shown = None;
+            self.warm_until = 0.;
+        }
+        if let (Some(anchor), None, false) = (self.hovered, self.shown, self.dismissed) {
+            if now - self.since >= settings.delay || now < self.warm_until {
+                self.shown = Some(anchor);
+            }
+        }
+        self.shown
+    }
+}
+
+/// Displays a tooltip next to an anchor element while the pointer rests on it.
+///
+/// The tooltip can be placed anywhere in the view tree, since it is displayed in the tooltip
+/// overlay layer; it only needs the entity id of the anchor. Controls such as
+/// [`Button`](super::Button) have a `tooltip` property which does this for you. The tooltip
+/// appears after the delay given by [`TooltipSettings`], and is hidden when the pointer leaves
+/// the anchor or a key is pressed.
+#[derive(Clone, PartialEq)]
+pub struct Tooltip {
+    /// The element which shows the tooltip when hovered.
+    pub anchor: Entity,
+
+    /// The content of the tooltip.
+    pub content: ViewChild,
+
+    /// Which side of the anchor the tooltip is placed. If it doesn't fit, it is placed on the
+    /// opposite side.
+    pub side: FloatSide,
+
+    /// Additional styles to be applied to the tooltip.
+    pub style: StyleHandle,
+}
+
+impl Tooltip {
+    /// Create a new tooltip for the given anchor element.
+    pub fn new(anchor: Entity) -> Self {
+        Self {
+            anchor,
+            content: ViewChild::default(),
+            side: FloatSide::Bottom,
+            style: StyleHandle::default(),
+        }
+    }
+
+    /// Set the content of the tooltip.
+    pub fn content(mut self, content: impl IntoViewChild) -> Self {
+        self.content = content.into_view_child();
+        self
+    }
+
+    /// Set which side of the anchor the tooltip is placed.
+    pub fn side(mut self, side: FloatSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Set additional styles to be applied to the tooltip.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for Tooltip {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let anchor = self.anchor;
+        let owner = cx.owner();
+        let previous = cx
+            .world()
+            .get::<TooltipOwner>(owner)
+            .map(|current| current.0);
+        cx.insert(TooltipOwner(anchor));
+        if let Some(previous) = previous.filter(|previous| *previous != anchor) {
+            ReleaseTooltipAnchor {
+                owner,
+                anchor: previous,
+            }
+            .apply(cx.world_mut());
+        }
+        let mut entt = cx.world_mut().entity_mut(anchor);
+        if !entt.contains::<TooltipAnchor>() {
+            let owns_hovering = !entt.contains::<Hovering>();
+            if owns_hovering {
+                entt.insert(Hovering(false));
+            }
+            entt.insert(TooltipAnchor { owns_hovering });
+        }
+        cx.on_cleanup(move |world| {
+            world.commands().add(ReleaseTooltipAnchor { owner, anchor });
+        });
+        let active = cx.use_resource::<ActiveTooltip>().0 == Some(anchor);
+        let side = self.side;
+
+        Cond::new(
+            active,
+            Portal::new(
+                Element::<NodeBundle>::new()
+                    .named("Tooltip")
+                    .style((typography::text_default, style_tooltip, self.style.clone()))
+                    .insert_dyn(
+                        move |side| {
+                            (
+                                Floating {
+                                    anchor,
+                                    position: [side, side.mirror()]
+                                        .into_iter()
+                                        .map(|side| FloatPosition {
+                                            side,
+                                            align: FloatAlign::Center,
+                                            stretch: false,
+                                            gap: 4.0,
+                                        })
+                                        .collect(),
+                                },
+                                Pickable::IGNORE,
+                            )
+                        },
+                        side,
+                    )
+                    .children(self.content.clone()),
+            )
+            .into(OverlayLayer::Tooltips),
+            (),
+        )
+    }
+}
+
+/// Decides which tooltip to show, from the hovered anchors and the time they have been hovered.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_tooltips(
+    time: Res<Time>,
+    settings: Res<TooltipSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    anchors: Query<(Entity, &Hovering), With<TooltipAnchor>>,
+    parents: Query<&Parent>,
+    mut timer: ResMut<TooltipTimer>,
+    mut active: ResMut<ActiveTooltip>,
+) {
+    let hovered: Vec<Entity> = anchors
+        .iter()
+        .filter(|(_, hovering)| hovering.0)
+        .map(|(entity, _)| entity)
+        .collect();
+    // When anchors are nested, the innermost one wins.
+    let innermost = hovered.iter().copied().find(|anchor| {
+        !hovered
+            .iter()
+            .any(|other| parents.iter_ancestors(*other).any(|e| e == *anchor))
+    });
+    let pressed =
+        keys.get_just_pressed().next().is_some() || buttons.get_just_pressed().next().is_some();
+    let shown = timer.update(time.elapsed_seconds(), innermost, pressed, &settings);
+    active.set_if_neq(ActiveTooltip(shown));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tooltip_timer() {
+        let settings = TooltipSettings {
+            delay: 0.5,
+            warmup: 0.5,
+        };
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let mut timer = TooltipTimer::default();
+        assert_eq!(timer.update(1.0, Some(a), false, &settings), None);
+        assert_eq!(timer.update(1.4, Some(a), false, &settings), None);
+        assert_eq!(timer.update(1.5, Some(a), false, &settings), Some(a));
+        // Moving to another anchor while warm shows its tooltip immediately.
+        assert_eq!(timer.update(2.0, Some(b), false, &settings), Some(b));
+        assert_eq!(timer.update(2.1, None, false, &settings), None);
+        assert_eq!(timer.update(2.3, Some(a), false, &settings), Some(a));
+        // Once the warm-up period has passed, the delay applies again.
+        assert_eq!(timer.update(2.4, None, false, &settings), None);
+        assert_eq!(timer.update(3.0, Some(b), false, &settings), None);
+        assert_eq!(timer.update(3.5, Some(b), false, &settings), Some(b));
+    }
+
+    #[test]
+    fn test_release_tooltip_anchor() {
+        let mut world = World::new();
+        let owner = world.spawn_empty().id();
+        let owned = world
+            .spawn((
+                TooltipAnchor {
+                    owns_hovering: true,
+                },
+                Hovering(true),
+            ))
+            .id();
+        let shared = world
+            .spawn((
+                TooltipAnchor {
+                    owns_hovering: false,
+                },
+                Hovering(true),
+            ))
+            .id();
+
+        // Nothing is removed while the tooltip is still attached to the anchor.
+        world.entity_mut(owner).insert(TooltipOwner(owned));
+        ReleaseTooltipAnchor {
+            owner,
+            anchor: owned,
+        }
+        .apply(&mut world);
+        assert!(world.get::<TooltipAnchor>(owned).is_some());
+
+        // Once the tooltip has moved to another anchor, the old one is released even though the
+        // tooltip is still alive.
+        let previous = world
+            .spawn((
+                TooltipAnchor {
+                    owns_hovering: true,
+                },
+                Hovering(false),
+            ))
+            .id();
+        ReleaseTooltipAnchor {
+            owner,
+            anchor: previous,
+        }
+        .apply(&mut world);
+        assert!(world.get::<TooltipAnchor>(previous).is_none());
+        assert!(world.get::<Hovering>(previous).is_none());
+
+        world.despawn(owner);
+        for anchor in [owned, shared] {
+            ReleaseTooltipAnchor { owner, anchor }.apply(&mut world);
+            assert!(world.get::<TooltipAnchor>(anchor).is_none());
+        }
+        assert!(world.get::<Hovering>(owned).is_none());
+        // Hover state which the anchor tracks itself is left alone.
+        assert!(world.get::<Hovering>(shared).is_some());
+    }
+
+    #[test]
+    fn test_tooltip_dismiss() {
+        let settings = TooltipSettings::default();
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let mut timer = TooltipTimer::default();
+        timer.update(0.0, Some(a), false, &settings);
+        assert_eq!(timer.update(1.0, Some(a), false, &settings), Some(a));
+        assert_eq!(timer.update(1.1, Some(a), true, &settings), None);
+        // Stays hidden while the pointer remains on the same anchor.
+        assert_eq!(timer.update(5.0, Some(a), false, &settings), None);
+        // Pressing a key also ends the warm-up period.
+        assert_eq!(timer.update(5.1, Some(b), false, &settings), None);
+    }
+}
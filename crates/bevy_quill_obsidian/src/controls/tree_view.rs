@@ -0,0 +1,1020 @@
+use bevy::{
+    a11y::{
+        accesskit::{NodeBuilder, Role},
+        AccessibilityNode, Focus,
+    },
+    prelude::*,
+    ui,
+    utils::HashSet,
+};
+use bevy_mod_picking::{events::PointerCancel, prelude::*};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{
+    colors,
+    focus::{ctrl_pressed, KeyCharEvent, KeyPressEvent, TabIndex},
+    hooks::{UseIsFocus, UseIsHover},
+    scrolling::ScrollArea,
+    size::Size,
+    typography,
+};
+
+use super::{
+    selection::{
+        handle_selection_key, move_cursor, press_row, ScrollToCursor, SelectionCursor,
+        SelectionProps,
+    },
+    DisclosureToggle, ListSelectionMode, ScrollView,
+};
+
+/// Indentation of each level of the tree, in pixels.
+const INDENT: f32 = 16.;
+
+/// Height of a row, in pixels.
+const ROW_HEIGHT: f32 = 22.;
+
+/// Seconds after the last keystroke before type-to-find starts a new search.
+const TYPEAHEAD_TIMEOUT: f32 = 1.0;
+
+fn style_tree_view(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .background_color(colors::U1)
+        .border_radius(5.0)
+        .padding(3);
+}
+
+fn style_tree_scroll(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.).min_height(0);
+}
+
+fn style_tree_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .align_items(ui::AlignItems::Stretch);
+}
+
+fn style_row(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .height(ROW_HEIGHT)
+        .padding_right(4)
+        .border_radius(3.0)
+        .color(colors::FOREGROUND);
+}
+
+fn style_toggle_spacer(ss: &mut StyleBuilder) {
+    ss.width(Size::Xs.height()).flex_shrink(0.);
+}
+
+fn style_label(ss: &mut StyleBuilder) {
+    ss.margin_left(2).flex_shrink(0.);
+}
+
+fn style_drop_line(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .right(0)
+        .height(2)
+        .background_color(colors::ACCENT);
+}
+
+/// A node in the data displayed by a [`TreeView`].
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct TreeNode {
+    /// Identifies the node. Must be unique within the tree.
+    pub id: u64,
+
+    /// Text displayed for the node.
+    pub label: String,
+
+    /// The children of the node.
+    pub children: TreeChildren,
+}
+
+/// The children of a [`TreeNode`].
+#[derive(Clone, PartialEq, Default, Debug)]
+pub enum TreeChildren {
+    /// The node is a leaf.
+    #[default]
+    None,
+
+    /// The node's children are known.
+    Loaded(Vec<TreeNode>),
+
+    /// The node has children which haven't been loaded yet. When the node is expanded, the
+    /// tree view calls `on_load`, and shows a placeholder until the children are supplied.
+    Lazy,
+}
+
+impl TreeNode {
+    /// Create a new leaf node.
+    pub fn new(id: u64, label: impl Into<String>) -> Self {
+        Self {
+            id,
+            label: label.into(),
+            children: TreeChildren::None,
+        }
+    }
+
+    /// Set the children of the node.
+    pub fn children(mut self, children: Vec<TreeNode>) -> Self {
+        self.children = TreeChildren::Loaded(children);
+        self
+    }
+
+    /// Mark the node as having children which will be loaded when it is expanded.
+    pub fn lazy(mut self) -> Self {
+        self.children = TreeChildren::Lazy;
+        self
+    }
+}
+
+/// How many nodes of a [`TreeView`] can be selected at once.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TreeSelectionMode {
+    /// Clicking a node selects it, and deselects the others.
+    #[default]
+    Single,
+
+    /// Ctrl-click toggles nodes in and out of the selection, and shift-click selects a range.
+    Multiple,
+}
+
+/// A request to move nodes to a new parent, produced by drag and drop.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TreeMove {
+    /// The nodes being moved, in display order.
+    pub nodes: Vec<u64>,
+
+    /// The new parent, or `None` for the top level.
+    pub parent: Option<u64>,
+
+    /// Where to insert the nodes, as an index into the parent's children before the moved
+    /// nodes are removed.
+    pub index: usize,
+}
+
+/// A hierarchical list with expandable nodes.
+///
+/// The tree itself takes keyboard focus; the arrow keys move the cursor between rows, with left
+/// and right collapsing and expanding nodes, and typing a prefix jumps to the next matching
+/// row. As with [`TabBar`](super::TabBar), the caller owns the data and the selection: the
+/// callbacks report what the user did. Which nodes are expanded is tracked by the tree view.
+#[derive(Clone, PartialEq, Default)]
+pub struct TreeView {
+    /// The top-level nodes.
+    pub nodes: Vec<TreeNode>,
+
+    /// Ids of the selected nodes.
+    pub selection: Vec<u64>,
+
+    /// Whether more than one node can be selected.
+    pub selection_mode: TreeSelectionMode,
+
+    /// Additional styles to be applied to the tree view.
+    pub style: StyleHandle,
+
+    /// The tab index of the tree view (default 0).
+    pub tab_index: i32,
+
+    /// Callback called with the new selection when the user changes it.
+    pub on_select: Option<Callback<Vec<u64>>>,
+
+    /// Callback called with the id of a [`TreeChildren::Lazy`] node when it is expanded.
+    pub on_load: Option<Callback<u64>>,
+
+    /// Callback called when nodes are dragged to a new position. If this isn't set, nodes
+    /// can't be dragged.
+    pub on_move: Option<Callback<TreeMove>>,
+}
+
+impl TreeView {
+    /// Create a new tree view.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the top-level nodes.
+    pub fn nodes(mut self, nodes: Vec<TreeNode>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    /// Set the ids of the selected nodes.
+    pub fn selection(mut self, selection: Vec<u64>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Set whether more than one node can be selected.
+    pub fn selection_mode(mut self, selection_mode: TreeSelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Set additional styles to be applied to the tree view.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+
+    /// Set the tab index of the tree view.
+    pub fn tab_index(mut self, tab_index: i32) -> Self {
+        self.tab_index = tab_index;
+        self
+    }
+
+    /// Set the callback called when the selection changes.
+    pub fn on_select(mut self, on_select: Callback<Vec<u64>>) -> Self {
+        self.on_select = Some(on_select);
+        self
+    }
+
+    /// Set the callback called when a lazily-loaded node is expanded.
+    pub fn on_load(mut self, on_load: Callback<u64>) -> Self {
+        self.on_load = Some(on_load);
+        self
+    }
+
+    /// Set the callback called when nodes are dragged to a new position.
+    pub fn on_move(mut self, on_move: Callback<TreeMove>) -> Self {
+        self.on_move = Some(on_move);
+        self
+    }
+}
+
+/// A visible row of a tree view.
+#[derive(Clone, PartialEq, Debug)]
+struct TreeRow {
+    id: u64,
+    label: String,
+    depth: usize,
+    parent: Option<u64>,
+    /// Position of the node among its siblings.
+    index: usize,
+    /// Number of loaded children.
+    child_count: usize,
+    expandable: bool,
+    expanded: bool,
+    lazy: bool,
+    /// True for the placeholder shown while a node's children are loading. Shares the id of
+    /// the node being loaded.
+    loading: bool,
+}
+
+/// Where a dragged node would be dropped, relative to the row under the pointer.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum DropPosition {
+    Before,
+    Inside,
+    After,
+}
+
+/// Properties of a tree view which are needed by the event handlers.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct TreeViewProps {
+    selection: Vec<u64>,
+    mode: TreeSelectionMode,
+    on_select: Option<Callback<Vec<u64>>>,
+    on_load: Option<Callback<u64>>,
+    on_move: Option<Callback<TreeMove>>,
+}
+
+impl SelectionProps for TreeViewProps {
+    type Key = u64;
+
+    fn mode(&self) -> ListSelectionMode {
+        match self.mode {
+            TreeSelectionMode::Single => ListSelectionMode::Single,
+            TreeSelectionMode::Multiple => ListSelectionMode::Multiple,
+        }
+    }
+
+    fn selection(&self) -> &[u64] {
+        &self.selection
+    }
+
+    fn on_selection_change(&self) -> Option<Callback<Vec<u64>>> {
+        self.on_select
+    }
+
+    fn keys(world: &World, tree: Entity) -> Vec<u64> {
+        world.get::<TreeViewRows>(tree).map_or(Vec::new(), |rows| {
+            rows.0
+                .iter()
+                .filter(|row| !row.loading)
+                .map(|row| row.id)
+                .collect()
+        })
+    }
+
+    fn reveal(world: &mut World, tree: Entity, _key: u64) {
+        if let Some(mut state) = world.get_mut::<TreeViewState>(tree) {
+            state.scroll_to_cursor = true;
+        }
+    }
+}
+
+/// The rows currently displayed by a tree view.
+#[derive(Component, Clone, PartialEq)]
+struct TreeViewRows(Vec<TreeRow>);
+
+/// Ids of the expanded nodes of a tree view.
+#[derive(Component, Default)]
+struct TreeViewExpanded(HashSet<u64>);
+
+/// Where dragged nodes would be dropped, which affects how rows are drawn.
+#[derive(Component, Default, PartialEq)]
+pub(crate) struct TreeViewHighlight {
+    drop: Option<(u64, DropPosition)>,
+}
+
+/// Interaction state of a tree view, stored on the root entity.
+#[derive(Component)]
+pub(crate) struct TreeViewState {
+    scroll: Entity,
+    /// Nodes being dragged.
+    dragging: Vec<u64>,
+    /// Text typed so far for type-to-find, and when the last character was typed.
+    search: String,
+    search_time: f32,
+    /// True if the cursor has moved and should be scrolled into view.
+    scroll_to_cursor: bool,
+}
+
+/// Identifies the tree view and node of a row entity.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct TreeViewRow {
+    tree: Entity,
+    id: u64,
+}
+
+impl ScrollToCursor for TreeViewState {
+    type Key = u64;
+    type Row = TreeViewRow;
+
+    fn row(row: &TreeViewRow) -> (Entity, &u64) {
+        (row.tree, &row.id)
+    }
+
+    fn scroll_area(&self) -> Entity {
+        self.scroll
+    }
+
+    fn scroll_to_cursor(&self) -> bool {
+        self.scroll_to_cursor
+    }
+
+    fn clear_scroll_to_cursor(&mut self) {
+        self.scroll_to_cursor = false;
+    }
+}
+
+impl ViewTemplate for TreeView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let scroll = cx.create_entity();
+        let focused = cx.is_focus_visible(id);
+
+        let mut entt = cx.world_mut().entity_mut(id);
+        if !entt.contains::<TreeViewState>() {
+            entt.insert((
+                TreeViewState {
+                    scroll,
+                    dragging: Vec::new(),
+                    search: String::new(),
+                    search_time: 0.,
+                    scroll_to_cursor: false,
+                },
+                TreeViewExpanded::default(),
+                TreeViewHighlight::default(),
+                SelectionCursor::<u64>::default(),
+            ));
+        }
+
+        let mut rows = Vec::new();
+        if let Some(expanded) = cx.use_component::<TreeViewExpanded>(id) {
+            flatten(&self.nodes, &expanded.0, None, 0, &mut rows);
+        }
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TreeView")
+            .style((
+                typography::text_default,
+                style_tree_view,
+                self.style.clone(),
+            ))
+            .style_dyn(
+                |focused, sb| {
+                    if focused {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_offset(1.0)
+                            .outline_width(2.0);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                focused,
+            )
+            .insert_dyn(
+                |(tab_index, props, rows)| {
+                    (
+                        TabIndex(tab_index),
+                        props,
+                        TreeViewRows(rows),
+                        AccessibilityNode::from(NodeBuilder::new(Role::Tree)),
+                    )
+                },
+                (
+                    self.tab_index,
+                    TreeViewProps {
+                        selection: self.selection.clone(),
+                        mode: self.selection_mode,
+                        on_select: self.on_select,
+                        on_load: self.on_load,
+                        on_move: self.on_move,
+                    },
+                    rows.clone(),
+                ),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<KeyPressEvent>::run(move |world: &mut World| {
+                            let event = world.resource::<ListenerInput<KeyPressEvent>>();
+                            let (key, shift, ctrl) = (event.key_code, event.shift, event.ctrl);
+                            if handle_key(world, id, key, shift, ctrl) {
+                                world
+                                    .resource_mut::<ListenerInput<KeyPressEvent>>()
+                                    .stop_propagation();
+                            }
+                        }),
+                        On::<KeyCharEvent>::run(move |world: &mut World| {
+                            let key = world.resource::<ListenerInput<KeyCharEvent>>().key;
+                            if find_as_you_type(world, id, key) {
+                                world
+                                    .resource_mut::<ListenerInput<KeyCharEvent>>()
+                                    .stop_propagation();
+                            }
+                        }),
+                    )
+                },
+                (),
+            )
+            .children(
+                ScrollView::new()
+                    .entity(Some(scroll))
+                    .scroll_enable_y(true)
+                    .style(style_tree_scroll)
+                    .content_style(style_tree_content)
+                    .children(For::each(rows, move |row| TreeRowView {
+                        tree: id,
+                        row: row.clone(),
+                    })),
+            )
+    }
+}
+
+/// A single row of a tree view.
+#[derive(Clone, PartialEq)]
+struct TreeRowView {
+    tree: Entity,
+    row: TreeRow,
+}
+
+impl ViewTemplate for TreeRowView {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let tree = self.tree;
+        let row = self.row.clone();
+        let node = row.id;
+        let loading = row.loading;
+        let hovering = cx.is_hovered(id);
+        let focused = cx.is_focus_within(tree);
+        let selected = !loading
+            && cx
+                .use_component::<TreeViewProps>(tree)
+                .is_some_and(|props| props.selection.contains(&node));
+        let cursor = !loading
+            && cx
+                .use_component::<SelectionCursor<u64>>(tree)
+                .is_some_and(|cursor| cursor.cursor == Some(node));
+        let drop = match cx.use_component::<TreeViewHighlight>(tree) {
+            Some(highlight) if !loading => highlight
+                .drop
+                .filter(|(target, _)| *target == node)
+                .map(|(_, position)| position),
+            _ => None,
+        };
+        let on_toggle = cx.create_callback(move |expand: In<bool>, world: &mut World| {
+            set_expanded(world, tree, node, *expand);
+        });
+        let indent = row.depth as f32 * INDENT;
+
+        Element::<NodeBundle>::for_entity(id)
+            .named("TreeView::Row")
+            .style((style_row, move |ss: &mut StyleBuilder| {
+                ss.padding_left(indent + 2.);
+            }))
+            .insert_dyn(
+                move |(expanded, selected)| {
+                    (
+                        TreeViewRow { tree, id: node },
+                        AccessibilityNode::from({
+                            let mut builder = NodeBuilder::new(Role::TreeItem);
+                            builder.set_selected(selected);
+                            if row.expandable {
+                                builder.set_expanded(expanded);
+                            }
+                            builder
+                        }),
+                    )
+                },
+                (self.row.expanded, selected),
+            )
+            .insert_dyn(
+                move |_| {
+                    (
+                        On::<Pointer<Down>>::run(move |world: &mut World| {
+                            if loading {
+                                world.resource_mut::<Focus>().0 = Some(tree);
+                                return;
+                            }
+                            // Pressing on a selected row without modifiers may start a drag
+                            // of the whole selection, so leave it to the click to narrow it.
+                            press_row::<TreeViewProps>(world, tree, node, true);
+                        }),
+                        On::<Pointer<Click>>::run(move |world: &mut World| {
+                            let keys = world.resource::<ButtonInput<KeyCode>>();
+                            let modifiers = keys
+                                .any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+                                || ctrl_pressed(keys);
+                            if !loading && !modifiers {
+                                move_cursor::<TreeViewProps>(world, tree, node, false, false, true);
+                            }
+                        }),
+                        On::<Pointer<DragStart>>::run(move |world: &mut World| {
+                            if loading {
+                                return;
+                            }
+                            let Some(props) = world.get::<TreeViewProps>(tree) else {
+                                return;
+                            };
+                            if props.on_move.is_none() {
+                                return;
+                            }
+                            let dragging = if props.selection.contains(&node) {
+                                let rows = &world.get::<TreeViewRows>(tree).unwrap().0;
+                                rows.iter()
+                                    .filter(|row| !row.loading && props.selection.contains(&row.id))
+                                    .map(|row| row.id)
+                                    .collect()
+                            } else {
+                                vec![node]
+                            };
+                            world.get_mut::<TreeViewState>(tree).unwrap().dragging = dragging;
+                        }),
+                        On::<Pointer<DragOver>>::run(move |world: &mut World| {
+                            let y = world
+                                .resource::<ListenerInput<Pointer<DragOver>>>()
+                                .pointer_location
+                                .position
+                                .y;
+                            let position = match (world.get::<Node>(id), world.get(id)) {
+                                (Some(row_node), Some(transform)) => {
+                                    let rect = row_node.logical_rect(transform);
+                                    let fraction = (y - rect.min.y) / rect.height().max(1.);
+                                    if fraction < 0.25 {
+                                        DropPosition::Before
+                                    } else if fraction > 0.75 {
+                                        DropPosition::After
+                                    } else {
+                                        DropPosition::Inside
+                                    }
+                                }
+                                _ => return,
+                            };
+                            let valid = {
+                                let state = world.get::<TreeViewState>(tree).unwrap();
+                                let rows = &world.get::<TreeViewRows>(tree).unwrap().0;
+                                !state.dragging.is_empty()
+                                    && drop_move(rows, &state.dragging, node, position).is_some()
+                            };
+                            let drop = valid.then_some((node, position));
+                            let mut highlight = world.get_mut::<TreeViewHighlight>(tree).unwrap();
+                            if highlight.drop != drop {
+                                highlight.drop = drop;
+                            }
+                        }),
+                        On::<Pointer<DragLeave>>::run(move |world: &mut World| {
+                            let mut highlight = world.get_mut::<TreeViewHighlight>(tree).unwrap();
+                            if highlight.drop.is_some_and(|(target, _)| target == node) {
+                                highlight.drop = None;
+                            }
+                        }),
+                        On::<Pointer<DragEnd>>::run(move |world: &mut World| {
+                            let drop = world
+                                .get_mut::<TreeViewHighlight>(tree)
+                                .unwrap()
+                                .drop
+                                .take();
+                            let dragging = std::mem::take(
+                                &mut world.get_mut::<TreeViewState>(tree).unwrap().dragging,
+                            );
+                            let Some((target, position)) = drop else {
+                                return;
+                            };
+                            let rows = &world.get::<TreeViewRows>(tree).unwrap().0;
+                            let on_move = world.get::<TreeViewProps>(tree).unwrap().on_move;
+                            if let (Some(on_move), Some(tree_move)) =
+                                (on_move, drop_move(rows, &dragging, target, position))
+                            {
+                                world.run_callback(on_move, tree_move);
+                            }
+                        }),
+                        On::<Pointer<PointerCancel>>::run(move |world: &mut World| {
+                            world
+                                .get_mut::<TreeViewState>(tree)
+                                .unwrap()
+                                .dragging
+                                .clear();
+                            world.get_mut::<TreeViewHighlight>(tree).unwrap().drop = None;
+                        }),
+                    )
+                },
+                (),
+            )
+            .style_dyn(
+                move |(selected, focused, hovering, drop_inside), sb| {
+                    let color = match (selected, focused, hovering || drop_inside) {
+                        (true, true, _) => colors::TEXT_SELECT,
+                        (true, false, _) => colors::U3,
+                        (false, _, true) => colors::U1.lighter(0.03),
+                        (false, _, false) => colors::TRANSPARENT,
+                    };
+                    sb.background_color(color);
+                },
+                (
+                    selected,
+                    focused,
+                    hovering,
+                    drop == Some(DropPosition::Inside),
+                ),
+            )
+            .style_dyn(
+                |cursor, sb| {
+                    if cursor {
+                        sb.outline_color(colors::FOCUS)
+                            .outline_width(2)
+                            .outline_offset(-2);
+                    } else {
+                        sb.outline_color(Option::<Color>::None);
+                    }
+                },
+                cursor && focused,
+            )
+            .children((
+                Cond::new(
+                    self.row.expandable && !loading,
+                    DisclosureToggle::new()
+                        .size(Size::Xs)
+                        .tab_index(-1)
+                        .expanded(self.row.expanded)
+                        .on_change(on_toggle),
+                    Element::<NodeBundle>::new().style(style_toggle_spacer),
+                ),
+                Element::<NodeBundle>::new()
+                    .named("TreeView::Label")
+                    .style(style_label)
+                    .style_dyn(
+                        |loading, sb| {
+                            sb.color(if loading {
+                                colors::DIM.with_alpha(0.5)
+                            } else {
+                                colors::FOREGROUND
+                            });
+                        },
+                        loading,
+                    )
+                    .children(if loading {
+                        "Loading\u{2026}".to_string()
+                    } else {
+                        self.row.label.clone()
+                    }),
+                Element::<NodeBundle>::new()
+                    .named("TreeView::DropLine")
+                    .style(style_drop_line)
+                    .style_dyn(
+                        move |drop, sb| match drop {
+                            Some(DropPosition::Before) => {
+                                sb.display(ui::Display::Flex).top(-1).left(indent);
+                            }
+                            Some(DropPosition::After) => {
+                                sb.display(ui::Display::Flex).bottom(-1).left(indent);
+                            }
+                            _ => {
+                                sb.display(ui::Display::None);
+                            }
+                        },
+                        drop,
+                    ),
+            ))
+    }
+}
+
+/// Append the visible rows for `nodes` to `out`.
+fn flatten(
+    nodes: &[TreeNode],
+    expanded: &HashSet<u64>,
+    parent: Option<u64>,
+    depth: usize,
+    out: &mut Vec<TreeRow>,
+) {
+    for (index, node) in nodes.iter().enumerate() {
+        let (expandable, child_count) = match &node.children {
+            TreeChildren::None => (false, 0),
+            TreeChildren::Loaded(children) => (!children.is_empty(), children.len()),
+            TreeChildren::Lazy => (true, 0),
+        };
+        let is_expanded = expandable && expanded.contains(&node.id);
+        out.push(TreeRow {
+            id: node.id,
+            label: node.label.clone(),
+            depth,
+            parent,
+            index,
+            child_count,
+            expandable,
+            expanded: is_expanded,
+            lazy: node.children == TreeChildren::Lazy,
+            loading: false,
+        });
+        if !is_expanded {
+            continue;
+        }
+        match &node.children {
+            TreeChildren::Loaded(children) => {
+                flatten(children, expanded, Some(node.id), depth + 1, out);
+            }
+            TreeChildren::Lazy => out.push(TreeRow {
+                id: node.id,
+                label: String::new(),
+                depth: depth + 1,
+                parent: Some(node.id),
+                index: 0,
+                child_count: 0,
+                expandable: false,
+                expanded: false,
+                lazy: false,
+                loading: true,
+            }),
+            TreeChildren::None => {}
+        }
+    }
+}
+
+/// Index of the next row after `start` whose label starts with `prefix`, ignoring case and
+/// wrapping around at the end. The row at `start` itself is checked last.
+fn find_prefix(rows: &[TreeRow], start: usize, prefix: &str) -> Option<usize> {
+    let count = rows.len();
+    (1..=count)
+        .map(|i| (start + i) % count)
+        .find(|i| !rows[*i].loading && rows[*i].label.to_lowercase().starts_with(prefix))
+}
+
+/// The move which results from dropping `dragging` at `position` relative to `target`, or
+/// `None` if the drop isn't allowed because it would put a node inside itself.
+fn drop_move(
+    rows: &[TreeRow],
+    dragging: &[u64],
+    target: u64,
+    position: DropPosition,
+) -> Option<TreeMove> {
+    let find = |id: u64| rows.iter().find(|row| !row.loading && row.id == id);
+    let row = find(target)?;
+    // Walk up from the target; none of its ancestors may be one of the dragged nodes.
+    let mut ancestor = Some(row);
+    while let Some(current) = ancestor {
+        if dragging.contains(&current.id) {
+            return None;
+        }
+        ancestor = current.parent.and_then(find);
+    }
+    let (parent, index) = match position {
+        DropPosition::Before => (row.parent, row.index),
+        DropPosition::Inside => (Some(row.id), row.child_count),
+        DropPosition::After => (row.parent, row.index + 1),
+    };
+    Some(TreeMove {
+        nodes: dragging.to_vec(),
+        parent,
+        index,
+    })
+}
+
+/// Expand or collapse a node, loading its children if needed.
+fn set_expanded(world: &mut World, tree: Entity, node: u64, expand: bool) {
+    let Some(mut expanded) = world.get_mut::<TreeViewExpanded>(tree) else {
+        return;
+    };
+    if expanded.0.contains(&node) == expand {
+        return;
+    }
+    if expand {
+        expanded.0.insert(node);
+    } else {
+        expanded.0.remove(&node);
+    }
+
+    let rows = &world.get::<TreeViewRows>(tree).unwrap().0;
+    let lazy = rows.iter().any(|row| row.id == node && row.lazy);
+    // Move the cursor out of a collapsed subtree, so that it doesn't disappear.
+    let cursor = world.get::<SelectionCursor<u64>>(tree).unwrap().cursor;
+    let hidden = !expand
+        && cursor.is_some_and(|cursor| {
+            let mut ancestor = rows.iter().find(|row| row.id == cursor && !row.loading);
+            while let Some(row) = ancestor {
+                if row.parent == Some(node) {
+                    return true;
+                }
+                ancestor = row
+                    .parent
+                    .and_then(|parent| rows.iter().find(|row| row.id == parent && !row.loading));
+            }
+            false
+        });
+    if hidden {
+        world.get_mut::<SelectionCursor<u64>>(tree).unwrap().cursor = Some(node);
+    }
+    let on_load = world.get::<TreeViewProps>(tree).unwrap().on_load;
+    if let (true, Some(on_load)) = (expand && lazy, on_load) {
+        world.run_callback(on_load, node);
+    }
+}
+
+/// Handle navigation keys. Returns true if the key was used.
+fn handle_key(world: &mut World, tree: Entity, key: KeyCode, shift: bool, ctrl: bool) -> bool {
+    let Some(rows) = world.get::<TreeViewRows>(tree).map(|rows| rows.0.clone()) else {
+        return false;
+    };
+    let cursor = world.get::<SelectionCursor<u64>>(tree).unwrap().cursor;
+    let current = cursor.and_then(|id| rows.iter().position(|row| !row.loading && row.id == id));
+    let target = match key {
+        KeyCode::ArrowLeft => {
+            let Some(row) = current.map(|index| &rows[index]) else {
+                return false;
+            };
+            if row.expanded {
+                set_expanded(world, tree, row.id, false);
+                return true;
+            }
+            row.parent
+                .and_then(|parent| rows.iter().position(|row| row.id == parent))
+        }
+        KeyCode::ArrowRight => {
+            let Some(index) = current else {
+                return false;
+            };
+            let row = &rows[index];
+            if row.expandable && !row.expanded {
+                set_expanded(world, tree, row.id, true);
+                return true;
+            }
+            Some(index + 1)
+                .filter(|next| row.expanded && rows.get(*next).is_some_and(|child| !child.loading))
+        }
+        _ => {
+            let scroll = world.get::<TreeViewState>(tree).unwrap().scroll;
+            let visible = world
+                .get::<ScrollArea>(scroll)
+                .map_or(0., |area| area.visible_size.y);
+            let page = ((visible / ROW_HEIGHT) as usize).max(1);
+            return handle_selection_key::<TreeViewProps>(world, tree, key, shift, ctrl, page);
+        }
+    };
+    if let Some(index) = target {
+        move_cursor::<TreeViewProps>(world, tree, rows[index].id, shift, ctrl, !ctrl);
+    }
+    true
+}
+
+/// Move the cursor to the next row whose label starts with the characters typed so far.
+fn find_as_you_type(world: &mut World, tree: Entity, key: char) -> bool {
+    if key.is_control() {
+        return false;
+    }
+    let now = world.resource::<Time>().elapsed_seconds();
+    let Some(mut state) = world.get_mut::<TreeViewState>(tree) else {
+        return false;
+    };
+    if now - state.search_time > TYPEAHEAD_TIMEOUT {
+        state.search.clear();
+    }
+    // Space is only part of a search once one has started; otherwise it selects.
+    if key == ' ' && state.search.is_empty() {
+        return false;
+    }
+    state.search.extend(key.to_lowercase());
+    state.search_time = now;
+    let search = state.search.clone();
+
+    let rows = &world.get::<TreeViewRows>(tree).unwrap().0;
+    let cursor = world.get::<SelectionCursor<u64>>(tree).unwrap().cursor;
+    let current = cursor.and_then(|id| rows.iter().position(|row| !row.loading && row.id == id));
+    // A new search starts after the cursor; typing more characters refines the current match.
+    let start = match current {
+        Some(index) if search.chars().count() > 1 => index + rows.len() - 1,
+        Some(index) => index,
+        None => rows.len().saturating_sub(1),
+    };
+    if let Some(index) = find_prefix(rows, start, &search) {
+        let node = rows[index].id;
+        move_cursor::<TreeViewProps>(world, tree, node, false, false, true);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controls::selection::range;
+
+    fn sample() -> Vec<TreeNode> {
+        vec![
+            TreeNode::new(1, "Assets").children(vec![
+                TreeNode::new(2, "Models").children(vec![TreeNode::new(3, "Tree.glb")]),
+                TreeNode::new(4, "Textures").lazy(),
+                TreeNode::new(5, "Readme"),
+            ]),
+            TreeNode::new(6, "Scenes"),
+        ]
+    }
+
+    fn rows(expanded: &[u64]) -> Vec<TreeRow> {
+        let expanded: HashSet<u64> = expanded.iter().copied().collect();
+        let mut rows = Vec::new();
+        flatten(&sample(), &expanded, None, 0, &mut rows);
+        rows
+    }
+
+    fn ids(rows: &[TreeRow]) -> Vec<(u64, usize, bool)> {
+        rows.iter().map(|r| (r.id, r.depth, r.loading)).collect()
+    }
+
+    #[test]
+    fn test_flatten() {
+        assert_eq!(ids(&rows(&[])), vec![(1, 0, false), (6, 0, false)]);
+        assert_eq!(
+            ids(&rows(&[1, 4, 3])),
+            vec![
+                (1, 0, false),
+                (2, 1, false),
+                (4, 1, false),
+                (4, 2, true),
+                (5, 1, false),
+                (6, 0, false),
+            ]
+        );
+        let rows = rows(&[1]);
+        assert_eq!(rows[0].child_count, 3);
+        assert!(rows[2].lazy && rows[2].expandable);
+        assert_eq!((rows[3].parent, rows[3].index), (Some(1), 2));
+    }
+
+    #[test]
+    fn test_range_and_find() {
+        let rows = rows(&[1, 4]);
+        let keys: Vec<u64> = rows
+            .iter()
+            .filter(|row| !row.loading)
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(range(&keys, 5, 2), vec![2, 4, 5]);
+        assert_eq!(range(&keys, 6, 6), vec![6]);
+        assert_eq!(find_prefix(&rows, 0, "s"), Some(5));
+        assert_eq!(find_prefix(&rows, 5, "a"), Some(0));
+        assert_eq!(find_prefix(&rows, 0, "TE".to_lowercase().as_str()), Some(2));
+        assert_eq!(find_prefix(&rows, 0, "x"), None);
+    }
+
+    #[test]
+    fn test_drop_move() {
+        let rows = rows(&[1, 2]);
+        let moved = |dragging: &[u64], target, position| {
+            drop_move(&rows, dragging, target, position).map(|m| (m.parent, m.index))
+        };
+        assert_eq!(moved(&[6], 3, DropPosition::Before), Some((Some(2), 0)));
+        assert_eq!(moved(&[6], 2, DropPosition::Inside), Some((Some(2), 1)));
+        assert_eq!(moved(&[6], 5, DropPosition::After), Some((Some(1), 3)));
+        assert_eq!(moved(&[3], 1, DropPosition::Before), Some((None, 0)));
+        // A node can't be dropped onto itself or its descendants.
+        assert_eq!(moved(&[1], 3, DropPosition::After), None);
+        assert_eq!(moved(&[2, 6], 2, DropPosition::Inside), None);
+    }
+}
@@ -0,0 +1,576 @@
+use std::{ops::Range, sync::Arc};
+
+use bevy::{prelude::*, ui};
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+
+use crate::{colors, scrolling::ScrollArea};
+
+use super::ScrollView;
+
+fn style_virtual_view(ss: &mut StyleBuilder) {
+    ss.background_color(colors::U1)
+        .border_radius(5.0)
+        .padding(3);
+}
+
+fn style_virtual_scroll_content(ss: &mut StyleBuilder) {
+    ss.display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Column)
+        .width(ui::Val::Percent(100.))
+        .border(0);
+}
+
+fn style_virtual_content(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Relative).flex_shrink(0.);
+}
+
+fn style_virtual_item(ss: &mut StyleBuilder) {
+    ss.position(ui::PositionType::Absolute)
+        .display(ui::Display::Flex)
+        .flex_direction(ui::FlexDirection::Row)
+        .align_items(ui::AlignItems::Center)
+        .overflow(ui::OverflowAxis::Clip);
+}
+
+/// Builds the view for an item of a [`VirtualListView`] or [`VirtualGridView`], given the
+/// index of the item.
+///
+/// Builders are compared by identity, so every visible item is rebuilt whenever the view is
+/// given a different builder, even one made from the same function. Because the `item()`
+/// methods make a new builder each time they are called, a parent which re-renders often should
+/// create the builder once, using [`Cx::create_memo`], and pass it to `item_builder()` instead.
+#[derive(Clone)]
+pub struct ItemBuilder(Arc<dyn Fn(usize) -> ViewChild + Send + Sync>);
+
+impl ItemBuilder {
+    /// Create a new item builder from a function.
+    pub fn new<V: IntoViewChild, F: Fn(usize) -> V + Send + Sync + 'static>(item_fn: F) -> Self {
+        Self(Arc::new(move |index| item_fn(index).into_view_child()))
+    }
+
+    pub(crate) fn build(&self, index: usize) -> ViewChild {
+        (self.0)(index)
+    }
+}
+
+impl Default for ItemBuilder {
+    fn default() -> Self {
+        Self(Arc::new(|_| ViewChild::default()))
+    }
+}
+
+impl PartialEq for ItemBuilder {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The heights of the rows of a [`VirtualListView`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum RowHeights {
+    /// Every row has the same height.
+    Fixed(f32),
+
+    /// The height of each row. Rows past the end of the list have zero height.
+    Variable(Arc<[f32]>),
+}
+
+impl Default for RowHeights {
+    fn default() -> Self {
+        Self::Fixed(20.)
+    }
+}
+
+/// Position of an item as `(left, top, width, height)`. A width of `None` means the item spans
+/// the full width of the view.
+type ItemRect = (f32, f32, Option<f32>, f32);
+
+/// How the items of a virtualized view are laid out.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum VirtualLayout {
+    /// Full-width rows of varying height. `offsets[i]` is the top of row `i`, and the last
+    /// entry is the total height.
+    Rows(Arc<Vec<f32>>),
+
+    /// Cells of a fixed size, as many to a row as will fit. A width of zero means one
+    /// full-width cell per row.
+    Cells { count: usize, size: Vec2, gap: f32 },
+}
+
+/// The range of items which should be built, and the number of columns they were laid out in.
+#[derive(Component, Clone, PartialEq, Default, Debug)]
+pub(crate) struct VirtualWindow {
+    pub(crate) range: Range<usize>,
+    pub(crate) columns: usize,
+}
+
+/// Properties of a virtualized view, stored on its scroll area.
+#[derive(Component, Clone, PartialEq)]
+pub(crate) struct VirtualViewProps {
+    pub(crate) layout: VirtualLayout,
+    pub(crate) overscan: usize,
+}
+
+impl VirtualLayout {
+    fn variable(count: usize, heights: &[f32]) -> Self {
+        let mut offsets = Vec::with_capacity(count + 1);
+        let mut top = 0.;
+        offsets.push(top);
+        for index in 0..count {
+            top += heights.get(index).copied().unwrap_or(0.);
+            offsets.push(top);
+        }
+        Self::Rows(Arc::new(offsets))
+    }
+
+    fn columns(&self, width: f32) -> usize {
+        match self {
+            Self::Cells { size, gap, .. } if size.x > 0. => {
+                (((width + gap) / (size.x + gap)).floor() as usize).max(1)
+            }
+            _ => 1,
+        }
+    }
+
+    fn total_height(&self, columns: usize) -> f32 {
+        match self {
+            Self::Rows(offsets) => offsets.last().copied().unwrap_or(0.),
+            Self::Cells { count, size, gap } => {
+                let rows = count.div_ceil(columns.max(1));
+                (rows as f32 * (size.y + gap) - gap).max(0.)
+            }
+        }
+    }
+
+    /// The items which overlap the region from `top` to `top + height`, plus `overscan` rows
+    /// before and after.
+    fn window(&self, width: f32, top: f32, height: f32, overscan: usize) -> VirtualWindow {
+        let columns = self.columns(width);
+        let bottom = top + height;
+        let range = match self {
+            Self::Rows(offsets) => {
+                let count = offsets.len() - 1;
+                let first = offsets[1..].partition_point(|row_bottom| *row_bottom <= top);
+                let last = offsets[..count].partition_point(|row_top| *row_top < bottom);
+                first.saturating_sub(overscan)..(last + overscan).min(count)
+            }
+            Self::Cells { count, size, gap } => {
+                let pitch = (size.y + gap).max(1.);
+                let rows = count.div_ceil(columns);
+                let first = ((top / pitch).floor().max(0.) as usize).saturating_sub(overscan);
+                let last = (((bottom / pitch).ceil().max(0.) as usize) + overscan).min(rows);
+                (first * columns).min(*count)..(last * columns).min(*count)
+            }
+        };
+        VirtualWindow { range, columns }
+    }
+
+    /// Position of an item within the content of the view.
+    fn item_rect(&self, index: usize, columns: usize) -> ItemRect {
+        match self {
+            Self::Rows(offsets) => (
+                0.,
+                offsets[index],
+                None,
+                offsets[index + 1] - offsets[index],
+            ),
+            Self::Cells { size, gap, .. } => {
+                let columns = columns.max(1);
+                let (row, column) = (index / columns, index % columns);
+                let width = (size.x > 0.).then_some(size.x);
+                (
+                    column as f32 * (size.x + gap),
+                    row as f32 * (size.y + gap),
+                    width,
+                    size.y,
+                )
+            }
+        }
+    }
+}
+
+/// Assigns the items in `range` to slots, returned in slot order as `(slot, index)`. While the
+/// window keeps the same length, an item keeps its slot as the view scrolls, and each item which
+/// scrolls into view takes over the slot of the one which scrolled out.
+fn assign_slots(range: Range<usize>) -> Vec<(usize, usize)> {
+    let len = range.len();
+    let mut slots: Vec<(usize, usize)> = range.map(|index| (index % len, index)).collect();
+    slots.sort_unstable();
+    slots
+}
+
+/// Builds the scroll view shared by the virtualized views. Only the items in the current
+/// [`VirtualWindow`] are built, each in one of a pool of slots. As the view scrolls, the slot
+/// entities are recycled: items which stay visible are left alone, and the slots of items which
+/// scroll out of view are rebuilt in place with the items which scroll into view.
+fn virtual_scroll_view(
+    cx: &mut Cx,
+    name: &'static str,
+    layout: VirtualLayout,
+    overscan: usize,
+    style: StyleHandle,
+    item: ItemBuilder,
+) -> ScrollView {
+    let scroll = cx.create_entity();
+    let mut entt = cx.world_mut().entity_mut(scroll);
+    if !entt.contains::<VirtualWindow>() {
+        entt.insert(VirtualWindow::default());
+    }
+    cx.create_effect(
+        move |world, props: VirtualViewProps| {
+            world.entity_mut(scroll).insert(props);
+        },
+        VirtualViewProps {
+            layout: layout.clone(),
+            overscan,
+        },
+    );
+
+    let window = cx
+        .use_component::<VirtualWindow>(scroll)
+        .cloned()
+        .unwrap_or_default();
+    let total = layout.total_height(window.columns);
+    let items: Vec<(usize, usize, ItemRect)> = assign_slots(window.range.clone())
+        .into_iter()
+        .map(|(slot, index)| (slot, index, layout.item_rect(index, window.columns)))
+        .collect();
+
+    ScrollView::new()
+        .entity(Some(scroll))
+        .scroll_enable_y(true)
+        .style((style_virtual_view, style))
+        .content_style(style_virtual_scroll_content)
+        .children(
+            Element::<NodeBundle>::new()
+                .named(name)
+                .style(style_virtual_content)
+                .style_dyn(
+                    |height, sb| {
+                        sb.height(height);
+                    },
+                    total,
+                )
+                .children(For::keyed(
+                    &items,
+                    |(slot, _, _)| *slot,
+                    move |(_, index, rect)| VirtualItem {
+                        index: *index,
+                        rect: *rect,
+                        item: item.clone(),
+                    },
+                )),
+        )
+}
+
+/// A slot in a virtualized view. When the slot is given a different item, its content is
+/// rebuilt in place, so the views returned by the item builder shouldn't keep state of their own
+/// which belongs to a particular item.
+#[derive(Clone, PartialEq)]
+struct VirtualItem {
+    index: usize,
+    rect: ItemRect,
+    item: ItemBuilder,
+}
+
+impl ViewTemplate for VirtualItem {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        Element::<NodeBundle>::for_entity(id)
+            .style(style_virtual_item)
+            .style_dyn(
+                |(left, top, width, height), sb| {
+                    sb.left(left).top(top).height(height);
+                    match width {
+                        Some(width) => sb.width(width),
+                        None => sb.right(0),
+                    };
+                },
+                self.rect,
+            )
+            .children(Dynamic::new(self.item.build(self.index)))
+    }
+}
+
+/// A scrollable list which only builds the rows that are visible.
+///
+/// Rather than taking child views, the list calls `item` with the index of each row it needs
+/// to display, so it can show very large data sets. The row views are recycled as the list
+/// scrolls, by rebuilding them with the index of a different row. Rows are either all the same height, or
+/// have their heights given by [`RowHeights::Variable`].
+#[derive(Clone, PartialEq, Default)]
+pub struct VirtualListView {
+    /// The number of rows.
+    pub item_count: usize,
+
+    /// The height of the rows.
+    pub row_heights: RowHeights,
+
+    /// The number of rows to build above and below the visible region.
+    pub overscan: usize,
+
+    /// Builds the view for a row.
+    pub item: ItemBuilder,
+
+    /// Additional styles to be applied to the list view.
+    pub style: StyleHandle,
+}
+
+impl VirtualListView {
+    /// Create a new virtualized list view.
+    pub fn new() -> Self {
+        Self {
+            overscan: 4,
+            ..default()
+        }
+    }
+
+    /// Set the number of rows.
+    pub fn item_count(mut self, item_count: usize) -> Self {
+        self.item_count = item_count;
+        self
+    }
+
+    /// Set the height of every row.
+    pub fn row_height(mut self, height: f32) -> Self {
+        self.row_heights = RowHeights::Fixed(height);
+        self
+    }
+
+    /// Set the height of each row individually.
+    pub fn row_heights(mut self, heights: impl Into<Arc<[f32]>>) -> Self {
+        self.row_heights = RowHeights::Variable(heights.into());
+        self
+    }
+
+    /// Set the number of rows to build above and below the visible region.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Set the function which builds the view for a row.
+    pub fn item<V: IntoViewChild, F: Fn(usize) -> V + Send + Sync + 'static>(
+        mut self,
+        item_fn: F,
+    ) -> Self {
+        self.item = ItemBuilder::new(item_fn);
+        self
+    }
+
+    /// Set the builder for the view of a row. Unlike [`item`](Self::item), this lets the
+    /// builder be kept between renders, so that the visible rows aren't rebuilt each time.
+    pub fn item_builder(mut self, item: ItemBuilder) -> Self {
+        self.item = item;
+        self
+    }
+
+    /// Set additional styles to be applied to the list view.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for VirtualListView {
+    type View = ScrollView;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let count = self.item_count;
+        let layout = match &self.row_heights {
+            RowHeights::Fixed(height) => VirtualLayout::Cells {
+                count,
+                size: Vec2::new(0., *height),
+                gap: 0.,
+            },
+            // Only recompute the row offsets when the heights change.
+            RowHeights::Variable(heights) => cx.create_memo(
+                |_, (count, heights): (usize, Arc<[f32]>)| VirtualLayout::variable(count, &heights),
+                (count, heights.clone()),
+            ),
+        };
+        virtual_scroll_view(
+            cx,
+            "VirtualListView",
+            layout,
+            self.overscan,
+            self.style.clone(),
+            self.item.clone(),
+        )
+    }
+}
+
+/// A scrollable grid of same-sized cells which only builds the cells that are visible.
+///
+/// The number of columns is as many cells as fit in the width of the view.
+#[derive(Clone, PartialEq, Default)]
+pub struct VirtualGridView {
+    /// The number of cells.
+    pub item_count: usize,
+
+    /// The size of each cell.
+    pub item_size: Vec2,
+
+    /// The space between cells, both horizontally and vertically.
+    pub gap: f32,
+
+    /// The number of rows of cells to build above and below the visible region.
+    pub overscan: usize,
+
+    /// Builds the view for a cell.
+    pub item: ItemBuilder,
+
+    /// Additional styles to be applied to the grid view.
+    pub style: StyleHandle,
+}
+
+impl VirtualGridView {
+    /// Create a new virtualized grid view.
+    pub fn new() -> Self {
+        Self {
+            item_size: Vec2::splat(64.),
+            gap: 4.,
+            overscan: 1,
+            ..default()
+        }
+    }
+
+    /// Set the number of cells.
+    pub fn item_count(mut self, item_count: usize) -> Self {
+        self.item_count = item_count;
+        self
+    }
+
+    /// Set the size of each cell.
+    pub fn item_size(mut self, item_size: Vec2) -> Self {
+        self.item_size = item_size;
+        self
+    }
+
+    /// Set the space between cells.
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Set the number of rows of cells to build above and below the visible region.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Set the function which builds the view for a cell.
+    pub fn item<V: IntoViewChild, F: Fn(usize) -> V + Send + Sync + 'static>(
+        mut self,
+        item_fn: F,
+    ) -> Self {
+        self.item = ItemBuilder::new(item_fn);
+        self
+    }
+
+    /// Set the builder for the view of a cell. Unlike [`item`](Self::item), this lets the
+    /// builder be kept between renders, so that the visible cells aren't rebuilt each time.
+    pub fn item_builder(mut self, item: ItemBuilder) -> Self {
+        self.item = item;
+        self
+    }
+
+    /// Set additional styles to be applied to the grid view.
+    pub fn style<S: StyleTuple + 'static>(mut self, style: S) -> Self {
+        self.style = style.into_handle();
+        self
+    }
+}
+
+impl ViewTemplate for VirtualGridView {
+    type View = ScrollView;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        virtual_scroll_view(
+            cx,
+            "VirtualGridView",
+            VirtualLayout::Cells {
+                count: self.item_count,
+                size: self.item_size,
+                gap: self.gap,
+            },
+            self.overscan,
+            self.style.clone(),
+            self.item.clone(),
+        )
+    }
+}
+
+/// Updates the range of items which virtualized views should build, from their scroll position.
+pub(crate) fn update_virtual_windows(
+    mut query: Query<(&VirtualViewProps, &ScrollArea, &mut VirtualWindow)>,
+) {
+    for (props, scroll, mut window) in query.iter_mut() {
+        let next = props.layout.window(
+            scroll.visible_size.x,
+            scroll.scroll_top,
+            scroll.visible_size.y,
+            props.overscan,
+        );
+        window.set_if_neq(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_rows() {
+        let layout = VirtualLayout::Cells {
+            count: 1000,
+            size: Vec2::new(0., 20.),
+            gap: 0.,
+        };
+        assert_eq!(layout.total_height(1), 20000.);
+        let window = layout.window(300., 0., 100., 2);
+        assert_eq!((window.range, window.columns), (0..7, 1));
+        assert_eq!(layout.window(300., 210., 100., 2).range, 8..18);
+        assert_eq!(layout.window(300., 19900., 100., 2).range, 993..1000);
+        assert_eq!(layout.item_rect(3, 1), (0., 60., None, 20.));
+    }
+
+    #[test]
+    fn test_variable_rows() {
+        let layout = VirtualLayout::variable(5, &[10., 30., 10., 50.]);
+        assert_eq!(layout.total_height(1), 100.);
+        assert_eq!(layout.window(100., 0., 15., 0).range, 0..2);
+        assert_eq!(layout.window(100., 40., 10., 0).range, 2..3);
+        assert_eq!(layout.window(100., 45., 20., 1).range, 1..5);
+        assert_eq!(layout.item_rect(3, 1), (0., 50., None, 50.));
+        assert_eq!(layout.item_rect(4, 1), (0., 100., None, 0.));
+    }
+
+    #[test]
+    fn test_assign_slots() {
+        assert_eq!(assign_slots(0..3), [(0, 0), (1, 1), (2, 2)]);
+        // Scrolling down by one row moves only the row which left the window.
+        assert_eq!(assign_slots(1..4), [(0, 3), (1, 1), (2, 2)]);
+        assert_eq!(assign_slots(2..5), [(0, 3), (1, 4), (2, 2)]);
+        assert!(assign_slots(5..5).is_empty());
+    }
+
+    #[test]
+    fn test_grid() {
+        let layout = VirtualLayout::Cells {
+            count: 10,
+            size: Vec2::new(50., 40.),
+            gap: 10.,
+        };
+        assert_eq!(layout.columns(170.), 3);
+        assert_eq!(layout.columns(20.), 1);
+        assert_eq!(layout.total_height(3), 190.);
+        let window = layout.window(170., 55., 40., 0);
+        assert_eq!((window.range, window.columns), (3..6, 3));
+        assert_eq!(layout.window(170., 0., 1000., 1).range, 0..10);
+        assert_eq!(layout.item_rect(4, 3), (60., 50., Some(50.), 40.));
+    }
+}
@@ -0,0 +1,271 @@
+use std::{f64::consts, iter::Peekable, str::CharIndices};
+
+/// Evaluate an arithmetic expression typed into a numeric field.
+///
+/// The expression may use `+ - * / ^`, parentheses, the constants `pi`, `tau` and `e`, and the
+/// functions `sqrt abs sin cos tan floor ceil round min max`. Numbers and parenthesized
+/// expressions can be followed by a unit suffix from `units`, which multiplies them by the
+/// unit's scale. An expression starting with `+=`, `-=`, `*=` or `/=` is applied to `current`.
+///
+/// Returns `None` if the expression is invalid, or the result is not a finite number.
+pub(crate) fn evaluate(input: &str, current: f64, units: &[(&str, f64)]) -> Option<f64> {
+    let input = input.trim();
+    let (op, expr) = match input.get(..2) {
+        Some(prefix @ ("+=" | "-=" | "*=" | "/=")) => (prefix.chars().next(), &input[2..]),
+        _ => (None, input),
+    };
+    let mut parser = Parser {
+        input: expr,
+        chars: expr.char_indices().peekable(),
+        units,
+    };
+    let value = parser.expr()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return None;
+    }
+    let result = match op {
+        Some('+') => current + value,
+        Some('-') => current - value,
+        Some('*') => current * value,
+        Some('/') => current / value,
+        _ => value,
+    };
+    result.is_finite().then_some(result)
+}
+
+/// Recursive-descent parser which evaluates as it goes.
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    units: &'a [(&'a str, f64)],
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    /// Consume the next non-whitespace character if it is `expected`.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|(_, c)| *c == expected).is_some()
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    /// term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                value /= self.unary()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    /// unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Option<f64> {
+        if self.eat('-') {
+            Some(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    /// power := atom ('^' unary)?
+    fn power(&mut self) -> Option<f64> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            Some(base.powf(self.unary()?))
+        } else {
+            Some(base)
+        }
+    }
+
+    /// atom := number unit? | '(' expr ')' unit? | constant | function '(' args ')'
+    fn atom(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+        let &(start, c) = self.chars.peek()?;
+        if c == '(' {
+            self.chars.next();
+            let value = self.expr()?;
+            if !self.eat(')') {
+                return None;
+            }
+            self.unit(value)
+        } else if c.is_ascii_digit() || c == '.' {
+            let value = self.number(start)?;
+            self.unit(value)
+        } else if is_ident_char(c) {
+            let name = self.ident();
+            match name {
+                "pi" => Some(consts::PI),
+                "tau" => Some(consts::TAU),
+                "e" => Some(consts::E),
+                _ => self.function(name),
+            }
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self, start: usize) -> Option<f64> {
+        let mut end = start;
+        let mut exponent = false;
+        while let Some(&(i, c)) = self.chars.peek() {
+            let exponent_sign = exponent
+                && (c == '-' || c == '+')
+                && matches!(self.input[..i].chars().next_back(), Some('e' | 'E'));
+            if c.is_ascii_digit() || c == '.' || exponent_sign {
+                self.chars.next();
+            } else if (c == 'e' || c == 'E') && !exponent && self.exponent_follows(i) {
+                exponent = true;
+                self.chars.next();
+            } else {
+                break;
+            }
+            end = i + c.len_utf8();
+        }
+        self.input[start..end].parse().ok()
+    }
+
+    /// True if the 'e' at `index` starts an exponent, rather than a unit or the constant `e`.
+    fn exponent_follows(&self, index: usize) -> bool {
+        let mut rest = self.input[index + 1..].chars();
+        match rest.next() {
+            Some('-' | '+') => rest.next().is_some_and(|c| c.is_ascii_digit()),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    fn ident(&mut self) -> &'a str {
+        let start = self.chars.peek().map_or(self.input.len(), |(i, _)| *i);
+        let mut end = start;
+        while let Some((i, c)) = self.chars.next_if(|(_, c)| is_ident_char(*c)) {
+            end = i + c.len_utf8();
+        }
+        &self.input[start..end]
+    }
+
+    /// Apply an optional unit suffix to a value.
+    fn unit(&mut self, value: f64) -> Option<f64> {
+        self.skip_whitespace();
+        let Some(&(start, c)) = self.chars.peek() else {
+            return Some(value);
+        };
+        if !is_ident_char(c) {
+            return Some(value);
+        }
+        // Peek at the identifier without consuming it, since it may not be a unit.
+        let name: String = self.input[start..]
+            .chars()
+            .take_while(|c| is_ident_char(*c))
+            .collect();
+        let (_, scale) = self.units.iter().find(|(unit, _)| *unit == name)?;
+        self.ident();
+        Some(value * scale)
+    }
+
+    fn function(&mut self, name: &str) -> Option<f64> {
+        if !self.eat('(') {
+            return None;
+        }
+        let mut args = vec![self.expr()?];
+        while self.eat(',') {
+            args.push(self.expr()?);
+        }
+        if !self.eat(')') {
+            return None;
+        }
+        match (name, args.as_slice()) {
+            ("sqrt", [x]) => Some(x.sqrt()),
+            ("abs", [x]) => Some(x.abs()),
+            ("sin", [x]) => Some(x.sin()),
+            ("cos", [x]) => Some(x.cos()),
+            ("tan", [x]) => Some(x.tan()),
+            ("floor", [x]) => Some(x.floor()),
+            ("ceil", [x]) => Some(x.ceil()),
+            ("round", [x]) => Some(x.round()),
+            ("min", [x, rest @ ..]) => Some(rest.iter().fold(*x, |a, b| a.min(*b))),
+            ("max", [x, rest @ ..]) => Some(rest.iter().fold(*x, |a, b| a.max(*b))),
+            _ => None,
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '°'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: &[(&str, f64)] = &[("m", 1.), ("cm", 0.01), ("deg", 1.), ("°", 1.)];
+
+    fn eval(input: &str) -> Option<f64> {
+        evaluate(input, 10., UNITS)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(eval("1 + 2 * 3"), Some(7.));
+        assert_eq!(eval("(1 + 2) * 3"), Some(9.));
+        assert_eq!(eval("-2^2"), Some(-4.));
+        assert_eq!(eval("2^-1"), Some(0.5));
+        assert_eq!(eval("1/4"), Some(0.25));
+        assert_eq!(eval("1.5e2"), Some(150.));
+        assert_eq!(eval("2e-1"), Some(0.2));
+        assert_eq!(eval(".5"), Some(0.5));
+        assert_eq!(eval("2*pi"), Some(consts::TAU));
+        assert_eq!(eval("max(1, 4, 2) + sqrt(9)"), Some(7.));
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        assert_eq!(eval("+=0.5"), Some(10.5));
+        assert_eq!(eval("-= 2 * 2"), Some(6.));
+        assert_eq!(eval("*=3"), Some(30.));
+        assert_eq!(eval("/=4"), Some(2.5));
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(eval("150cm"), Some(1.5));
+        assert_eq!(eval("1 m + 50 cm"), Some(1.5));
+        assert_eq!(eval("(1 + 1)cm"), Some(0.02));
+        assert_eq!(eval("90°"), Some(90.));
+        assert_eq!(eval("3 ft"), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(eval(""), None);
+        assert_eq!(eval("1 +"), None);
+        assert_eq!(eval("(1"), None);
+        assert_eq!(eval("1 2"), None);
+        assert_eq!(eval("1/0"), None);
+        assert_eq!(eval("foo(1)"), None);
+        assert_eq!(eval("sqrt"), None);
+    }
+}
@@ -2,21 +2,26 @@ use bevy::{
     a11y::Focus,
     app::{App, Plugin, Update},
     ecs::{
+        change_detection::DetectChangesMut,
         component::Component,
         entity::Entity,
         event::{Event, EventReader, EventWriter},
-        query::{Added, With, Without},
+        query::{Added, Has, With, Without},
+        schedule::IntoSystemConfigs,
         system::{Query, Res, ResMut, Resource, SystemParam},
     },
     hierarchy::{Children, Parent},
     input::{
-        keyboard::{KeyCode, KeyboardInput},
+        keyboard::{Key, KeyCode, KeyboardInput},
         ButtonInput, ButtonState,
     },
     log::*,
     ui::Node,
 };
 use bevy_mod_picking::prelude::{EntityEvent, EventListenerPlugin};
+use bevy_mod_stylebuilder::{InteractionState, StyleBuilderSystemSet};
+
+use crate::controls::Disabled;
 
 /// Bubbling event for key character input.
 #[derive(Clone, Event, EntityEvent)]
@@ -31,8 +36,12 @@ pub struct KeyCharEvent {
 }
 
 /// Bubbling event for key press.
+///
+/// Further modifier fields may be added in the future, so events constructed outside of this
+/// crate must use [`KeyPressEvent::new`].
 #[derive(Clone, Event, EntityEvent)]
 #[can_bubble]
+#[non_exhaustive]
 pub struct KeyPressEvent {
     /// The target of the event
     #[target]
@@ -46,6 +55,55 @@ pub struct KeyPressEvent {
 
     /// Whether the shift key is held down.
     pub shift: bool,
+
+    /// Whether the control key (or the command key on macOS) is held down.
+    pub ctrl: bool,
+}
+
+impl KeyPressEvent {
+    /// Construct a new key press event for the given target, with no modifiers held down.
+    pub fn new(target: Entity, key_code: KeyCode) -> Self {
+        Self {
+            target,
+            key_code,
+            repeat: false,
+            shift: false,
+            ctrl: false,
+        }
+    }
+
+    /// Set whether this is a repeated key.
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Set whether the shift key is held down.
+    pub fn with_shift(mut self, shift: bool) -> Self {
+        self.shift = shift;
+        self
+    }
+
+    /// Set whether the control key (or the command key on macOS) is held down.
+    pub fn with_ctrl(mut self, ctrl: bool) -> Self {
+        self.ctrl = ctrl;
+        self
+    }
+}
+
+/// True if the platform's shortcut modifier is held down: the control key, or on macOS also
+/// the command key.
+pub fn ctrl_pressed(keys: &ButtonInput<KeyCode>) -> bool {
+    #[cfg(target_os = "macos")]
+    let modifiers = [
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ];
+    #[cfg(not(target_os = "macos"))]
+    let modifiers = [KeyCode::ControlLeft, KeyCode::ControlRight];
+    keys.any_pressed(modifiers)
 }
 
 /// A component which indicates that an entity wants to participate in tab navigation.
@@ -71,6 +129,12 @@ pub struct AutoFocus;
 #[derive(Debug, Default, Component, Copy, Clone)]
 pub struct DefaultKeyListener;
 
+/// Indicates that this widget uses the Tab key itself while focused, for example to insert
+/// indentation, so that Tab does not move the focus. Shift+Tab still moves to the previous
+/// widget, so that keyboard users are not trapped.
+#[derive(Debug, Default, Component, Copy, Clone)]
+pub struct CaptureTab;
+
 /// Resource that controls whether the focus indicators are visible or not. Generally
 /// these are only visible after the Tab key has been pressed, and become hidden when
 /// the user interacts with the UI in with the pointing device.
@@ -219,6 +283,46 @@ impl TabNavigation<'_, '_> {
         focusable.get(next).map(|(e, _)| e).copied()
     }
 
+    /// Navigate among the items of a composite widget, such as the tabs of a tab bar, for
+    /// arrow-key navigation. The items are the outermost entities under `root` which have a
+    /// [`TabIndex`], in tree order. Unlike [`navigate`](Self::navigate), items with a negative
+    /// tab index are included, so that a widget can leave only its current item in the
+    /// sequential tab order. Navigation wraps around at either end.
+    pub fn navigate_within(
+        &self,
+        root: Entity,
+        focus: Option<Entity>,
+        action: NavAction,
+    ) -> Option<Entity> {
+        let mut items: Vec<Entity> = Vec::new();
+        self.gather_items(&mut items, root);
+        if items.is_empty() {
+            return None;
+        }
+        let index = items.iter().position(|e| Some(*e) == focus);
+        let count = items.len();
+        let next = match (index, action) {
+            (Some(idx), NavAction::Next) => (idx + 1) % count,
+            (Some(idx), NavAction::Previous) => (idx + count - 1) % count,
+            (None, NavAction::Next) | (_, NavAction::First) => 0,
+            (None, NavAction::Previous) | (_, NavAction::Last) => count - 1,
+        };
+        items.get(next).copied()
+    }
+
+    /// Gather the outermost entities with a tab index under `parent`, in tree order.
+    fn gather_items(&self, out: &mut Vec<Entity>, parent: Entity) {
+        if let Ok((_, _, Some(children))) = self.tabindex.get(parent) {
+            for child in children.iter() {
+                match self.tabindex.get(*child) {
+                    Ok((entity, Some(_), _)) => out.push(entity),
+                    Ok(_) => self.gather_items(out, *child),
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+
     /// Gather all focusable entities in tree order.
     fn gather_focusable(&self, out: &mut Vec<(Entity, TabIndex)>, parent: Entity) {
         if let Ok((entity, tabindex, children)) = self.tabindex.get(parent) {
@@ -268,11 +372,16 @@ fn handle_tab(
     key: Res<ButtonInput<KeyCode>>,
     mut focus: ResMut<Focus>,
     mut visible: ResMut<FocusVisible>,
+    capture: Query<(), With<CaptureTab>>,
 ) {
     if key.just_pressed(KeyCode::Tab) {
+        let shift = key.pressed(KeyCode::ShiftLeft) || key.pressed(KeyCode::ShiftRight);
+        if !shift && focus.0.is_some_and(|e| capture.contains(e)) {
+            return;
+        }
         let next = nav.navigate(
             focus.0,
-            if key.pressed(KeyCode::ShiftLeft) || key.pressed(KeyCode::ShiftRight) {
+            if shift {
                 NavAction::Previous
             } else {
                 NavAction::Next
@@ -293,51 +402,52 @@ fn handle_text_input(
     mut char_writer: EventWriter<KeyCharEvent>,
     default_listener: Query<Entity, With<DefaultKeyListener>>,
 ) {
-    if let Some(focus_elt) = focus.0 {
-        for ev in key_events.read() {
-            if ev.state == ButtonState::Pressed {
-                let press_event = KeyPressEvent {
-                    target: focus_elt,
-                    key_code: ev.key_code,
-                    repeat: !key.just_pressed(ev.key_code),
-                    shift: key.pressed(KeyCode::ShiftLeft) || key.pressed(KeyCode::ShiftRight),
-                };
-                press_writer.send(press_event);
-
-                if let bevy::input::keyboard::Key::Character(ref ch) = ev.logical_key {
-                    let ev = KeyCharEvent {
-                        target: focus_elt,
-                        key: ch.chars().next().unwrap(),
-                    };
-                    char_writer.send(ev);
-                }
-            }
+    let shift = key.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = ctrl_pressed(&key);
+    let targets: Vec<Entity> = match focus.0 {
+        Some(focus_elt) => vec![focus_elt],
+        None => default_listener.iter().collect(),
+    };
+    for ev in key_events.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
         }
-    } else {
-        for listener in default_listener.iter() {
-            for ev in key_events.read() {
-                if ev.state == ButtonState::Pressed {
-                    let press_event = KeyPressEvent {
-                        target: listener,
-                        key_code: ev.key_code,
-                        repeat: !key.just_pressed(ev.key_code),
-                        shift: key.pressed(KeyCode::ShiftLeft) || key.pressed(KeyCode::ShiftRight),
-                    };
-                    press_writer.send(press_event);
-
-                    if let bevy::input::keyboard::Key::Character(ref ch) = ev.logical_key {
-                        let ev = KeyCharEvent {
-                            target: listener,
-                            key: ch.chars().next().unwrap(),
-                        };
-                        char_writer.send(ev);
-                    }
+        for target in targets.iter().copied() {
+            press_writer.send(
+                KeyPressEvent::new(target, ev.key_code)
+                    .with_repeat(!key.just_pressed(ev.key_code))
+                    .with_shift(shift)
+                    .with_ctrl(ctrl),
+            );
+
+            // Characters typed while control is held are shortcuts, not text. The space bar is
+            // not a character key, so text controls handle it as a key press.
+            if let Key::Character(ref ch) = ev.logical_key {
+                if let Some(key) = ch.chars().next().filter(|_| !ctrl) {
+                    char_writer.send(KeyCharEvent { target, key });
                 }
             }
         }
     }
 }
 
+/// Updates the parts of the [`InteractionState`] which depend on the focus ring visibility
+/// and the [`Disabled`] marker, so that `on_focus_visible` and `on_disabled` styles work.
+fn update_interaction_states(
+    focus: Res<Focus>,
+    visible: Res<FocusVisible>,
+    mut query: Query<(Entity, &mut InteractionState, Has<Disabled>)>,
+) {
+    for (entity, mut state, disabled) in query.iter_mut() {
+        let next = InteractionState {
+            focus_visible: visible.0 && focus.0 == Some(entity),
+            disabled,
+            ..*state
+        };
+        state.set_if_neq(next);
+    }
+}
+
 /// Plugin for handling keyboard input.
 pub struct KeyboardInputPlugin;
 
@@ -350,6 +460,12 @@ impl Plugin for KeyboardInputPlugin {
         .init_resource::<FocusVisible>()
         .add_event::<KeyPressEvent>()
         .add_event::<KeyCharEvent>()
-        .add_systems(Update, (handle_auto_focus, handle_tab, handle_text_input));
+        .add_systems(Update, (handle_auto_focus, handle_tab, handle_text_input))
+        .add_systems(
+            Update,
+            update_interaction_states
+                .after(handle_tab)
+                .before(StyleBuilderSystemSet),
+        );
     }
 }
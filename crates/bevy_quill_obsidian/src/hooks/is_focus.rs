@@ -8,7 +8,7 @@ use bevy_quill_core::Cx;
 use crate::focus::FocusVisible;
 
 /// True if the given entity is a descendant of the given ancestor.
-fn is_descendant(world: &World, e: &Entity, ancestor: &Entity) -> bool {
+pub(crate) fn is_descendant(world: &World, e: &Entity, ancestor: &Entity) -> bool {
     let mut ha = e;
     loop {
         if ha == ancestor {
@@ -1,6 +1,6 @@
 mod bistable_transition;
 mod element_rect;
-mod is_focus;
+pub(crate) mod is_focus;
 pub(crate) mod is_hover;
 
 pub use bistable_transition::{
@@ -16,6 +16,9 @@ pub mod controls;
 /// Module containing utilities for creating custom window cursors.
 pub mod cursor;
 
+/// Evaluator for expressions typed into numeric fields.
+mod expression;
+
 /// Utilities for tabbing between widgets.
 pub mod focus;
 
@@ -31,6 +34,9 @@ mod materials;
 /// Utilities for managing scrolling views.
 pub mod scrolling;
 
+/// Text editing state shared by the text input controls.
+mod text_editing;
+
 /// Module containing standard sizes.
 pub mod size;
 
@@ -47,9 +53,13 @@ pub mod prelude {
 }
 
 use bevy_mod_picking::prelude::EventListenerPlugin;
-use controls::{MenuCloseEvent, RecentColors};
+use bevy_mod_stylebuilder::{FontFamilies, FontWeight};
+use controls::{
+    ActiveContextMenu, ActiveTooltip, MenuCloseEvent, RecentColors, TooltipSettings, TooltipTimer,
+};
 use materials::{GradientRectMaterial, SliderRectMaterial, SwatchRectMaterial};
 pub use rounded_corners::RoundedCorners;
+pub use text_editing::TextClipboard;
 
 pub use hooks::is_hover::UseIsHover as _;
 
@@ -73,6 +83,7 @@ impl Plugin for ObsidianUiPlugin {
         embedded_asset!(app, "assets/icons/chevron_down.png");
         embedded_asset!(app, "assets/icons/chevron_left.png");
         embedded_asset!(app, "assets/icons/chevron_right.png");
+        embedded_asset!(app, "assets/icons/chevron_up.png");
         embedded_asset!(app, "assets/icons/close.png");
         embedded_asset!(app, "assets/icons/disc.png");
         embedded_asset!(app, "assets/icons/gradient_thumb.png");
@@ -103,10 +114,51 @@ impl Plugin for ObsidianUiPlugin {
                 scrolling::handle_scroll_events,
                 scrolling::update_scroll_positions,
                 hooks::is_hover::update_hover_states,
+                controls::update_tooltips.after(hooks::is_hover::update_hover_states),
+                controls::open_context_menus,
                 cursor::update_cursor,
+                controls::commit_text_inputs_on_blur,
+                controls::update_text_input_layout
+                    .after(bevy_mod_stylebuilder::StyleBuilderSystemSet),
+                controls::commit_text_areas_on_blur,
+                controls::update_text_area_layout
+                    .after(bevy_mod_stylebuilder::StyleBuilderSystemSet)
+                    .after(scrolling::update_scroll_positions),
+                controls::update_tab_bars.after(bevy_mod_stylebuilder::StyleBuilderSystemSet),
+                controls::scroll_views_to_cursor::<controls::TreeViewState>,
+                controls::scroll_views_to_cursor::<controls::ListViewState>,
+                controls::update_virtual_windows.after(scrolling::update_scroll_positions),
+                controls::update_data_grid_headers.after(scrolling::update_scroll_positions),
             ),
         )
+        .init_resource::<TooltipSettings>()
+        .init_resource::<TooltipTimer>()
+        .init_resource::<ActiveTooltip>()
+        .init_resource::<ActiveContextMenu>()
         .init_resource::<RecentColors>()
+        .init_resource::<FontFamilies>()
+        .init_resource::<TextClipboard>()
+        .add_systems(Startup, register_fonts)
         .add_systems(PostUpdate, floating::position_floating);
     }
 }
+
+/// Register the embedded Open Sans faces as the [`typography::FONT_FAMILY`] font family.
+fn register_fonts(mut families: ResMut<FontFamilies>, server: Res<AssetServer>) {
+    const PREFIX: &str = "embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans";
+    for (suffix, weight, italic) in [
+        ("Regular", FontWeight::Normal, false),
+        ("Italic", FontWeight::Normal, true),
+        ("Medium", FontWeight::Medium, false),
+        ("MediumItalic", FontWeight::Medium, true),
+        ("Bold", FontWeight::Bold, false),
+        ("BoldItalic", FontWeight::Bold, true),
+    ] {
+        families.add(
+            typography::FONT_FAMILY,
+            weight,
+            italic,
+            server.load(format!("{}-{}.ttf", PREFIX, suffix)),
+        );
+    }
+}
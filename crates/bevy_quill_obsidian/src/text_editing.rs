@@ -0,0 +1,578 @@
+use std::ops::Range;
+
+use ab_glyph::{Font as _, FontArc, ScaleFont as _};
+use bevy::prelude::*;
+
+/// Maximum time between two clicks for them to count as a double-click, in seconds.
+pub(crate) const DOUBLE_CLICK_TIME: f32 = 0.4;
+
+/// Application-wide clipboard used by the text editing controls for copy, cut and paste.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct TextClipboard(pub String);
+
+/// The text being edited, along with the selection. Positions are byte offsets into the text,
+/// and always lie on character boundaries. The `caret` is the end of the selection which moves
+/// when extending the selection, and `anchor` is the end which stays put; when they are equal,
+/// nothing is selected.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TextEditState {
+    pub(crate) text: String,
+    pub(crate) anchor: usize,
+    pub(crate) caret: usize,
+}
+
+impl TextEditState {
+    /// Replace the text, keeping the selection where possible.
+    pub(crate) fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+            self.anchor = self.clamp(self.anchor);
+            self.caret = self.clamp(self.caret);
+        }
+    }
+
+    /// The selected range, in ascending order.
+    pub(crate) fn selection(&self) -> Range<usize> {
+        self.anchor.min(self.caret)..self.anchor.max(self.caret)
+    }
+
+    /// True if any text is selected.
+    pub(crate) fn has_selection(&self) -> bool {
+        self.anchor != self.caret
+    }
+
+    /// The selected text.
+    pub(crate) fn selected_text(&self) -> &str {
+        &self.text[self.selection()]
+    }
+
+    /// Move the caret to the given position, extending the selection if `extend` is true.
+    pub(crate) fn move_to(&mut self, position: usize, extend: bool) {
+        self.caret = self.clamp(position);
+        if !extend {
+            self.anchor = self.caret;
+        }
+    }
+
+    /// Move the caret one character (or one word) to the left. If there is a selection and it
+    /// is not being extended, the caret moves to the start of the selection instead.
+    pub(crate) fn move_left(&mut self, extend: bool, word: bool) {
+        if self.has_selection() && !extend && !word {
+            self.move_to(self.selection().start, false);
+        } else if word {
+            self.move_to(self.prev_word_boundary(self.caret), extend);
+        } else {
+            self.move_to(self.prev_char_boundary(self.caret), extend);
+        }
+    }
+
+    /// Move the caret one character (or one word) to the right. If there is a selection and it
+    /// is not being extended, the caret moves to the end of the selection instead.
+    pub(crate) fn move_right(&mut self, extend: bool, word: bool) {
+        if self.has_selection() && !extend && !word {
+            self.move_to(self.selection().end, false);
+        } else if word {
+            self.move_to(self.next_word_boundary(self.caret), extend);
+        } else {
+            self.move_to(self.next_char_boundary(self.caret), extend);
+        }
+    }
+
+    /// Select the entire text.
+    pub(crate) fn select_all(&mut self) {
+        self.anchor = 0;
+        self.caret = self.text.len();
+    }
+
+    /// Select the word surrounding the given position.
+    pub(crate) fn select_word(&mut self, position: usize) {
+        let position = self.clamp(position);
+        let start = self.text[..position]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_word_char(*c))
+            .last()
+            .map_or(position, |(i, _)| i);
+        let end = self.text[position..]
+            .char_indices()
+            .find(|(_, c)| !is_word_char(*c))
+            .map_or(self.text.len(), |(i, _)| position + i);
+        self.anchor = start;
+        self.caret = end;
+    }
+
+    /// Replace the selection with the given text, which is truncated if the result would be
+    /// longer than `max_length` characters. Returns true if the text changed.
+    pub(crate) fn insert(&mut self, text: &str, max_length: Option<usize>) -> bool {
+        let selection = self.selection();
+        let text = match max_length {
+            Some(max_length) => {
+                let remaining =
+                    self.text.chars().count() - self.text[selection.clone()].chars().count();
+                let available = max_length.saturating_sub(remaining);
+                match text.char_indices().nth(available) {
+                    Some((end, _)) => &text[..end],
+                    None => text,
+                }
+            }
+            None => text,
+        };
+        if text.is_empty() && selection.is_empty() {
+            return false;
+        }
+        self.text.replace_range(selection.clone(), text);
+        self.move_to(selection.start + text.len(), false);
+        true
+    }
+
+    /// Delete the selection, or else the character (or word) before the caret. Returns true if
+    /// the text changed.
+    pub(crate) fn backspace(&mut self, word: bool) -> bool {
+        if !self.has_selection() {
+            self.move_left(true, word);
+        }
+        self.insert("", None)
+    }
+
+    /// Delete the selection, or else the character (or word) after the caret. Returns true if
+    /// the text changed.
+    pub(crate) fn delete(&mut self, word: bool) -> bool {
+        if !self.has_selection() {
+            self.move_right(true, word);
+        }
+        self.insert("", None)
+    }
+
+    /// Remove the selected text and return it.
+    pub(crate) fn cut(&mut self) -> Option<String> {
+        if !self.has_selection() {
+            return None;
+        }
+        let text = self.selected_text().to_string();
+        self.insert("", None);
+        Some(text)
+    }
+
+    /// The number of characters before the given byte position.
+    pub(crate) fn char_index(&self, position: usize) -> usize {
+        self.text[..position].chars().count()
+    }
+
+    /// The byte position of the given character index.
+    pub(crate) fn byte_index(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn clamp(&self, position: usize) -> usize {
+        let mut position = position.min(self.text.len());
+        while !self.text.is_char_boundary(position) {
+            position -= 1;
+        }
+        position
+    }
+
+    fn prev_char_boundary(&self, position: usize) -> usize {
+        self.text[..position]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_char_boundary(&self, position: usize) -> usize {
+        self.text[position..]
+            .chars()
+            .next()
+            .map_or(position, |c| position + c.len_utf8())
+    }
+
+    /// The start of the word before the position, skipping any whitespace or punctuation.
+    fn prev_word_boundary(&self, position: usize) -> usize {
+        let mut chars = self.text[..position].char_indices().rev().peekable();
+        while chars.next_if(|(_, c)| !is_word_char(*c)).is_some() {}
+        let mut start = chars.peek().map_or(0, |(i, _)| *i);
+        while let Some((i, _)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+            start = i;
+        }
+        start
+    }
+
+    /// The end of the word after the position, skipping any whitespace or punctuation.
+    fn next_word_boundary(&self, position: usize) -> usize {
+        let mut chars = self.text[position..].char_indices().peekable();
+        while chars.next_if(|(_, c)| !is_word_char(*c)).is_some() {}
+        while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+        chars.peek().map_or(self.text.len(), |(i, _)| position + i)
+    }
+}
+
+/// Maximum number of undo steps which are kept.
+const MAX_UNDO: usize = 100;
+
+/// The kind of an edit. Consecutive edits of the same kind are merged into a single undo step,
+/// so that undo removes a run of typing rather than a single character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditKind {
+    /// Typing characters.
+    Insert,
+    /// Deleting characters with backspace or delete.
+    Delete,
+    /// Any other edit, such as a paste or line break, which is never merged.
+    Other,
+}
+
+/// Undo and redo stacks for a [`TextEditState`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EditHistory {
+    undo: Vec<TextEditState>,
+    redo: Vec<TextEditState>,
+    /// The kind of the last edit, if further edits of the same kind should be merged with it.
+    merge: Option<EditKind>,
+}
+
+impl EditHistory {
+    /// Record the state before an edit of the given kind.
+    pub(crate) fn record(&mut self, before: &TextEditState, kind: EditKind) {
+        if kind == EditKind::Other || self.merge != Some(kind) {
+            self.undo.push(before.clone());
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+        self.merge = Some(kind);
+        self.redo.clear();
+    }
+
+    /// Stop merging edits into the current undo step; called when the caret is moved.
+    pub(crate) fn break_merge(&mut self) {
+        self.merge = None;
+    }
+
+    /// Restore the state before the last edit. Returns true if there was anything to undo.
+    pub(crate) fn undo(&mut self, state: &mut TextEditState) -> bool {
+        self.merge = None;
+        match self.undo.pop() {
+            Some(prev) => {
+                self.redo.push(std::mem::replace(state, prev));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply the last undone edit. Returns true if there was anything to redo.
+    pub(crate) fn redo(&mut self, state: &mut TextEditState) -> bool {
+        self.merge = None;
+        match self.redo.pop() {
+            Some(next) => {
+                self.undo.push(std::mem::replace(state, next));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget all edits, for example when the text is replaced from outside.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Compute the horizontal offset of each character boundary when the text is laid out on a
+/// single line, including kerning. The result has one more entry than there are characters.
+pub(crate) fn char_offsets(font: &FontArc, font_size: f32, text: &str) -> Vec<f32> {
+    let font = font.as_scaled(font_size);
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut x = 0.;
+    let mut prev = None;
+    offsets.push(x);
+    for c in text.chars() {
+        let glyph = font.glyph_id(c);
+        if let Some(prev) = prev {
+            x += font.kern(prev, glyph);
+        }
+        x += font.h_advance(glyph);
+        offsets.push(x);
+        prev = Some(glyph);
+    }
+    offsets
+}
+
+/// Find the index of the character boundary nearest to the given offset.
+pub(crate) fn nearest_offset(offsets: &[f32], x: f32) -> usize {
+    offsets
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - x).abs().total_cmp(&(*b - x).abs()))
+        .map_or(0, |(i, _)| i)
+}
+
+/// The height of a line of text, which is the distance between consecutive baselines.
+pub(crate) fn line_height(font: &FontArc, font_size: f32) -> f32 {
+    let font = font.as_scaled(font_size);
+    font.height() + font.line_gap()
+}
+
+/// A line of text as laid out for display.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VisualLine {
+    /// The range of the text on this line, not including the line break.
+    pub(crate) range: Range<usize>,
+    /// Horizontal offset of each character boundary on the line, starting at zero.
+    pub(crate) offsets: Vec<f32>,
+    /// Index of the line in the unwrapped text.
+    pub(crate) line: usize,
+    /// True if this line is the continuation of a wrapped line.
+    pub(crate) continued: bool,
+}
+
+/// Break the text into lines, wrapping lines which are wider than `width`. Lines are broken
+/// after whitespace where possible, and otherwise between characters; trailing whitespace is
+/// allowed to overhang. `measure` returns the character offsets of a line, as computed by
+/// [`char_offsets`].
+pub(crate) fn wrap_lines(
+    text: &str,
+    width: f32,
+    measure: impl Fn(&str) -> Vec<f32>,
+) -> Vec<VisualLine> {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    for (index, line) in text.split('\n').enumerate() {
+        let offsets = measure(line);
+        let chars: Vec<(usize, char)> = line.char_indices().collect();
+        let byte = |i: usize| line_start + chars.get(i).map_or(line.len(), |(b, _)| *b);
+        let mut push = |start: usize, end: usize| {
+            let base = offsets[start];
+            lines.push(VisualLine {
+                range: byte(start)..byte(end),
+                offsets: offsets[start..=end].iter().map(|x| x - base).collect(),
+                line: index,
+                continued: start > 0,
+            });
+        };
+
+        let mut start = 0;
+        let mut after_space = None;
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i].1.is_whitespace() {
+                after_space = Some(i + 1);
+            } else if i > start && offsets[i + 1] - offsets[start] > width {
+                let end = after_space.filter(|b| *b > start).unwrap_or(i);
+                push(start, end);
+                start = end;
+                after_space = None;
+                i = end;
+                continue;
+            }
+            i += 1;
+        }
+        push(start, chars.len());
+        line_start += line.len() + 1;
+    }
+    lines
+}
+
+/// Find the index of the visual line containing the given position. A position at a wrap
+/// point belongs to the start of the following line.
+pub(crate) fn line_at(lines: &[VisualLine], position: usize) -> usize {
+    lines
+        .partition_point(|line| line.range.start <= position)
+        .saturating_sub(1)
+}
+
+/// The last position on a visual line at which the caret is displayed on that line. This is
+/// the end of the line, except for wrapped lines, where the end belongs to the next line.
+pub(crate) fn line_end(lines: &[VisualLine], index: usize, text: &str) -> usize {
+    let line = &lines[index];
+    match lines.get(index + 1) {
+        Some(next) if next.continued => text[..line.range.end]
+            .char_indices()
+            .next_back()
+            .map_or(line.range.end, |(i, _)| i.max(line.range.start)),
+        _ => line.range.end,
+    }
+}
+
+/// Find the position on a visual line nearest to the given horizontal offset.
+pub(crate) fn position_on_line(lines: &[VisualLine], index: usize, x: f32, text: &str) -> usize {
+    let line = &lines[index];
+    let chars = nearest_offset(&line.offsets, x);
+    let position = text[line.range.start..]
+        .char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(i, _)| line.range.start + i);
+    position.min(line_end(lines, index, text))
+}
+
+/// The horizontal offset of a position on its visual line.
+pub(crate) fn offset_on_line(
+    lines: &[VisualLine],
+    index: usize,
+    position: usize,
+    text: &str,
+) -> f32 {
+    let line = &lines[index];
+    let chars = text[line.range.start..position.max(line.range.start)]
+        .chars()
+        .count();
+    line.offsets[chars.min(line.offsets.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(text: &str, anchor: usize, caret: usize) -> TextEditState {
+        TextEditState {
+            text: text.to_string(),
+            anchor,
+            caret,
+        }
+    }
+
+    #[test]
+    fn test_move() {
+        let mut s = state("héllo", 0, 0);
+        s.move_right(false, false);
+        assert_eq!(s.caret, 1);
+        s.move_right(true, false);
+        assert_eq!((s.anchor, s.caret), (1, 3));
+        assert_eq!(s.selected_text(), "é");
+        s.move_left(false, false);
+        assert_eq!((s.anchor, s.caret), (1, 1));
+        s.move_to(100, false);
+        assert_eq!(s.caret, 6);
+    }
+
+    #[test]
+    fn test_word_jumps() {
+        let mut s = state("one two, three", 0, 0);
+        s.move_right(false, true);
+        assert_eq!(s.caret, 3);
+        s.move_right(false, true);
+        assert_eq!(s.caret, 7);
+        s.move_right(true, true);
+        assert_eq!(s.caret, 14);
+        assert_eq!(s.selected_text(), ", three");
+        s.move_left(false, true);
+        assert_eq!(s.caret, 9);
+        s.move_left(false, true);
+        assert_eq!(s.caret, 4);
+        s.select_word(5);
+        assert_eq!(s.selected_text(), "two");
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut s = state("hello", 1, 4);
+        assert!(s.insert("ipp", None));
+        assert_eq!(s.text, "hippo");
+        assert_eq!((s.anchor, s.caret), (4, 4));
+        assert!(s.insert("potamus", Some(8)));
+        assert_eq!(s.text, "hipppoto");
+        assert!(!s.insert("x", Some(8)));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut s = state("one two", 7, 7);
+        assert!(s.backspace(false));
+        assert_eq!(s.text, "one tw");
+        assert!(s.backspace(true));
+        assert_eq!(s.text, "one ");
+        s.move_to(0, false);
+        assert!(s.delete(true));
+        assert_eq!(s.text, " ");
+        assert!(!s.backspace(false));
+        s.select_all();
+        assert_eq!(s.cut(), Some(" ".to_string()));
+        assert_eq!(s.text, "");
+        assert_eq!(s.cut(), None);
+    }
+
+    #[test]
+    fn test_char_offsets() {
+        let font = FontArc::try_from_slice(include_bytes!(
+            "assets/fonts/Open_Sans/static/OpenSans-Medium.ttf"
+        ))
+        .unwrap();
+        let offsets = char_offsets(&font, 16., "a b");
+        assert_eq!(offsets.len(), 4);
+        assert_eq!(offsets[0], 0.);
+        assert!(offsets[1] > 0.);
+        // Spaces have an advance, even though they have no outline.
+        assert!(offsets[2] > offsets[1]);
+        assert!(offsets[3] > offsets[2]);
+        assert_eq!(nearest_offset(&offsets, offsets[2] + 0.1), 2);
+        assert_eq!(nearest_offset(&offsets, -5.), 0);
+        assert_eq!(nearest_offset(&offsets, 1000.), 3);
+    }
+
+    /// Measure text as if every character were 10 pixels wide.
+    fn monospace(text: &str) -> Vec<f32> {
+        (0..=text.chars().count()).map(|i| i as f32 * 10.).collect()
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let text = "one two three\n\nabcdefgh";
+        let lines = wrap_lines(text, 75., monospace);
+        let ranges: Vec<&str> = lines.iter().map(|l| &text[l.range.clone()]).collect();
+        assert_eq!(ranges, ["one two ", "three", "", "abcdefg", "h"]);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|l| (l.line, l.continued))
+                .collect::<Vec<_>>(),
+            [(0, false), (0, true), (1, false), (2, false), (2, true)]
+        );
+        assert_eq!(lines[1].offsets, [0., 10., 20., 30., 40., 50.]);
+
+        assert_eq!(line_at(&lines, 0), 0);
+        assert_eq!(line_at(&lines, 8), 1);
+        assert_eq!(line_at(&lines, 13), 1);
+        assert_eq!(line_at(&lines, 14), 2);
+        assert_eq!(line_at(&lines, text.len()), 4);
+        assert_eq!(line_end(&lines, 0, text), 7);
+        assert_eq!(line_end(&lines, 1, text), 13);
+        assert_eq!(position_on_line(&lines, 0, 1000., text), 7);
+        assert_eq!(position_on_line(&lines, 1, 22., text), 10);
+        assert_eq!(offset_on_line(&lines, 1, 10, text), 20.);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = EditHistory::default();
+        let mut s = state("", 0, 0);
+        for c in ["a", "b"] {
+            let before = s.clone();
+            s.insert(c, None);
+            history.record(&before, EditKind::Insert);
+        }
+        history.break_merge();
+        let before = s.clone();
+        s.insert("c", None);
+        history.record(&before, EditKind::Insert);
+        assert_eq!(s.text, "abc");
+
+        assert!(history.undo(&mut s));
+        assert_eq!(s.text, "ab");
+        assert!(history.undo(&mut s));
+        assert_eq!(s.text, "");
+        assert!(!history.undo(&mut s));
+        assert!(history.redo(&mut s));
+        assert_eq!((s.text.as_str(), s.caret), ("ab", 2));
+
+        let before = s.clone();
+        s.backspace(false);
+        history.record(&before, EditKind::Delete);
+        assert!(!history.redo(&mut s));
+    }
+}
@@ -1,13 +1,18 @@
-use bevy_mod_stylebuilder::{StyleBuilder, StyleBuilderFont};
+use bevy_mod_stylebuilder::{FontWeight, StyleBuilder, StyleBuilderFont};
+
+/// Name of the font family used by the Obsidian widgets.
+pub const FONT_FAMILY: &str = "Open Sans";
 
 /// Default text style for UI.
 pub fn text_default(ss: &mut StyleBuilder) {
-    ss.font("embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Medium.ttf")
+    ss.font_family(FONT_FAMILY)
+        .font_weight(FontWeight::Medium)
         .font_size(16);
 }
 
 /// When we need to emphasize a label
 pub fn text_strong(ss: &mut StyleBuilder) {
-    ss.font("embedded://bevy_quill_obsidian/assets/fonts/Open_Sans/static/OpenSans-Bold.ttf")
+    ss.font_family(FONT_FAMILY)
+        .font_weight(FontWeight::Bold)
         .font_size(16);
 }
@@ -1,5 +1,5 @@
 use core::panic;
-use std::sync::Arc;
+use std::{any::TypeId, sync::Arc};
 
 use bevy::{
     prelude::*,
@@ -9,12 +9,27 @@ use bevy::{
 };
 use bevy_quill_core::Cx;
 
+/// Identity of an inspected item: its type, and the entity for components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InspectableId {
+    /// The type of the item.
+    pub type_id: TypeId,
+    /// The entity which owns the item, if any.
+    pub entity: Option<Entity>,
+}
+
 /// Trait that represents an item that can be inspected
 #[allow(unused_variables)]
 pub trait InspectableRoot: Send + Sync {
     /// The name of the item being inspected
     fn name(&self, cx: &Cx) -> String;
 
+    /// Identifies the item being inspected, so that the inspector can reset its state when
+    /// the target changes. If `None`, the state is kept when switching targets.
+    fn id(&self) -> Option<InspectableId> {
+        None
+    }
+
     /// The reflect data for a path within the reflected item.
     fn reflect_path<'a>(&self, cx: &'a Cx, path: &ParsedPath) -> Option<&'a dyn Reflect>;
 
@@ -51,6 +66,13 @@ impl<T: Resource + Reflect> InspectableRoot for InspectableResource<T> {
         res.reflect_short_type_path().to_string()
     }
 
+    fn id(&self) -> Option<InspectableId> {
+        Some(InspectableId {
+            type_id: TypeId::of::<T>(),
+            entity: None,
+        })
+    }
+
     fn reflect_path<'a>(&self, cx: &'a Cx, path: &ParsedPath) -> Option<&'a dyn Reflect> {
         let res = cx.use_resource::<T>();
         match res.reflect_path(path) {
@@ -105,6 +127,13 @@ impl<T: Component + Reflect> InspectableRoot for InspectableComponent<T> {
         cmp.reflect_short_type_path().to_string()
     }
 
+    fn id(&self) -> Option<InspectableId> {
+        Some(InspectableId {
+            type_id: TypeId::of::<T>(),
+            entity: Some(self.entity),
+        })
+    }
+
     fn reflect_path<'a>(&self, cx: &'a Cx, path: &ParsedPath) -> Option<&'a dyn Reflect> {
         let cmp = cx.use_component::<T>(self.entity).unwrap();
         match cmp.reflect_path(path) {
@@ -233,3 +262,22 @@ impl PartialEq for Inspectable {
             }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspectable_id() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let transform = |e| InspectableComponent::<Transform>::new(e).id();
+        assert_eq!(transform(a), transform(a));
+        assert_ne!(transform(a), transform(b));
+        assert_ne!(transform(a), InspectableComponent::<Name>::new(a).id());
+        assert_ne!(
+            InspectableResource::<Time>::default().id(),
+            InspectableResource::<AmbientLight>::default().id()
+        );
+    }
+}
@@ -58,15 +58,20 @@ impl ViewTemplate for Inspector {
             can_remove: true,
             attributes: None,
         });
-        InspectorPanel::new()
-            .title((
-                self.target.name(cx),
-                Spacer,
-                StructInspectorHeaderControls {
-                    target: inspectable.clone(),
-                },
-            ))
-            .body(self.create_fields(cx, inspectable))
-            .expanded(true)
+        // Key the panel by the identity of the target, so that the state of the panel and its
+        // fields (such as which sections are expanded) is reset when the target changes.
+        Keyed::new(
+            self.target.id(),
+            InspectorPanel::new()
+                .title((
+                    self.target.name(cx),
+                    Spacer,
+                    StructInspectorHeaderControls {
+                        target: inspectable.clone(),
+                    },
+                ))
+                .body(self.create_fields(cx, inspectable))
+                .expanded(true),
+        )
     }
 }
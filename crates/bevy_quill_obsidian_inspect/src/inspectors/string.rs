@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_mod_stylebuilder::*;
+use bevy_quill_core::*;
+use bevy_quill_obsidian::controls::{TextArea, TextInput};
+
+use crate::{templates::field_label::FieldLabel, Inspectable, Multiline};
+
+#[derive(Clone)]
+pub struct StringFieldInspector(pub(crate) Arc<Inspectable>);
+
+impl PartialEq for StringFieldInspector {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl ViewTemplate for StringFieldInspector {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let field = self.0.clone();
+        let value = match field.reflect(cx) {
+            Some(value) if value.is::<String>() => value.downcast_ref::<String>().unwrap().clone(),
+            _ => String::new(),
+        };
+        let multiline = field
+            .attributes
+            .is_some_and(|attrs| attrs.contains::<Multiline>());
+
+        let field = self.0.clone();
+        let on_change = cx.create_callback(move |value: In<String>, world: &mut World| {
+            field.set_value(world, value.as_reflect());
+        });
+        (
+            FieldLabel {
+                field: self.0.clone(),
+            },
+            // Don't need `Cond` here because condition is not reactive; reflection data
+            // is constant.
+            match multiline {
+                true => TextArea::new()
+                    .value(value)
+                    .on_change(on_change)
+                    .style(|ss: &mut StyleBuilder| {
+                        ss.height(72);
+                    })
+                    .into_view_child(),
+                false => TextInput::new()
+                    .value(value)
+                    .on_change(on_change)
+                    .into_view_child(),
+            },
+        )
+    }
+}
@@ -6,7 +6,11 @@ use bevy::{
 use bevy_mod_picking::prelude::*;
 use bevy_mod_stylebuilder::*;
 use bevy_quill::*;
-use bevy_quill_obsidian::{colors, controls::ListView, typography::text_strong};
+use bevy_quill_obsidian::{
+    colors,
+    controls::{ListRow, ListSelectionMode, ListView},
+    typography::text_strong,
+};
 
 use crate::{
     graph::{GraphNode, NodeSelected},
@@ -54,20 +58,73 @@ impl ViewTemplate for CatalogView {
 
     fn create(&self, cx: &mut bevy_quill::Cx) -> Self::View {
         let catalog = cx.use_resource::<OperatorCatalog>();
+        let selected = cx.use_resource::<SelectedCatalogEntry>();
+        // Rows are keyed by their position in the catalog.
+        let selection: Vec<u64> = catalog
+            .0
+            .iter()
+            .position(|entry| Some(entry.path) == selected.0)
+            .map(|index| index as u64)
+            .into_iter()
+            .collect();
+        let entries: Vec<(u64, CatalogEntry)> = catalog
+            .0
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, entry)| (index as u64, entry))
+            .collect();
+        let on_selection_change = cx.create_callback(
+            |keys: In<Vec<u64>>,
+             catalog: Res<OperatorCatalog>,
+             mut selected: ResMut<SelectedCatalogEntry>,
+             mut graph_nodes: Query<&mut NodeSelected, With<GraphNode>>| {
+                clear_node_selection(&mut graph_nodes);
+                selected.0 = keys
+                    .first()
+                    .and_then(|key| catalog.0.get(*key as usize))
+                    .map(|entry| entry.path);
+            },
+        );
+        // Clicking or activating the entry which is already selected doesn't change the list's
+        // selection, but should still take the selection away from the graph.
+        let on_activate = cx.create_callback(
+            |_key: In<u64>, mut graph_nodes: Query<&mut NodeSelected, With<GraphNode>>| {
+                clear_node_selection(&mut graph_nodes);
+            },
+        );
         ListView::new()
             .style(style_catalog)
-            .children(For::each(catalog.0.clone(), |entry| {
-                CatalogRow(entry.clone())
+            .selection(selection)
+            .selection_mode(ListSelectionMode::Single)
+            .on_selection_change(on_selection_change)
+            .on_activate(on_activate)
+            .children(For::each(entries, |(key, entry)| {
+                ListRow::new(*key)
+                    .style(style_catalog_row)
+                    .children(CatalogRow(entry.clone()))
             }))
     }
 }
 
 fn style_catalog_row(ss: &mut StyleBuilder) {
-    ss.align_self(ui::AlignSelf::Stretch)
+    ss.align_self(ui::AlignSelf::Stretch);
+}
+
+fn style_catalog_row_content(ss: &mut StyleBuilder) {
+    ss.flex_grow(1.)
         .display(ui::Display::Flex)
         .flex_direction(ui::FlexDirection::Row);
 }
 
+fn clear_node_selection(graph_nodes: &mut Query<&mut NodeSelected, With<GraphNode>>) {
+    for mut selected in graph_nodes.iter_mut() {
+        if selected.0 {
+            selected.0 = false;
+        }
+    }
+}
+
 fn style_catalog_operator_class(ss: &mut StyleBuilder) {
     ss.width(ui::Val::Percent(30.))
         .padding(2)
@@ -89,33 +146,16 @@ struct CatalogRow(CatalogEntry);
 
 impl ViewTemplate for CatalogRow {
     type View = impl View;
-    fn create(&self, cx: &mut Cx) -> Self::View {
-        let selected = cx.use_resource::<SelectedCatalogEntry>();
-        let is_selected = Some(self.0.path) == selected.0;
-        let path = self.0.path;
+    fn create(&self, _cx: &mut Cx) -> Self::View {
         Element::<NodeBundle>::new()
-            .style(style_catalog_row)
-            .style_dyn(
-                |selected, sb| {
-                    sb.background_color(if selected {
-                        colors::TEXT_SELECT
-                    } else {
-                        colors::TRANSPARENT
-                    });
-                },
-                is_selected,
-            )
+            .style(style_catalog_row_content)
             .insert_dyn(
-                move |_| {
+                |_| {
                     On::<Pointer<Click>>::run(
-                        move |mut selected: ResMut<SelectedCatalogEntry>,
-                        mut graph_nodes: Query<&mut NodeSelected, With<GraphNode>>| {
-                        // Clear node selection
-                        for mut selected in graph_nodes.iter_mut() {
-                            selected.0 = false;
-                        }
-                        selected.0 = Some(path);
-                    })
+                        |mut graph_nodes: Query<&mut NodeSelected, With<GraphNode>>| {
+                            clear_node_selection(&mut graph_nodes);
+                        },
+                    )
                 },
                 (),
             )
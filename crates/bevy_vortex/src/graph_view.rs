@@ -1,12 +1,17 @@
 use crate::{
+    commands::DeleteSelectedCmd,
     graph::{Connection, GraphNode, GraphResource, NodeSelected, Terminal},
     operator::{DisplayName, DisplayWidth, OperatorInput, OperatorInputOnly, OperatorOutput},
     propedit::GraphNodePropertyEdit,
 };
-use bevy::{color::Color, prelude::*, reflect::TypeInfo, ui};
+use bevy::{color::Color, ecs::world::Command, prelude::*, reflect::TypeInfo, ui};
 use bevy_mod_stylebuilder::*;
 use bevy_quill::{prelude::*, Dynamic, IntoViewChild};
-use bevy_quill_obsidian::{colors, hooks::UseElementRect};
+use bevy_quill_obsidian::{
+    colors,
+    controls::{ContextMenu, MenuItem, MenuPopup},
+    hooks::UseElementRect,
+};
 use bevy_quill_obsidian_graph::{
     ConnectionAnchor, ConnectionTarget, EdgeDisplay, GraphDisplay, InputTerminalDisplay,
     NoTerminalDisplay, NodeDisplay, OutputTerminalDisplay,
@@ -68,6 +73,13 @@ impl ViewTemplate for GraphNodeView {
     fn create(&self, cx: &mut Cx) -> Self::View {
         let display_id = cx.create_entity();
         let node_id = self.0;
+        let delete = cx.create_callback(move |world: &mut World| {
+            let mut nodes = world.query_filtered::<(Entity, &mut NodeSelected), With<GraphNode>>();
+            for (node, mut selected) in nodes.iter_mut(world) {
+                selected.0 = node == node_id;
+            }
+            DeleteSelectedCmd.apply(world);
+        });
         let size = cx.use_element_size(display_id);
         let node = cx.use_component::<GraphNode>(node_id).unwrap();
         if node.size != size.as_ivec2() {
@@ -107,15 +119,25 @@ impl ViewTemplate for GraphNodeView {
             names
         };
 
-        NodeDisplay::new(display_id, node_id)
-            .position(node.position)
-            .width(display_width)
-            .title(node.title())
-            .selected(is_selected)
-            .children(For::each(field_names, move |field| GraphNodePropertyView {
-                node: node_id,
-                field,
-            }))
+        (
+            NodeDisplay::new(display_id, node_id)
+                .position(node.position)
+                .width(display_width)
+                .title(node.title())
+                .selected(is_selected)
+                .children(For::each(field_names, move |field| GraphNodePropertyView {
+                    node: node_id,
+                    field,
+                })),
+            ContextMenu::new(display_id).popup(
+                MenuPopup::new().children(
+                    MenuItem::new()
+                        .label("Delete")
+                        .accelerator("Del")
+                        .on_click(delete),
+                ),
+            ),
+        )
     }
 }
 
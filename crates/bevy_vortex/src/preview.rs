@@ -61,7 +61,8 @@ impl ViewTemplate for PreviewControls {
             .style(style_preview_controls)
             .children((
                 PreviewModeButtons,
-                IconButton::new("embedded://bevy_quill_obsidian/assets/icons/lock.png"),
+                IconButton::new("embedded://bevy_quill_obsidian/assets/icons/lock.png")
+                    .tooltip("Lock preview"),
                 Button::new().children("Source..."),
                 Button::new().children("Export..."),
             ))
@@ -80,6 +81,7 @@ impl ViewTemplate for PreviewModeButtons {
         ToolPalette::new().columns(7).children((
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/square.png")
                 .size(Vec2::splat(20.))
+                .tooltip("Square")
                 .corners(RoundedCorners::Left)
                 .selected(mode == PreviewMode::Square)
                 .on_click(
@@ -89,6 +91,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/square2x2.png")
                 .size(Vec2::splat(20.))
+                .tooltip("2x2 tiles")
                 .corners(RoundedCorners::None)
                 .selected(mode == PreviewMode::Square2X2)
                 .on_click(
@@ -98,6 +101,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/square3x3.png")
                 .size(Vec2::splat(20.))
+                .tooltip("3x3 tiles")
                 .corners(RoundedCorners::None)
                 .selected(mode == PreviewMode::Square3X3)
                 .on_click(
@@ -107,6 +111,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/sphere.png")
                 .size(Vec2::splat(20.))
+                .tooltip("Sphere")
                 .no_tint(true)
                 .corners(RoundedCorners::None)
                 .selected(mode == PreviewMode::Sphere)
@@ -117,6 +122,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/cuboid.png")
                 .size(Vec2::splat(20.))
+                .tooltip("Cuboid")
                 .no_tint(true)
                 .corners(RoundedCorners::None)
                 .selected(mode == PreviewMode::Cuboid)
@@ -127,6 +133,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/tetra.png")
                 .size(Vec2::splat(20.))
+                .tooltip("Tetrahedron")
                 .no_tint(true)
                 .corners(RoundedCorners::None)
                 .selected(mode == PreviewMode::Tetra)
@@ -137,6 +144,7 @@ impl ViewTemplate for PreviewModeButtons {
                 ),
             ToolIconButton::new("embedded://bevy_vortex/assets/icons/torus.png")
                 .size(Vec2::splat(20.))
+                .tooltip("Torus")
                 .no_tint(true)
                 .corners(RoundedCorners::Right)
                 .selected(mode == PreviewMode::Torus)
@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use bevy::prelude::*;
 use bevy_quill::*;
-use bevy_quill_obsidian_inspect::{InspectableResource, Inspector, Precision, ValueRange};
+use bevy_quill_obsidian_inspect::{
+    InspectableResource, Inspector, Multiline, Precision, ValueRange,
+};
 
 #[derive(Debug, Reflect, Clone, Default)]
 pub enum TestEnum {
@@ -20,6 +22,11 @@ pub enum TestEnum {
 pub struct TestStruct {
     pub selected: bool,
 
+    pub name: String,
+
+    #[reflect(@Multiline)]
+    pub notes: String,
+
     #[reflect(@ValueRange::<f32>(0.0..1.0))]
     pub scale: f32,
 
@@ -1,6 +1,8 @@
 //! Example of a simple UI layout
 #![feature(impl_trait_in_assoc_type)]
 
+use std::sync::Arc;
+
 use bevy::{color::palettes, prelude::*, ui};
 use bevy_mod_picking::DefaultPickingPlugins;
 use bevy_mod_stylebuilder::*;
@@ -8,10 +10,13 @@ use bevy_quill::*;
 use bevy_quill_obsidian::{
     colors,
     controls::{
-        Button, ButtonVariant, Checkbox, ColorGradient, Dialog, DialogFooter, DialogHeader,
-        GradientSlider, MenuButton, MenuDivider, MenuItem, MenuPopup, Slider, SpinBox, Swatch,
+        Button, ButtonVariant, Checkbox, ColorGradient, ContextMenu, DataColumn, DataGrid, Dialog,
+        DialogFooter, DialogHeader, GradientSlider, ItemBuilder, ListRow, ListSelectionMode,
+        ListView, MenuButton, MenuDivider, MenuItem, MenuPopup, Slider, SortDirection, SpinBox,
+        SubMenu, Swatch, TabBar, TabBehavior, TabItem, TabPanel, TextArea, TextInput, TreeChildren,
+        TreeMove, TreeNode, TreeSelectionMode, TreeView, VirtualGridView, VirtualListView,
     },
-    ObsidianUiPlugin,
+    typography, ObsidianUiPlugin,
 };
 
 fn style_test(ss: &mut StyleBuilder) {
@@ -79,9 +84,39 @@ impl ViewTemplate for ButtonsDemo {
             // info!("Checked: {}", *value);
         });
         let spin_value = cx.create_mutable::<f32>(50.);
+        let text_value = cx.create_mutable::<String>(String::new());
         let slider_value = cx.create_mutable::<f32>(50.);
         let color_value = cx.create_mutable::<Srgba>(Srgba::new(1.0, 0.0, 0.0, 1.0));
         let color = color_value.get(cx);
+        // Made once, so that the virtualized views aren't rebuilt when the demo re-renders.
+        let (log_line, swatch) = cx.create_memo(
+            |_, ()| {
+                (
+                    ItemBuilder::new(|index| format!("Log line {}", index + 1)),
+                    ItemBuilder::new(|index| {
+                        Swatch::new(Hsla::hsl((index * 7 % 360) as f32, 0.7, 0.5))
+                            .style(style_swatch)
+                    }),
+                )
+            },
+            (),
+        );
+        let tabs = cx.create_mutable::<Vec<TabItem>>(
+            [
+                "Scene",
+                "Assets",
+                "Console",
+                "Profiler",
+                "Animation",
+                "Settings",
+            ]
+            .into_iter()
+            .map(|label| TabItem::new(label).closable(label != "Scene"))
+            .collect(),
+        );
+        let selected_tab = cx.create_mutable::<usize>(0);
+        let tab_items = tabs.get_clone(cx);
+        let selected = selected_tab.get(cx).min(tab_items.len().saturating_sub(1));
         Element::<NodeBundle>::new()
             .insert_dyn(TargetCamera, self.camera)
             .style(style_test)
@@ -126,6 +161,111 @@ impl ViewTemplate for ButtonsDemo {
                                 spin_value.set(world, *value);
                             },
                         )),)),
+                "TextInput",
+                Element::<NodeBundle>::new().style(style_row).children((
+                    TextInput::new()
+                        .style(|sb: &mut StyleBuilder| {
+                            sb.width(160);
+                        })
+                        .placeholder("Name")
+                        .value(text_value.get_clone(cx))
+                        .on_change(cx.create_callback(
+                            move |value: In<String>, world: &mut World| {
+                                text_value.update(world, |mut text| *text = value.clone());
+                            },
+                        )),
+                    TextInput::new()
+                        .style(|sb: &mut StyleBuilder| {
+                            sb.width(100);
+                        })
+                        .placeholder("Password")
+                        .max_length(16)
+                        .password(true),
+                )),
+                "TextArea",
+                Element::<NodeBundle>::new().style(style_row).children(
+                    TextArea::new()
+                        .style(|sb: &mut StyleBuilder| {
+                            sb.width(300).height(120);
+                        })
+                        .placeholder("Notes")
+                        .line_numbers(true)
+                        .tab_behavior(TabBehavior::InsertSpaces(4)),
+                ),
+                "Tabs",
+                Element::<NodeBundle>::new()
+                    .style(|sb: &mut StyleBuilder| {
+                        sb.display(Display::Flex)
+                            .flex_direction(FlexDirection::Column)
+                            .width(300);
+                    })
+                    .children((
+                        TabBar::new()
+                            .tabs(tab_items.clone())
+                            .selected(selected)
+                            .on_select(cx.create_callback(
+                                move |index: In<usize>, world: &mut World| {
+                                    selected_tab.set(world, *index);
+                                },
+                            ))
+                            .on_close(cx.create_callback(
+                                move |index: In<usize>, world: &mut World| {
+                                    tabs.update(world, |mut tabs| {
+                                        tabs.remove(*index);
+                                    });
+                                    let selected = selected_tab.get(world);
+                                    if selected > *index {
+                                        selected_tab.set(world, selected - 1);
+                                    }
+                                },
+                            ))
+                            .on_reorder(cx.create_callback(
+                                move |moved: In<(usize, usize)>, world: &mut World| {
+                                    let (from, to) = *moved;
+                                    tabs.update(world, |mut tabs| {
+                                        let tab = tabs.remove(from);
+                                        tabs.insert(to, tab);
+                                    });
+                                    let selected = selected_tab.get(world);
+                                    if selected == from {
+                                        selected_tab.set(world, to);
+                                    } else if from < selected && selected <= to {
+                                        selected_tab.set(world, selected - 1);
+                                    } else if to <= selected && selected < from {
+                                        selected_tab.set(world, selected + 1);
+                                    }
+                                },
+                            )),
+                        TabPanel::new().children(
+                            tab_items
+                                .get(selected)
+                                .map_or(String::new(), |tab| format!("{} panel", tab.label)),
+                        ),
+                    )),
+                "ListView",
+                ListViewDemo,
+                "TreeView",
+                TreeViewDemo,
+                "DataGrid",
+                DataGridDemo,
+                "Virtualized",
+                Element::<NodeBundle>::new().style(style_row).children((
+                    VirtualListView::new()
+                        .style(|sb: &mut StyleBuilder| {
+                            sb.width(200).height(100);
+                        })
+                        .item_count(50_000)
+                        .row_height(18.)
+                        .item_builder(log_line),
+                    VirtualGridView::new()
+                        .style(|sb: &mut StyleBuilder| {
+                            sb.width(200).height(100);
+                        })
+                        .item_count(10_000)
+                        .item_size(Vec2::splat(24.))
+                        .gap(2.)
+                        .item_builder(swatch),
+                )),
                 "Slider",
                 Element::<NodeBundle>::new().style(style_row).children((
                     " Normal:",
@@ -216,6 +356,12 @@ impl ViewTemplate for ButtonsDemo {
                         .on_click(cx.create_callback(move |world: &mut World| {
                             dialog_open.set(world, true);
                         }))
+                        .tooltip((
+                            Element::<NodeBundle>::new()
+                                .style(typography::text_strong)
+                                .children("Open dialog"),
+                            "Shows a modal dialog with a header and footer.",
+                        ))
                         .children("Open..."),
                     Dialog::new()
                         .width(ui::Val::Px(400.))
@@ -274,12 +420,342 @@ impl ViewTemplate for ButtonsDemo {
                                 })),
                         )),
                     ),)),
+                "ContextMenu",
+                ContextMenuDemo,
             ))
     }
 }
 
+fn style_context_area(ss: &mut StyleBuilder) {
+    ss.display(Display::Flex)
+        .justify_content(JustifyContent::Center)
+        .align_items(AlignItems::Center)
+        .width(200)
+        .height(60)
+        .border(1)
+        .border_color(colors::U3)
+        .color(colors::DIM);
+}
+
+#[derive(Clone, PartialEq)]
+struct ContextMenuDemo;
+
+impl ViewTemplate for ContextMenuDemo {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let id = cx.create_entity();
+        let grid = cx.create_mutable::<bool>(true);
+        let zoom = cx.create_mutable::<u32>(100);
+        let zoom_level = zoom.get(cx);
+        let zoom_items: Vec<ViewChild> = [50, 100, 200]
+            .into_iter()
+            .map(|level| {
+                MenuItem::new()
+                    .label(format!("{}%", level))
+                    .radio(true)
+                    .checked(zoom_level == level)
+                    .on_click(cx.create_callback(move |world: &mut World| {
+                        zoom.set(world, level);
+                    }))
+                    .into_view_child()
+            })
+            .collect();
+
+        (
+            Element::<NodeBundle>::for_entity(id)
+                .style(style_context_area)
+                .children("Right-click here"),
+            ContextMenu::new(id).popup(
+                MenuPopup::new().children((
+                    MenuItem::new()
+                        .label("Undo")
+                        .icon("embedded://bevy_quill_obsidian/assets/icons/undo.png")
+                        .accelerator("Ctrl+Z")
+                        .on_click(cx.create_callback(|| {
+                            println!("Undo clicked");
+                        })),
+                    MenuItem::new()
+                        .label("Redo")
+                        .icon("embedded://bevy_quill_obsidian/assets/icons/redo.png")
+                        .accelerator("Ctrl+Shift+Z")
+                        .on_click(cx.create_callback(|| {
+                            println!("Redo clicked");
+                        })),
+                    MenuDivider,
+                    MenuItem::new()
+                        .label("Show Grid")
+                        .checked(grid.get(cx))
+                        .on_click(cx.create_callback(move |world: &mut World| {
+                            grid.update(world, |mut grid| *grid = !*grid);
+                        })),
+                    SubMenu::new().label("Zoom").children(zoom_items),
+                    MenuDivider,
+                    MenuItem::new()
+                        .label("Delete")
+                        .accelerator("Del")
+                        .disabled(true),
+                )),
+            ),
+        )
+    }
+}
+
 pub fn close_on_esc(input: Res<ButtonInput<KeyCode>>, mut exit: EventWriter<AppExit>) {
     if input.just_pressed(KeyCode::Escape) {
         exit.send(AppExit::Success);
     }
 }
+
+#[derive(Clone, PartialEq)]
+struct ListViewDemo;
+
+impl ViewTemplate for ListViewDemo {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let selection = cx.create_mutable::<Vec<u64>>(Vec::new());
+        let fruits = [
+            "Apple",
+            "Banana",
+            "Cherry",
+            "Date",
+            "Elderberry",
+            "Fig",
+            "Grape",
+            "Honeydew",
+        ];
+
+        ListView::new()
+            .style(|sb: &mut StyleBuilder| {
+                sb.width(200).height(100);
+            })
+            .selection(selection.get_clone(cx))
+            .selection_mode(ListSelectionMode::Multiple)
+            .on_selection_change(cx.create_callback(
+                move |keys: In<Vec<u64>>, world: &mut World| {
+                    selection.update(world, |mut selection| *selection = keys.clone());
+                },
+            ))
+            .on_activate(cx.create_callback(move |key: In<u64>| {
+                println!("Activated {}", fruits[*key as usize]);
+            }))
+            .children(
+                fruits
+                    .iter()
+                    .enumerate()
+                    .map(|(index, fruit)| {
+                        ListRow::new(index as u64)
+                            .style(|sb: &mut StyleBuilder| {
+                                sb.padding((4, 2));
+                            })
+                            .children(*fruit)
+                            .into_view_child()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct DataGridDemo;
+
+impl ViewTemplate for DataGridDemo {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let sort = cx.create_mutable::<Option<(usize, SortDirection)>>(None);
+        let selection = cx.create_mutable::<Vec<u64>>(Vec::new());
+        let sort_order = sort.get(cx);
+        // The grid displays rows in the order given by `order`, which maps each displayed row to
+        // an entity id. The ids are also the row keys, so the selection survives re-sorting.
+        let order: Arc<Vec<u32>> = cx.create_memo(
+            |_, sort_order: Option<(usize, SortDirection)>| {
+                let mut order: Vec<u32> = (0..10_000).collect();
+                if let Some((column, direction)) = sort_order {
+                    match column {
+                        1 => order.sort_by_key(|id| entity_size(*id)),
+                        _ => order.sort_by_key(|id| entity_name(*id)),
+                    }
+                    if direction == SortDirection::Descending {
+                        order.reverse();
+                    }
+                }
+                Arc::new(order)
+            },
+            sort_order,
+        );
+        // The cell builders are only remade when the order changes, so that changing the
+        // selection doesn't rebuild every visible row.
+        let (name_cell, size_cell) = cx.create_memo(
+            |_, order: Arc<Vec<u32>>| {
+                let names = order.clone();
+                (
+                    ItemBuilder::new(move |row| entity_name(names[row])),
+                    ItemBuilder::new(move |row| format!("{} KB", entity_size(order[row]))),
+                )
+            },
+            order.clone(),
+        );
+
+        DataGrid::new()
+            .style(|sb: &mut StyleBuilder| {
+                sb.width(300).height(160);
+            })
+            .columns(vec![
+                DataColumn::new("Name")
+                    .width(140.)
+                    .sortable(true)
+                    .cell_builder(name_cell),
+                DataColumn::new("Size")
+                    .width(80.)
+                    .sortable(true)
+                    .cell_builder(size_cell),
+                DataColumn::new("Kind")
+                    .width(120.)
+                    .cell(|_| "Entity".to_string()),
+            ])
+            .rows(order.iter().map(|id| *id as u64).collect())
+            .sort(sort_order)
+            .selection(selection.get_clone(cx))
+            .selection_mode(ListSelectionMode::Multiple)
+            .on_sort(cx.create_callback(
+                move |sort_by: In<(usize, SortDirection)>, world: &mut World| {
+                    sort.set(world, Some(*sort_by));
+                },
+            ))
+            .on_selection_change(cx.create_callback(
+                move |keys: In<Vec<u64>>, world: &mut World| {
+                    selection.update(world, |mut selection| *selection = keys.clone());
+                },
+            ))
+            .on_activate(cx.create_callback(move |key: In<u64>| {
+                println!("Activated {}", entity_name(*key as u32));
+            }))
+    }
+}
+
+fn entity_name(id: u32) -> String {
+    format!("Entity {:05}", id)
+}
+
+fn entity_size(id: u32) -> u32 {
+    id.wrapping_mul(2_654_435_761) % 4096
+}
+
+#[derive(Clone, PartialEq)]
+struct TreeViewDemo;
+
+impl ViewTemplate for TreeViewDemo {
+    type View = impl View;
+
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let nodes = cx.create_mutable::<Vec<TreeNode>>(vec![
+            TreeNode::new(1, "Scene").children(vec![
+                TreeNode::new(2, "Camera"),
+                TreeNode::new(3, "Light"),
+                TreeNode::new(4, "Player")
+                    .children(vec![TreeNode::new(5, "Body"), TreeNode::new(6, "Weapon")]),
+            ]),
+            TreeNode::new(7, "Assets").lazy(),
+        ]);
+        let selection = cx.create_mutable::<Vec<u64>>(Vec::new());
+
+        TreeView::new()
+            .style(|sb: &mut StyleBuilder| {
+                sb.width(200).height(120);
+            })
+            .nodes(nodes.get_clone(cx))
+            .selection(selection.get_clone(cx))
+            .selection_mode(TreeSelectionMode::Multiple)
+            .on_select(
+                cx.create_callback(move |ids: In<Vec<u64>>, world: &mut World| {
+                    selection.update(world, |mut selection| *selection = ids.clone());
+                }),
+            )
+            .on_load(cx.create_callback(move |id: In<u64>, world: &mut World| {
+                nodes.update(world, |mut nodes| {
+                    if let Some(node) = find_node(&mut nodes, *id) {
+                        node.children = TreeChildren::Loaded(
+                            ["Meshes", "Textures", "Sounds"]
+                                .iter()
+                                .enumerate()
+                                .map(|(i, label)| TreeNode::new(*id * 100 + i as u64, *label))
+                                .collect(),
+                        );
+                    }
+                });
+            }))
+            .on_move(
+                cx.create_callback(move |tree_move: In<TreeMove>, world: &mut World| {
+                    nodes.update(world, |mut nodes| move_nodes(&mut nodes, &tree_move));
+                }),
+            )
+    }
+}
+
+fn find_node(nodes: &mut [TreeNode], id: u64) -> Option<&mut TreeNode> {
+    for node in nodes.iter_mut() {
+        if node.id == id {
+            return Some(node);
+        }
+        if let TreeChildren::Loaded(children) = &mut node.children {
+            if let Some(found) = find_node(children, id) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+fn take_nodes(nodes: &mut Vec<TreeNode>, ids: &[u64], out: &mut Vec<TreeNode>) {
+    let mut i = 0;
+    while i < nodes.len() {
+        if ids.contains(&nodes[i].id) {
+            out.push(nodes.remove(i));
+        } else {
+            if let TreeChildren::Loaded(children) = &mut nodes[i].children {
+                take_nodes(children, ids, out);
+            }
+            i += 1;
+        }
+    }
+}
+
+fn move_nodes(nodes: &mut Vec<TreeNode>, tree_move: &TreeMove) {
+    // Account for moved nodes which precede the insertion point in the new parent.
+    let siblings = match tree_move.parent {
+        None => Some(&*nodes),
+        Some(parent) => match find_node(nodes, parent).map(|node| &node.children) {
+            Some(TreeChildren::Loaded(children)) => Some(children),
+            _ => None,
+        },
+    };
+    let removed_before = siblings.map_or(0, |siblings| {
+        siblings[..tree_move.index.min(siblings.len())]
+            .iter()
+            .filter(|node| tree_move.nodes.contains(&node.id))
+            .count()
+    });
+
+    let mut moved = Vec::new();
+    take_nodes(nodes, &tree_move.nodes, &mut moved);
+    moved.sort_by_key(|node| tree_move.nodes.iter().position(|id| *id == node.id));
+    let siblings = match tree_move.parent {
+        None => nodes,
+        Some(parent) => {
+            let Some(node) = find_node(nodes, parent) else {
+                return;
+            };
+            if !matches!(node.children, TreeChildren::Loaded(_)) {
+                node.children = TreeChildren::Loaded(Vec::new());
+            }
+            let TreeChildren::Loaded(children) = &mut node.children else {
+                unreachable!();
+            };
+            children
+        }
+    };
+    let index = (tree_move.index - removed_before).min(siblings.len());
+    siblings.splice(index..index, moved);
+}
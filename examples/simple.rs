@@ -6,7 +6,7 @@ mod common;
 
 use bevy::{color::palettes, prelude::*};
 use bevy_mod_stylebuilder::*;
-use bevy_quill::{Cond, Cx, Element, QuillPlugin, View, ViewTemplate};
+use bevy_quill::{Cond, Cx, Element, FontWeight, QuillPlugin, RichText, Span, View, ViewTemplate};
 use common::*;
 
 fn main() {
@@ -43,6 +43,7 @@ fn setup_view_root(mut commands: Commands) {
                 " ",
                 Nested,
                 DynamicStyle,
+                RichCount,
             ))
             .to_root(),
     );
@@ -172,6 +173,25 @@ impl ViewTemplate for DynamicStyle {
     }
 }
 
+/// Example of a `RichText` view with individually-styled spans.
+#[derive(Clone, PartialEq)]
+struct RichCount;
+
+impl ViewTemplate for RichCount {
+    type View = impl View;
+    fn create(&self, cx: &mut Cx) -> Self::View {
+        let counter = cx.use_resource::<Counter>();
+        RichText::new()
+            .span(" Count: ")
+            .span(
+                Span::new(format!("{}", counter.count))
+                    .weight(FontWeight::Bold)
+                    .color(palettes::css::YELLOW),
+            )
+            .span(Span::new(" (press space)").font_size(10.))
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct Counter {
     pub count: u32,